@@ -0,0 +1,138 @@
+//! Author filter (`A`): restrict the log to one author's commits, for App.
+//!
+//! The author is picked from the loaded log in a list drawn like the fuzzy
+//! finder's, and combined with the revset filter as `author(exact:"name")`.
+
+use crate::error::XorcistError;
+use crate::text::fuzzy_score;
+
+use super::paths::path_revset;
+use super::{App, FinderState, InputMode};
+
+/// Revset matching the commits of `author` (by exact name or email).
+pub fn author_revset(author: &str) -> String {
+    let quoted = author.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("author(exact:\"{quoted}\")")
+}
+
+impl App {
+    /// Open the author list: every author of the loaded log, most commits first.
+    ///
+    /// The first entry clears the filter.
+    pub fn open_author_filter(&mut self) {
+        let mut authors: Vec<(String, usize)> = Vec::new();
+        for author in self
+            .graph_log
+            .lines
+            .iter()
+            .filter_map(|line| line.author.as_deref())
+        {
+            match authors.iter_mut().find(|(name, _)| name == author) {
+                Some((_, count)) => *count += 1,
+                None => authors.push((author.to_string(), 1)),
+            }
+        }
+        // Stable sort keeps log order among equal counts
+        authors.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        authors.insert(0, (String::new(), 0));
+        self.author_choices = authors;
+        self.start_input_mode(InputMode::AuthorFilter);
+        self.finder = Some(FinderState::default());
+        self.update_finder();
+    }
+
+    /// Authors listed by the author filter: `(name, loaded commit count)`.
+    ///
+    /// The first entry (with an empty name) stands for all authors.
+    pub fn author_choices(&self) -> &[(String, usize)] {
+        &self.author_choices
+    }
+
+    /// Indices of the listed authors matching `query`, best first.
+    pub(super) fn author_matches(&self, query: &str) -> Vec<usize> {
+        let mut scored: Vec<(i64, usize)> = self
+            .author_choices
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(i, (name, _))| fuzzy_score(name, query).map(|score| (score, i)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        let mut results: Vec<usize> = scored.into_iter().map(|(_, i)| i).collect();
+        if query.is_empty() {
+            results.insert(0, 0);
+        }
+        results
+    }
+
+    /// Filter the log by the highlighted author (or clear the filter).
+    pub(super) fn submit_author_filter(&mut self) -> Result<(), XorcistError> {
+        let Some(finder) = self.finder.take() else {
+            return Ok(());
+        };
+        let choices = std::mem::take(&mut self.author_choices);
+        let Some(&index) = finder.results.get(finder.selected) else {
+            return Ok(());
+        };
+        let author = choices
+            .into_iter()
+            .nth(index)
+            .map(|(name, _)| name)
+            .filter(|name| !name.is_empty());
+        self.set_author_filter(author)
+    }
+
+    /// Author the log is filtered by, if any.
+    pub fn author_filter(&self) -> Option<&str> {
+        self.author_filter.as_deref()
+    }
+
+    /// Filter the log by an author (None = all authors) and reload it.
+    ///
+    /// As with [`App::set_revset`], a failure keeps the previous filter.
+    pub fn set_author_filter(&mut self, author: Option<String>) -> Result<(), XorcistError> {
+        let previous = std::mem::replace(&mut self.author_filter, author);
+        let reloaded = self.reload_filtered_log();
+        if !matches!(reloaded, Ok(true)) {
+            self.author_filter = previous;
+        }
+        reloaded.map(|_| ())
+    }
+
+    /// Revset the log is fetched with: the revset filter, the author filter
+    /// and the path filter, plus any expanded elided revisions and the shown
+    /// hidden commits.
+    pub fn log_revset(&self) -> Option<String> {
+        let additions: Vec<&str> = self
+            .elided_expansions
+            .iter()
+            .map(String::as_str)
+            .chain(self.hidden_revset.as_deref())
+            .collect();
+        if additions.is_empty() {
+            return self.filtered_revset();
+        }
+        let base = self
+            .filtered_revset()
+            .or_else(|| self.default_log_revset.clone())
+            .unwrap_or_else(|| "all()".to_string());
+        Some(format!("({base}) | {}", additions.join(" | ")))
+    }
+
+    /// The revset filter combined with the author and path filters.
+    pub(super) fn filtered_revset(&self) -> Option<String> {
+        let filters: Vec<String> = [
+            self.author_filter.as_deref().map(author_revset),
+            self.path_filter.as_deref().map(path_revset),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        match (self.revset.as_deref(), filters.is_empty()) {
+            (Some(revset), true) => Some(revset.to_string()),
+            (Some(revset), false) => Some(format!("({revset}) & {}", filters.join(" & "))),
+            (None, true) => None,
+            (None, false) => Some(filters.join(" & ")),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Cached `jj show` and `jj diff` output for App.
+//!
+//! The working copy is left out: its commit is rewritten by every snapshot,
+//! which may happen without xorcist seeing a new operation first.
+
+use crate::error::XorcistError;
+use crate::jj::{
+    CacheKind, CachedOutput, DiffStat, ShowOutput, fetch_diff, fetch_diff_file, fetch_diff_stat,
+    fetch_show,
+};
+
+use super::App;
+
+impl App {
+    /// Whether output for `revision` may be cached.
+    fn is_cacheable(&self, revision: &str) -> bool {
+        revision != "@" && !self.is_working_copy(revision)
+    }
+
+    /// [`fetch_show`] through the output cache.
+    pub(super) fn cached_show(&mut self, revision: &str) -> Result<ShowOutput, XorcistError> {
+        if let Some(show) = self.output_cache.show(revision) {
+            return Ok(show);
+        }
+        let show = fetch_show(&self.runner, revision)?;
+        self.cache_output(
+            revision,
+            CacheKind::Show,
+            CachedOutput::Show(Box::new(show.clone())),
+        );
+        Ok(show)
+    }
+
+    /// [`fetch_diff_stat`] through the output cache.
+    pub(super) fn cached_stat(&mut self, revision: &str) -> Result<DiffStat, XorcistError> {
+        if let Some(stat) = self.output_cache.stat(revision) {
+            return Ok(stat);
+        }
+        let stat = fetch_diff_stat(&self.runner, revision)?;
+        self.cache_output(revision, CacheKind::Stat, CachedOutput::Stat(stat.clone()));
+        Ok(stat)
+    }
+
+    /// [`fetch_diff`] through the output cache.
+    pub(super) fn cached_diff(&mut self, revision: &str) -> Result<String, XorcistError> {
+        if let Some(output) = self.output_cache.diff(revision, &CacheKind::Diff) {
+            return Ok(output);
+        }
+        let output = fetch_diff(&self.runner, revision)?;
+        self.cache_output(
+            revision,
+            CacheKind::Diff,
+            CachedOutput::Diff(output.clone()),
+        );
+        Ok(output)
+    }
+
+    /// [`fetch_diff_file`] through the output cache.
+    pub(super) fn cached_file_diff(
+        &mut self,
+        revision: &str,
+        path: &str,
+    ) -> Result<String, XorcistError> {
+        let kind = CacheKind::FileDiff(path.to_string());
+        if let Some(output) = self.output_cache.diff(revision, &kind) {
+            return Ok(output);
+        }
+        let output = fetch_diff_file(&self.runner, revision, path)?;
+        self.cache_output(revision, kind, CachedOutput::Diff(output.clone()));
+        Ok(output)
+    }
+
+    /// Cache output fetched elsewhere (e.g., by the preview's worker thread).
+    pub(super) fn cache_output(&mut self, revision: &str, kind: CacheKind, output: CachedOutput) {
+        if self.is_cacheable(revision) {
+            self.output_cache.insert(revision, kind, output);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Panel listing every jj command run this session, for App.
+
+use crate::error::XorcistError;
+use crate::jj::HistoryEntry;
+
+use super::{App, PendingAction};
+
+/// State of the command history panel.
+#[derive(Debug, Clone, Default)]
+pub struct CommandHistoryPanel {
+    /// Whether the panel is shown.
+    pub visible: bool,
+    /// Selected command (index into the newest-first history).
+    pub selected: usize,
+    /// Scroll offset (in commands).
+    pub scroll: usize,
+}
+
+impl App {
+    /// Every jj command run this session, newest first.
+    pub fn command_history(&self) -> Vec<HistoryEntry> {
+        self.runner.history().entries()
+    }
+
+    /// Get the command history panel state.
+    pub fn command_history_panel(&self) -> &CommandHistoryPanel {
+        &self.command_history_panel
+    }
+
+    /// Show or hide the command history panel (it opens on the newest command).
+    pub fn toggle_command_history(&mut self) {
+        let panel = &mut self.command_history_panel;
+        panel.visible = !panel.visible;
+        panel.selected = 0;
+        panel.scroll = 0;
+    }
+
+    /// Whether the command history panel is shown.
+    pub fn is_command_history_open(&self) -> bool {
+        self.command_history_panel.visible
+    }
+
+    /// Select the next (older) command.
+    pub fn command_history_select_next(&mut self) {
+        let count = self.runner.history().len();
+        let panel = &mut self.command_history_panel;
+        if panel.selected + 1 < count {
+            panel.selected += 1;
+        }
+    }
+
+    /// Select the previous (newer) command.
+    pub fn command_history_select_previous(&mut self) {
+        let panel = &mut self.command_history_panel;
+        panel.selected = panel.selected.saturating_sub(1);
+    }
+
+    /// Select the newest command.
+    pub fn command_history_select_first(&mut self) {
+        self.command_history_panel.selected = 0;
+    }
+
+    /// Select the oldest command.
+    pub fn command_history_select_last(&mut self) {
+        self.command_history_panel.selected = self.runner.history().len().saturating_sub(1);
+    }
+
+    /// Adjust the scroll offset so the selected command is visible.
+    ///
+    /// New commands are added at the top, so the selection is also clamped
+    /// to the current history length.
+    pub fn ensure_command_history_visible(&mut self, count: usize, visible_height: usize) {
+        let panel = &mut self.command_history_panel;
+        panel.selected = panel.selected.min(count.saturating_sub(1));
+        if visible_height == 0 {
+            return;
+        }
+        if panel.selected < panel.scroll {
+            panel.scroll = panel.selected;
+        } else if panel.selected >= panel.scroll + visible_height {
+            panel.scroll = panel.selected + 1 - visible_height;
+        }
+    }
+
+    /// Ask to run the selected command again.
+    pub fn rerun_selected_command(&mut self) -> Result<(), XorcistError> {
+        let selected = self.command_history_panel.selected;
+        let Some(entry) = self.command_history().into_iter().nth(selected) else {
+            return Ok(());
+        };
+        self.command_history_panel.visible = false;
+        self.request_confirm(PendingAction::Rerun { args: entry.args })
+    }
+}
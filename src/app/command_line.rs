@@ -0,0 +1,468 @@
+//! Ex-style `:` command line.
+//!
+//! Commands are typed as `name arguments`, e.g. `:describe fix typo`,
+//! `:rebase -d main` or `:revset mine()`. Names may be abbreviated to any
+//! unique prefix (`:desc`, `:q`).
+
+use std::path::Path;
+
+use tui_input::Input;
+
+use crate::error::XorcistError;
+use crate::export::{ExportFormat, export_log};
+
+use super::{App, CommandResult};
+
+/// Command names with a short usage summary, in completion order.
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("abandon", "abandon"),
+    ("bookmark", "bookmark <name>"),
+    ("commit", "commit [message]"),
+    ("describe", "describe <message>"),
+    ("duplicate", "duplicate [-d <destination>]"),
+    ("edit", "edit"),
+    ("export", "export <file>"),
+    ("fetch", "fetch"),
+    ("new", "new [message]"),
+    ("patch", "patch <file or directory>"),
+    ("push", "push"),
+    ("quit", "quit"),
+    ("rebase", "rebase -d <destination>"),
+    ("refresh", "refresh"),
+    ("revset", "revset [expression]"),
+    ("shell", "shell"),
+    ("sign", "sign"),
+    ("squash", "squash"),
+    ("undo", "undo"),
+    ("unsign", "unsign"),
+];
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExCommand {
+    /// `jj abandon` the selected revision (with confirmation).
+    Abandon,
+    /// `jj bookmark set <name>` on the selected revision.
+    Bookmark(String),
+    /// `jj commit -m <message>` on the working copy (empty = keep its description).
+    Commit(String),
+    /// `jj describe -m <message>` on the selected revision.
+    Describe(String),
+    /// `jj duplicate` the selected revision, optionally onto a destination
+    /// (with confirmation).
+    Duplicate(String),
+    /// `jj edit` the selected revision.
+    Edit,
+    /// Write the loaded log to a file (`.tsv` for tab-separated rows).
+    Export(String),
+    /// `jj git fetch`.
+    Fetch,
+    /// `jj new` on the selected revision, with an optional message.
+    New(String),
+    /// Write the selected commit's patch to a file, or the marked
+    /// commits' patch series into a directory.
+    Patch(String),
+    /// Open the push dialog (`p`).
+    Push,
+    /// Quit xorcist.
+    Quit,
+    /// `jj rebase -d <destination>` of the selected revision.
+    Rebase(String),
+    /// Reload the log.
+    Refresh,
+    /// Filter the log by a revset (None = show all history again).
+    Revset(Option<String>),
+    /// Drop into a shell in the repository root.
+    Shell,
+    /// `jj sign` the selected revision.
+    Sign,
+    /// `jj squash` the selected revision (with confirmation).
+    Squash,
+    /// `jj undo` (with confirmation).
+    Undo,
+    /// `jj unsign` the selected revision.
+    Unsign,
+}
+
+impl ExCommand {
+    /// Parse a command line (without the leading `:`).
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim().trim_start_matches(':').trim_start();
+        let (name, args) = match line.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (line, ""),
+        };
+        if name.is_empty() {
+            return Err("empty command".to_string());
+        }
+
+        let command = resolve_name(name)?;
+        let parsed = match command {
+            "abandon" => Self::Abandon,
+            "bookmark" => Self::Bookmark(require(command, unquote(args))?),
+            "commit" => Self::Commit(unquote(args)),
+            "describe" => Self::Describe(require(command, unquote(args))?),
+            "duplicate" => Self::Duplicate(parse_rebase_args(args)),
+            "edit" => Self::Edit,
+            "export" => Self::Export(require(command, unquote(args))?),
+            "fetch" => Self::Fetch,
+            "new" => Self::New(unquote(args)),
+            "patch" => Self::Patch(require(command, unquote(args))?),
+            "push" => Self::Push,
+            "quit" => Self::Quit,
+            "rebase" => Self::Rebase(require(command, parse_rebase_args(args))?),
+            "refresh" => Self::Refresh,
+            "revset" => Self::Revset((!args.is_empty()).then(|| args.to_string())),
+            "shell" => Self::Shell,
+            "sign" => Self::Sign,
+            "squash" => Self::Squash,
+            "undo" => Self::Undo,
+            "unsign" => Self::Unsign,
+            _ => unreachable!("every entry in COMMANDS is handled"),
+        };
+
+        let takes_args = matches!(
+            parsed,
+            Self::Bookmark(_)
+                | Self::Commit(_)
+                | Self::Describe(_)
+                | Self::Duplicate(_)
+                | Self::Export(_)
+                | Self::New(_)
+                | Self::Patch(_)
+                | Self::Rebase(_)
+                | Self::Revset(_)
+        );
+        if !takes_args && !args.is_empty() {
+            return Err(format!("{command}: unexpected arguments `{args}`"));
+        }
+        Ok(parsed)
+    }
+}
+
+/// Command names starting with `prefix`.
+pub fn matching_commands(prefix: &str) -> Vec<&'static str> {
+    COMMANDS
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| name.starts_with(prefix))
+        .collect()
+}
+
+/// Complete the command name of a partially typed line.
+///
+/// Returns the new line, or `None` if nothing can be completed (arguments
+/// already started, or no command matches). A unique match is completed
+/// with a trailing space; several matches are completed to their longest
+/// common prefix.
+pub fn complete(line: &str) -> Option<String> {
+    if line.contains(char::is_whitespace) {
+        return None;
+    }
+    let matches = matching_commands(line);
+    match matches.as_slice() {
+        [] => None,
+        [only] => Some(format!("{only} ")),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(*first, |common, name| {
+                let len = common
+                    .chars()
+                    .zip(name.chars())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                &common[..len]
+            });
+            (common.len() > line.len()).then(|| common.to_string())
+        }
+    }
+}
+
+/// Resolve a (possibly abbreviated) command name.
+fn resolve_name(name: &str) -> Result<&'static str, String> {
+    if let Some((exact, _)) = COMMANDS.iter().find(|(n, _)| *n == name) {
+        return Ok(exact);
+    }
+    match matching_commands(name).as_slice() {
+        [] => Err(format!("unknown command `{name}`")),
+        [only] => Ok(only),
+        several => Err(format!(
+            "ambiguous command `{name}`: {}",
+            several.join(", ")
+        )),
+    }
+}
+
+/// Reject empty arguments for commands that need one.
+fn require(command: &str, value: String) -> Result<String, String> {
+    if value.is_empty() {
+        let usage = COMMANDS
+            .iter()
+            .find(|(name, _)| *name == command)
+            .map_or(command, |(_, usage)| usage);
+        Err(format!("usage: {usage}"))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Extract the destination (of `rebase` or `duplicate`) from `-d <dest>`, `--destination <dest>` or `<dest>`.
+fn parse_rebase_args(args: &str) -> String {
+    let dest = ["-d", "--destination"]
+        .iter()
+        .find_map(|flag| {
+            let rest = args.strip_prefix(flag)?;
+            (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(rest)
+        })
+        .unwrap_or(args);
+    unquote(dest.trim())
+}
+
+/// Strip one pair of matching surrounding quotes.
+fn unquote(text: &str) -> String {
+    let text = text.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    text.to_string()
+}
+
+impl App {
+    /// Complete the command name in the `:` command line.
+    pub fn complete_command_line(&mut self) {
+        if let Some(completed) = complete(self.input.value()) {
+            self.input = Input::new(completed);
+        }
+    }
+
+    /// Parse and execute a `:` command line.
+    ///
+    /// Parse errors are reported in the status bar.
+    pub fn run_command_line(&mut self, line: &str) -> Result<(), XorcistError> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let command = match ExCommand::parse(line) {
+            Ok(command) => command,
+            Err(message) => {
+                self.report(CommandResult {
+                    success: false,
+                    message,
+                });
+                return Ok(());
+            }
+        };
+
+        match command {
+            ExCommand::Abandon => self.show_abandon_confirm()?,
+            ExCommand::Bookmark(name) => self.execute_bookmark_set(&name)?,
+            ExCommand::Commit(message) => self.execute_commit(&message),
+            ExCommand::Describe(message) => self.execute_describe(&message)?,
+            ExCommand::Duplicate(destination) => self.show_duplicate_confirm(&destination),
+            ExCommand::Edit => self.execute_edit()?,
+            ExCommand::Export(path) => self.export_log_to(Path::new(&path)),
+            ExCommand::Fetch => self.execute_git_fetch()?,
+            ExCommand::New(message) => self.execute_new_with_message(&message)?,
+            ExCommand::Patch(path) => self.export_patches_to(Path::new(&path)),
+            ExCommand::Push => self.open_push_dialog(),
+            ExCommand::Quit => self.quit(),
+            ExCommand::Rebase(destination) => self.execute_rebase(&destination)?,
+            ExCommand::Refresh => self.refresh_log_keep_selection()?,
+            ExCommand::Revset(revset) => self.set_revset(revset)?,
+            ExCommand::Shell => self.start_shell(),
+            ExCommand::Sign => self.execute_sign(),
+            ExCommand::Squash => self.show_squash_confirm()?,
+            ExCommand::Undo => self.show_undo_confirm(),
+            ExCommand::Unsign => self.execute_unsign(),
+        }
+        Ok(())
+    }
+
+    /// Write the loaded log to `path` and report the result in the status bar.
+    ///
+    /// The format follows the file extension (see [`ExportFormat::from_path`]).
+    pub fn export_log_to(&mut self, path: &Path) {
+        let format = ExportFormat::from_path(path);
+        let revset = self.log_revset();
+        let exported = export_log(&self.runner, &self.graph_log, revset.as_deref(), format)
+            .map_err(|e| e.to_string())
+            .and_then(|text| std::fs::write(path, text).map_err(|e| e.to_string()));
+        let result = match exported {
+            Ok(()) => CommandResult {
+                success: true,
+                message: format!(
+                    "Exported {} commits to {}",
+                    self.commit_count(),
+                    path.display()
+                ),
+            },
+            Err(e) => CommandResult {
+                success: false,
+                message: format!("Failed to export to {}: {e}", path.display()),
+            },
+        };
+        self.report(result);
+    }
+
+    /// Current revset filter, if any.
+    pub fn revset(&self) -> Option<&str> {
+        self.revset.as_deref()
+    }
+
+    /// Record the revset the initial log was fetched with.
+    pub fn set_initial_revset(&mut self, revset: Option<String>) {
+        self.revset = revset;
+    }
+
+    /// Filter the log by a revset and reload it.
+    ///
+    /// An invalid revset is reported in the status bar and the previous
+    /// filter is kept.
+    pub fn set_revset(&mut self, revset: Option<String>) -> Result<(), XorcistError> {
+        let previous = std::mem::replace(&mut self.revset, revset);
+        let reloaded = self.reload_filtered_log();
+        if !matches!(reloaded, Ok(true)) {
+            self.revset = previous;
+        }
+        reloaded.map(|_| ())
+    }
+
+    /// Reload the log from the top after one of its filters changed.
+    ///
+    /// Returns `Ok(false)` if jj rejected the filter (reported in the status
+    /// bar); the caller then restores the previous filter.
+    pub(super) fn reload_filtered_log(&mut self) -> Result<bool, XorcistError> {
+        self.selected = 0;
+        self.scroll_offset = 0;
+        match self.refresh_log() {
+            Ok(()) => {
+                let limit = self.log_limit;
+                self.set_log_limit(limit);
+                Ok(true)
+            }
+            Err(XorcistError::JjError(message)) => {
+                self.report(CommandResult {
+                    success: false,
+                    message,
+                });
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands_with_arguments() {
+        assert_eq!(
+            ExCommand::parse("describe fix typo"),
+            Ok(ExCommand::Describe("fix typo".to_string()))
+        );
+        assert_eq!(
+            ExCommand::parse(":describe \"quoted message\""),
+            Ok(ExCommand::Describe("quoted message".to_string()))
+        );
+        // A bare `:describe` would clear the description
+        assert_eq!(
+            ExCommand::parse("describe"),
+            Err("usage: describe <message>".to_string())
+        );
+        assert_eq!(
+            ExCommand::parse("describe ''"),
+            Err("usage: describe <message>".to_string())
+        );
+        assert_eq!(
+            ExCommand::parse("rebase -d main"),
+            Ok(ExCommand::Rebase("main".to_string()))
+        );
+        assert_eq!(
+            ExCommand::parse("rebase --destination 'trunk()'"),
+            Ok(ExCommand::Rebase("trunk()".to_string()))
+        );
+        assert_eq!(
+            ExCommand::parse("rebase @-"),
+            Ok(ExCommand::Rebase("@-".to_string()))
+        );
+        assert_eq!(
+            ExCommand::parse("revset mine() & ~empty()"),
+            Ok(ExCommand::Revset(Some("mine() & ~empty()".to_string())))
+        );
+        assert_eq!(ExCommand::parse("revset"), Ok(ExCommand::Revset(None)));
+        assert_eq!(ExCommand::parse("new"), Ok(ExCommand::New(String::new())));
+        assert_eq!(
+            ExCommand::parse("commit 'feat: done'"),
+            Ok(ExCommand::Commit("feat: done".to_string()))
+        );
+        assert_eq!(
+            ExCommand::parse("duplicate -d main"),
+            Ok(ExCommand::Duplicate("main".to_string()))
+        );
+        assert_eq!(
+            ExCommand::parse("dup"),
+            Ok(ExCommand::Duplicate(String::new()))
+        );
+        assert_eq!(
+            ExCommand::parse("pa 'my fix.patch'"),
+            Ok(ExCommand::Patch("my fix.patch".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_abbreviations() {
+        assert_eq!(
+            ExCommand::parse("desc wip"),
+            Ok(ExCommand::Describe("wip".to_string()))
+        );
+        assert_eq!(ExCommand::parse("q"), Ok(ExCommand::Quit));
+        assert_eq!(ExCommand::parse("ab"), Ok(ExCommand::Abandon));
+        assert_eq!(ExCommand::parse("sign"), Ok(ExCommand::Sign));
+        assert_eq!(ExCommand::parse("unsign"), Ok(ExCommand::Unsign));
+
+        let err = ExCommand::parse("re").unwrap_err();
+        assert!(err.contains("ambiguous"), "{err}");
+        assert!(err.contains("rebase, refresh, revset"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(ExCommand::parse("").is_err());
+        assert!(
+            ExCommand::parse("frobnicate")
+                .unwrap_err()
+                .contains("unknown")
+        );
+        assert_eq!(
+            ExCommand::parse("bookmark").unwrap_err(),
+            "usage: bookmark <name>"
+        );
+        assert_eq!(
+            ExCommand::parse("rebase -d").unwrap_err(),
+            "usage: rebase -d <destination>"
+        );
+        assert!(
+            ExCommand::parse("undo now")
+                .unwrap_err()
+                .contains("unexpected")
+        );
+    }
+
+    #[test]
+    fn test_complete() {
+        assert_eq!(complete("desc"), Some("describe ".to_string()));
+        assert_eq!(complete("ex"), Some("export ".to_string()));
+        assert_eq!(complete("r"), Some("re".to_string()));
+        assert_eq!(complete("re"), None); // rebase / refresh / revset
+        assert_eq!(complete("ref"), Some("refresh ".to_string()));
+        assert_eq!(complete("xyz"), None);
+        assert_eq!(complete("describe fix"), None);
+        assert_eq!(matching_commands("re"), vec!["rebase", "refresh", "revset"]);
+    }
+}
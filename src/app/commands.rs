@@ -1,14 +1,34 @@
 //! jj command execution methods for App.
 
+use std::time::Instant;
+
+use crate::config::{ConfirmConfig, ConfirmPolicy, DiffConfig, PushConfig};
 use crate::error::XorcistError;
-use crate::jj::{fetch_diff_file, fetch_graph_log, parse_diff_summary};
+use crate::highlight::{highlight_diff, syntax_for_path};
+use crate::jj::{
+    DiffRange, GitDiff, fetch_commit_lines, fetch_diff_file_range, fetch_diff_stat_range,
+    fetch_diff_summary_range, fetch_graph_log, fetch_op_log, fetch_show, resolve_single_revision,
+};
+use crate::theme::Theme;
+use crate::word_diff::word_diff;
 
-use super::{App, CommandResult, DiffState, ModalState, PendingAction, View};
+use super::tasks::AfterCommand;
+use super::{
+    App, CommandResult, ConfirmButton, DiffState, InputMode, ModalState, PendingAction, View,
+};
 
 impl App {
     /// Refresh log entries.
     pub fn refresh_log(&mut self) -> Result<(), XorcistError> {
-        self.graph_log = fetch_graph_log(&self.runner, self.log_limit)?;
+        // A full fetch replaces a log still being streamed
+        self.log_stream = None;
+        self.graph_log =
+            fetch_graph_log(&self.runner, self.log_revset().as_deref(), self.log_limit)?;
+        self.last_refresh = Instant::now();
+        self.refresh_metadata();
+        self.record_op_heads();
+        self.refresh_search_matches();
+        self.invalidate_preview();
         // Clamp selection to valid range
         let count = self.commit_count();
         if count > 0 && self.selected >= count {
@@ -17,14 +37,36 @@ impl App {
         Ok(())
     }
 
+    /// Re-fetch only the lines of `change_ids`, their descendants (rebased
+    /// onto the rewritten commits, so with new commit IDs) and `@`, patching
+    /// them into the log in place so that a big log is not reloaded.
+    ///
+    /// Falls back to [`App::refresh_log`] while the log is filtered (the
+    /// changes may no longer match) or if the lines cannot be patched.
+    pub(super) fn refresh_revisions(&mut self, change_ids: &[String]) -> Result<(), XorcistError> {
+        if self.log_revset().is_some() {
+            return self.refresh_log();
+        }
+        let patched = fetch_commit_lines(&self.runner, &revisions_revset(change_ids))
+            .is_ok_and(|output| self.graph_log.patch_commits(&output));
+        if !patched {
+            return self.refresh_log();
+        }
+        self.last_refresh = Instant::now();
+        self.refresh_metadata();
+        self.record_op_heads();
+        self.refresh_search_matches();
+        Ok(())
+    }
+
     /// Handle command result (store for status display).
     pub(super) fn handle_command_result(&mut self, result: Result<CommandResult, XorcistError>) {
         match result {
             Ok(cmd_result) => {
-                self.last_command_result = Some(cmd_result);
+                self.report(cmd_result);
             }
             Err(e) => {
-                self.last_command_result = Some(CommandResult {
+                self.report(CommandResult {
                     success: false,
                     message: e.to_string(),
                 });
@@ -32,120 +74,429 @@ impl App {
         }
     }
 
-    /// Show confirmation dialog for abandon.
-    pub fn show_abandon_confirm(&mut self) {
-        if let Some(change_id) = self.selected_change_id() {
-            let description = self.selected_description().unwrap_or_default();
-            self.modal = ModalState::Confirm(PendingAction::Abandon {
-                change_id: change_id.to_string(),
-                description,
-            });
+    /// Show confirmation dialog for abandon (runs immediately in quick mode).
+    pub fn show_abandon_confirm(&mut self) -> Result<(), XorcistError> {
+        if self.has_marks() {
+            return self.show_abandon_marked_confirm();
         }
+        let Some(change_id) = self.selected_change_id() else {
+            return Ok(());
+        };
+        let change_id = change_id.to_string();
+        let description = self.selected_description().unwrap_or_default();
+        self.request_confirm(PendingAction::Abandon {
+            change_id,
+            description,
+        })
     }
 
-    /// Show confirmation dialog for squash.
-    pub fn show_squash_confirm(&mut self) {
-        if let Some(change_id) = self.selected_change_id() {
-            let description = self.selected_description().unwrap_or_default();
-            self.modal = ModalState::Confirm(PendingAction::Squash {
-                change_id: change_id.to_string(),
-                description,
+    /// Show confirmation dialog for abandoning every empty commit in the
+    /// loaded log, except the working copy and immutable commits.
+    ///
+    /// Quick mode does not skip it: the list is worth a look.
+    pub fn show_abandon_empty_confirm(&mut self) -> Result<(), XorcistError> {
+        let revisions: Vec<(String, String)> = self
+            .graph_log
+            .lines
+            .iter()
+            .filter(|line| line.flags.empty && !line.flags.working_copy && !line.flags.immutable)
+            .filter_map(|line| {
+                let change_id = line.change_id.clone()?;
+                Some((change_id, line.description.clone().unwrap_or_default()))
+            })
+            .collect();
+        if revisions.is_empty() {
+            self.report(CommandResult {
+                success: true,
+                message: "No empty changes to abandon in the loaded log".to_string(),
             });
+            return Ok(());
         }
+        self.request_confirm(PendingAction::AbandonEmpty { revisions })
     }
 
-    /// Get the description of the selected commit (parsed from plain text).
-    fn selected_description(&self) -> Option<String> {
-        let line_idx = self.selected_line_index()?;
-        let line = &self.graph_log.lines[line_idx];
-        // The description is the last part of the line after change_id, author, timestamp
-        // Format: "change_id author timestamp description..."
-        // We'll extract everything after the third space-separated token
-        let parts: Vec<&str> = line.plain.split_whitespace().collect();
-        if parts.len() > 3 {
-            Some(parts[3..].join(" "))
-        } else {
-            None
+    /// Show confirmation dialog for squash (runs immediately in quick mode).
+    pub fn show_squash_confirm(&mut self) -> Result<(), XorcistError> {
+        if self.has_marks() {
+            return self.show_squash_marked_confirm();
         }
+        let Some(change_id) = self.selected_change_id() else {
+            return Ok(());
+        };
+        let change_id = change_id.to_string();
+        let description = self.selected_description().unwrap_or_default();
+        self.request_confirm(PendingAction::Squash {
+            change_id,
+            description,
+        })
+    }
+
+    /// Get the description of the selected commit.
+    pub(super) fn selected_description(&self) -> Option<String> {
+        let line_idx = self.selected_line_index()?;
+        self.graph_log.lines[line_idx].description.clone()
     }
 
-    /// Show confirmation dialog for git push.
-    pub fn show_push_confirm(&mut self) {
-        self.modal = ModalState::Confirm(PendingAction::GitPush);
+    /// Show confirmation dialog for pushing the selected change.
+    pub fn show_push_change_confirm(&mut self) -> Result<(), XorcistError> {
+        let Some(change_id) = self.selected_change_id() else {
+            return Ok(());
+        };
+        let change_id = change_id.to_string();
+        self.request_confirm(PendingAction::PushChange {
+            change_id,
+            remote: self.push_config.remote.clone(),
+        })
     }
 
-    /// Show confirmation dialog for undo.
+    /// Show confirmation dialog for undo, with the operation it reverts.
     pub fn show_undo_confirm(&mut self) {
-        self.modal = ModalState::Confirm(PendingAction::Undo);
+        let operation = fetch_op_log(&self.runner, 1)
+            .ok()
+            .and_then(|ops| ops.into_iter().next());
+        self.ask_confirm(PendingAction::Undo { operation });
+    }
+
+    /// Ask for confirmation, or execute right away if the command's
+    /// `confirm.<command>` policy or quick mode says so.
+    pub(super) fn request_confirm(&mut self, action: PendingAction) -> Result<(), XorcistError> {
+        let ask = match self.confirm_config.policy(action.command()) {
+            ConfirmPolicy::Always => true,
+            ConfirmPolicy::Never => false,
+            ConfirmPolicy::Default => {
+                action.confirms_by_default() && !self.quick_mode_skips(&action)
+            }
+        };
+        self.modal = ModalState::Confirm(action);
+        if !ask {
+            self.confirm_action()?;
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::request_confirm`], reporting errors in the status bar.
+    pub(super) fn ask_confirm(&mut self, action: PendingAction) {
+        if let Err(e) = self.request_confirm(action) {
+            self.handle_command_result(Err(e));
+        }
+    }
+
+    /// Whether quick mode applies to the action.
+    fn quick_mode_skips(&self, action: &PendingAction) -> bool {
+        if !self.quick_mode {
+            return false;
+        }
+        match action {
+            PendingAction::Abandon { .. }
+            | PendingAction::Squash { .. }
+            | PendingAction::SquashInto { .. }
+            | PendingAction::AbandonMarked { .. }
+            | PendingAction::SquashMarked { .. } => true,
+            PendingAction::PushChange { .. } => self.confirm_config.quick_mode_push,
+            _ => false,
+        }
+    }
+
+    /// Apply `[confirm]` settings from the config file.
+    pub fn apply_confirm_config(&mut self, config: &ConfirmConfig) {
+        self.confirm_config = config.clone();
+        if !config.allow_quick_mode {
+            self.quick_mode = false;
+        }
+    }
+
+    /// Apply `[diff]` settings from the config file.
+    pub fn apply_diff_config(&mut self, config: &DiffConfig) {
+        self.syntax_highlight = config.syntax_highlight;
+        self.diff_tool = config.tool.clone();
+    }
+
+    /// Apply `[push]` settings from the config file.
+    pub fn apply_push_config(&mut self, config: &PushConfig) {
+        self.push_config = config.clone();
+    }
+
+    /// Set the UI colors.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// UI colors.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Whether quick mode (no confirmation for abandon/squash) is on.
+    pub fn quick_mode(&self) -> bool {
+        self.quick_mode
+    }
+
+    /// Toggle quick mode, if allowed by the config file.
+    pub fn toggle_quick_mode(&mut self) {
+        if !self.confirm_config.allow_quick_mode {
+            self.report(CommandResult {
+                success: false,
+                message: "Quick mode is disabled (set confirm.allow_quick_mode = true)".to_string(),
+            });
+            return;
+        }
+        self.quick_mode = !self.quick_mode;
+        let message = if self.quick_mode {
+            "Quick mode on: abandon and squash run without confirmation"
+        } else {
+            "Quick mode off"
+        };
+        self.report(CommandResult {
+            success: true,
+            message: message.to_string(),
+        });
     }
 
     /// Confirm and execute the pending action.
     pub fn confirm_action(&mut self) -> Result<(), XorcistError> {
         let action = match std::mem::take(&mut self.modal) {
             ModalState::Confirm(action) => action,
-            ModalState::None => return Ok(()),
+            other => {
+                self.modal = other;
+                return Ok(());
+            }
         };
+        self.confirm_focus = ConfirmButton::default();
 
         match action {
             PendingAction::Abandon { change_id, .. } => {
-                let result = self.runner.execute_abandon(&change_id);
-                self.handle_command_result(result);
-                self.refresh_log()?;
+                self.run_in_background("jj abandon", AfterCommand::Refresh, move |runner| {
+                    runner.execute_abandon(&change_id)
+                });
+            }
+            PendingAction::New {
+                change_id,
+                message,
+                placement,
+            } => {
+                self.run_in_background("jj new", AfterCommand::Refresh, move |runner| {
+                    match placement.flag() {
+                        Some(flag) => runner.execute_new_inserted(flag, &change_id, &message),
+                        None if message.is_empty() => runner.execute_new(&change_id),
+                        None => runner.execute_new_with_message(&change_id, &message),
+                    }
+                });
+            }
+            PendingAction::Edit { change_id, .. } => {
+                self.run_in_background("jj edit", AfterCommand::Refresh, move |runner| {
+                    runner.execute_edit(&change_id)
+                });
+            }
+            PendingAction::Rebase {
+                change_id,
+                destination,
+                ..
+            } => {
+                self.run_in_background("jj rebase", AfterCommand::Refresh, move |runner| {
+                    runner.execute_rebase(&change_id, &destination)
+                });
+            }
+            PendingAction::GitFetch { remote } => {
+                self.run_in_background("jj git fetch", AfterCommand::Refresh, move |runner| {
+                    runner.execute_git_fetch(remote.as_deref())
+                });
             }
             PendingAction::Squash { change_id, .. } => {
-                let result = self.runner.execute_squash(&change_id);
-                self.handle_command_result(result);
-                self.refresh_log()?;
+                self.run_in_background("jj squash", AfterCommand::Refresh, move |runner| {
+                    runner.execute_squash(&change_id)
+                });
+            }
+            PendingAction::SquashInto {
+                change_id,
+                into: (target, _),
+                ..
+            } => {
+                self.run_in_background("jj squash", AfterCommand::Refresh, move |runner| {
+                    runner.execute_squash_into(&[change_id], &target)
+                });
+            }
+            PendingAction::Duplicate {
+                change_id,
+                destination,
+                ..
+            } => {
+                self.run_in_background("jj duplicate", AfterCommand::Refresh, move |runner| {
+                    runner.execute_duplicate(&change_id, destination.as_deref())
+                });
+            }
+            PendingAction::PushChange { change_id, remote } => {
+                self.run_in_background("jj git push", AfterCommand::Refresh, move |runner| {
+                    runner.execute_git_push_change(&change_id, remote.as_deref())
+                });
             }
-            PendingAction::GitPush => {
-                let result = self.runner.execute_git_push();
-                self.handle_command_result(result);
-                self.refresh_log()?;
+            PendingAction::Undo { .. } => {
+                self.run_in_background("jj undo", AfterCommand::Refresh, |runner| {
+                    runner.execute_undo()
+                });
+            }
+            PendingAction::WorkspaceForget { name } => {
+                self.run_in_background(
+                    "jj workspace forget",
+                    AfterCommand::Refresh,
+                    move |runner| runner.execute_workspace_forget(&name),
+                );
             }
-            PendingAction::Undo => {
-                let result = self.runner.execute_undo();
-                self.handle_command_result(result);
-                self.refresh_log()?;
+            PendingAction::SparseRemove { pattern } => {
+                self.run_in_background("jj sparse set", AfterCommand::Refresh, move |runner| {
+                    runner.execute_sparse_remove(&pattern)
+                });
+            }
+            PendingAction::SparseReset => {
+                self.run_in_background("jj sparse reset", AfterCommand::Refresh, |runner| {
+                    runner.execute_sparse_reset()
+                });
+            }
+            PendingAction::OpRestore { op_id, .. } => {
+                self.run_in_background("jj op restore", AfterCommand::Refresh, move |runner| {
+                    runner.execute_op_restore(&op_id)
+                });
+            }
+            PendingAction::Split {
+                change_id,
+                description,
+                paths,
+            } => {
+                self.run_in_background("jj split", AfterCommand::ReloadViews, move |runner| {
+                    runner.execute_split(&change_id, &description, &paths)
+                });
+            }
+            PendingAction::SquashFiles { change_id, paths } => {
+                self.run_in_background("jj squash", AfterCommand::ReloadViews, move |runner| {
+                    runner.execute_squash_paths(&change_id, &paths)
+                });
+            }
+            PendingAction::RestoreFiles { from, into, paths } => {
+                self.run_in_background("jj restore", AfterCommand::ReloadViews, move |runner| {
+                    runner.execute_restore_paths(from.as_deref(), &into, &paths)
+                });
+            }
+            PendingAction::AbandonMarked { revisions } => {
+                let change_ids: Vec<String> = revisions.into_iter().map(|(id, _)| id).collect();
+                self.run_in_background(
+                    "jj abandon",
+                    AfterCommand::ClearMarksOnSuccess,
+                    move |runner| runner.execute_abandon_revisions(&change_ids),
+                );
+            }
+            PendingAction::AbandonEmpty { revisions } => {
+                let change_ids: Vec<String> = revisions.into_iter().map(|(id, _)| id).collect();
+                self.run_in_background("jj abandon", AfterCommand::Refresh, move |runner| {
+                    runner.execute_abandon_revisions(&change_ids)
+                });
+            }
+            PendingAction::SquashMarked {
+                revisions,
+                into: (target, _),
+            } => {
+                let change_ids: Vec<String> = revisions.into_iter().map(|(id, _)| id).collect();
+                self.run_in_background(
+                    "jj squash",
+                    AfterCommand::ClearMarksOnSuccess,
+                    move |runner| runner.execute_squash_into(&change_ids, &target),
+                );
+            }
+            PendingAction::RebaseMarked {
+                revisions,
+                destination,
+            } => {
+                let change_ids: Vec<String> = revisions.into_iter().map(|(id, _)| id).collect();
+                self.run_in_background(
+                    "jj rebase",
+                    AfterCommand::ClearMarksOnSuccess,
+                    move |runner| runner.execute_rebase_revisions(&change_ids, &destination),
+                );
+            }
+            PendingAction::RebaseSkipEmptied {
+                revisions,
+                destination,
+            } => {
+                let change_ids: Vec<String> = revisions.into_iter().map(|(id, _)| id).collect();
+                self.run_in_background("jj rebase", AfterCommand::ShowOutput, move |runner| {
+                    runner.execute_rebase_skip_emptied(&change_ids, &destination)
+                });
+            }
+            PendingAction::SimplifyParents { revisions } => {
+                let change_ids: Vec<String> = revisions.into_iter().map(|(id, _)| id).collect();
+                self.run_in_background(
+                    "jj simplify-parents",
+                    AfterCommand::ShowOutput,
+                    move |runner| runner.execute_simplify_parents(&change_ids),
+                );
+            }
+            PendingAction::RebaseStack { head, .. } => self.execute_rebase_stack(head),
+            PendingAction::ResolveDivergence {
+                keep: (target, _),
+                others,
+                squash,
+            } => {
+                let commit_ids: Vec<String> = others.into_iter().map(|(id, _)| id).collect();
+                let label = if squash { "jj squash" } else { "jj abandon" };
+                self.run_in_background(label, AfterCommand::Refresh, move |runner| {
+                    if squash {
+                        runner.execute_squash_into(&commit_ids, &target)
+                    } else {
+                        runner.execute_abandon_revisions(&commit_ids)
+                    }
+                });
+            }
+            PendingAction::Rerun { args } => {
+                let label = format!("jj {}", args.first().map_or("", String::as_str));
+                self.run_in_background(&label, AfterCommand::Refresh, move |runner| {
+                    runner.execute_args(&args)
+                });
             }
         }
 
         Ok(())
     }
 
-    /// Execute `jj git fetch`.
+    /// Execute `jj git fetch` in the background.
     pub fn execute_git_fetch(&mut self) -> Result<(), XorcistError> {
-        let result = self.runner.execute_git_fetch();
-        self.handle_command_result(result);
-        self.refresh_log()?;
-        Ok(())
+        self.request_confirm(PendingAction::GitFetch {
+            remote: self.push_config.remote.clone(),
+        })
     }
 
     /// Execute `jj new` on the selected revision.
     pub fn execute_new(&mut self) -> Result<(), XorcistError> {
-        let Some(change_id) = self.selected_change_id() else {
-            return Ok(());
-        };
-        let change_id = change_id.to_string();
-        let result = self.runner.execute_new(&change_id);
-        self.handle_command_result(result);
-        self.refresh_log()?;
-        Ok(())
+        self.execute_new_with_message("")
     }
 
-    /// Execute `jj new -m` with the given message.
+    /// Execute `jj new -m` with the given message (empty = no message).
+    ///
+    /// The change is placed as picked in the placement menu (`I`), if the
+    /// message prompt was opened from it.
     pub fn execute_new_with_message(&mut self, message: &str) -> Result<(), XorcistError> {
+        let placement = std::mem::take(&mut self.new_placement);
         let Some(change_id) = self.selected_change_id() else {
             return Ok(());
         };
         let change_id = change_id.to_string();
-        let result = if message.is_empty() {
-            self.runner.execute_new(&change_id)
-        } else {
-            self.runner.execute_new_with_message(&change_id, message)
-        };
-        self.handle_command_result(result);
-        self.refresh_log()?;
-        Ok(())
+        self.request_confirm(PendingAction::New {
+            change_id,
+            message: message.to_string(),
+            placement,
+        })
+    }
+
+    /// Execute `jj commit -m <message>` on the working copy, in the background.
+    ///
+    /// An empty message keeps the working copy's current description.
+    pub fn execute_commit(&mut self, message: &str) {
+        let message = message.trim().to_string();
+        self.run_in_background("jj commit", AfterCommand::Refresh, move |runner| {
+            let message = if message.is_empty() {
+                fetch_show(runner, "@")?.description
+            } else {
+                message
+            };
+            runner.execute_commit(&message)
+        });
     }
 
     /// Execute `jj edit` on the selected revision.
@@ -154,10 +505,11 @@ impl App {
             return Ok(());
         };
         let change_id = change_id.to_string();
-        let result = self.runner.execute_edit(&change_id);
-        self.handle_command_result(result);
-        self.refresh_log()?;
-        Ok(())
+        let description = self.selected_description().unwrap_or_default();
+        self.request_confirm(PendingAction::Edit {
+            change_id,
+            description,
+        })
     }
 
     /// Execute `jj describe -m` on the selected revision.
@@ -166,16 +518,18 @@ impl App {
             return Ok(());
         };
         let change_id = change_id.to_string();
-        let result = self.runner.execute_describe(&change_id, message);
-        self.handle_command_result(result);
-        self.refresh_log()?;
+        let message = message.to_string();
+        let after = AfterCommand::RefreshRevisions(vec![change_id.clone()]);
+        self.run_in_background("jj describe", after, move |runner| {
+            runner.execute_describe(&change_id, &message)
+        });
         Ok(())
     }
 
     /// Execute `jj bookmark set` on the selected revision.
     pub fn execute_bookmark_set(&mut self, name: &str) -> Result<(), XorcistError> {
         if name.is_empty() {
-            self.last_command_result = Some(CommandResult {
+            self.report(CommandResult {
                 success: false,
                 message: "Bookmark name cannot be empty".to_string(),
             });
@@ -185,30 +539,66 @@ impl App {
             return Ok(());
         };
         let change_id = change_id.to_string();
-        let result = self.runner.execute_bookmark_set(name, &change_id);
-        self.handle_command_result(result);
-        self.refresh_log()?;
+        let name = name.to_string();
+        // The commit the bookmark moves away from loses it
+        let mut change_ids = vec![change_id.clone()];
+        change_ids.extend(
+            self.graph_log
+                .lines
+                .iter()
+                .filter(|line| line.bookmarks.contains(&name))
+                .filter_map(|line| line.change_id.clone()),
+        );
+        let after = AfterCommand::RefreshRevisions(change_ids);
+        self.run_in_background("jj bookmark set", after, move |runner| {
+            runner.execute_bookmark_set(&name, &change_id)
+        });
         Ok(())
     }
 
     /// Execute `jj rebase -d` on the selected revision.
+    ///
+    /// If commits are marked, all of them are rebased instead, after a
+    /// confirmation listing every moved change.
     pub fn execute_rebase(&mut self, destination: &str) -> Result<(), XorcistError> {
         let destination = destination.trim();
         if destination.is_empty() {
-            self.last_command_result = Some(CommandResult {
+            self.report(CommandResult {
                 success: false,
                 message: "Destination cannot be empty".to_string(),
             });
             return Ok(());
         }
+        if self.has_marks() {
+            self.show_rebase_marked_confirm(destination);
+            return Ok(());
+        }
         let Some(change_id) = self.selected_change_id() else {
             return Ok(());
         };
         let change_id = change_id.to_string();
-        let result = self.runner.execute_rebase(&change_id, destination);
-        self.handle_command_result(result);
-        self.refresh_log()?;
-        Ok(())
+        let description = self.selected_description().unwrap_or_default();
+        self.request_confirm(PendingAction::Rebase {
+            change_id,
+            description,
+            destination: destination.to_string(),
+        })
+    }
+
+    /// Show confirmation dialog for duplicating the selected revision.
+    ///
+    /// An empty destination duplicates onto the original's parents.
+    pub fn show_duplicate_confirm(&mut self, destination: &str) {
+        let Some(change_id) = self.selected_change_id() else {
+            return;
+        };
+        let change_id = change_id.to_string();
+        let destination = destination.trim();
+        self.ask_confirm(PendingAction::Duplicate {
+            change_id,
+            description: self.selected_description().unwrap_or_default(),
+            destination: (!destination.is_empty()).then(|| destination.to_string()),
+        });
     }
 
     /// Open diff view for the current detail state.
@@ -217,35 +607,207 @@ impl App {
             return Ok(());
         };
         let change_id = detail.show_output.change_id.clone();
+        // The detail view already fetched the diff summary and stat; reuse them
+        let files = detail.show_output.diff_summary.clone();
+        let stat = detail.stat.clone();
 
-        // Fetch diff summary
-        let summary_output =
-            self.runner
-                .run_capture(&["diff", "-r", &change_id, "--color=never", "--summary"])?;
-        let files = parse_diff_summary(&summary_output);
-
+        let remembered = self.remembered_position(View::Diff, &change_id);
         self.diff_state = DiffState::new(change_id, files);
+        self.diff_state.stat = stat;
+
+        // Restore the file selected when this diff was last left
+        if let Some(position) = remembered {
+            self.diff_state.selected = position
+                .selected
+                .min(self.diff_state.files.len().saturating_sub(1));
+        }
 
         // Fetch initial diff text if files exist
         if !self.diff_state.files.is_empty() {
             self.refresh_diff_text()?;
         }
 
-        self.view = View::Diff;
+        // Scroll offsets are restored after the text is loaded
+        self.push_view(View::Diff);
+        Ok(())
+    }
+
+    /// Base revision of compare mode, if one is set.
+    pub fn compare_base(&self) -> Option<&str> {
+        self.compare_base.as_deref()
+    }
+
+    /// Clear the compare base.
+    pub fn clear_compare_base(&mut self) {
+        self.compare_base = None;
+    }
+
+    /// Compare mode step for the selected commit.
+    ///
+    /// The first call sets the selected commit as the base; the second
+    /// opens a diff from the base to the (different) selected commit.
+    /// Selecting the base again clears it. With exactly two commits marked
+    /// and no base set, the marked commits are diffed instead.
+    pub fn compare_selected(&mut self) -> Result<(), XorcistError> {
+        if self.compare_base.is_none() && self.mark_count() == 2 {
+            return self.diff_marked_pair();
+        }
+        let Some(selected) = self.selected_change_id().map(str::to_string) else {
+            return Ok(());
+        };
+        match self.compare_base.take() {
+            None => {
+                self.report(CommandResult {
+                    success: true,
+                    message: format!(
+                        "Compare base: {selected} (select another commit and press c)"
+                    ),
+                });
+                self.compare_base = Some(selected);
+            }
+            Some(base) if base == selected => {
+                self.report(CommandResult {
+                    success: true,
+                    message: "Compare base cleared".to_string(),
+                });
+            }
+            Some(base) => {
+                self.open_range_diff(DiffRange {
+                    from: base,
+                    to: selected,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start the `--from` / `--to` prompts for an arbitrary diff.
+    ///
+    /// `--from` is prefilled with the compare base or the selected commit.
+    pub fn start_diff_prompts(&mut self) {
+        let initial = self
+            .compare_base
+            .clone()
+            .or_else(|| self.selected_change_id().map(str::to_string))
+            .unwrap_or_default();
+        self.pending_diff_from = None;
+        self.start_input_mode_with(InputMode::DiffFrom, &initial);
+    }
+
+    /// Validate the `--from` revision and move on to the `--to` prompt.
+    pub(super) fn submit_diff_from(&mut self, from: &str) -> Result<(), XorcistError> {
+        if !self.validate_revision_input(InputMode::DiffFrom, from)? {
+            return Ok(());
+        }
+        self.pending_diff_from = Some(from.trim().to_string());
+        self.start_input_mode_with(InputMode::DiffTo, "@");
+        Ok(())
+    }
+
+    /// Validate the `--to` revision and open the comparison.
+    pub(super) fn submit_diff_to(&mut self, to: &str) -> Result<(), XorcistError> {
+        let Some(from) = self.pending_diff_from.clone() else {
+            return Ok(());
+        };
+        if !self.validate_revision_input(InputMode::DiffTo, to)? {
+            return Ok(());
+        }
+        self.pending_diff_from = None;
+        self.open_range_diff(DiffRange {
+            from,
+            to: to.trim().to_string(),
+        })
+    }
+
+    /// Check that a prompt's revset names exactly one revision.
+    ///
+    /// On failure the error is shown in the status bar and the prompt is
+    /// reopened with the same text so it can be corrected.
+    fn validate_revision_input(
+        &mut self,
+        mode: InputMode,
+        revset: &str,
+    ) -> Result<bool, XorcistError> {
+        match resolve_single_revision(&self.runner, revset) {
+            Ok(_) => Ok(true),
+            Err(e @ (XorcistError::JjError(_) | XorcistError::InvalidRevision(_))) => {
+                self.report(CommandResult {
+                    success: false,
+                    message: e.to_string(),
+                });
+                self.start_input_mode_with(mode, revset);
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Open the diff view for the changes between two revisions.
+    pub fn open_range_diff(&mut self, range: DiffRange) -> Result<(), XorcistError> {
+        let files = fetch_diff_summary_range(&self.runner, &range)?;
+        let stat = fetch_diff_stat_range(&self.runner, &range).unwrap_or_default();
+        let remembered = self.remembered_position(View::Diff, &range.key());
+        self.diff_state = DiffState::for_range(range, files);
+        self.diff_state.stat = stat;
+
+        if let Some(position) = remembered {
+            self.diff_state.selected = position
+                .selected
+                .min(self.diff_state.files.len().saturating_sub(1));
+        }
+        if !self.diff_state.files.is_empty() {
+            self.refresh_diff_text()?;
+        }
+
+        self.push_view(View::Diff);
         Ok(())
     }
 
     /// Refresh diff text for the currently selected file.
     pub fn refresh_diff_text(&mut self) -> Result<(), XorcistError> {
         let Some(file) = self.diff_state.selected_file() else {
-            self.diff_state.diff_lines = Vec::new();
+            self.diff_state.diff = GitDiff::default();
+            self.diff_state.highlights.clear();
+            self.diff_state.word_changes.clear();
             return Ok(());
         };
         let path = file.path.clone();
-        let output = fetch_diff_file(&self.runner, &self.diff_state.change_id, &path)?;
-        self.diff_state.diff_lines = output.lines().map(|s| s.to_string()).collect();
+        let output = match &self.diff_state.range {
+            Some(range) => fetch_diff_file_range(&self.runner, range, &path)?,
+            None => {
+                let change_id = self.diff_state.change_id.clone();
+                self.cached_file_diff(&change_id, &path)?
+            }
+        };
+        self.diff_state.diff = self.runner.time_parse("diff", || GitDiff::parse(&output));
+        self.diff_state.highlights = match syntax_for_path(&path) {
+            Some(syntax) if self.syntax_highlight => self.runner.time_parse("highlight", || {
+                highlight_diff(&self.diff_state.diff, syntax)
+            }),
+            _ => Vec::new(),
+        };
+        self.diff_state.word_changes = if self.diff_state.highlights.is_empty() {
+            Vec::new()
+        } else {
+            self.runner
+                .time_parse("word diff", || word_diff(&self.diff_state.diff))
+        };
         self.diff_state.diff_scroll = 0; // Reset vertical scroll on file change
         self.diff_state.diff_h_scroll = 0; // Reset horizontal scroll on file change
         Ok(())
     }
 }
+
+/// Revset of `change_ids`, their descendants and `@`.
+pub(super) fn revisions_revset(change_ids: &[String]) -> String {
+    let changes = change_ids
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" | ");
+    if changes.is_empty() {
+        "@".to_string()
+    } else {
+        format!("({changes}):: | @")
+    }
+}
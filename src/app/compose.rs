@@ -0,0 +1,181 @@
+//! Conventional commit builder for the describe prompt (`t`), for App.
+//!
+//! The type and the scope are picked from lists drawn like the fuzzy
+//! finder's; the subject is typed last, giving `type(scope): subject`.
+
+use crate::conventional::{COMMIT_TYPES, ConventionalCommit, collect_scopes, is_valid_type};
+use crate::error::XorcistError;
+use crate::text::fuzzy_score;
+
+use super::{App, CommandResult, FinderState, InputMode};
+
+/// A conventional message being put together, step by step.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConventionalDraft {
+    /// Picked type, without the breaking `!`.
+    pub commit_type: String,
+    /// Whether the type was typed with a trailing `!`.
+    pub breaking: bool,
+    /// Picked scope (`None` for no scope).
+    pub scope: Option<String>,
+    /// Entries of the current list: `(value, hint)`.
+    ///
+    /// The first entry is the typed query itself, so a type or scope that is
+    /// not listed can still be used; for scopes, an empty one means no scope.
+    pub options: Vec<(String, String)>,
+}
+
+impl ConventionalDraft {
+    /// Prefix of the finished message, shown while typing the subject.
+    pub fn prefix(&self) -> String {
+        ConventionalCommit {
+            commit_type: &self.commit_type,
+            scope: self.scope.as_deref(),
+            breaking: self.breaking,
+            description: "",
+        }
+        .to_message()
+    }
+}
+
+impl App {
+    /// Start building a conventional message for the selected revision.
+    pub fn start_conventional_describe(&mut self) {
+        if self.selected_change_id().is_none() {
+            return;
+        }
+        let mut options = vec![(String::new(), String::new())];
+        options.extend(
+            COMMIT_TYPES
+                .iter()
+                .map(|(commit_type, summary)| (commit_type.to_string(), summary.to_string())),
+        );
+        self.conventional_draft = Some(ConventionalDraft {
+            options,
+            ..ConventionalDraft::default()
+        });
+        self.start_input_mode(InputMode::ConventionalType);
+        self.finder = Some(FinderState::default());
+        self.update_finder();
+    }
+
+    /// Get the conventional message being built, if any.
+    pub fn conventional_draft(&self) -> Option<&ConventionalDraft> {
+        self.conventional_draft.as_ref()
+    }
+
+    /// Indices of the draft's list entries matching `query`, best first.
+    ///
+    /// The typed query is offered after the matches, unless it is listed
+    /// already; an empty scope ("no scope") is offered first.
+    pub(super) fn conventional_matches(&mut self, query: &str) -> Vec<usize> {
+        let scope_step = self.input_mode == Some(InputMode::ConventionalScope);
+        let Some(draft) = &mut self.conventional_draft else {
+            return Vec::new();
+        };
+        draft.options[0].0 = query.to_string();
+        let mut scored: Vec<(i64, usize)> = draft
+            .options
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(i, (value, hint))| {
+                fuzzy_score(&format!("{value} {hint}"), query).map(|score| (score, i))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        let mut results: Vec<usize> = scored.into_iter().map(|(_, i)| i).collect();
+        if query.is_empty() {
+            if scope_step {
+                results.insert(0, 0);
+            }
+        } else if !draft.options[1..].iter().any(|(value, _)| value == query) {
+            results.push(0);
+        }
+        results
+    }
+
+    /// Take the highlighted type and go on to the scope list.
+    pub(super) fn submit_conventional_type(&mut self) {
+        let Some(value) = self.take_conventional_pick() else {
+            return;
+        };
+        let (commit_type, breaking) = match value.strip_suffix('!') {
+            Some(commit_type) => (commit_type.to_string(), true),
+            None => (value, false),
+        };
+        if !is_valid_type(&commit_type) {
+            self.cancel_conventional_describe(format!(
+                "Invalid commit type '{commit_type}': use lowercase letters"
+            ));
+            return;
+        }
+        let scopes = collect_scopes(
+            self.graph_log
+                .lines
+                .iter()
+                .filter_map(|line| line.description.as_deref()),
+        );
+        if let Some(draft) = &mut self.conventional_draft {
+            draft.commit_type = commit_type;
+            draft.breaking = breaking;
+            draft.options = vec![(String::new(), String::new())];
+            draft
+                .options
+                .extend(scopes.into_iter().map(|scope| (scope, String::new())));
+        }
+        self.input_mode = Some(InputMode::ConventionalScope);
+        self.finder = Some(FinderState::default());
+        self.update_finder();
+    }
+
+    /// Take the highlighted scope (or none) and go on to the subject.
+    pub(super) fn submit_conventional_scope(&mut self) {
+        let Some(scope) = self.take_conventional_pick() else {
+            return;
+        };
+        if scope.contains(['(', ')']) {
+            self.cancel_conventional_describe(format!("Invalid scope '{scope}'"));
+            return;
+        }
+        if let Some(draft) = &mut self.conventional_draft {
+            draft.scope = Some(scope).filter(|scope| !scope.is_empty());
+            draft.options.clear();
+        }
+        self.input_mode = Some(InputMode::ConventionalSubject);
+    }
+
+    /// Describe the selected revision with the finished message.
+    pub(super) fn submit_conventional_subject(
+        &mut self,
+        subject: &str,
+    ) -> Result<(), XorcistError> {
+        let Some(draft) = self.conventional_draft.take() else {
+            return Ok(());
+        };
+        let subject = subject.trim();
+        if subject.is_empty() {
+            self.cancel_conventional_describe("Subject cannot be empty".to_string());
+            return Ok(());
+        }
+        let message = format!("{}{subject}", draft.prefix());
+        self.execute_describe(&message)
+    }
+
+    /// Value of the highlighted list entry (the query when nothing matches).
+    fn take_conventional_pick(&mut self) -> Option<String> {
+        let finder = self.finder.take()?;
+        let draft = self.conventional_draft.as_ref()?;
+        let index = finder.results.get(finder.selected).copied().unwrap_or(0);
+        draft.options.get(index).map(|(value, _)| value.clone())
+    }
+
+    /// Stop building the message and report why.
+    fn cancel_conventional_describe(&mut self, message: String) {
+        self.cancel_input_mode();
+        self.report(CommandResult {
+            success: false,
+            message,
+        });
+    }
+}
@@ -0,0 +1,141 @@
+//! Describing revisions at the prompt or in an external editor, for App.
+
+use std::io;
+
+use crate::error::XorcistError;
+use crate::jj::fetch_description;
+
+use super::tasks::AfterCommand;
+use super::{App, CommandResult, InputMode};
+
+/// A description waiting to be edited in the external editor.
+///
+/// The event loop suspends the TUI, runs the editor and hands the result
+/// back to [`App::finish_external_describe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorRequest {
+    /// Revision to describe.
+    pub change_id: String,
+    /// Text opened in the editor.
+    pub text: String,
+    /// Whether `text` is the revision's current description (so saving it
+    /// unchanged is a no-op) rather than text typed at the prompt.
+    pub is_current: bool,
+}
+
+impl App {
+    /// Open the describe prompt with the selected revision's current message.
+    ///
+    /// The prompt is a single line, so only the subject is shown for
+    /// descriptions with a body; the body is kept when the prompt is submitted.
+    pub fn start_describe(&mut self) -> Result<(), XorcistError> {
+        let Some(change_id) = self.selected_change_id() else {
+            return Ok(());
+        };
+        let description = fetch_description(&self.runner, change_id)?;
+        self.open_describe_prompt(description);
+        Ok(())
+    }
+
+    /// Open the describe prompt prefilled with the subject of `description`.
+    pub(super) fn open_describe_prompt(&mut self, description: String) {
+        let subject = description.lines().next().unwrap_or_default().to_string();
+        self.start_input_mode_with(InputMode::Describe, &subject);
+        self.describe_original = Some(description);
+    }
+
+    /// Whether the describe prompt hides a body that is kept on submit.
+    pub fn describe_keeps_body(&self) -> bool {
+        self.describe_original
+            .as_deref()
+            .is_some_and(|description| description.contains('\n'))
+    }
+
+    /// Full message for the describe prompt's text: the typed subject
+    /// followed by the body of the current description, if any.
+    fn describe_message(&self, typed: &str) -> String {
+        match self
+            .describe_original
+            .as_deref()
+            .and_then(|description| description.split_once('\n'))
+        {
+            Some((_, body)) => format!("{typed}\n{body}"),
+            None => typed.to_string(),
+        }
+    }
+
+    /// Run `jj describe` with the prompt's message, unless nothing changed.
+    pub(super) fn submit_describe(&mut self, typed: &str) -> Result<(), XorcistError> {
+        let message = self.describe_message(typed);
+        let original = self.describe_original.take();
+        if original.is_some_and(|original| !original.is_empty() && original == message) {
+            self.report(CommandResult {
+                success: true,
+                message: "Description unchanged".to_string(),
+            });
+            return Ok(());
+        }
+        self.execute_describe(&message)
+    }
+
+    /// Edit the selected revision's description in the external editor.
+    ///
+    /// From the describe prompt, the typed text (with the kept body) is
+    /// opened instead.
+    pub fn start_external_describe(&mut self) -> Result<(), XorcistError> {
+        let typed = match self.input_mode {
+            Some(InputMode::Describe) => Some(self.describe_message(self.input.value())),
+            _ => None,
+        };
+        let original = self.describe_original.clone();
+        self.cancel_input_mode();
+        let Some(change_id) = self.selected_change_id() else {
+            return Ok(());
+        };
+        let change_id = change_id.to_string();
+        let (text, is_current) = match typed.filter(|text| !text.is_empty()) {
+            Some(text) => {
+                let is_current = original.as_ref() == Some(&text);
+                (text, is_current)
+            }
+            None => (fetch_description(&self.runner, &change_id)?, true),
+        };
+        self.editor_request = Some(EditorRequest {
+            change_id,
+            text,
+            is_current,
+        });
+        Ok(())
+    }
+
+    /// Take the pending editor request (the event loop runs the editor).
+    pub fn take_editor_request(&mut self) -> Option<EditorRequest> {
+        self.editor_request.take()
+    }
+
+    /// Run `jj describe` with the text saved in the editor.
+    pub fn finish_external_describe(&mut self, request: EditorRequest, edited: io::Result<String>) {
+        let message = match edited {
+            Ok(message) => message,
+            Err(e) => {
+                self.report(CommandResult {
+                    success: false,
+                    message: format!("Editor failed: {e}"),
+                });
+                return;
+            }
+        };
+        if request.is_current && message == request.text.trim_end() {
+            self.report(CommandResult {
+                success: true,
+                message: "Description unchanged".to_string(),
+            });
+            return;
+        }
+        let change_id = request.change_id;
+        let after = AfterCommand::RefreshRevisions(vec![change_id.clone()]);
+        self.run_in_background("jj describe", after, move |runner| {
+            runner.execute_describe(&change_id, &message)
+        });
+    }
+}
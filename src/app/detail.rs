@@ -0,0 +1,149 @@
+//! Full hunk-level diff in the detail view for App.
+
+use crate::error::XorcistError;
+use crate::jj::GitDiff;
+
+use super::{App, DetailDiff};
+
+impl App {
+    /// Show or hide the full diff below the file summary.
+    pub fn toggle_detail_diff(&mut self) -> Result<(), XorcistError> {
+        let Some(state) = &self.detail_state else {
+            return Ok(());
+        };
+        if state.full_diff.is_some() {
+            if let Some(state) = &mut self.detail_state {
+                state.full_diff = None;
+            }
+            return Ok(());
+        }
+        let change_id = state.show_output.change_id.clone();
+        let diff = self.fetch_detail_diff(&change_id)?;
+        if let Some(state) = &mut self.detail_state {
+            state.full_diff = Some(DetailDiff {
+                diff,
+                ..Default::default()
+            });
+        }
+        Ok(())
+    }
+
+    /// Re-fetch the full diff if it is shown, keeping collapsed files collapsed.
+    pub(super) fn reload_detail_diff(&mut self) -> Result<(), XorcistError> {
+        let Some(state) = &self.detail_state else {
+            return Ok(());
+        };
+        if state.full_diff.is_none() {
+            return Ok(());
+        }
+        let change_id = state.show_output.change_id.clone();
+        let diff = self.fetch_detail_diff(&change_id)?;
+        if let Some(full_diff) = self
+            .detail_state
+            .as_mut()
+            .and_then(|s| s.full_diff.as_mut())
+        {
+            let file_count = diff.files.len();
+            full_diff.collapsed.retain(|&idx| idx < file_count);
+            full_diff.diff = diff;
+        }
+        Ok(())
+    }
+
+    pub(super) fn fetch_detail_diff(&mut self, change_id: &str) -> Result<GitDiff, XorcistError> {
+        let output = self.cached_diff(change_id)?;
+        Ok(self.runner.time_parse("diff", || GitDiff::parse(&output)))
+    }
+
+    /// Record where each file header of the full diff was drawn (called from render).
+    pub fn set_detail_file_offsets(&mut self, offsets: Vec<usize>) {
+        if let Some(full_diff) = self
+            .detail_state
+            .as_mut()
+            .and_then(|s| s.full_diff.as_mut())
+        {
+            full_diff.file_offsets = offsets;
+        }
+    }
+
+    /// Index of the file at the top of the detail view (0 above the first file).
+    fn current_detail_file(&self) -> Option<usize> {
+        let state = self.detail_state.as_ref()?;
+        let offsets = &state.full_diff.as_ref()?.file_offsets;
+        if offsets.is_empty() {
+            return None;
+        }
+        Some(
+            offsets
+                .iter()
+                .rposition(|&offset| offset <= state.scroll)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Scroll the detail view to the header of the next file in the full diff.
+    pub fn detail_next_file(&mut self) {
+        let Some(state) = &mut self.detail_state else {
+            return;
+        };
+        let Some(full_diff) = &state.full_diff else {
+            return;
+        };
+        if let Some(&offset) = full_diff.file_offsets.iter().find(|&&o| o > state.scroll) {
+            state.scroll = offset;
+        }
+    }
+
+    /// Scroll the detail view to the header of the previous file in the full diff.
+    pub fn detail_previous_file(&mut self) {
+        let Some(state) = &mut self.detail_state else {
+            return;
+        };
+        let Some(full_diff) = &state.full_diff else {
+            return;
+        };
+        if let Some(&offset) = full_diff
+            .file_offsets
+            .iter()
+            .rev()
+            .find(|&&o| o < state.scroll)
+        {
+            state.scroll = offset;
+        }
+    }
+
+    /// Collapse or expand the hunks of the file at the top of the view.
+    pub fn toggle_detail_file_collapse(&mut self) {
+        let Some(idx) = self.current_detail_file() else {
+            return;
+        };
+        let Some(state) = &mut self.detail_state else {
+            return;
+        };
+        let Some(full_diff) = &mut state.full_diff else {
+            return;
+        };
+        if !full_diff.collapsed.remove(&idx) {
+            full_diff.collapsed.insert(idx);
+        }
+        // Keep the toggled file's header in view
+        state.scroll = full_diff.file_offsets[idx];
+    }
+
+    /// Collapse every file of the full diff, or expand all if all are collapsed.
+    pub fn toggle_detail_collapse_all(&mut self) {
+        let Some(full_diff) = self
+            .detail_state
+            .as_mut()
+            .and_then(|s| s.full_diff.as_mut())
+        else {
+            return;
+        };
+        let file_count = full_diff.diff.files.len();
+        if full_diff.collapsed.len() == file_count {
+            full_diff.collapsed.clear();
+        } else {
+            full_diff.collapsed = (0..file_count).collect();
+        }
+    }
+}
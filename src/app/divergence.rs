@@ -0,0 +1,61 @@
+//! Resolving divergent changes, for App.
+//!
+//! A divergent change has several visible commits (e.g., after rewriting it
+//! in two workspaces). The log tells them apart by commit ID; resolving
+//! keeps the selected one and abandons the others, or squashes the others
+//! into it to keep their changes.
+
+use crate::error::XorcistError;
+use crate::jj::fetch_divergent_commits;
+
+use super::{App, CommandResult, ModalState, PendingAction};
+
+impl App {
+    /// Ask to resolve the divergence of the selected commit, keeping it.
+    pub fn show_resolve_divergence_confirm(&mut self) -> Result<(), XorcistError> {
+        let Some(line) = self
+            .selected_line_index()
+            .map(|idx| &self.graph_log.lines[idx])
+        else {
+            return Ok(());
+        };
+        let Some(keep) = line.change_id.clone() else {
+            return Ok(());
+        };
+        if !line.flags.divergent {
+            self.report(CommandResult {
+                success: false,
+                message: format!("{keep} is not divergent"),
+            });
+            return Ok(());
+        }
+        let description = line.description.clone().unwrap_or_default();
+        let others: Vec<(String, String)> = fetch_divergent_commits(&self.runner, &keep)?
+            .into_iter()
+            .filter(|commit| commit.commit_id != keep)
+            .map(|commit| (commit.commit_id, commit.description))
+            .collect();
+        if others.is_empty() {
+            self.report(CommandResult {
+                success: false,
+                message: format!("{keep} has no other visible commit"),
+            });
+            return Ok(());
+        }
+        self.request_confirm(PendingAction::ResolveDivergence {
+            keep: (keep, description),
+            others,
+            squash: false,
+        })
+    }
+
+    /// Switch the open divergence confirmation between abandoning and
+    /// squashing the other commits.
+    pub fn toggle_divergence_resolution(&mut self) {
+        if let ModalState::Confirm(PendingAction::ResolveDivergence { squash, .. }) =
+            &mut self.modal
+        {
+            *squash = !*squash;
+        }
+    }
+}
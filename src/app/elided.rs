@@ -0,0 +1,108 @@
+//! Expanding the elided revisions of a filtered log (`E`), for App.
+//!
+//! jj draws `~  (elided revisions)` below a commit whose parents are left
+//! out of the revset. Expanding it adds the commits between the commit and
+//! its nearest ancestors in the log to the revset, and Esc collapses them.
+
+use crate::error::XorcistError;
+use crate::jj::fetch_default_log_revset;
+
+use super::{App, CommandResult};
+
+impl App {
+    /// Show the revisions elided below the selected commit.
+    pub fn expand_elided(&mut self) -> Result<(), XorcistError> {
+        let Some(line_idx) = self.selected_line_index() else {
+            return Ok(());
+        };
+        if !self.graph_log.has_elided_below(line_idx) {
+            let change_id = self.selected_change_id().unwrap_or_default().to_string();
+            self.report(CommandResult {
+                success: false,
+                message: format!("No elided revisions below {change_id}"),
+            });
+            return Ok(());
+        }
+        self.expand_elided_below(line_idx)
+    }
+
+    /// Show the revisions an elided line of the log stands for (clicked).
+    pub fn expand_elided_line(&mut self, line_idx: usize) -> Result<(), XorcistError> {
+        match self.graph_log.elided_owner(line_idx) {
+            Some(owner) => self.expand_elided_below(owner),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether elided revisions are expanded in the log.
+    pub fn has_elided_expansions(&self) -> bool {
+        !self.elided_expansions.is_empty()
+    }
+
+    /// Number of expanded elided ranges.
+    pub fn elided_expansion_count(&self) -> usize {
+        self.elided_expansions.len()
+    }
+
+    /// Hide the expanded revisions again and reload the log.
+    pub fn collapse_elided(&mut self) -> Result<(), XorcistError> {
+        self.elided_expansions.clear();
+        self.refresh_log_keep_selection()
+    }
+
+    /// Revset the log shows before expansions: the filtered revset, or
+    /// `revsets.log` (fetched once).
+    pub(super) fn base_log_revset(&mut self) -> Result<String, XorcistError> {
+        if let Some(revset) = self.filtered_revset() {
+            return Ok(revset);
+        }
+        if let Some(revset) = &self.default_log_revset {
+            return Ok(revset.clone());
+        }
+        let revset = fetch_default_log_revset(&self.runner)?;
+        self.default_log_revset = Some(revset.clone());
+        Ok(revset)
+    }
+
+    /// Add the ancestors elided below the commit on line `line_idx` and reload.
+    ///
+    /// The commit stays selected; if jj rejects the revset, or nothing new
+    /// shows up, the expansion is dropped again.
+    fn expand_elided_below(&mut self, line_idx: usize) -> Result<(), XorcistError> {
+        let Some(change_id) = self.graph_log.lines[line_idx].change_id.clone() else {
+            return Ok(());
+        };
+        let base = self.base_log_revset()?;
+        self.select_change_id(&change_id);
+        let commits_before = self.commit_count();
+        self.elided_expansions
+            .push(format!("heads(({base}) & ::{change_id}-)::{change_id}-"));
+
+        let result = match self.refresh_log_keep_selection() {
+            Ok(()) if self.commit_count() > commits_before => CommandResult {
+                success: true,
+                message: format!("Expanded the revisions elided below {change_id} (Esc: collapse)"),
+            },
+            Ok(()) => {
+                self.elided_expansions.pop();
+                CommandResult {
+                    success: false,
+                    message: format!("No elided revisions below {change_id}"),
+                }
+            }
+            Err(XorcistError::JjError(message)) => {
+                self.elided_expansions.pop();
+                CommandResult {
+                    success: false,
+                    message,
+                }
+            }
+            Err(e) => {
+                self.elided_expansions.pop();
+                return Err(e);
+            }
+        };
+        self.report(result);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Running external programs (a shell, a diff tool) with the TUI suspended,
+//! for App.
+
+use std::io;
+use std::process::ExitStatus;
+
+use crate::error::XorcistError;
+use crate::external::ExternalCommand;
+
+use super::{App, CommandResult};
+
+impl App {
+    /// Drop into a shell in the repository root (`$`); the log is
+    /// refreshed when it exits.
+    pub fn start_shell(&mut self) {
+        self.external_command = Some(ExternalCommand::shell(self.runner.work_dir()));
+    }
+
+    /// Open the selected file of the diff view in the configured diff tool
+    /// (`jj diff --tool <diff.tool>`).
+    pub fn open_diff_tool(&mut self) {
+        let Some(tool) = self.diff_tool.clone() else {
+            self.report(CommandResult {
+                success: false,
+                message:
+                    "No diff tool configured: set diff.tool in the config file (e.g. \"difft\")"
+                        .to_string(),
+            });
+            return;
+        };
+        let state = &self.diff_state;
+        let Some(file) = state.selected_file() else {
+            return;
+        };
+        let mut args = vec!["diff".to_string(), "--tool".to_string(), tool];
+        match &state.range {
+            Some(range) => args.extend([
+                "--from".to_string(),
+                range.from.clone(),
+                "--to".to_string(),
+                range.to.clone(),
+            ]),
+            None => args.extend(["-r".to_string(), state.change_id.clone()]),
+        }
+        args.extend(["--".to_string(), file.path.clone()]);
+        self.external_command = Some(ExternalCommand::jj(self.runner.work_dir(), args));
+    }
+
+    /// Take the program to run with the terminal released, if any.
+    pub fn take_external_command(&mut self) -> Option<ExternalCommand> {
+        self.external_command.take()
+    }
+
+    /// Report how the program exited and refresh the log, which it may
+    /// have changed.
+    pub fn finish_external_command(
+        &mut self,
+        command: &ExternalCommand,
+        status: io::Result<ExitStatus>,
+    ) -> Result<(), XorcistError> {
+        let result = match status {
+            Ok(status) if status.success() => CommandResult {
+                success: true,
+                message: format!("Back from `{}`", command.display()),
+            },
+            Ok(status) => CommandResult {
+                success: false,
+                message: format!("`{}` exited with {status}", command.display()),
+            },
+            Err(e) => CommandResult {
+                success: false,
+                message: format!("Failed to run `{}`: {e}", command.display()),
+            },
+        };
+        self.refresh_log_keep_selection()?;
+        self.refresh_metadata();
+        self.report(result);
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! Fuzzy finder over the loaded commits (`Ctrl+P`), for App.
+//!
+//! The command palette and the conventional commit builder reuse the
+//! finder's list, ranking actions, types or scopes instead.
+
+use crate::jj::GraphLine;
+use crate::text::fuzzy_score;
+
+use super::palette::PALETTE_ENTRIES;
+use super::{App, InputMode};
+
+/// State of the fuzzy finder (or the command palette) while its prompt is open.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FinderState {
+    /// Indices (in commit order) of the matching commits, or of the matching
+    /// palette actions or conventional draft options, best match first.
+    pub results: Vec<usize>,
+    /// Highlighted result.
+    pub selected: usize,
+    /// Scroll offset of the result list.
+    pub scroll: usize,
+}
+
+/// Text a commit is matched against: change ID, bookmarks, tags, description and author.
+fn finder_text(line: &GraphLine) -> String {
+    [
+        line.change_id.as_deref().unwrap_or_default(),
+        &line.bookmarks.join(" "),
+        &line.tags.join(" "),
+        line.description.as_deref().unwrap_or_default(),
+        line.author.as_deref().unwrap_or_default(),
+    ]
+    .join(" ")
+}
+
+impl App {
+    /// Open the fuzzy finder, listing every loaded commit.
+    pub fn open_finder(&mut self) {
+        self.start_input_mode(InputMode::Finder);
+        self.finder = Some(FinderState::default());
+        self.update_finder();
+    }
+
+    /// Get the open fuzzy finder, if any.
+    pub fn finder(&self) -> Option<&FinderState> {
+        self.finder.as_ref()
+    }
+
+    /// Re-rank the commits (or palette actions) against the query as the
+    /// user types.
+    ///
+    /// Ties keep log order, so an empty query lists the log as is.
+    pub fn update_finder(&mut self) {
+        if matches!(
+            self.input_mode,
+            Some(
+                InputMode::ConventionalType
+                    | InputMode::ConventionalScope
+                    | InputMode::AuthorFilter
+            )
+        ) {
+            let query = self.input.value().to_string();
+            let results = if self.input_mode == Some(InputMode::AuthorFilter) {
+                self.author_matches(&query)
+            } else {
+                self.conventional_matches(&query)
+            };
+            if let Some(finder) = &mut self.finder {
+                *finder = FinderState {
+                    results,
+                    ..FinderState::default()
+                };
+            }
+            return;
+        }
+        let Some(finder) = &mut self.finder else {
+            return;
+        };
+        let query = self.input.value();
+        let mut scored: Vec<(i64, usize)> = if self.input_mode == Some(InputMode::Palette) {
+            PALETTE_ENTRIES
+                .iter()
+                .enumerate()
+                .filter_map(|(i, entry)| fuzzy_score(entry.name, query).map(|score| (score, i)))
+                .collect()
+        } else {
+            self.graph_log
+                .commit_line_indices
+                .iter()
+                .enumerate()
+                .filter_map(|(commit, &line_idx)| {
+                    let text = finder_text(&self.graph_log.lines[line_idx]);
+                    fuzzy_score(&text, query).map(|score| (score, commit))
+                })
+                .collect()
+        };
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        finder.results = scored.into_iter().map(|(_, index)| index).collect();
+        finder.selected = 0;
+        finder.scroll = 0;
+    }
+
+    /// Highlight the next (worse) result.
+    pub fn finder_select_next(&mut self) {
+        if let Some(finder) = &mut self.finder
+            && finder.selected + 1 < finder.results.len()
+        {
+            finder.selected += 1;
+        }
+    }
+
+    /// Highlight the previous (better) result.
+    pub fn finder_select_previous(&mut self) {
+        if let Some(finder) = &mut self.finder {
+            finder.selected = finder.selected.saturating_sub(1);
+        }
+    }
+
+    /// Adjust the scroll offset so the highlighted result is visible.
+    pub fn ensure_finder_visible(&mut self, visible_height: usize) {
+        let Some(finder) = &mut self.finder else {
+            return;
+        };
+        if visible_height == 0 {
+            return;
+        }
+        if finder.selected < finder.scroll {
+            finder.scroll = finder.selected;
+        } else if finder.selected >= finder.scroll + visible_height {
+            finder.scroll = finder.selected + 1 - visible_height;
+        }
+    }
+
+    /// Jump the log cursor to the highlighted result and close the finder.
+    pub(super) fn submit_finder(&mut self) {
+        let Some(finder) = self.finder.take() else {
+            return;
+        };
+        if let Some(&commit) = finder.results.get(finder.selected) {
+            self.selected = commit;
+        }
+    }
+}
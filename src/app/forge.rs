@@ -0,0 +1,47 @@
+//! Opening the selected commit on its forge in the browser, for App.
+
+use crate::error::XorcistError;
+use crate::forge::{commit_url, open_in_browser};
+use crate::jj::fetch_git_remotes;
+
+use super::yank::YankTarget;
+use super::{App, CommandResult};
+
+impl App {
+    /// Open the commit in the detail view, or the one selected in the log,
+    /// on the forge of the `origin` remote (or the only/first remote).
+    pub fn open_on_forge(&mut self) -> Result<(), XorcistError> {
+        let remotes = fetch_git_remotes(&self.runner)?;
+        let Some(remote) = remotes
+            .iter()
+            .find(|remote| remote.name == "origin")
+            .or_else(|| remotes.first())
+        else {
+            self.set_forge_result(false, "No git remote to open the commit on".to_string());
+            return Ok(());
+        };
+        // The full commit ID: forges do not resolve jj's shortest prefixes
+        let Some(commit_id) = self.yank_text(YankTarget::CommitId)? else {
+            return Ok(());
+        };
+        let Some(url) = commit_url(&remote.url, &commit_id) else {
+            self.set_forge_result(
+                false,
+                format!(
+                    "Remote {} is not on a web forge: {}",
+                    remote.name, remote.url
+                ),
+            );
+            return Ok(());
+        };
+        match open_in_browser(&url) {
+            Ok(()) => self.set_forge_result(true, format!("Opened {url}")),
+            Err(e) => self.set_forge_result(false, format!("Failed to open {url}: {e}")),
+        }
+        Ok(())
+    }
+
+    fn set_forge_result(&mut self, success: bool, message: String) {
+        self.report(CommandResult { success, message });
+    }
+}
@@ -0,0 +1,105 @@
+//! Showing recently hidden (abandoned or rewritten) commits in the log (`h`),
+//! for App.
+//!
+//! jj has no revset for hidden commits, so the log adds the mutable commits
+//! of the last few operations that are no longer visible
+//! (`at_operation(<op>, mutable()) ~ all()`). They are drawn dimmed and
+//! addressed by commit ID; `n` on one makes it visible again.
+
+use crate::error::XorcistError;
+use crate::jj::fetch_op_log;
+
+use super::{App, CommandResult};
+
+/// Number of earlier operations whose commits are looked at.
+const HIDDEN_OPERATION_COUNT: usize = 10;
+
+/// Revset of the commits visible at one of `op_ids` but hidden now.
+fn hidden_commits_revset(op_ids: &[String]) -> String {
+    let at_operations: Vec<String> = op_ids
+        .iter()
+        .map(|op_id| format!("at_operation({op_id}, mutable())"))
+        .collect();
+    format!("({}) ~ all()", at_operations.join(" | "))
+}
+
+impl App {
+    /// Show or hide the commits hidden by the last few operations.
+    pub fn toggle_hidden_commits(&mut self) -> Result<(), XorcistError> {
+        if self.hidden_revset.take().is_some() {
+            self.refresh_log_keep_selection()?;
+            self.report(CommandResult {
+                success: true,
+                message: "Hidden commits are no longer shown".to_string(),
+            });
+            return Ok(());
+        }
+        // The newest operation is the current state
+        let op_ids: Vec<String> = fetch_op_log(&self.runner, HIDDEN_OPERATION_COUNT + 1)?
+            .into_iter()
+            .skip(1)
+            .map(|entry| entry.id)
+            .collect();
+        if op_ids.is_empty() {
+            self.report(CommandResult {
+                success: false,
+                message: "No earlier operations to find hidden commits in".to_string(),
+            });
+            return Ok(());
+        }
+        self.base_log_revset()?;
+        self.hidden_revset = Some(hidden_commits_revset(&op_ids));
+        let result = match self.refresh_log_keep_selection() {
+            Ok(()) => {
+                let count = self.hidden_commit_count();
+                CommandResult {
+                    success: true,
+                    message: format!(
+                        "{count} hidden commits from the last {} operations (n on one: new change to recover it)",
+                        op_ids.len()
+                    ),
+                }
+            }
+            Err(XorcistError::JjError(message)) => {
+                self.hidden_revset = None;
+                CommandResult {
+                    success: false,
+                    message,
+                }
+            }
+            Err(e) => {
+                self.hidden_revset = None;
+                return Err(e);
+            }
+        };
+        self.report(result);
+        Ok(())
+    }
+
+    /// Whether hidden commits are shown in the log.
+    pub fn shows_hidden_commits(&self) -> bool {
+        self.hidden_revset.is_some()
+    }
+
+    /// Number of hidden commits in the loaded log.
+    pub fn hidden_commit_count(&self) -> usize {
+        self.graph_log
+            .lines
+            .iter()
+            .filter(|line| line.flags.hidden)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hidden_commits_revset() {
+        assert_eq!(
+            hidden_commits_revset(&["a1b2c3".to_string(), "d4e5f6".to_string()]),
+            "(at_operation(a1b2c3, mutable()) | at_operation(d4e5f6, mutable())) ~ all()"
+        );
+    }
+}
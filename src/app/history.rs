@@ -0,0 +1,149 @@
+//! File history view (`jj log` limited to one file) for App.
+
+use crate::error::XorcistError;
+use crate::jj::{GraphLog, fetch_file_history};
+
+use super::{App, View};
+
+/// State of the file history view.
+#[derive(Debug, Clone, Default)]
+pub struct FileHistoryState {
+    /// File whose history is shown.
+    pub path: String,
+    /// Commits that touched the file.
+    pub graph_log: GraphLog,
+    /// Selected commit index (in commit_line_indices).
+    pub selected: usize,
+    /// Scroll offset (in graph lines).
+    pub scroll: usize,
+}
+
+impl FileHistoryState {
+    /// Change ID of the selected commit.
+    pub fn selected_change_id(&self) -> Option<&str> {
+        self.graph_log.change_id_for_selection(self.selected)
+    }
+}
+
+impl App {
+    /// Open the history of the file selected in the diff view.
+    pub fn open_file_history(&mut self) -> Result<(), XorcistError> {
+        let Some(path) = self.diff_state.selected_file().map(|f| f.path.clone()) else {
+            return Ok(());
+        };
+        let graph_log = fetch_file_history(&self.runner, &path, self.log_limit)?;
+        // Start at the revision the diff view was showing, if it touched the file
+        let change_id = self.diff_state.change_id.clone();
+        let selected = (0..graph_log.commit_count())
+            .find(|&i| {
+                graph_log
+                    .change_id_for_selection(i)
+                    .is_some_and(|id| change_id.starts_with(id))
+            })
+            .unwrap_or(0);
+        self.file_history = Some(FileHistoryState {
+            path,
+            graph_log,
+            selected,
+            scroll: 0,
+        });
+        self.push_view(View::FileHistory);
+        Ok(())
+    }
+
+    /// Close the file history view.
+    pub fn close_file_history(&mut self) {
+        self.pop_view();
+        self.file_history = None;
+    }
+
+    /// Get the file history view state, if open.
+    pub fn file_history(&self) -> Option<&FileHistoryState> {
+        self.file_history.as_ref()
+    }
+
+    /// Re-fetch the file history, keeping the selected commit if it still touches the file.
+    pub(super) fn refresh_file_history(&mut self) -> Result<(), XorcistError> {
+        let Some(state) = &self.file_history else {
+            return Ok(());
+        };
+        let graph_log = fetch_file_history(&self.runner, &state.path, self.log_limit)?;
+        let Some(state) = &mut self.file_history else {
+            return Ok(());
+        };
+        let selected_id = state.selected_change_id().map(str::to_string);
+        state.selected = selected_id
+            .and_then(|id| {
+                (0..graph_log.commit_count())
+                    .find(|&i| graph_log.change_id_for_selection(i) == Some(id.as_str()))
+            })
+            .unwrap_or_else(|| {
+                state
+                    .selected
+                    .min(graph_log.commit_count().saturating_sub(1))
+            });
+        state.graph_log = graph_log;
+        Ok(())
+    }
+
+    /// Select the next (older) commit in the file history.
+    pub fn file_history_select_next(&mut self) {
+        if let Some(state) = &mut self.file_history
+            && state.selected + 1 < state.graph_log.commit_count()
+        {
+            state.selected += 1;
+        }
+    }
+
+    /// Select the previous (newer) commit in the file history.
+    pub fn file_history_select_previous(&mut self) {
+        if let Some(state) = &mut self.file_history {
+            state.selected = state.selected.saturating_sub(1);
+        }
+    }
+
+    /// Select the newest commit in the file history.
+    pub fn file_history_select_first(&mut self) {
+        if let Some(state) = &mut self.file_history {
+            state.selected = 0;
+        }
+    }
+
+    /// Select the oldest commit in the file history.
+    pub fn file_history_select_last(&mut self) {
+        if let Some(state) = &mut self.file_history {
+            state.selected = state.graph_log.commit_count().saturating_sub(1);
+        }
+    }
+
+    /// Adjust the scroll offset so the selected commit's line is visible.
+    pub fn ensure_file_history_visible(&mut self, visible_height: usize) {
+        let Some(state) = &mut self.file_history else {
+            return;
+        };
+        let Some(line_idx) = state.graph_log.line_index_for_selection(state.selected) else {
+            return;
+        };
+        if visible_height == 0 {
+            return;
+        }
+        if line_idx < state.scroll {
+            state.scroll = line_idx;
+        } else if line_idx >= state.scroll + visible_height {
+            state.scroll = line_idx + 1 - visible_height;
+        }
+    }
+
+    /// Open the detail view for the commit selected in the file history.
+    pub fn open_file_history_detail(&mut self) -> Result<(), XorcistError> {
+        let Some(change_id) = self
+            .file_history
+            .as_ref()
+            .and_then(|state| state.selected_change_id())
+            .map(str::to_string)
+        else {
+            return Ok(());
+        };
+        self.open_detail_for(&change_id)
+    }
+}
@@ -0,0 +1,58 @@
+//! History cleanup from the command palette, for App: `jj simplify-parents`
+//! and `jj rebase --skip-emptied`.
+//!
+//! Both act on the marked commits, or on the selected one without marks,
+//! and open the output pane on jj's summary when they finish.
+
+use crate::error::XorcistError;
+
+use super::{App, CommandResult, PendingAction};
+
+impl App {
+    /// Show confirmation dialog for `jj simplify-parents`, removing parent
+    /// edges already implied by other parents.
+    pub fn show_simplify_parents_confirm(&mut self) -> Result<(), XorcistError> {
+        let revisions = self.housekeeping_revisions();
+        if revisions.is_empty() {
+            return Ok(());
+        }
+        self.request_confirm(PendingAction::SimplifyParents { revisions })
+    }
+
+    /// Show confirmation dialog for rebasing onto `destination` with
+    /// `--skip-emptied`, abandoning commits the rebase leaves empty.
+    pub(super) fn show_rebase_skip_emptied_confirm(
+        &mut self,
+        destination: &str,
+    ) -> Result<(), XorcistError> {
+        let destination = destination.trim();
+        if destination.is_empty() {
+            self.report(CommandResult {
+                success: false,
+                message: "Destination cannot be empty".to_string(),
+            });
+            return Ok(());
+        }
+        let revisions = self.housekeeping_revisions();
+        if revisions.is_empty() {
+            return Ok(());
+        }
+        self.request_confirm(PendingAction::RebaseSkipEmptied {
+            revisions,
+            destination: destination.to_string(),
+        })
+    }
+
+    /// (change_id, description) of the marked commits, or of the selected one.
+    fn housekeeping_revisions(&self) -> Vec<(String, String)> {
+        if self.has_marks() {
+            return self.marked_revisions();
+        }
+        self.selected_change_id()
+            .map(|change_id| {
+                let description = self.selected_description().unwrap_or_default();
+                vec![(change_id.to_string(), description)]
+            })
+            .unwrap_or_default()
+    }
+}
@@ -1,8 +1,10 @@
 //! Input mode methods for App.
 
+use tui_input::Input;
+
 use crate::error::XorcistError;
 
-use super::{App, InputMode};
+use super::{App, InputMode, NewPlacement};
 
 impl App {
     /// Start input mode for text entry.
@@ -11,10 +13,27 @@ impl App {
         self.input.reset();
     }
 
+    /// Start input mode with the given initial text (cursor at the end).
+    pub fn start_input_mode_with(&mut self, mode: InputMode, initial: &str) {
+        self.input_mode = Some(mode);
+        self.input = Input::new(initial.to_string());
+    }
+
     /// Cancel input mode without executing.
     pub fn cancel_input_mode(&mut self) {
+        if self.input_mode == Some(InputMode::Search) {
+            self.cancel_search();
+        }
         self.input_mode = None;
         self.input.reset();
+        self.pending_diff_from = None;
+        self.pending_restore = None;
+        self.finder = None;
+        self.describe_original = None;
+        self.conventional_draft = None;
+        self.protected_push = None;
+        self.author_choices.clear();
+        self.new_placement = NewPlacement::default();
     }
 
     /// Check if currently in input mode.
@@ -31,10 +50,32 @@ impl App {
         self.input.reset();
 
         match mode {
-            InputMode::Describe => self.execute_describe(&value)?,
+            InputMode::Describe => self.submit_describe(&value)?,
             InputMode::BookmarkSet => self.execute_bookmark_set(&value)?,
             InputMode::NewWithMessage => self.execute_new_with_message(&value)?,
+            InputMode::Commit => self.execute_commit(&value),
             InputMode::RebaseDestination => self.execute_rebase(&value)?,
+            InputMode::DuplicateDestination => self.show_duplicate_confirm(&value),
+            InputMode::RebaseSkipEmptiedDestination => {
+                self.show_rebase_skip_emptied_confirm(&value)?
+            }
+            InputMode::Command => self.run_command_line(&value)?,
+            InputMode::DiffFrom => self.submit_diff_from(&value)?,
+            InputMode::DiffTo => self.submit_diff_to(&value)?,
+            InputMode::Search => self.submit_search(&value),
+            InputMode::WorkspaceAdd => self.submit_workspace_add(&value),
+            InputMode::SparseAdd => self.submit_sparse_add(&value),
+            InputMode::SetAuthor => self.submit_set_author(&value),
+            InputMode::ProtectedPush => self.submit_protected_push(&value),
+            InputMode::RestoreInto => self.submit_restore_into(&value),
+            InputMode::Finder => self.submit_finder(),
+            InputMode::PatchPath => self.submit_patch_path(&value),
+            InputMode::Palette => self.submit_palette()?,
+            InputMode::ConventionalType => self.submit_conventional_type(),
+            InputMode::ConventionalScope => self.submit_conventional_scope(),
+            InputMode::ConventionalSubject => self.submit_conventional_subject(&value)?,
+            InputMode::AuthorFilter => self.submit_author_filter()?,
+            InputMode::PathFilter => self.submit_path_filter(&value)?,
         }
         Ok(())
     }
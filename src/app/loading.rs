@@ -1,9 +1,16 @@
 //! Lazy loading methods for App.
 
+use std::time::Duration;
+
+use crate::config::{DEFAULT_LOG_LIMIT, LogColumn, LogColumnKind, LogConfig};
 use crate::error::XorcistError;
-use crate::jj::fetch_graph_log_after;
+use crate::jj::{ABSOLUTE_TIMESTAMP_WIDTH, LineStream, TimestampFormat, fetch_graph_log_after};
+
+use super::{App, CommandResult};
 
-use super::{App, DEFAULT_BATCH_SIZE, LOAD_MORE_THRESHOLD};
+/// Most lines of a streamed log taken per event loop iteration, so that
+/// the UI keeps responding while a large log is read.
+const STREAM_LINES_PER_POLL: usize = 10_000;
 
 impl App {
     /// Set the log entry limit and determine if more entries might be available.
@@ -17,6 +24,55 @@ impl App {
         };
     }
 
+    /// Apply incremental loading settings from the config file.
+    pub fn apply_log_config(&mut self, config: &LogConfig) {
+        self.batch_size = config.batch_size;
+        self.load_more_threshold = config.load_more_threshold;
+        self.wrap_descriptions = config.wrap_descriptions;
+        self.set_stale_after(Some(Duration::from_secs(config.stale_after)));
+        self.refresh_on_external_change = config.refresh_on_external_change;
+        self.log_columns = config.columns.clone();
+    }
+
+    /// Columns after the description in the log view.
+    ///
+    /// The timestamp column is widened to fit absolute timestamps.
+    pub fn log_columns(&self) -> Vec<LogColumn> {
+        let absolute = self.runner.timestamp_format() == TimestampFormat::Absolute
+            && !self.runner.has_custom_log_template();
+        self.log_columns
+            .iter()
+            .map(|&column| match column.kind {
+                LogColumnKind::Timestamp if absolute => LogColumn {
+                    width: column.width.max(ABSOLUTE_TIMESTAMP_WIDTH),
+                    ..column
+                },
+                _ => column,
+            })
+            .collect()
+    }
+
+    /// Switch the log between relative and absolute timestamps, and reload it.
+    pub fn toggle_timestamp_format(&mut self) -> Result<(), XorcistError> {
+        if self.runner.has_custom_log_template() {
+            self.report(CommandResult {
+                success: false,
+                message: "Timestamps are set by log.template".to_string(),
+            });
+            return Ok(());
+        }
+        let format = self.runner.timestamp_format().toggled();
+        self.runner.set_timestamp_format(format);
+        self.refresh_log_keep_selection()
+    }
+
+    /// Number of entries fetched per incremental load.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+            .or(self.log_limit)
+            .unwrap_or(DEFAULT_LOG_LIMIT)
+    }
+
     /// Request a check for loading more entries.
     /// This sets a flag that will be checked by the event loop.
     pub fn request_load_more_check(&mut self) {
@@ -39,7 +95,7 @@ impl App {
         }
 
         let entries_from_end = self.commit_count().saturating_sub(self.selected);
-        entries_from_end <= LOAD_MORE_THRESHOLD
+        entries_from_end <= self.load_more_threshold
     }
 
     /// Mark that we're starting to load more entries.
@@ -48,6 +104,38 @@ impl App {
         self.pending_load_more = false;
     }
 
+    /// Fill the log from a `jj log` that is still running.
+    pub fn start_log_stream(&mut self, stream: LineStream) {
+        self.log_stream = Some(stream);
+    }
+
+    /// Whether the log is still being read from jj.
+    pub fn is_log_streaming(&self) -> bool {
+        self.log_stream.is_some()
+    }
+
+    /// Append the lines of the streamed log read since the last call.
+    ///
+    /// Returns `true` if the log grew. Fails if jj failed (e.g., a bad revset).
+    pub fn poll_log_stream(&mut self) -> Result<bool, XorcistError> {
+        let Some(stream) = &self.log_stream else {
+            return Ok(false);
+        };
+        let (lines, finished) = stream.poll(STREAM_LINES_PER_POLL);
+        let grew = !lines.is_empty();
+        for line in lines {
+            self.graph_log.push_line(line);
+        }
+        if grew {
+            self.refresh_search_matches();
+        }
+        if let Some(result) = finished {
+            self.log_stream = None;
+            result?;
+        }
+        Ok(grew)
+    }
+
     /// Actually load more entries.
     /// Should be called after start_loading() and a redraw.
     pub fn load_more_entries(&mut self) -> Result<bool, XorcistError> {
@@ -60,8 +148,13 @@ impl App {
         let after_change_id = after_change_id.to_string();
 
         // Fetch more entries
-        let batch_size = self.log_limit.unwrap_or(DEFAULT_BATCH_SIZE);
-        let additional = fetch_graph_log_after(&self.runner, &after_change_id, batch_size)?;
+        let batch_size = self.batch_size();
+        let additional = fetch_graph_log_after(
+            &self.runner,
+            self.log_revset().as_deref(),
+            &after_change_id,
+            batch_size,
+        )?;
 
         self.is_loading_more = false;
 
@@ -77,6 +170,7 @@ impl App {
 
         // Merge additional lines into existing graph_log
         self.graph_log.extend(additional);
+        self.refresh_search_matches();
         Ok(true)
     }
 }
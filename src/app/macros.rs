@@ -0,0 +1,131 @@
+//! Keyboard macros (`Q<register>` to record, `@<register>` to replay) for App.
+//!
+//! A replayed macro is fed to the event loop one key at a time, waiting for
+//! each background command to finish, so "describe, bookmark, push" runs
+//! the same as when typed.
+
+use std::collections::{HashMap, VecDeque};
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::{App, CommandResult};
+
+/// Macro registers and the recording or replay in progress.
+#[derive(Debug, Default)]
+pub(super) struct Macros {
+    /// Recorded keys, by register.
+    registers: HashMap<char, Vec<KeyEvent>>,
+    /// Register being recorded and the keys so far.
+    recording: Option<(char, Vec<KeyEvent>)>,
+    /// Whether the next key names a register, and what for.
+    prompt: Option<MacroPrompt>,
+    /// Keys of the macro being replayed that are still to run.
+    queue: VecDeque<KeyEvent>,
+}
+
+/// What the register typed after `Q` or `@` is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MacroPrompt {
+    Record,
+    Replay,
+}
+
+impl App {
+    /// Start recording (asking for a register), or stop the recording.
+    pub fn toggle_macro_recording(&mut self) {
+        let Some((register, mut keys)) = self.macros.recording.take() else {
+            self.macros.prompt = Some(MacroPrompt::Record);
+            return;
+        };
+        // Drop the `Q` that stopped the recording
+        keys.pop();
+        let message = format!("Recorded {} keys into @{register}", keys.len());
+        self.macros.registers.insert(register, keys);
+        self.set_macro_status(true, message);
+    }
+
+    /// Ask for the register of a macro to replay.
+    pub fn start_macro_replay(&mut self) {
+        if self.macros.recording.is_some() {
+            self.set_macro_status(
+                false,
+                "Stop recording (Q) before replaying a macro".to_string(),
+            );
+            return;
+        }
+        self.macros.prompt = Some(MacroPrompt::Replay);
+    }
+
+    /// Whether the next key names a macro register.
+    pub fn is_macro_prompt_pending(&self) -> bool {
+        self.macros.prompt.is_some()
+    }
+
+    /// Take the register after `Q` or `@` (any other key cancels).
+    pub fn finish_macro_prompt(&mut self, key: KeyEvent) {
+        let Some(prompt) = self.macros.prompt.take() else {
+            return;
+        };
+        let KeyCode::Char(register) = key.code else {
+            return;
+        };
+        if !register.is_ascii_lowercase() {
+            self.set_macro_status(
+                false,
+                format!("Invalid macro register '{register}' (use a-z)"),
+            );
+            return;
+        }
+        match prompt {
+            MacroPrompt::Record => self.macros.recording = Some((register, Vec::new())),
+            MacroPrompt::Replay => match self.macros.registers.get(&register) {
+                Some(keys) if !keys.is_empty() => {
+                    self.macros.queue = keys.iter().copied().collect()
+                }
+                _ => self.set_macro_status(false, format!("Macro @{register} is empty")),
+            },
+        }
+    }
+
+    /// Register being recorded, if any.
+    pub fn recording_macro(&self) -> Option<char> {
+        self.macros
+            .recording
+            .as_ref()
+            .map(|(register, _)| *register)
+    }
+
+    /// Record a key pressed while recording.
+    pub fn record_macro_key(&mut self, key: KeyEvent) {
+        if let Some((_, keys)) = &mut self.macros.recording {
+            keys.push(key);
+        }
+    }
+
+    /// Next key of the macro being replayed (None while a command runs).
+    pub fn next_macro_key(&mut self) -> Option<KeyEvent> {
+        if self.is_command_running() {
+            return None;
+        }
+        self.macros.queue.pop_front()
+    }
+
+    /// Stop the macro being replayed; returns whether one was.
+    pub fn stop_macro_replay(&mut self) -> bool {
+        if self.macros.queue.is_empty() {
+            return false;
+        }
+        self.macros.queue.clear();
+        self.set_macro_status(false, "Macro stopped".to_string());
+        true
+    }
+
+    /// Drop the rest of the macro being replayed after a failed command.
+    pub(super) fn abort_macro_replay(&mut self) {
+        self.macros.queue.clear();
+    }
+
+    fn set_macro_status(&mut self, success: bool, message: String) {
+        self.report(CommandResult { success, message });
+    }
+}
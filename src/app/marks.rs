@@ -0,0 +1,170 @@
+//! Marking commits for bulk operations.
+
+use crate::error::XorcistError;
+use crate::jj::DiffRange;
+
+use super::{App, CommandResult, PendingAction};
+
+impl App {
+    /// Toggle the mark on the selected commit and move to the next one.
+    pub fn toggle_mark(&mut self) {
+        let Some(change_id) = self.selected_change_id() else {
+            return;
+        };
+        let change_id = change_id.to_string();
+        if !self.marked.remove(&change_id) {
+            self.marked.insert(change_id);
+        }
+        self.select_next();
+    }
+
+    /// Remove all marks.
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    /// Check if a commit is marked.
+    pub fn is_marked(&self, change_id: &str) -> bool {
+        self.marked.contains(change_id)
+    }
+
+    /// Check if any loaded commit is marked.
+    pub fn has_marks(&self) -> bool {
+        self.mark_count() > 0
+    }
+
+    /// Number of marked commits that are currently loaded.
+    pub fn mark_count(&self) -> usize {
+        self.marked_change_ids().len()
+    }
+
+    /// Change IDs of marked commits, in log order.
+    ///
+    /// Marks on commits that are no longer in the log are ignored.
+    pub fn marked_change_ids(&self) -> Vec<String> {
+        (0..self.commit_count())
+            .filter_map(|i| self.graph_log.change_id_for_selection(i))
+            .filter(|id| self.marked.contains(*id))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Open the diff between the two marked commits (an interdiff).
+    ///
+    /// The diff goes from the older commit (lower in the log) to the newer.
+    pub fn diff_marked_pair(&mut self) -> Result<(), XorcistError> {
+        let marked = self.marked_change_ids();
+        let [to, from] = &marked[..] else {
+            self.report(CommandResult {
+                success: false,
+                message: format!(
+                    "Mark exactly two commits to diff them ({} marked)",
+                    marked.len()
+                ),
+            });
+            return Ok(());
+        };
+        self.open_range_diff(DiffRange {
+            from: from.clone(),
+            to: to.clone(),
+        })
+    }
+
+    /// Start visual mode at the selected commit, or mark the range from
+    /// where it started to the selected commit and end it.
+    pub fn toggle_visual_mode(&mut self) {
+        let Some(anchor) = self.visual_anchor.take() else {
+            if self.selected_change_id().is_some() {
+                self.visual_anchor = Some(self.selected);
+            }
+            return;
+        };
+        let (start, end) = (anchor.min(self.selected), anchor.max(self.selected));
+        for i in start..=end {
+            if let Some(change_id) = self.graph_log.change_id_for_selection(i) {
+                self.marked.insert(change_id.to_string());
+            }
+        }
+    }
+
+    /// Whether visual (range select) mode is active.
+    pub fn is_visual_mode(&self) -> bool {
+        self.visual_anchor.is_some()
+    }
+
+    /// Leave visual mode without marking the range.
+    pub fn cancel_visual_mode(&mut self) {
+        self.visual_anchor = None;
+    }
+
+    /// Number of commits in the visual range.
+    pub fn visual_range_len(&self) -> usize {
+        self.visual_anchor
+            .map_or(0, |anchor| anchor.abs_diff(self.selected) + 1)
+    }
+
+    /// Whether a commit is inside the visual range (from the anchor to the selection).
+    pub fn in_visual_range(&self, change_id: &str) -> bool {
+        let Some(anchor) = self.visual_anchor else {
+            return false;
+        };
+        (anchor.min(self.selected)..=anchor.max(self.selected))
+            .any(|i| self.graph_log.change_id_for_selection(i) == Some(change_id))
+    }
+
+    /// (change_id, description) of every marked commit, in log order.
+    pub(super) fn marked_revisions(&self) -> Vec<(String, String)> {
+        self.marked_change_ids()
+            .into_iter()
+            .map(|change_id| {
+                let description = self.description_of(&change_id).unwrap_or_default();
+                (change_id, description)
+            })
+            .collect()
+    }
+
+    /// Show confirmation dialog for rebasing all marked commits.
+    pub(super) fn show_rebase_marked_confirm(&mut self, destination: &str) {
+        self.ask_confirm(PendingAction::RebaseMarked {
+            revisions: self.marked_revisions(),
+            destination: destination.to_string(),
+        });
+    }
+
+    /// Show confirmation dialog for abandoning all marked commits at once.
+    pub(super) fn show_abandon_marked_confirm(&mut self) -> Result<(), XorcistError> {
+        self.request_confirm(PendingAction::AbandonMarked {
+            revisions: self.marked_revisions(),
+        })
+    }
+
+    /// Show confirmation dialog for squashing all marked commits into the selected one.
+    ///
+    /// The selected commit is the destination, so it must not be marked itself.
+    pub(super) fn show_squash_marked_confirm(&mut self) -> Result<(), XorcistError> {
+        let Some(target) = self.selected_change_id().map(str::to_string) else {
+            return Ok(());
+        };
+        if self.is_marked(&target) {
+            self.report(CommandResult {
+                success: false,
+                message: "Select an unmarked commit to squash the marked changes into".to_string(),
+            });
+            return Ok(());
+        }
+        let description = self.description_of(&target).unwrap_or_default();
+        self.request_confirm(PendingAction::SquashMarked {
+            revisions: self.marked_revisions(),
+            into: (target, description),
+        })
+    }
+
+    /// Description of a loaded commit.
+    fn description_of(&self, change_id: &str) -> Option<String> {
+        self.graph_log
+            .lines
+            .iter()
+            .find(|line| line.change_id.as_deref() == Some(change_id))
+            .and_then(|line| line.description.clone())
+    }
+}
@@ -0,0 +1,357 @@
+//! Context menu of actions for the selected commit.
+
+use crate::error::XorcistError;
+use crate::jj::CommitFlags;
+
+use super::yank::YankTarget;
+use super::{App, InputMode, ModalState};
+
+/// An action offered by the context menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    ShowDetail,
+    Describe,
+    SetAuthor,
+    New,
+    NewWithMessage,
+    Edit,
+    SetBookmark,
+    Rebase,
+    Duplicate,
+    Squash,
+    Abandon,
+    PushChange,
+    ResolveDivergence,
+    ToggleMark,
+    CompareBase,
+    CopyChangeId,
+    CopyCommitId,
+    OpenOnForge,
+}
+
+impl MenuAction {
+    /// All actions, in menu order.
+    const ALL: [MenuAction; 18] = [
+        MenuAction::ShowDetail,
+        MenuAction::Describe,
+        MenuAction::SetAuthor,
+        MenuAction::New,
+        MenuAction::NewWithMessage,
+        MenuAction::Edit,
+        MenuAction::SetBookmark,
+        MenuAction::Rebase,
+        MenuAction::Duplicate,
+        MenuAction::Squash,
+        MenuAction::Abandon,
+        MenuAction::PushChange,
+        MenuAction::ResolveDivergence,
+        MenuAction::ToggleMark,
+        MenuAction::CompareBase,
+        MenuAction::CopyChangeId,
+        MenuAction::CopyCommitId,
+        MenuAction::OpenOnForge,
+    ];
+
+    /// Menu label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            MenuAction::ShowDetail => "Show details",
+            MenuAction::Describe => "Describe...",
+            MenuAction::SetAuthor => "Set author...",
+            MenuAction::New => "New change on top",
+            MenuAction::NewWithMessage => "New change with message...",
+            MenuAction::Edit => "Edit (check out)",
+            MenuAction::SetBookmark => "Set bookmark...",
+            MenuAction::Rebase => "Rebase to...",
+            MenuAction::Duplicate => "Duplicate onto...",
+            MenuAction::Squash => "Squash into parent",
+            MenuAction::Abandon => "Abandon",
+            MenuAction::PushChange => "Push this change",
+            MenuAction::ResolveDivergence => "Resolve divergence (keep this)...",
+            MenuAction::ToggleMark => "Mark / unmark",
+            MenuAction::CompareBase => "Compare (set base / diff with base)",
+            MenuAction::CopyChangeId => "Copy change ID",
+            MenuAction::CopyCommitId => "Copy commit ID",
+            MenuAction::OpenOnForge => "Open on forge",
+        }
+    }
+
+    /// Shortcut key that activates the action inside the menu.
+    ///
+    /// Matches the log view key binding where there is one; the other
+    /// actions use keys that the log view leaves unbound.
+    pub fn key(&self) -> char {
+        match self {
+            MenuAction::ShowDetail => 'l',
+            MenuAction::Describe => 'd',
+            MenuAction::SetAuthor => 'U',
+            MenuAction::New => 'n',
+            MenuAction::NewWithMessage => 'N',
+            MenuAction::Edit => 'e',
+            MenuAction::SetBookmark => 'b',
+            MenuAction::Rebase => 'r',
+            MenuAction::Duplicate => 'Y',
+            MenuAction::Squash => 's',
+            MenuAction::Abandon => 'a',
+            MenuAction::PushChange => '^',
+            MenuAction::ResolveDivergence => 'x',
+            MenuAction::ToggleMark => ' ',
+            MenuAction::CompareBase => 'c',
+            MenuAction::CopyChangeId => 'y',
+            MenuAction::CopyCommitId => '#',
+            MenuAction::OpenOnForge => 'B',
+        }
+    }
+
+    /// Whether the action applies to a commit in the given state.
+    ///
+    /// jj refuses to rewrite immutable commits, pushing a conflicted change
+    /// fails, and editing the working copy is a no-op. Hidden commits can
+    /// only be looked at or built on (which makes them visible again).
+    fn applies_to(&self, flags: CommitFlags) -> bool {
+        if flags.hidden {
+            return matches!(
+                self,
+                MenuAction::ShowDetail
+                    | MenuAction::New
+                    | MenuAction::NewWithMessage
+                    | MenuAction::Duplicate
+                    | MenuAction::CompareBase
+                    | MenuAction::CopyChangeId
+                    | MenuAction::CopyCommitId
+            );
+        }
+        match self {
+            MenuAction::Describe
+            | MenuAction::SetAuthor
+            | MenuAction::Rebase
+            | MenuAction::Squash
+            | MenuAction::Abandon => !flags.immutable,
+            MenuAction::Edit => !flags.immutable && !flags.working_copy,
+            MenuAction::PushChange => !flags.immutable && !flags.conflict,
+            MenuAction::ResolveDivergence => flags.divergent,
+            _ => true,
+        }
+    }
+}
+
+/// State of an open context menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextMenu {
+    /// Change the menu was opened for.
+    pub change_id: String,
+    /// Applicable actions.
+    pub items: Vec<MenuAction>,
+    /// Highlighted item.
+    pub selected: usize,
+}
+
+impl ContextMenu {
+    /// Build the menu for a commit in the given state.
+    pub fn for_commit(change_id: String, flags: CommitFlags) -> Self {
+        let items = MenuAction::ALL
+            .into_iter()
+            .filter(|action| action.applies_to(flags))
+            .collect();
+        Self {
+            change_id,
+            items,
+            selected: 0,
+        }
+    }
+
+    /// Move the highlight down (wrapping).
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + 1) % self.items.len();
+        }
+    }
+
+    /// Move the highlight up (wrapping).
+    pub fn select_previous(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+        }
+    }
+
+    /// Action bound to a shortcut key.
+    pub fn action_for_key(&self, key: char) -> Option<MenuAction> {
+        self.items
+            .iter()
+            .copied()
+            .find(|action| action.key() == key)
+    }
+}
+
+impl App {
+    /// Open the context menu for the selected commit.
+    pub fn open_context_menu(&mut self) {
+        let Some(line_idx) = self.selected_line_index() else {
+            return;
+        };
+        let line = &self.graph_log.lines[line_idx];
+        let Some(change_id) = line.change_id.clone() else {
+            return;
+        };
+        self.modal = ModalState::Menu(ContextMenu::for_commit(change_id, line.flags));
+    }
+
+    /// Get the open context menu, if any.
+    pub fn context_menu_mut(&mut self) -> Option<&mut ContextMenu> {
+        match &mut self.modal {
+            ModalState::Menu(menu) => Some(menu),
+            _ => None,
+        }
+    }
+
+    /// Execute the highlighted context menu action.
+    pub fn activate_menu_item(&mut self) -> Result<(), XorcistError> {
+        let ModalState::Menu(menu) = &self.modal else {
+            return Ok(());
+        };
+        match menu.items.get(menu.selected).copied() {
+            Some(action) => self.execute_menu_action(action),
+            None => {
+                self.close_modal();
+                Ok(())
+            }
+        }
+    }
+
+    /// Close the menu and execute an action on the selected commit.
+    pub fn execute_menu_action(&mut self, action: MenuAction) -> Result<(), XorcistError> {
+        self.close_modal();
+        match action {
+            MenuAction::ShowDetail => self.open_detail()?,
+            MenuAction::Describe => self.start_describe()?,
+            MenuAction::SetAuthor => self.start_set_author(),
+            MenuAction::New => self.execute_new()?,
+            MenuAction::NewWithMessage => self.start_input_mode(InputMode::NewWithMessage),
+            MenuAction::Edit => self.execute_edit()?,
+            MenuAction::SetBookmark => self.start_input_mode(InputMode::BookmarkSet),
+            MenuAction::Rebase => self.start_input_mode(InputMode::RebaseDestination),
+            MenuAction::Duplicate => self.start_input_mode(InputMode::DuplicateDestination),
+            MenuAction::Squash => self.show_squash_confirm()?,
+            MenuAction::Abandon => self.show_abandon_confirm()?,
+            MenuAction::PushChange => self.show_push_change_confirm()?,
+            MenuAction::ResolveDivergence => self.show_resolve_divergence_confirm()?,
+            MenuAction::ToggleMark => self.toggle_mark(),
+            MenuAction::CompareBase => self.compare_selected()?,
+            MenuAction::CopyChangeId => self.yank(YankTarget::ChangeId)?,
+            MenuAction::CopyCommitId => self.yank(YankTarget::CommitId)?,
+            MenuAction::OpenOnForge => self.open_on_forge()?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_filters_immutable_commits() {
+        let flags = CommitFlags {
+            immutable: true,
+            ..Default::default()
+        };
+        let menu = ContextMenu::for_commit("abcdefgh".to_string(), flags);
+        assert!(menu.items.contains(&MenuAction::ShowDetail));
+        assert!(menu.items.contains(&MenuAction::New));
+        assert!(!menu.items.contains(&MenuAction::Describe));
+        assert!(!menu.items.contains(&MenuAction::Abandon));
+        assert!(!menu.items.contains(&MenuAction::PushChange));
+    }
+
+    #[test]
+    fn test_menu_filters_conflicted_and_working_copy() {
+        let flags = CommitFlags {
+            conflict: true,
+            working_copy: true,
+            ..Default::default()
+        };
+        let menu = ContextMenu::for_commit("abcdefgh".to_string(), flags);
+        assert!(menu.items.contains(&MenuAction::Describe));
+        assert!(!menu.items.contains(&MenuAction::Edit));
+        assert!(!menu.items.contains(&MenuAction::PushChange));
+
+        let menu = ContextMenu::for_commit("abcdefgh".to_string(), CommitFlags::default());
+        assert!(!menu.items.contains(&MenuAction::ResolveDivergence));
+        let divergent = CommitFlags {
+            divergent: true,
+            ..Default::default()
+        };
+        let menu = ContextMenu::for_commit("0f9e8d7c".to_string(), divergent);
+        assert_eq!(menu.items.len(), MenuAction::ALL.len());
+
+        let hidden = CommitFlags {
+            hidden: true,
+            ..Default::default()
+        };
+        let menu = ContextMenu::for_commit("1a2b3c4d".to_string(), hidden);
+        assert!(menu.items.contains(&MenuAction::New));
+        assert!(menu.items.contains(&MenuAction::CopyChangeId));
+        assert!(!menu.items.contains(&MenuAction::Abandon));
+        assert!(!menu.items.contains(&MenuAction::OpenOnForge));
+    }
+
+    #[test]
+    fn test_menu_navigation_wraps() {
+        let mut menu = ContextMenu::for_commit("abcdefgh".to_string(), CommitFlags::default());
+        menu.select_previous();
+        assert_eq!(menu.selected, menu.items.len() - 1);
+        menu.select_next();
+        assert_eq!(menu.selected, 0);
+        assert_eq!(menu.action_for_key('a'), Some(MenuAction::Abandon));
+        assert_eq!(menu.action_for_key('z'), None);
+    }
+
+    #[test]
+    fn test_menu_keys_agree_with_the_log_view() {
+        use crate::app::View;
+        use crate::keys::help::help_sections;
+
+        // Actions bound to the same key in the log view (`y` starts a copy)
+        let shared = [
+            MenuAction::Describe,
+            MenuAction::SetAuthor,
+            MenuAction::New,
+            MenuAction::NewWithMessage,
+            MenuAction::Edit,
+            MenuAction::SetBookmark,
+            MenuAction::Rebase,
+            MenuAction::Duplicate,
+            MenuAction::Squash,
+            MenuAction::Abandon,
+            MenuAction::ToggleMark,
+            MenuAction::CompareBase,
+            MenuAction::CopyChangeId,
+            MenuAction::OpenOnForge,
+        ];
+        let log_keys: Vec<String> = help_sections(View::Log)
+            .iter()
+            .flat_map(|section| section.bindings)
+            .flat_map(|(keys, _)| keys.split(" / ").flat_map(|part| part.split('/')))
+            .map(|key| match key {
+                "Space" => " ".to_string(),
+                // A two-key sequence like `yy` is bound to its first key
+                _ if key.len() == 2 && key.chars().all(|c| c.is_ascii_lowercase()) => {
+                    key[..1].to_string()
+                }
+                _ => key.to_string(),
+            })
+            .collect();
+        for action in MenuAction::ALL {
+            let bound = log_keys.contains(&action.key().to_string());
+            assert_eq!(bound, shared.contains(&action), "{action:?}");
+        }
+    }
+
+    #[test]
+    fn test_menu_shortcut_keys_are_unique() {
+        let mut keys: Vec<char> = MenuAction::ALL.iter().map(MenuAction::key).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), MenuAction::ALL.len());
+    }
+}
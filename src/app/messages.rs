@@ -0,0 +1,81 @@
+//! History of status bar messages (`L`), for App.
+//!
+//! Every result shown in the status bar is kept here, so one that was
+//! overwritten by the next can still be read.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use super::{App, CommandResult};
+
+/// Number of messages kept in the history.
+pub const MESSAGE_HISTORY_LEN: usize = 100;
+
+/// A message shown in the status bar.
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    /// Whether it reported a success.
+    pub success: bool,
+    /// Full message (the status bar showed its first line).
+    pub message: String,
+    /// When it was shown.
+    pub shown: Instant,
+}
+
+/// State of the message history panel.
+#[derive(Debug, Clone, Default)]
+pub struct MessagesPanel {
+    /// Recent messages, newest first.
+    pub entries: VecDeque<StatusMessage>,
+    /// Whether the panel is shown.
+    pub visible: bool,
+    /// Vertical scroll offset (in lines).
+    pub scroll: usize,
+}
+
+impl App {
+    /// Show a result in the status bar and keep it in the message history.
+    pub fn report(&mut self, result: CommandResult) {
+        let entries = &mut self.messages_panel.entries;
+        entries.push_front(StatusMessage {
+            success: result.success,
+            message: result.message.clone(),
+            shown: Instant::now(),
+        });
+        entries.truncate(MESSAGE_HISTORY_LEN);
+        self.last_command_result = Some(result);
+    }
+
+    /// Get the message history panel state.
+    pub fn messages_panel(&self) -> &MessagesPanel {
+        &self.messages_panel
+    }
+
+    /// Show or hide the message history (it opens on the newest message).
+    pub fn toggle_messages_panel(&mut self) {
+        let panel = &mut self.messages_panel;
+        panel.visible = !panel.visible;
+        panel.scroll = 0;
+    }
+
+    /// Whether the message history is shown.
+    pub fn is_messages_panel_open(&self) -> bool {
+        self.messages_panel.visible
+    }
+
+    /// Scroll the message history down.
+    pub fn messages_scroll_down(&mut self, amount: usize) {
+        self.messages_panel.scroll = self.messages_panel.scroll.saturating_add(amount);
+    }
+
+    /// Scroll the message history up.
+    pub fn messages_scroll_up(&mut self, amount: usize) {
+        self.messages_panel.scroll = self.messages_panel.scroll.saturating_sub(amount);
+    }
+
+    /// Clamp the message history scroll to its content (called during render).
+    pub fn clamp_messages_scroll(&mut self, content_height: usize, visible_height: usize) {
+        let max_scroll = content_height.saturating_sub(visible_height);
+        self.messages_panel.scroll = self.messages_panel.scroll.min(max_scroll);
+    }
+}
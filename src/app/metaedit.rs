@@ -0,0 +1,67 @@
+//! Changing the author of a revision (`jj metaedit`), for App.
+//!
+//! The prompt takes `Name <email>`, prefilled with the current author; an
+//! empty prompt resets the author to the configured user with a new
+//! timestamp, for commits made with the wrong identity.
+
+use crate::jj::fetch_author;
+
+use super::tasks::AfterCommand;
+use super::{App, CommandResult, InputMode};
+
+/// Whether `author` has the `Name <email>` form jj expects.
+fn is_valid_author(author: &str) -> bool {
+    author
+        .strip_suffix('>')
+        .and_then(|rest| rest.split_once(" <"))
+        .is_some_and(|(name, email)| {
+            !name.trim().is_empty() && !email.is_empty() && !email.contains(['<', '>'])
+        })
+}
+
+impl App {
+    /// Prompt for the author of the selected revision.
+    pub fn start_set_author(&mut self) {
+        let Some(change_id) = self.selected_change_id() else {
+            return;
+        };
+        // Without the current author the prompt starts empty (reset)
+        let author = fetch_author(&self.runner, change_id).unwrap_or_default();
+        self.pending_author_change = Some(change_id.to_string());
+        self.start_input_mode_with(InputMode::SetAuthor, &author);
+    }
+
+    /// Set the typed author on the revision (empty resets it to the configured user).
+    pub(super) fn submit_set_author(&mut self, author: &str) {
+        let Some(change_id) = self.pending_author_change.take() else {
+            return;
+        };
+        let author = author.trim();
+        if !author.is_empty() && !is_valid_author(author) {
+            self.report(CommandResult {
+                success: false,
+                message: format!("Invalid author '{author}': use Name <email>"),
+            });
+            return;
+        }
+        let author = (!author.is_empty()).then(|| author.to_string());
+        let after = AfterCommand::RefreshRevisions(vec![change_id.clone()]);
+        self.run_in_background("jj metaedit", after, move |runner| {
+            runner.execute_set_author(&change_id, author.as_deref())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_author() {
+        assert!(is_valid_author("Jane Doe <jane@example.com>"));
+        assert!(!is_valid_author("Jane Doe"));
+        assert!(!is_valid_author("<jane@example.com>"));
+        assert!(!is_valid_author("Jane <>"));
+        assert!(!is_valid_author("Jane <a<b>"));
+    }
+}
@@ -1,26 +1,144 @@
 //! Application state management.
 
+mod authors;
+mod cache;
+mod command_history;
+mod command_line;
 mod commands;
+mod compose;
+mod describe;
+mod detail;
+mod divergence;
+mod elided;
+mod external;
+mod finder;
+mod forge;
+mod hidden;
+mod history;
+mod housekeeping;
 mod input;
 mod loading;
+mod macros;
+mod marks;
+mod menu;
+mod messages;
+mod metaedit;
+mod mouse;
 mod navigation;
+mod oplog;
+mod output;
+mod palette;
+mod patch;
+mod paths;
+mod placement;
+mod preview;
+mod push;
+mod refresh;
+mod related;
+mod restore;
+mod search;
+mod sign;
+mod sparse;
+mod split;
+mod squash;
+mod stacks;
+mod tags;
+mod tasks;
+mod views;
+mod workspaces;
+mod yank;
 
 #[cfg(test)]
 mod tests;
 
+pub use command_history::CommandHistoryPanel;
+pub use command_line::matching_commands;
+pub use compose::ConventionalDraft;
+pub use history::FileHistoryState;
+pub use menu::ContextMenu;
+pub use messages::StatusMessage;
+pub use output::CommandOutput;
+pub use palette::PALETTE_ENTRIES;
+pub use placement::{NewPlacement, PlacementMenu};
+pub use preview::{PreviewKind, PreviewState};
+pub use push::{ProtectedPush, PushDialog};
+pub use search::SearchState;
+pub use sparse::SparseState;
+pub use stacks::StacksState;
+pub use tags::TagsState;
+pub use workspaces::WorkspacesState;
+
+use describe::EditorRequest;
+use finder::FinderState;
+use messages::MessagesPanel;
+use output::OutputPane;
+use tasks::RunningCommand;
+
+use std::collections::{HashSet, VecDeque};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
 use tui_input::Input;
 
+use crate::config::{
+    ConfirmConfig, DEFAULT_LOAD_MORE_THRESHOLD, DEFAULT_LOG_COLUMNS, DEFAULT_LOG_LIMIT,
+    DEFAULT_STALE_AFTER_SECS, LogColumn, PushConfig,
+};
 use crate::error::XorcistError;
-use crate::jj::{GraphLog, JjRunner, ShowOutput, fetch_show};
+use crate::external::ExternalCommand;
+use crate::highlight::Token;
+use crate::jj::journal::command_line;
+use crate::jj::{
+    DiffRange, DiffStat, GitDiff, GraphLog, JjRunner, LineStream, MetadataCache, OpLogEntry,
+    OutputCache, RelatedCommit, RepoMetadata, ShowOutput, fetch_children,
+};
+use crate::profile::Profiler;
 use crate::text::truncate_str;
+use crate::theme::Theme;
 
 /// Current view mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum View {
     #[default]
     Log,
     Detail,
     Diff,
+    /// Operation log (`jj op log`).
+    OpLog,
+    /// History of one file (`jj log` limited to a path).
+    FileHistory,
+    /// Workspaces of the repository (`jj workspace list`).
+    Workspaces,
+    /// Tags of the repository (`jj tag list`).
+    Tags,
+    /// Stacks of mutable commits on top of trunk.
+    Stacks,
+    /// Sparse patterns of the working copy (`jj sparse list`).
+    Sparse,
+}
+
+/// Scroll and selection position of a view.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ViewPosition {
+    /// Selected item (log commit or diff file).
+    pub selected: usize,
+    /// Vertical scroll offset.
+    pub scroll: usize,
+    /// Horizontal scroll offset.
+    pub h_scroll: usize,
+    /// Change ID of the selected log commit, which finds it again after the
+    /// log has been reloaded.
+    pub change_id: Option<String>,
+}
+
+/// A view to return to, with the position it was left at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewEntry {
+    /// The view underneath the current one.
+    pub view: View,
+    /// Where that view was left.
+    pub position: ViewPosition,
 }
 
 /// Input mode for text entry.
@@ -32,18 +150,88 @@ pub enum InputMode {
     BookmarkSet,
     /// Creating new change with message for `jj new -m`.
     NewWithMessage,
+    /// Entering the message for `jj commit -m` (working copy).
+    Commit,
     /// Entering destination for `jj rebase -d`.
     RebaseDestination,
+    /// Entering destination for `jj duplicate -d` (empty = same parents).
+    DuplicateDestination,
+    /// Entering destination for `jj rebase --skip-emptied -d`.
+    RebaseSkipEmptiedDestination,
+    /// Entering an ex-style `:` command.
+    Command,
+    /// Entering the `--from` revision of an arbitrary diff.
+    DiffFrom,
+    /// Entering the `--to` revision of an arbitrary diff.
+    DiffTo,
+    /// Typing a `/` search over the log.
+    Search,
+    /// Entering the path of a new workspace.
+    WorkspaceAdd,
+    /// Entering the author of a revision (`jj metaedit --author`).
+    SetAuthor,
+    /// Typing the names of protected bookmarks to push them.
+    ProtectedPush,
+    /// Entering a sparse pattern to add (`jj sparse set --add`).
+    SparseAdd,
+    /// Entering the revision to restore files into (`jj restore --into`).
+    RestoreInto,
+    /// Typing the query of the fuzzy finder.
+    Finder,
+    /// Entering the file (or directory, for a series) to write patches to.
+    PatchPath,
+    /// Typing the query of the command palette.
+    Palette,
+    /// Picking the type of a conventional commit message.
+    ConventionalType,
+    /// Picking the scope of a conventional commit message.
+    ConventionalScope,
+    /// Entering the subject of a conventional commit message.
+    ConventionalSubject,
+    /// Picking the author to filter the log by.
+    AuthorFilter,
+    /// Entering the path to filter the log by.
+    PathFilter,
 }
 
 impl InputMode {
     /// Get the placeholder text for this input mode.
     pub fn placeholder(&self) -> &'static str {
         match self {
-            InputMode::Describe => "Enter commit message...",
+            InputMode::Describe => "Enter commit message (Ctrl+E: open in $EDITOR)...",
             InputMode::BookmarkSet => "Enter bookmark name...",
             InputMode::NewWithMessage => "Enter message (empty for no message)...",
+            InputMode::Commit => "Enter commit message for @ (empty keeps its description)...",
             InputMode::RebaseDestination => "Enter destination (e.g., @-, main, abc123)...",
+            InputMode::RebaseSkipEmptiedDestination => {
+                "Enter destination (commits left empty are abandoned)..."
+            }
+            InputMode::DuplicateDestination => {
+                "Enter destination (empty = onto the same parents)..."
+            }
+            InputMode::DiffFrom => "Enter --from revision (e.g., main, @--, abc123)...",
+            InputMode::DiffTo => "Enter --to revision (e.g., @)...",
+            InputMode::Search => "Search descriptions, change IDs, authors, bookmarks",
+            InputMode::WorkspaceAdd => {
+                "Enter path for the new workspace (e.g., ../repo-feature)..."
+            }
+            InputMode::SetAuthor => "Enter the author as Name <email> (empty resets it to you)...",
+            InputMode::ProtectedPush => {
+                "Type the protected bookmark names shown above (Esc cancels)..."
+            }
+            InputMode::SparseAdd => "Enter a path to check out (e.g., services/api)...",
+            InputMode::RestoreInto => "Enter revision to restore into (e.g., @, abc123)...",
+            InputMode::Finder => "Fuzzy-find descriptions, change IDs, authors, bookmarks",
+            InputMode::Palette => "Type an action (e.g., abandon, push, theme)",
+            InputMode::PatchPath => "Enter patch file (or directory for a series)...",
+            InputMode::Command => {
+                "describe fix typo, rebase -d main, revset mine() (Tab completes)"
+            }
+            InputMode::ConventionalType => "Pick a type (append ! for a breaking change)",
+            InputMode::ConventionalScope => "Pick a scope used before, or type a new one",
+            InputMode::ConventionalSubject => "Enter the subject...",
+            InputMode::AuthorFilter => "Filter the log by author",
+            InputMode::PathFilter => "Show commits touching a path (e.g., src/ui.rs, docs/)...",
         }
     }
 }
@@ -57,25 +245,68 @@ pub struct DetailState {
     pub scroll: usize,
     /// Total content height (for scroll calculation).
     pub content_height: usize,
+    /// Hunk-level diff shown below the file summary (None = summary only).
+    pub full_diff: Option<DetailDiff>,
+    /// Added/removed line counts of the changed files.
+    pub stat: DiffStat,
+    /// Child commits (the parents are in `show_output`).
+    pub children: Vec<RelatedCommit>,
+    /// Selected entry of the parents followed by the children.
+    pub related: usize,
+    /// Whether the revision is the working-copy commit, whose changes are
+    /// not committed yet (its full diff is shown right away).
+    pub working_copy: bool,
+}
+
+/// Full diff of the revision in the detail view.
+#[derive(Debug, Clone, Default)]
+pub struct DetailDiff {
+    /// Parsed `jj diff --git` output.
+    pub diff: GitDiff,
+    /// Indices of files whose hunks are collapsed.
+    pub collapsed: HashSet<usize>,
+    /// Content line of each file header (calculated during render).
+    pub file_offsets: Vec<usize>,
+}
+
+/// State for the operation log view.
+#[derive(Debug, Clone, Default)]
+pub struct OpLogState {
+    /// Operations, newest first.
+    pub entries: Vec<OpLogEntry>,
+    /// Selected operation index.
+    pub selected: usize,
+    /// Scroll offset (in entries).
+    pub scroll: usize,
 }
 
 /// State for diff view.
 #[derive(Debug, Clone, Default)]
 pub struct DiffState {
-    /// Target change ID.
+    /// Target change ID (or [`DiffRange::key`] when comparing).
     pub change_id: String,
+    /// Compared revisions, if this is a comparison rather than one revision.
+    pub range: Option<DiffRange>,
     /// List of changed files.
     pub files: Vec<crate::jj::DiffEntry>,
+    /// Added/removed line counts of the changed files.
+    pub stat: DiffStat,
     /// Currently selected file index.
     pub selected: usize,
     /// Scroll offset for file list.
     pub file_scroll: usize,
-    /// Diff text lines for selected file.
-    pub diff_lines: Vec<String>,
+    /// Parsed diff of the selected file.
+    pub diff: GitDiff,
+    /// Syntax tokens per line of `diff` (empty = not highlighted).
+    pub highlights: Vec<Vec<Token>>,
+    /// Changed byte ranges per line of `diff`, emphasized when highlighted.
+    pub word_changes: Vec<Vec<Range<usize>>>,
     /// Vertical scroll offset for diff text.
     pub diff_scroll: usize,
     /// Horizontal scroll offset for diff text.
     pub diff_h_scroll: usize,
+    /// Paths of files marked for `jj split`.
+    pub marked_files: HashSet<String>,
 }
 
 impl DiffState {
@@ -83,12 +314,25 @@ impl DiffState {
     pub fn new(change_id: String, files: Vec<crate::jj::DiffEntry>) -> Self {
         Self {
             change_id,
+            range: None,
             files,
+            stat: DiffStat::default(),
             selected: 0,
             file_scroll: 0,
-            diff_lines: Vec::new(),
+            diff: GitDiff::default(),
+            highlights: Vec::new(),
+            word_changes: Vec::new(),
             diff_scroll: 0,
             diff_h_scroll: 0,
+            marked_files: HashSet::new(),
+        }
+    }
+
+    /// Create a DiffState comparing two revisions.
+    pub fn for_range(range: DiffRange, files: Vec<crate::jj::DiffEntry>) -> Self {
+        Self {
+            range: Some(range.clone()),
+            ..Self::new(range.key(), files)
         }
     }
 
@@ -111,10 +355,144 @@ pub enum PendingAction {
         change_id: String,
         description: String,
     },
-    /// Push to remote.
-    GitPush,
+    /// Squash a change into a picked commit (`jj squash --from … --into`).
+    SquashInto {
+        change_id: String,
+        description: String,
+        /// (change_id, description) of the destination.
+        into: (String, String),
+    },
+    /// Copy a change with `jj duplicate`.
+    Duplicate {
+        change_id: String,
+        description: String,
+        /// Parent of the copy (None = the original's parents).
+        destination: Option<String>,
+    },
+    /// Create a new change on top of a revision (`jj new`, optionally `-m`).
+    New {
+        change_id: String,
+        /// Description of the new change (empty = none).
+        message: String,
+        /// On top of `change_id`, or inserted after / before it.
+        placement: NewPlacement,
+    },
+    /// Make a revision the working copy (`jj edit`).
+    Edit {
+        change_id: String,
+        description: String,
+    },
+    /// Rebase one revision (`jj rebase -r … -d`).
+    Rebase {
+        change_id: String,
+        description: String,
+        destination: String,
+    },
+    /// Fetch from the git remotes (`remote`: `push.remote`, if set).
+    GitFetch { remote: Option<String> },
+    /// Push a single change (`jj git push --change`).
+    PushChange {
+        change_id: String,
+        remote: Option<String>,
+    },
     /// Undo the last operation.
-    Undo,
+    Undo {
+        /// The operation `jj undo` reverts (None if the operation log could
+        /// not be read).
+        operation: Option<OpLogEntry>,
+    },
+    /// Restore the repository to an earlier operation (`jj op restore`).
+    OpRestore { op_id: String, description: String },
+    /// Stop tracking a workspace (`jj workspace forget`).
+    WorkspaceForget { name: String },
+    /// Stop checking out the paths of a sparse pattern (`jj sparse set --remove`).
+    SparseRemove { pattern: String },
+    /// Check out the whole repository again (`jj sparse reset`).
+    SparseReset,
+    /// Split files out of a change into a new first commit (`jj split`).
+    Split {
+        change_id: String,
+        /// Description kept by the first commit.
+        description: String,
+        paths: Vec<String>,
+    },
+    /// Squash some files of a change into its parent (`jj squash -r … -- <paths>`).
+    SquashFiles {
+        change_id: String,
+        paths: Vec<String>,
+    },
+    /// Restore files into a revision (`jj restore --into … -- <paths>`).
+    RestoreFiles {
+        /// Revision the contents come from (None = the parents of `into`,
+        /// discarding its changes to the files).
+        from: Option<String>,
+        into: String,
+        paths: Vec<String>,
+    },
+    /// Abandon several marked revisions in one `jj abandon`.
+    AbandonMarked {
+        /// (change_id, description) of every revision being abandoned.
+        revisions: Vec<(String, String)>,
+    },
+    /// Abandon the empty commits of the loaded log in one `jj abandon`.
+    AbandonEmpty {
+        /// (change_id, description) of every revision being abandoned.
+        revisions: Vec<(String, String)>,
+    },
+    /// Squash several marked revisions into one (`jj squash --from … --into`).
+    SquashMarked {
+        /// (change_id, description) of every revision being squashed.
+        revisions: Vec<(String, String)>,
+        /// (change_id, description) of the destination.
+        into: (String, String),
+    },
+    /// Rebase several marked revisions onto one destination.
+    RebaseMarked {
+        /// (change_id, description) of every revision being moved.
+        revisions: Vec<(String, String)>,
+        destination: String,
+    },
+    /// Rebase revisions, abandoning those left empty (`jj rebase --skip-emptied`).
+    RebaseSkipEmptied {
+        /// (change_id, description) of every revision being moved.
+        revisions: Vec<(String, String)>,
+        destination: String,
+    },
+    /// Remove redundant parent edges (`jj simplify-parents -r …`).
+    SimplifyParents {
+        /// (change_id, description) of every revision being simplified.
+        revisions: Vec<(String, String)>,
+    },
+    /// Rebase a whole stack onto trunk (`jj rebase -b <head> -d trunk()`).
+    RebaseStack {
+        head: String,
+        /// Name of the stack (its bookmarks or head change ID).
+        name: String,
+        /// (change_id, description) of every revision of the stack.
+        revisions: Vec<(String, String)>,
+    },
+    /// Resolve a divergent change: keep one commit, abandon the others or
+    /// squash them into it.
+    ResolveDivergence {
+        /// (commit_id, description) of the commit kept.
+        keep: (String, String),
+        /// (commit_id, description) of the other commits of the change.
+        others: Vec<(String, String)>,
+        /// Squash the others into the kept commit instead of abandoning them.
+        squash: bool,
+    },
+    /// Run a command from the command history again.
+    Rerun {
+        /// Arguments passed to `jj`.
+        args: Vec<String>,
+    },
+}
+
+/// `--remote <name>` arguments for fetch and push, if a remote is configured.
+fn remote_args(remote: Option<&str>) -> Vec<&str> {
+    remote
+        .map(|remote| vec!["--remote", remote])
+        .unwrap_or_default()
 }
 
 impl PendingAction {
@@ -130,12 +508,465 @@ impl PendingAction {
                     truncate_str(description, 40)
                 )
             }
-            PendingAction::GitPush => "Push to remote?".to_string(),
-            PendingAction::Undo => "Undo last operation?".to_string(),
+            PendingAction::SquashInto {
+                description,
+                into: (change_id, into_description),
+                ..
+            } => format!(
+                "Squash change: \"{}\" into {change_id} (\"{}\")?",
+                truncate_str(description, 30),
+                truncate_str(into_description, 30)
+            ),
+            PendingAction::Duplicate {
+                description,
+                destination,
+                ..
+            } => match destination {
+                Some(destination) => format!(
+                    "Duplicate change: \"{}\" onto \"{}\"?",
+                    truncate_str(description, 40),
+                    truncate_str(destination, 30)
+                ),
+                None => format!("Duplicate change: \"{}\"?", truncate_str(description, 40)),
+            },
+            PendingAction::New {
+                change_id,
+                message,
+                placement,
+            } => {
+                let position = match placement {
+                    NewPlacement::OnTop => "on top of",
+                    NewPlacement::InsertAfter => "after",
+                    NewPlacement::InsertBefore => "before",
+                };
+                if message.is_empty() {
+                    format!("Create a new change {position} {change_id}?")
+                } else {
+                    format!(
+                        "Create a new change \"{}\" {position} {change_id}?",
+                        truncate_str(message, 40)
+                    )
+                }
+            }
+            PendingAction::Edit { description, .. } => format!(
+                "Edit change: \"{}\" (make it the working copy)?",
+                truncate_str(description, 40)
+            ),
+            PendingAction::Rebase {
+                description,
+                destination,
+                ..
+            } => format!(
+                "Rebase change: \"{}\" onto \"{}\"?",
+                truncate_str(description, 40),
+                truncate_str(destination, 30)
+            ),
+            PendingAction::GitFetch { remote } => {
+                format!("Fetch from {}?", remote.as_deref().unwrap_or("remote"))
+            }
+            PendingAction::PushChange { change_id, remote } => format!(
+                "Push change {change_id} to {}?",
+                remote.as_deref().unwrap_or("remote")
+            ),
+            PendingAction::Undo { .. } => "Undo last operation?".to_string(),
+            PendingAction::WorkspaceForget { name } => format!(
+                "Forget workspace \"{}\"? (its files stay on disk)",
+                truncate_str(name, 30)
+            ),
+            PendingAction::SparseRemove { pattern } => format!(
+                "Remove sparse pattern \"{}\"? (its files leave the working copy)",
+                truncate_str(pattern, 30)
+            ),
+            PendingAction::SparseReset => {
+                "Reset the sparse patterns and check out every file?".to_string()
+            }
+            PendingAction::OpRestore { op_id, description } => format!(
+                "Restore repository to operation {op_id} (\"{}\")?",
+                truncate_str(description, 40)
+            ),
+            PendingAction::Split {
+                change_id, paths, ..
+            } => format!(
+                "Split {} of the files out of change {change_id}?",
+                paths.len()
+            ),
+            PendingAction::SquashFiles {
+                change_id, paths, ..
+            } => format!(
+                "Squash {} of the files of change {change_id} into its parent?",
+                paths.len()
+            ),
+            PendingAction::RestoreFiles { from, into, paths } => match from {
+                Some(from) => format!(
+                    "Overwrite {} files in {} with their contents from {}?",
+                    paths.len(),
+                    truncate_str(into, 20),
+                    truncate_str(from, 20)
+                ),
+                None => format!(
+                    "Discard the changes to {} files in {}?",
+                    paths.len(),
+                    truncate_str(into, 20)
+                ),
+            },
+            PendingAction::AbandonMarked { revisions } => {
+                format!("Abandon {} changes?", revisions.len())
+            }
+            PendingAction::AbandonEmpty { revisions } => {
+                format!("Abandon {} empty changes?", revisions.len())
+            }
+            PendingAction::SquashMarked {
+                revisions,
+                into: (change_id, description),
+            } => format!(
+                "Squash {} changes into {change_id} (\"{}\")?",
+                revisions.len(),
+                truncate_str(description, 30)
+            ),
+            PendingAction::RebaseMarked {
+                revisions,
+                destination,
+            } => format!(
+                "Rebase {} changes onto \"{}\"?",
+                revisions.len(),
+                truncate_str(destination, 30)
+            ),
+            PendingAction::RebaseSkipEmptied {
+                revisions,
+                destination,
+            } => format!(
+                "Rebase {} {} onto \"{}\", abandoning commits left empty?",
+                revisions.len(),
+                if revisions.len() == 1 {
+                    "change"
+                } else {
+                    "changes"
+                },
+                truncate_str(destination, 30)
+            ),
+            PendingAction::SimplifyParents { revisions } => format!(
+                "Remove redundant parents of {} {}?",
+                revisions.len(),
+                if revisions.len() == 1 {
+                    "change"
+                } else {
+                    "changes"
+                }
+            ),
+            PendingAction::RebaseStack {
+                name, revisions, ..
+            } => format!(
+                "Rebase stack \"{}\" ({} changes) onto trunk()?",
+                truncate_str(name, 30),
+                revisions.len()
+            ),
+            PendingAction::ResolveDivergence {
+                keep: (commit_id, description),
+                others,
+                squash,
+            } => {
+                let sides = if others.len() == 1 {
+                    "the other commit".to_string()
+                } else {
+                    format!("the {} other commits", others.len())
+                };
+                if *squash {
+                    format!(
+                        "Squash {sides} into {commit_id} (\"{}\")?",
+                        truncate_str(description, 30)
+                    )
+                } else {
+                    format!(
+                        "Keep {commit_id} (\"{}\") and abandon {sides}?",
+                        truncate_str(description, 30)
+                    )
+                }
+            }
+            PendingAction::Rerun { .. } => "Run this command again?".to_string(),
+        }
+    }
+
+    /// Name of the command in `confirm.<command>` settings (see [`crate::config::CONFIRM_COMMANDS`]).
+    pub fn command(&self) -> &'static str {
+        match self {
+            PendingAction::Abandon { .. }
+            | PendingAction::AbandonMarked { .. }
+            | PendingAction::AbandonEmpty { .. } => "abandon",
+            PendingAction::Squash { .. }
+            | PendingAction::SquashInto { .. }
+            | PendingAction::SquashFiles { .. }
+            | PendingAction::SquashMarked { .. } => "squash",
+            PendingAction::ResolveDivergence { squash: true, .. } => "squash",
+            PendingAction::ResolveDivergence { squash: false, .. } => "abandon",
+            PendingAction::Duplicate { .. } => "duplicate",
+            PendingAction::Rebase { .. }
+            | PendingAction::RebaseMarked { .. }
+            | PendingAction::RebaseSkipEmptied { .. }
+            | PendingAction::RebaseStack { .. } => "rebase",
+            PendingAction::SimplifyParents { .. } => "simplify_parents",
+            PendingAction::New { .. } => "new",
+            PendingAction::Edit { .. } => "edit",
+            PendingAction::GitFetch { .. } => "fetch",
+            PendingAction::PushChange { .. } => "push",
+            PendingAction::Undo { .. } => "undo",
+            PendingAction::OpRestore { .. } => "op_restore",
+            PendingAction::WorkspaceForget { .. } => "workspace_forget",
+            PendingAction::SparseRemove { .. } | PendingAction::SparseReset => "sparse",
+            PendingAction::Split { .. } => "split",
+            PendingAction::RestoreFiles { .. } => "restore",
+            PendingAction::Rerun { .. } => "rerun",
+        }
+    }
+
+    /// Arguments of the `jj` command the action runs, as passed by
+    /// [`App::confirm_action`] to the runner.
+    pub fn command_args(&self) -> Vec<String> {
+        let args: Vec<&str> = match self {
+            PendingAction::Abandon { change_id, .. } => vec!["abandon", change_id],
+            PendingAction::Squash { change_id, .. } => vec!["squash", "-r", change_id],
+            PendingAction::SquashInto {
+                change_id,
+                into: (target, _),
+                ..
+            } => vec!["squash", "--from", change_id, "--into", target],
+            PendingAction::Duplicate {
+                change_id,
+                destination,
+                ..
+            } => match destination {
+                Some(destination) => vec!["duplicate", change_id, "-d", destination],
+                None => vec!["duplicate", change_id],
+            },
+            PendingAction::New {
+                change_id,
+                message,
+                placement,
+            } => {
+                let mut args = vec!["new"];
+                args.extend(placement.flag());
+                args.push(change_id);
+                if !message.is_empty() {
+                    args.extend(["-m", message]);
+                }
+                args
+            }
+            PendingAction::Edit { change_id, .. } => vec!["edit", change_id],
+            PendingAction::Rebase {
+                change_id,
+                destination,
+                ..
+            } => vec!["rebase", "-r", change_id, "-d", destination],
+            PendingAction::GitFetch { remote } => {
+                let mut args = vec!["git", "fetch"];
+                args.extend(remote_args(remote.as_deref()));
+                args
+            }
+            PendingAction::PushChange { change_id, remote } => {
+                let mut args = vec!["git", "push", "--change", change_id];
+                args.extend(remote_args(remote.as_deref()));
+                args
+            }
+            PendingAction::Undo { .. } => vec!["undo"],
+            PendingAction::OpRestore { op_id, .. } => vec!["op", "restore", op_id],
+            PendingAction::WorkspaceForget { name } => vec!["workspace", "forget", name],
+            PendingAction::SparseRemove { pattern } => vec!["sparse", "set", "--remove", pattern],
+            PendingAction::SparseReset => vec!["sparse", "reset"],
+            PendingAction::Split {
+                change_id,
+                description,
+                paths,
+            } => {
+                let mut args = vec!["split", "-r", change_id, "-m", description, "--"];
+                args.extend(paths.iter().map(String::as_str));
+                args
+            }
+            PendingAction::SquashFiles { change_id, paths } => {
+                let mut args = vec!["squash", "-r", change_id, "--"];
+                args.extend(paths.iter().map(String::as_str));
+                args
+            }
+            PendingAction::RestoreFiles { from, into, paths } => {
+                let mut args = match from {
+                    Some(from) => vec!["restore", "--from", from, "--into", into, "--"],
+                    None => vec!["restore", "--changes-in", into, "--"],
+                };
+                args.extend(paths.iter().map(String::as_str));
+                args
+            }
+            PendingAction::AbandonMarked { revisions }
+            | PendingAction::AbandonEmpty { revisions } => {
+                let mut args = vec!["abandon"];
+                args.extend(revisions.iter().map(|(change_id, _)| change_id.as_str()));
+                args
+            }
+            PendingAction::SquashMarked {
+                revisions,
+                into: (target, _),
+            } => {
+                let mut args = vec!["squash"];
+                for (change_id, _) in revisions {
+                    args.extend(["--from", change_id]);
+                }
+                args.extend(["--into", target]);
+                args
+            }
+            PendingAction::RebaseMarked {
+                revisions,
+                destination,
+            } => {
+                let mut args = vec!["rebase"];
+                for (change_id, _) in revisions {
+                    args.extend(["-r", change_id]);
+                }
+                args.extend(["-d", destination]);
+                args
+            }
+            PendingAction::RebaseSkipEmptied {
+                revisions,
+                destination,
+            } => {
+                let mut args = vec!["rebase"];
+                for (change_id, _) in revisions {
+                    args.extend(["-r", change_id]);
+                }
+                args.extend(["-d", destination, "--skip-emptied"]);
+                args
+            }
+            PendingAction::SimplifyParents { revisions } => {
+                let mut args = vec!["simplify-parents"];
+                for (change_id, _) in revisions {
+                    args.extend(["-r", change_id]);
+                }
+                args
+            }
+            PendingAction::RebaseStack { head, .. } => {
+                vec!["rebase", "-b", head, "-d", stacks::STACK_DESTINATION]
+            }
+            PendingAction::ResolveDivergence {
+                keep: (target, _),
+                others,
+                squash: true,
+            } => {
+                let mut args = vec!["squash"];
+                for (commit_id, _) in others {
+                    args.extend(["--from", commit_id]);
+                }
+                args.extend(["--into", target]);
+                args
+            }
+            PendingAction::ResolveDivergence {
+                others,
+                squash: false,
+                ..
+            } => {
+                let mut args = vec!["abandon"];
+                args.extend(others.iter().map(|(commit_id, _)| commit_id.as_str()));
+                args
+            }
+            PendingAction::Rerun { args } => return args.clone(),
+        };
+        args.into_iter().map(str::to_string).collect()
+    }
+
+    /// Shell-quoted command line the action runs (`jj rebase -r abc -d main`).
+    pub fn command_preview(&self) -> String {
+        let args = self.command_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        command_line(&args)
+    }
+
+    /// Whether the action asks for confirmation unless configured otherwise.
+    ///
+    /// Creating, editing, rebasing a single change and fetching are easy to
+    /// undo, so they run right away by default.
+    pub fn confirms_by_default(&self) -> bool {
+        !matches!(
+            self,
+            PendingAction::New { .. }
+                | PendingAction::Edit { .. }
+                | PendingAction::Rebase { .. }
+                | PendingAction::GitFetch { .. }
+        )
+    }
+
+    /// Extra lines listing what the action affects (shown below the message).
+    pub fn details(&self) -> Vec<String> {
+        match self {
+            PendingAction::RebaseMarked { revisions, .. }
+            | PendingAction::RebaseSkipEmptied { revisions, .. }
+            | PendingAction::SimplifyParents { revisions }
+            | PendingAction::RebaseStack { revisions, .. }
+            | PendingAction::AbandonMarked { revisions }
+            | PendingAction::AbandonEmpty { revisions }
+            | PendingAction::SquashMarked { revisions, .. } => revisions
+                .iter()
+                .map(|(change_id, description)| {
+                    let description = if description.is_empty() {
+                        "(no desc)"
+                    } else {
+                        description
+                    };
+                    format!("{change_id} {}", truncate_str(description, 50))
+                })
+                .collect(),
+            PendingAction::Split { paths, .. } | PendingAction::SquashFiles { paths, .. } => {
+                paths.clone()
+            }
+            PendingAction::RestoreFiles { paths, .. } => {
+                let mut lines = paths.clone();
+                lines.push("t: restore into another revision instead".to_string());
+                lines
+            }
+            PendingAction::Squash { .. } => {
+                vec!["t: squash into another commit instead".to_string()]
+            }
+            PendingAction::ResolveDivergence { others, squash, .. } => {
+                let mut lines =
+                    vec!["These commits share the change ID of the kept one:".to_string()];
+                lines.extend(others.iter().map(|(commit_id, description)| {
+                    let description = if description.is_empty() {
+                        "(no desc)"
+                    } else {
+                        description
+                    };
+                    format!("{commit_id} {}", truncate_str(description, 50))
+                }));
+                lines.push(if *squash {
+                    "t: abandon them instead (drops their changes)".to_string()
+                } else {
+                    "t: squash them into it instead (keeps their changes)".to_string()
+                });
+                lines
+            }
+            PendingAction::Undo {
+                operation: Some(op),
+            } => {
+                let mut lines = vec![
+                    format!("{} {}", op.id, op.description),
+                    format!("{} by {}", op.time, op.user),
+                ];
+                if !op.tags.is_empty() {
+                    lines.push(op.tags.clone());
+                }
+                lines
+            }
+            PendingAction::Undo { operation: None } => {
+                vec!["(could not read the operation log)".to_string()]
+            }
+            _ => Vec::new(),
         }
     }
 }
 
+/// Button focused in a confirmation dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfirmButton {
+    Yes,
+    /// Focused by default so that a stray Enter does not confirm.
+    #[default]
+    No,
+}
+
 /// Modal dialog state.
 #[derive(Debug, Clone, Default)]
 pub enum ModalState {
@@ -144,6 +975,47 @@ pub enum ModalState {
     None,
     /// Confirmation dialog for a pending action.
     Confirm(PendingAction),
+    /// Context menu of actions for a commit.
+    Menu(ContextMenu),
+    /// Dialog choosing which bookmarks to push.
+    Push(PushDialog),
+    /// Menu choosing where a new change goes.
+    Placement(PlacementMenu),
+}
+
+/// Where the log list was drawn, recorded during render.
+#[derive(Debug, Clone, Default)]
+struct LogLayout {
+    /// First screen row of the list.
+    top: u16,
+    /// Number of visible rows.
+    height: u16,
+    /// Screen rows used by each graph line (empty: one row per line).
+    line_heights: Vec<usize>,
+}
+
+/// Clickable part of a modal dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModalTarget {
+    /// The Yes button of a confirmation dialog.
+    Yes,
+    /// The No button of a confirmation dialog.
+    No,
+    /// An item of the context menu (by index).
+    MenuItem(usize),
+}
+
+/// Screen region of a modal's clickable part, recorded during render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModalRegion {
+    /// Leftmost screen column.
+    pub x: u16,
+    /// Screen row.
+    pub y: u16,
+    /// Width in columns.
+    pub width: u16,
+    /// What clicking the region does.
+    pub target: ModalTarget,
 }
 
 /// Result of a command execution.
@@ -151,54 +1023,178 @@ pub enum ModalState {
 pub struct CommandResult {
     /// Whether the command succeeded.
     pub success: bool,
-    /// Output message (stdout or stderr).
+    /// Output message (stdout and stderr; the first line is shown in the status bar).
     pub message: String,
 }
 
-/// Default batch size for loading more entries.
-const DEFAULT_BATCH_SIZE: usize = 500;
-
-/// Threshold for triggering load more (entries from end).
-const LOAD_MORE_THRESHOLD: usize = 50;
-
 /// Application state.
 pub struct App {
     /// Graph log with all lines and commit metadata.
     pub graph_log: GraphLog,
     /// Currently selected commit index (in commit_line_indices).
     pub selected: usize,
-    /// Scroll offset for the log view (line-based).
+    /// Scroll offset for the log view (in screen rows).
     pub scroll_offset: usize,
+    /// Whether long descriptions wrap onto continuation rows in the log view.
+    pub wrap_descriptions: bool,
+    /// Columns after the description in the log view.
+    log_columns: Vec<LogColumn>,
     /// Whether the app should quit.
     pub should_quit: bool,
     /// Repository root path.
     pub repo_root: String,
+    /// Whether the repository is colocated with git (`.jj` and `.git`).
+    colocated: bool,
     /// Current view mode.
     pub view: View,
+    /// Views to return to when closing the current one.
+    pub view_stack: Vec<ViewEntry>,
+    /// Base revision for compare mode (set with `c`).
+    compare_base: Option<String>,
+    /// (change_id, description) of the change being squashed while its target is picked.
+    squash_source: Option<(String, String)>,
+    /// Validated `--from` revision while the `--to` prompt is open.
+    pending_diff_from: Option<String>,
+    /// Source revision and paths while the restore target is entered.
+    pending_restore: Option<(String, Vec<String>)>,
+    /// Change IDs of marked commits.
+    marked: HashSet<String>,
+    /// Where visual (range select) mode started, as a commit index.
+    visual_anchor: Option<usize>,
+    /// Screen position of the log list from the last render (for mouse clicks).
+    log_layout: LogLayout,
+    /// Where the open modal's buttons or menu items were drawn.
+    modal_regions: Vec<ModalRegion>,
+    /// Last position of revision-specific views, keyed by view and change ID.
+    saved_positions: VecDeque<((View, String), ViewPosition)>,
     /// Detail view state.
     pub detail_state: Option<DetailState>,
+    /// Detail views a parent or child was opened from, innermost last.
+    detail_back: Vec<DetailState>,
+    /// Keyboard macros.
+    macros: macros::Macros,
     /// Diff view state.
     pub diff_state: DiffState,
+    /// Operation log view state.
+    pub op_log_state: OpLogState,
+    /// File history view state.
+    file_history: Option<FileHistoryState>,
+    /// Workspaces view state.
+    workspaces_state: WorkspacesState,
+    /// Tags view state.
+    tags_state: TagsState,
+    /// Preview pane beside the log (`v`).
+    preview: PreviewState,
+    /// Stacks view state.
+    stacks_state: StacksState,
+    /// Sparse patterns view state.
+    sparse_state: SparseState,
     /// Whether the help modal is shown.
     pub show_help: bool,
+    /// Scroll offset of the help modal.
+    help_scroll: usize,
+    /// Full output of recent commands.
+    output_pane: OutputPane,
+    /// Status bar messages shown this session (`L`).
+    messages_panel: MessagesPanel,
+    /// Panel listing every jj command run this session.
+    command_history_panel: CommandHistoryPanel,
     /// jj command runner.
     runner: JjRunner,
     /// Modal dialog state.
     pub modal: ModalState,
+    /// Focused button of the confirmation dialog.
+    pub confirm_focus: ConfirmButton,
+    /// Whether abandon/squash run without confirmation (quick mode).
+    quick_mode: bool,
+    /// Confirmation settings from the config file.
+    confirm_config: ConfirmConfig,
     /// Last command result for status display.
     pub last_command_result: Option<CommandResult>,
     /// Current input mode (if any).
     pub input_mode: Option<InputMode>,
     /// Text input buffer.
     pub input: Input,
+    /// Revset filtering the log view (None = all history).
+    revset: Option<String>,
     /// Log entry limit (None = no limit, i.e., all history).
     log_limit: Option<usize>,
+    /// Entries fetched per incremental load (None = same as log_limit).
+    batch_size: Option<usize>,
+    /// Load more entries when the selection is this close to the end.
+    load_more_threshold: usize,
     /// Whether there are more entries to load.
     pub has_more_entries: bool,
     /// Whether we are currently loading more entries.
     pub is_loading_more: bool,
     /// Whether a load-more check has been requested.
     pending_load_more: bool,
+    /// `jj log` still being read into the log (startup with `--all`).
+    log_stream: Option<LineStream>,
+    /// Auto-refresh interval (None = disabled).
+    auto_refresh_interval: Option<Duration>,
+    /// When the log was last fetched.
+    last_refresh: Instant,
+    /// Age after which the log is shown as stale (None = never).
+    stale_after: Option<Duration>,
+    /// Directory of jj's operation heads, watched for external changes.
+    op_heads_dir: Option<PathBuf>,
+    /// Operation heads as of the last log refresh.
+    known_op_heads: Option<Vec<String>>,
+    /// Whether another process changed the repository since the last refresh.
+    repo_changed: bool,
+    /// When refreshing after an external change last failed.
+    external_refresh_failed: Option<Instant>,
+    /// Refresh automatically on external changes instead of showing a banner.
+    refresh_on_external_change: bool,
+    /// When the user last pressed a key.
+    last_activity: Instant,
+    /// Cached auxiliary metadata (bookmarks, tags, conflicts).
+    metadata: MetadataCache,
+    /// Cached show and diff output of the current operation.
+    output_cache: OutputCache,
+    /// Syntax-highlight file contents in the diff view.
+    syntax_highlight: bool,
+    /// External diff tool (`diff.tool`), if configured.
+    diff_tool: Option<String>,
+    /// Protected bookmarks and the remote to push to (`[push]`).
+    push_config: PushConfig,
+    /// UI colors.
+    theme: Theme,
+    /// jj command running on a worker thread.
+    running_command: Option<RunningCommand>,
+    /// Active `/` search in the log view.
+    search: Option<SearchState>,
+    /// Open fuzzy finder (`Ctrl+P`).
+    finder: Option<FinderState>,
+    /// Whether `y` was pressed and the next key picks what to copy.
+    yank_pending: bool,
+    /// Description to edit in the external editor (taken by the event loop).
+    editor_request: Option<EditorRequest>,
+    /// Current description of the revision while the describe prompt is open.
+    describe_original: Option<String>,
+    /// Conventional commit message being built (`t`).
+    conventional_draft: Option<ConventionalDraft>,
+    /// Program to run with the terminal released (taken by the event loop).
+    external_command: Option<ExternalCommand>,
+    /// Author the log is filtered by (combined with `revset`).
+    author_filter: Option<String>,
+    /// Authors listed while the author filter is being picked.
+    author_choices: Vec<(String, usize)>,
+    /// Path the log is filtered by (combined with `revset`).
+    path_filter: Option<String>,
+    /// Placement of the change created by the open message prompt (`I`).
+    new_placement: NewPlacement,
+    /// Revision whose author is being entered (`U`).
+    pending_author_change: Option<String>,
+    /// Push waiting for its protected bookmark names to be typed.
+    protected_push: Option<ProtectedPush>,
+    /// Revsets of the elided revisions shown in the log (`E`).
+    elided_expansions: Vec<String>,
+    /// `revsets.log` of the repository, once an expansion needed it.
+    default_log_revset: Option<String>,
+    /// Revset of the hidden commits shown in the log (`h`).
+    hidden_revset: Option<String>,
 }
 
 impl App {
@@ -208,21 +1204,86 @@ impl App {
             graph_log,
             selected: 0,
             scroll_offset: 0,
+            wrap_descriptions: false,
+            log_columns: DEFAULT_LOG_COLUMNS.to_vec(),
             should_quit: false,
             repo_root,
+            colocated: false,
             view: View::default(),
+            view_stack: Vec::new(),
+            compare_base: None,
+            squash_source: None,
+            pending_diff_from: None,
+            pending_restore: None,
+            marked: HashSet::new(),
+            visual_anchor: None,
+            log_layout: LogLayout::default(),
+            modal_regions: Vec::new(),
+            saved_positions: VecDeque::new(),
             detail_state: None,
+            detail_back: Vec::new(),
+            macros: macros::Macros::default(),
             diff_state: DiffState::default(),
+            op_log_state: OpLogState::default(),
+            file_history: None,
+            workspaces_state: WorkspacesState::default(),
+            tags_state: TagsState::default(),
+            preview: PreviewState::default(),
+            stacks_state: StacksState::default(),
+            sparse_state: SparseState::default(),
             show_help: false,
+            help_scroll: 0,
+            output_pane: OutputPane::default(),
+            messages_panel: MessagesPanel::default(),
+            command_history_panel: CommandHistoryPanel::default(),
             runner,
             modal: ModalState::default(),
+            confirm_focus: ConfirmButton::default(),
+            quick_mode: false,
+            confirm_config: ConfirmConfig::default(),
             last_command_result: None,
             input_mode: None,
             input: Input::default(),
-            log_limit: Some(DEFAULT_BATCH_SIZE),
+            revset: None,
+            log_limit: Some(DEFAULT_LOG_LIMIT),
+            batch_size: None,
+            load_more_threshold: DEFAULT_LOAD_MORE_THRESHOLD,
             has_more_entries: false, // Will be set by set_log_limit
             is_loading_more: false,
+            log_stream: None,
             pending_load_more: false,
+            auto_refresh_interval: None,
+            last_refresh: Instant::now(),
+            stale_after: Some(Duration::from_secs(DEFAULT_STALE_AFTER_SECS)),
+            op_heads_dir: None,
+            known_op_heads: None,
+            repo_changed: false,
+            external_refresh_failed: None,
+            refresh_on_external_change: false,
+            last_activity: Instant::now(),
+            metadata: MetadataCache::default(),
+            output_cache: OutputCache::default(),
+            syntax_highlight: true,
+            diff_tool: None,
+            push_config: PushConfig::default(),
+            theme: Theme::default(),
+            running_command: None,
+            search: None,
+            finder: None,
+            yank_pending: false,
+            editor_request: None,
+            describe_original: None,
+            conventional_draft: None,
+            external_command: None,
+            author_filter: None,
+            author_choices: Vec::new(),
+            path_filter: None,
+            pending_author_change: None,
+            protected_push: None,
+            elided_expansions: Vec::new(),
+            hidden_revset: None,
+            default_log_revset: None,
+            new_placement: NewPlacement::default(),
         }
     }
 
@@ -231,9 +1292,39 @@ impl App {
         self.should_quit = true;
     }
 
-    /// Toggle help modal visibility.
+    /// Toggle help modal visibility (it opens scrolled to the top).
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
+        self.help_scroll = 0;
+    }
+
+    /// Get the scroll offset of the help modal.
+    pub fn help_scroll(&self) -> usize {
+        self.help_scroll
+    }
+
+    /// Scroll the help modal down.
+    pub fn help_scroll_down(&mut self, amount: usize) {
+        self.help_scroll = self.help_scroll.saturating_add(amount);
+    }
+
+    /// Scroll the help modal up.
+    pub fn help_scroll_up(&mut self, amount: usize) {
+        self.help_scroll = self.help_scroll.saturating_sub(amount);
+    }
+
+    /// Clamp the help scroll to its content (called during render).
+    pub fn clamp_help_scroll(&mut self, content_height: usize, visible_height: usize) {
+        let max_scroll = content_height.saturating_sub(visible_height);
+        self.help_scroll = self.help_scroll.min(max_scroll);
+    }
+
+    /// Toggle wrapping of long descriptions in the log view.
+    pub fn toggle_wrap_descriptions(&mut self) {
+        self.wrap_descriptions = !self.wrap_descriptions;
+        // Row-based offsets differ between the two layouts; let the next
+        // render scroll the selection back into view.
+        self.scroll_offset = 0;
     }
 
     /// Close help modal.
@@ -241,6 +1332,38 @@ impl App {
         self.show_help = false;
     }
 
+    /// Get the cached auxiliary metadata, if available.
+    pub fn metadata(&self) -> Option<&RepoMetadata> {
+        self.metadata.get()
+    }
+
+    /// Record whether the repository is colocated with git.
+    pub fn set_colocated(&mut self, colocated: bool) {
+        self.colocated = colocated;
+    }
+
+    /// Whether the repository is colocated with git.
+    pub fn is_colocated(&self) -> bool {
+        self.colocated
+    }
+
+    /// Refresh the auxiliary metadata cache.
+    ///
+    /// Failures are not fatal: metadata only decorates the views, so the
+    /// previous (possibly empty) cache is kept. The operation id it reads
+    /// also decides whether the cached show and diff output is still valid.
+    pub fn refresh_metadata(&mut self) {
+        match self.metadata.refresh(&self.runner) {
+            Ok(metadata) => self.output_cache.set_op_id(&metadata.op_id),
+            Err(_) => self.output_cache.clear(),
+        }
+    }
+
+    /// Profiler in use with `--profile`.
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.runner.profiler()
+    }
+
     /// Check if a modal is currently shown.
     pub fn is_modal_open(&self) -> bool {
         !matches!(self.modal, ModalState::None)
@@ -249,31 +1372,83 @@ impl App {
     /// Close the modal dialog without executing.
     pub fn close_modal(&mut self) {
         self.modal = ModalState::None;
+        self.confirm_focus = ConfirmButton::default();
+    }
+
+    /// Move focus to the other button of the confirmation dialog.
+    pub fn toggle_confirm_focus(&mut self) {
+        self.confirm_focus = match self.confirm_focus {
+            ConfirmButton::Yes => ConfirmButton::No,
+            ConfirmButton::No => ConfirmButton::Yes,
+        };
+    }
+
+    /// Activate the focused button of the confirmation dialog.
+    pub fn activate_confirm_focus(&mut self) -> Result<(), XorcistError> {
+        match self.confirm_focus {
+            ConfirmButton::Yes => self.confirm_action(),
+            ConfirmButton::No => {
+                self.close_modal();
+                Ok(())
+            }
+        }
     }
 
     /// Open detail view for selected entry.
     pub fn open_detail(&mut self) -> Result<(), XorcistError> {
-        if let Some(change_id) = self.selected_change_id() {
-            let show_output = fetch_show(&self.runner, change_id)?;
-            self.detail_state = Some(DetailState {
-                show_output,
-                scroll: 0,
-                content_height: 0, // Calculated during render
+        let Some(change_id) = self.selected_change_id().map(str::to_string) else {
+            return Ok(());
+        };
+        self.open_detail_for(&change_id)
+    }
+
+    /// Open detail view for a revision.
+    pub(super) fn open_detail_for(&mut self, change_id: &str) -> Result<(), XorcistError> {
+        let show_output = self.cached_show(change_id)?;
+        // Line counts are extra information; show the files without them on failure
+        let stat = self.cached_stat(change_id).unwrap_or_default();
+        let children = fetch_children(&self.runner, change_id).unwrap_or_default();
+        // The working copy's uncommitted changes are reviewed in full (like
+        // `jj diff`); `D` still hides them
+        let working_copy = self.is_working_copy(change_id);
+        let full_diff = working_copy
+            .then(|| self.fetch_detail_diff(change_id).ok())
+            .flatten()
+            .map(|diff| DetailDiff {
+                diff,
+                ..Default::default()
             });
-            self.view = View::Detail;
-        }
+        self.detail_state = Some(DetailState {
+            show_output,
+            scroll: 0,
+            content_height: 0, // Calculated during render
+            full_diff,
+            stat,
+            children,
+            related: 0,
+            working_copy,
+        });
+        self.push_view(View::Detail);
         Ok(())
     }
 
-    /// Close detail view and return to log.
+    /// Close detail view and return to the previous view.
+    ///
+    /// Going back from a parent or child opened in the detail view shows the
+    /// commit it was opened from.
     pub fn close_detail(&mut self) {
-        self.view = View::Log;
-        self.detail_state = None;
+        self.pop_view();
+        if self.view == View::Detail {
+            self.detail_state = self.detail_back.pop();
+        } else {
+            self.detail_state = None;
+            self.detail_back.clear();
+        }
     }
 
-    /// Close diff view and return to detail.
+    /// Close diff view and return to the previous view.
     pub fn close_diff(&mut self) {
-        self.view = View::Detail;
+        self.pop_view();
     }
 
     /// Scroll detail view down.
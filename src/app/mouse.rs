@@ -0,0 +1,80 @@
+//! Mouse handling for App.
+
+use crate::error::XorcistError;
+
+use super::{App, ModalRegion, ModalState, ModalTarget, View};
+
+/// Lines moved per scroll wheel step.
+const WHEEL_STEP: usize = 3;
+
+impl App {
+    /// Record where the open modal's clickable parts were drawn (called from render).
+    pub fn set_modal_regions(&mut self, regions: Vec<ModalRegion>) {
+        self.modal_regions = regions;
+    }
+
+    /// Clickable part of the open modal at screen position (`x`, `y`), if any.
+    pub fn modal_target_at(&self, x: u16, y: u16) -> Option<ModalTarget> {
+        self.modal_regions
+            .iter()
+            .find(|r| r.y == y && x >= r.x && x < r.x + r.width)
+            .map(|r| r.target)
+    }
+
+    /// Handle a left click while a modal is open.
+    ///
+    /// Clicking a button or menu item activates it, clicking a bookmark in
+    /// the push dialog checks it; clicking outside a menu or dialog closes it.
+    pub fn click_modal(&mut self, x: u16, y: u16) -> Result<(), XorcistError> {
+        match (self.modal_target_at(x, y), &mut self.modal) {
+            (Some(ModalTarget::Yes), ModalState::Confirm(_)) => self.confirm_action()?,
+            (Some(ModalTarget::No), ModalState::Confirm(_)) => self.close_modal(),
+            (Some(ModalTarget::MenuItem(idx)), ModalState::Menu(menu)) => {
+                menu.selected = idx;
+                self.activate_menu_item()?;
+            }
+            (Some(ModalTarget::MenuItem(idx)), ModalState::Push(dialog)) => {
+                dialog.selected = idx;
+                dialog.toggle_selected();
+            }
+            (Some(ModalTarget::MenuItem(idx)), ModalState::Placement(menu)) => {
+                menu.selected = idx;
+                self.activate_placement()?;
+            }
+            (None, ModalState::Menu(_) | ModalState::Push(_) | ModalState::Placement(_)) => {
+                self.close_modal()
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Scroll the current view with the mouse wheel (`down` = towards the end).
+    ///
+    /// The log, operation log, file history, workspaces, tags, stacks and sparse patterns move their selection; the detail and
+    /// diff views scroll their text.
+    pub fn scroll_wheel(&mut self, down: bool) {
+        for _ in 0..WHEEL_STEP {
+            match (self.view, down) {
+                (View::Log, true) => self.select_next(),
+                (View::Log, false) => self.select_previous(),
+                (View::Detail, true) => self.detail_scroll_down(1),
+                (View::Detail, false) => self.detail_scroll_up(1),
+                (View::Diff, true) => self.diff_scroll_down(1),
+                (View::Diff, false) => self.diff_scroll_up(1),
+                (View::OpLog, true) => self.op_log_select_next(),
+                (View::OpLog, false) => self.op_log_select_previous(),
+                (View::FileHistory, true) => self.file_history_select_next(),
+                (View::FileHistory, false) => self.file_history_select_previous(),
+                (View::Workspaces, true) => self.workspaces_select_next(),
+                (View::Workspaces, false) => self.workspaces_select_previous(),
+                (View::Tags, true) => self.tags_select_next(),
+                (View::Tags, false) => self.tags_select_previous(),
+                (View::Stacks, true) => self.stacks_select_next(),
+                (View::Stacks, false) => self.stacks_select_previous(),
+                (View::Sparse, true) => self.sparse_select_next(),
+                (View::Sparse, false) => self.sparse_select_previous(),
+            }
+        }
+    }
+}
@@ -2,7 +2,10 @@
 
 use unicode_width::UnicodeWidthStr;
 
-use super::App;
+use crate::error::XorcistError;
+use crate::jj::resolve_revisions;
+
+use super::{App, CommandResult, LogLayout};
 
 impl App {
     /// Get the number of commits in the log.
@@ -25,6 +28,103 @@ impl App {
         self.graph_log.change_id_for_selection(self.selected)
     }
 
+    /// Move the selection to the commit with the given change_id.
+    ///
+    /// Returns `false` (leaving the selection unchanged) if it is not loaded.
+    pub fn select_change_id(&mut self, change_id: &str) -> bool {
+        let found = (0..self.commit_count())
+            .find(|&i| self.graph_log.change_id_for_selection(i) == Some(change_id));
+        match found {
+            Some(idx) => {
+                self.selected = idx;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the selection to the parent of the selected commit (`[`).
+    pub fn select_parent(&mut self) -> Result<(), XorcistError> {
+        self.select_related("parents", "parent")
+    }
+
+    /// Move the selection to the child of the selected commit (`]`).
+    pub fn select_child(&mut self) -> Result<(), XorcistError> {
+        self.select_related("children", "child")
+    }
+
+    /// Move the selection to the `trunk()` commit (`J`).
+    pub fn select_trunk(&mut self) {
+        let Some(trunk) = self
+            .metadata()
+            .and_then(|meta| meta.trunk_change_id())
+            .map(str::to_string)
+        else {
+            self.report(CommandResult {
+                success: false,
+                message: "No trunk() commit (set revset-aliases.\"trunk()\" in jj's config)"
+                    .to_string(),
+            });
+            return;
+        };
+        match self.nearest_loaded(std::slice::from_ref(&trunk)) {
+            Some(idx) => self.selected = idx,
+            None => {
+                let short: String = trunk.chars().take(8).collect();
+                self.report(CommandResult {
+                    success: false,
+                    message: format!("trunk() ({short}) is not loaded in the log"),
+                });
+            }
+        }
+    }
+
+    /// Whether `change_id` (as shown in the log) is the `trunk()` commit.
+    pub fn is_trunk(&self, change_id: &str) -> bool {
+        self.metadata()
+            .and_then(|meta| meta.trunk_change_id())
+            .is_some_and(|trunk| trunk.starts_with(change_id))
+    }
+
+    /// Follow the graph from the selected commit to one of `function(…)`
+    /// (`parents` or `children`), which may be on another branch than the
+    /// neighbouring row.
+    fn select_related(&mut self, function: &str, noun: &str) -> Result<(), XorcistError> {
+        let Some(change_id) = self.selected_change_id() else {
+            return Ok(());
+        };
+        let related = resolve_revisions(&self.runner, &format!("{function}({change_id})"))?;
+        let (success, message) = match self.nearest_loaded(&related) {
+            Some(idx) => {
+                self.selected = idx;
+                if related.len() == 1 {
+                    return Ok(());
+                }
+                (true, format!("Nearest of {} {function}", related.len()))
+            }
+            None if related.is_empty() => (false, format!("No {noun} to go to")),
+            None => (false, format!("The {noun} is not loaded in the log")),
+        };
+        self.report(CommandResult { success, message });
+        Ok(())
+    }
+
+    /// Index of the loaded commit among `change_ids` closest to the selection.
+    ///
+    /// The ids may be longer than the ones shown in the log, so they are
+    /// matched by prefix.
+    pub(super) fn nearest_loaded(&self, change_ids: &[String]) -> Option<usize> {
+        (0..self.commit_count())
+            .filter(|&i| {
+                self.graph_log.change_id_for_selection(i).is_some_and(|id| {
+                    change_ids
+                        .iter()
+                        .any(|other| other.starts_with(id) || id.starts_with(other.as_str()))
+                })
+            })
+            .min_by_key(|&i| i.abs_diff(self.selected))
+    }
+
     /// Ensure the selected line is visible in the viewport.
     pub fn ensure_selected_visible(&mut self, viewport_height: usize) {
         if let Some(line_idx) = self.selected_line_index() {
@@ -39,6 +139,80 @@ impl App {
         }
     }
 
+    /// Ensure the selected commit is visible when lines span several rows.
+    ///
+    /// `line_heights[i]` is the number of screen rows used by graph line `i`,
+    /// and `scroll_offset` is measured in rows. All rows of the selected
+    /// commit are brought into view (its first row if it is taller than the
+    /// viewport).
+    pub fn ensure_selected_visible_rows(&mut self, viewport_height: usize, line_heights: &[usize]) {
+        let Some(line_idx) = self.selected_line_index() else {
+            return;
+        };
+        let top: usize = line_heights.iter().take(line_idx).sum();
+        let height = line_heights.get(line_idx).copied().unwrap_or(1);
+        let bottom = top + height;
+
+        if top < self.scroll_offset {
+            self.scroll_offset = top;
+        } else if bottom > self.scroll_offset + viewport_height {
+            self.scroll_offset = bottom.saturating_sub(viewport_height).min(top);
+        }
+    }
+
+    /// Record where the log list was drawn so that clicks can be mapped to lines.
+    ///
+    /// Empty `line_heights` means every graph line uses one row.
+    pub fn set_log_layout(&mut self, top: u16, height: u16, line_heights: Vec<usize>) {
+        self.log_layout = LogLayout {
+            top,
+            height,
+            line_heights,
+        };
+    }
+
+    /// Select the commit drawn at screen row `y` of the log list.
+    ///
+    /// Clicking a graph-only line selects the commit above it. Returns
+    /// `false` if the row is outside the list or above the first commit.
+    pub fn select_at_row(&mut self, y: u16) -> bool {
+        let Some(line_idx) = self.line_at_row(y) else {
+            return false;
+        };
+        let commit_lines = &self.graph_log.commit_line_indices;
+        let selection = match commit_lines.binary_search(&line_idx) {
+            Ok(selection) => selection,
+            Err(0) => return false,
+            Err(next) => next - 1,
+        };
+        self.selected = selection;
+        true
+    }
+
+    /// Elided-revisions line drawn at screen row `y` of the log list, if any.
+    pub fn elided_line_at_row(&self, y: u16) -> Option<usize> {
+        self.line_at_row(y)
+            .filter(|&line_idx| self.graph_log.lines[line_idx].is_elided())
+    }
+
+    /// Index of the graph line drawn at screen row `y` of the log list.
+    fn line_at_row(&self, y: u16) -> Option<usize> {
+        let layout = &self.log_layout;
+        if y < layout.top || y >= layout.top + layout.height {
+            return None;
+        }
+        let row = self.scroll_offset + usize::from(y - layout.top);
+        if layout.line_heights.is_empty() {
+            Some(row).filter(|&row| row < self.graph_log.lines.len())
+        } else {
+            let mut row_top = 0;
+            layout.line_heights.iter().position(|&height| {
+                row_top += height;
+                row < row_top
+            })
+        }
+    }
+
     /// Move selection down.
     pub fn select_next(&mut self) {
         let count = self.commit_count();
@@ -111,7 +285,7 @@ impl App {
 
     /// Clamp diff scroll to valid range.
     pub fn clamp_diff_scroll(&mut self, visible_height: usize) {
-        let content_height = self.diff_state.diff_lines.len();
+        let content_height = self.diff_state.diff.line_count();
         let max_scroll = content_height.saturating_sub(visible_height);
         if self.diff_state.diff_scroll > max_scroll {
             self.diff_state.diff_scroll = max_scroll;
@@ -130,12 +304,13 @@ impl App {
 
     /// Clamp horizontal diff scroll to valid range.
     pub fn clamp_diff_h_scroll(&mut self, visible_width: usize) {
-        // Use display width (unicode_width) instead of byte length for correct CJK handling
+        // Use display width (unicode_width) instead of byte length for correct CJK handling.
+        // Color codes take no space on screen, so measure the plain text.
         let max_line_width = self
             .diff_state
-            .diff_lines
-            .iter()
-            .map(|l| l.width())
+            .diff
+            .lines()
+            .map(|l| l.text.width())
             .max()
             .unwrap_or(0);
         let max_scroll = max_line_width.saturating_sub(visible_width);
@@ -0,0 +1,97 @@
+//! Operation log view methods for App.
+
+use crate::error::XorcistError;
+use crate::jj::{DEFAULT_OP_LOG_LIMIT, OpLogEntry, fetch_op_log};
+
+use super::{App, CommandResult, OpLogState, PendingAction, View};
+
+impl App {
+    /// Open the operation log view with the current operation selected.
+    pub fn open_op_log(&mut self) -> Result<(), XorcistError> {
+        let entries = fetch_op_log(&self.runner, DEFAULT_OP_LOG_LIMIT)?;
+        let selected = entries.iter().position(|e| e.is_current).unwrap_or(0);
+        self.op_log_state = OpLogState {
+            entries,
+            selected,
+            scroll: 0,
+        };
+        self.push_view(View::OpLog);
+        Ok(())
+    }
+
+    /// Close the operation log view.
+    pub fn close_op_log(&mut self) {
+        self.pop_view();
+    }
+
+    /// Re-fetch the operation log, keeping the selected operation if it still exists.
+    pub fn refresh_op_log(&mut self) -> Result<(), XorcistError> {
+        let entries = fetch_op_log(&self.runner, DEFAULT_OP_LOG_LIMIT)?;
+        let state = &mut self.op_log_state;
+        let selected_id = state.entries.get(state.selected).map(|e| e.id.clone());
+        state.selected = selected_id
+            .and_then(|id| entries.iter().position(|e| e.id == id))
+            .unwrap_or_else(|| state.selected.min(entries.len().saturating_sub(1)));
+        state.entries = entries;
+        Ok(())
+    }
+
+    /// Get the selected operation.
+    pub fn selected_operation(&self) -> Option<&OpLogEntry> {
+        self.op_log_state.entries.get(self.op_log_state.selected)
+    }
+
+    /// Select the next (older) operation.
+    pub fn op_log_select_next(&mut self) {
+        let state = &mut self.op_log_state;
+        if state.selected + 1 < state.entries.len() {
+            state.selected += 1;
+        }
+    }
+
+    /// Select the previous (newer) operation.
+    pub fn op_log_select_previous(&mut self) {
+        self.op_log_state.selected = self.op_log_state.selected.saturating_sub(1);
+    }
+
+    /// Select the newest operation.
+    pub fn op_log_select_first(&mut self) {
+        self.op_log_state.selected = 0;
+    }
+
+    /// Select the oldest loaded operation.
+    pub fn op_log_select_last(&mut self) {
+        self.op_log_state.selected = self.op_log_state.entries.len().saturating_sub(1);
+    }
+
+    /// Adjust the scroll offset so the selected operation is visible.
+    pub fn ensure_op_log_visible(&mut self, visible_height: usize) {
+        let state = &mut self.op_log_state;
+        if visible_height == 0 {
+            return;
+        }
+        if state.selected < state.scroll {
+            state.scroll = state.selected;
+        } else if state.selected >= state.scroll + visible_height {
+            state.scroll = state.selected + 1 - visible_height;
+        }
+    }
+
+    /// Show confirmation dialog for restoring the selected operation.
+    pub fn show_op_restore_confirm(&mut self) {
+        let Some(entry) = self.selected_operation() else {
+            return;
+        };
+        if entry.is_current {
+            self.report(CommandResult {
+                success: false,
+                message: "Already at this operation".to_string(),
+            });
+            return;
+        }
+        self.ask_confirm(PendingAction::OpRestore {
+            op_id: entry.id.clone(),
+            description: entry.description.clone(),
+        });
+    }
+}
@@ -0,0 +1,96 @@
+//! Output pane with the full output of recent commands for App.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::error::XorcistError;
+
+use super::{App, CommandResult};
+
+/// Number of commands kept in the output pane.
+pub const OUTPUT_HISTORY_LEN: usize = 20;
+
+/// Full output of a finished command.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    /// Command that ran (e.g., `jj git push`).
+    pub label: String,
+    /// Whether the command succeeded.
+    pub success: bool,
+    /// Everything the command printed (or the error that stopped it).
+    pub output: String,
+    /// When the command finished.
+    pub finished: Instant,
+}
+
+/// State of the output pane.
+#[derive(Debug, Clone, Default)]
+pub struct OutputPane {
+    /// Recent commands, newest first.
+    pub entries: VecDeque<CommandOutput>,
+    /// Whether the pane is shown.
+    pub visible: bool,
+    /// Vertical scroll offset (in lines).
+    pub scroll: usize,
+}
+
+impl App {
+    /// Record the output of a finished command for the output pane.
+    pub(super) fn record_command_output(
+        &mut self,
+        label: &str,
+        result: &Result<CommandResult, XorcistError>,
+    ) {
+        let (success, output) = match result {
+            Ok(result) => (result.success, result.message.clone()),
+            Err(e) => (false, e.to_string()),
+        };
+        let entries = &mut self.output_pane.entries;
+        entries.push_front(CommandOutput {
+            label: label.to_string(),
+            success,
+            output,
+            finished: Instant::now(),
+        });
+        entries.truncate(OUTPUT_HISTORY_LEN);
+    }
+
+    /// Get the output pane state.
+    pub fn output_pane(&self) -> &OutputPane {
+        &self.output_pane
+    }
+
+    /// Show or hide the output pane (it opens scrolled to the newest command).
+    pub fn toggle_output_pane(&mut self) {
+        let pane = &mut self.output_pane;
+        pane.visible = !pane.visible;
+        pane.scroll = 0;
+    }
+
+    /// Show the output pane scrolled to the newest command.
+    pub(super) fn open_output_pane(&mut self) {
+        self.output_pane.visible = true;
+        self.output_pane.scroll = 0;
+    }
+
+    /// Whether the output pane is shown.
+    pub fn is_output_pane_open(&self) -> bool {
+        self.output_pane.visible
+    }
+
+    /// Scroll the output pane down.
+    pub fn output_scroll_down(&mut self, amount: usize) {
+        self.output_pane.scroll = self.output_pane.scroll.saturating_add(amount);
+    }
+
+    /// Scroll the output pane up.
+    pub fn output_scroll_up(&mut self, amount: usize) {
+        self.output_pane.scroll = self.output_pane.scroll.saturating_sub(amount);
+    }
+
+    /// Clamp the output pane scroll to its content (called during render).
+    pub fn clamp_output_scroll(&mut self, content_height: usize, visible_height: usize) {
+        let max_scroll = content_height.saturating_sub(visible_height);
+        self.output_pane.scroll = self.output_pane.scroll.min(max_scroll);
+    }
+}
@@ -0,0 +1,243 @@
+//! Command palette (`Ctrl+Shift+P` / `Alt+P`): every log view action by name, for App.
+
+use crate::error::XorcistError;
+use crate::theme::{PRESET_NAMES, Theme};
+
+use super::{App, FinderState, InputMode};
+
+/// An action listed in the command palette.
+#[derive(Debug, Clone, Copy)]
+pub struct PaletteEntry {
+    /// Name the palette is filtered by.
+    pub name: &'static str,
+    /// Key that runs the action directly in the log view (empty if none).
+    pub key: &'static str,
+    run: fn(&mut App) -> Result<(), XorcistError>,
+}
+
+impl PaletteEntry {
+    const fn new(
+        name: &'static str,
+        key: &'static str,
+        run: fn(&mut App) -> Result<(), XorcistError>,
+    ) -> Self {
+        Self { name, key, run }
+    }
+}
+
+/// Every action of the palette, in the order listed for an empty query.
+pub const PALETTE_ENTRIES: &[PaletteEntry] = &[
+    PaletteEntry::new("Show details", "Enter", |app| app.open_detail()),
+    PaletteEntry::new("Describe", "d", |app| app.start_describe()),
+    PaletteEntry::new("Describe with a conventional message", "t", |app| {
+        app.start_conventional_describe();
+        Ok(())
+    }),
+    PaletteEntry::new("Set author", "U", |app| {
+        app.start_set_author();
+        Ok(())
+    }),
+    PaletteEntry::new("Describe in $EDITOR", "D", |app| {
+        app.start_external_describe()
+    }),
+    PaletteEntry::new("New change", "n", |app| app.execute_new()),
+    PaletteEntry::new("Insert new change (after / before)", "i", |app| {
+        app.open_placement_menu(false);
+        Ok(())
+    }),
+    PaletteEntry::new("New change with message", "N", |app| {
+        app.start_input_mode(InputMode::NewWithMessage);
+        Ok(())
+    }),
+    PaletteEntry::new("Commit working copy", "M", |app| {
+        app.start_input_mode(InputMode::Commit);
+        Ok(())
+    }),
+    PaletteEntry::new("Edit (check out)", "e", |app| app.execute_edit()),
+    PaletteEntry::new("Set bookmark", "b", |app| {
+        app.start_input_mode(InputMode::BookmarkSet);
+        Ok(())
+    }),
+    PaletteEntry::new("Rebase", "r", |app| {
+        app.start_input_mode(InputMode::RebaseDestination);
+        Ok(())
+    }),
+    PaletteEntry::new("Rebase, abandoning commits left empty", "", |app| {
+        app.start_input_mode(InputMode::RebaseSkipEmptiedDestination);
+        Ok(())
+    }),
+    PaletteEntry::new("Simplify parents (remove redundant edges)", "", |app| {
+        app.show_simplify_parents_confirm()
+    }),
+    PaletteEntry::new("Duplicate", "Y", |app| {
+        app.start_input_mode(InputMode::DuplicateDestination);
+        Ok(())
+    }),
+    PaletteEntry::new("Abandon", "a", |app| app.show_abandon_confirm()),
+    PaletteEntry::new("Abandon empty commits", "X", |app| {
+        app.show_abandon_empty_confirm()
+    }),
+    PaletteEntry::new("Squash into parent", "s", |app| app.show_squash_confirm()),
+    PaletteEntry::new("Squash into another revision", "S", |app| {
+        app.start_squash_target_pick();
+        Ok(())
+    }),
+    PaletteEntry::new("Undo last operation", "u", |app| {
+        app.show_undo_confirm();
+        Ok(())
+    }),
+    PaletteEntry::new("Sign revision", "", |app| {
+        app.execute_sign();
+        Ok(())
+    }),
+    PaletteEntry::new("Unsign revision", "", |app| {
+        app.execute_unsign();
+        Ok(())
+    }),
+    PaletteEntry::new("Git fetch", "f", |app| app.execute_git_fetch()),
+    PaletteEntry::new("Git push (choose bookmarks)", "p", |app| {
+        app.open_push_dialog();
+        Ok(())
+    }),
+    PaletteEntry::new("Push this change", "", |app| app.show_push_change_confirm()),
+    PaletteEntry::new("Resolve divergence (keep this commit)", "", |app| {
+        app.show_resolve_divergence_confirm()
+    }),
+    PaletteEntry::new("Export patch", "P", |app| {
+        app.start_patch_export();
+        Ok(())
+    }),
+    PaletteEntry::new("Export log to file", ":export", |app| {
+        app.start_input_mode_with(InputMode::Command, "export ");
+        Ok(())
+    }),
+    PaletteEntry::new("Filter log by revset", ":revset", |app| {
+        app.start_input_mode_with(InputMode::Command, "revset ");
+        Ok(())
+    }),
+    PaletteEntry::new("Open commit on forge", "B", |app| app.open_on_forge()),
+    PaletteEntry::new("Compare with base", "c", |app| app.compare_selected()),
+    PaletteEntry::new("Diff the two marked commits", "", |app| {
+        app.diff_marked_pair()
+    }),
+    PaletteEntry::new("Diff two revisions", "C", |app| {
+        app.start_diff_prompts();
+        Ok(())
+    }),
+    PaletteEntry::new("Mark / unmark", "Space", |app| {
+        app.toggle_mark();
+        Ok(())
+    }),
+    PaletteEntry::new("Clear marks", "Esc", |app| {
+        app.clear_marks();
+        Ok(())
+    }),
+    PaletteEntry::new("Search", "/", |app| {
+        app.start_search();
+        Ok(())
+    }),
+    PaletteEntry::new("Find commit", "Ctrl+p", |app| {
+        app.open_finder();
+        Ok(())
+    }),
+    PaletteEntry::new("Jump to parent", "[", |app| app.select_parent()),
+    PaletteEntry::new("Jump to child", "]", |app| app.select_child()),
+    PaletteEntry::new("Jump to trunk", "J", |app| {
+        app.select_trunk();
+        Ok(())
+    }),
+    PaletteEntry::new("Operation log", "O", |app| app.open_op_log()),
+    PaletteEntry::new("Workspaces", "W", |app| app.open_workspaces()),
+    PaletteEntry::new("Tags", "T", |app| app.open_tags()),
+    PaletteEntry::new("Stacks", "K", |app| app.open_stacks()),
+    PaletteEntry::new("Sparse patterns", "Z", |app| app.open_sparse()),
+    PaletteEntry::new("Filter by author", "A", |app| {
+        app.open_author_filter();
+        Ok(())
+    }),
+    PaletteEntry::new("Filter by path", "F", |app| {
+        app.start_path_filter();
+        Ok(())
+    }),
+    PaletteEntry::new("Expand elided revisions", "E", |app| app.expand_elided()),
+    PaletteEntry::new("Show / hide hidden commits", "h", |app| {
+        app.toggle_hidden_commits()
+    }),
+    PaletteEntry::new("Update stale workspace", "", |app| {
+        app.execute_workspace_update_stale();
+        Ok(())
+    }),
+    PaletteEntry::new("Open a shell in the repository", "$", |app| {
+        app.start_shell();
+        Ok(())
+    }),
+    PaletteEntry::new("Command history", "H", |app| {
+        app.toggle_command_history();
+        Ok(())
+    }),
+    PaletteEntry::new("Command output", "o", |app| {
+        app.toggle_output_pane();
+        Ok(())
+    }),
+    PaletteEntry::new("Toggle quick mode", "!", |app| {
+        app.toggle_quick_mode();
+        Ok(())
+    }),
+    PaletteEntry::new("Toggle preview pane (detail, diff)", "v", |app| {
+        app.cycle_preview();
+        Ok(())
+    }),
+    PaletteEntry::new("Toggle description wrapping", "w", |app| {
+        app.toggle_wrap_descriptions();
+        Ok(())
+    }),
+    PaletteEntry::new("Toggle relative / absolute timestamps", "Ctrl+t", |app| {
+        app.toggle_timestamp_format()
+    }),
+    PaletteEntry::new("Switch color theme", "", |app| {
+        app.cycle_theme();
+        Ok(())
+    }),
+    PaletteEntry::new("Refresh", "R", |app| app.reload_all()),
+    PaletteEntry::new("Help", "?", |app| {
+        app.toggle_help();
+        Ok(())
+    }),
+    PaletteEntry::new("Quit", "q", |app| {
+        app.quit();
+        Ok(())
+    }),
+];
+
+impl App {
+    /// Open the command palette, listing every action.
+    pub fn open_palette(&mut self) {
+        self.start_input_mode(InputMode::Palette);
+        self.finder = Some(FinderState::default());
+        self.update_finder();
+    }
+
+    /// Run the highlighted action and close the palette.
+    pub(super) fn submit_palette(&mut self) -> Result<(), XorcistError> {
+        let Some(finder) = self.finder.take() else {
+            return Ok(());
+        };
+        match finder.results.get(finder.selected) {
+            Some(&entry) => (PALETTE_ENTRIES[entry].run)(self),
+            None => Ok(()),
+        }
+    }
+
+    /// Switch to the next color preset (dark, light, solarized).
+    ///
+    /// A theme with overridden colors starts over at the first preset.
+    pub fn cycle_theme(&mut self) {
+        let current = PRESET_NAMES
+            .iter()
+            .position(|name| Theme::preset(name) == Some(self.theme));
+        let next = current.map_or(0, |i| (i + 1) % PRESET_NAMES.len());
+        if let Some(theme) = Theme::preset(PRESET_NAMES[next]) {
+            self.theme = theme;
+        }
+    }
+}
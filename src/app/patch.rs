@@ -0,0 +1,104 @@
+//! Exporting revisions as patch files (`P`, `:patch`), for App.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::XorcistError;
+use crate::export::{format_patch, patch_file_name};
+use crate::jj::{JjRunner, fetch_patch};
+
+use super::{App, CommandResult, InputMode};
+
+/// Directory suggested for a patch series.
+const DEFAULT_SERIES_DIR: &str = "patches/";
+
+impl App {
+    /// Prompt for where to write the selected commit's patch, or the
+    /// directory for the marked commits' patch series.
+    pub fn start_patch_export(&mut self) {
+        let revisions = self.patch_revisions();
+        let initial = match revisions.len() {
+            0 => return,
+            1 => patch_file_name(1, &self.selected_description().unwrap_or_default()),
+            _ => DEFAULT_SERIES_DIR.to_string(),
+        };
+        self.start_input_mode_with(InputMode::PatchPath, &initial);
+    }
+
+    /// Revisions to export: the marked commits (oldest first, so the series
+    /// applies in order), or the selected commit.
+    pub(super) fn patch_revisions(&self) -> Vec<String> {
+        if self.has_marks() {
+            let mut revisions = self.marked_change_ids();
+            revisions.reverse();
+            revisions
+        } else {
+            self.selected_change_id()
+                .map(|id| vec![id.to_string()])
+                .unwrap_or_default()
+        }
+    }
+
+    /// Write the patches to the entered path.
+    pub(super) fn submit_patch_path(&mut self, path: &str) {
+        let path = path.trim();
+        if !path.is_empty() {
+            self.export_patches_to(Path::new(path));
+        }
+    }
+
+    /// Write the selected commit's patch to `path`, or the marked commits'
+    /// patch series into the directory `path` (`0001-<subject>.patch`, ...),
+    /// and report the result in the status bar.
+    ///
+    /// A single patch written to a directory gets its generated name too.
+    pub fn export_patches_to(&mut self, path: &Path) {
+        let revisions = self.patch_revisions();
+        if revisions.is_empty() {
+            return;
+        }
+        let result = match write_patches(&self.runner, &revisions, path) {
+            Ok(written) if revisions.len() == 1 => CommandResult {
+                success: true,
+                message: format!("Wrote patch to {}", written.display()),
+            },
+            Ok(written) => CommandResult {
+                success: true,
+                message: format!("Wrote {} patches to {}", revisions.len(), written.display()),
+            },
+            Err(e) => CommandResult {
+                success: false,
+                message: format!("Failed to write patches to {}: {e}", path.display()),
+            },
+        };
+        self.report(result);
+    }
+}
+
+/// Write the patches of `revisions` and return the file (single patch) or
+/// directory (series) written to.
+fn write_patches(
+    runner: &JjRunner,
+    revisions: &[String],
+    path: &Path,
+) -> Result<PathBuf, XorcistError> {
+    let total = revisions.len();
+    let into_dir = total > 1 || path.is_dir() || path.to_string_lossy().ends_with('/');
+    if into_dir {
+        fs::create_dir_all(path)?;
+    }
+    let mut written = path.to_path_buf();
+    for (i, revision) in revisions.iter().enumerate() {
+        let patch = fetch_patch(runner, revision)?;
+        let file = if into_dir {
+            path.join(patch_file_name(i + 1, &patch.description))
+        } else {
+            path.to_path_buf()
+        };
+        fs::write(&file, format_patch(&patch, i + 1, total))?;
+        if total == 1 {
+            written = file;
+        }
+    }
+    Ok(written)
+}
@@ -0,0 +1,45 @@
+//! Path filter (`F`): restrict the log to commits touching a path, for App.
+//!
+//! The path is a fileset, like the paths of `jj log <path>`, and combined
+//! with the other filters as `files("path")`.
+
+use crate::error::XorcistError;
+
+use super::{App, InputMode};
+
+/// Revset matching the commits that touch `path` (a file or a directory).
+pub fn path_revset(path: &str) -> String {
+    let quoted = path.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("files(\"{quoted}\")")
+}
+
+impl App {
+    /// Open the path prompt, prefilled with the current path filter.
+    pub fn start_path_filter(&mut self) {
+        let initial = self.path_filter.clone().unwrap_or_default();
+        self.start_input_mode_with(InputMode::PathFilter, &initial);
+    }
+
+    /// Filter the log by the typed path (empty clears the filter).
+    pub(super) fn submit_path_filter(&mut self, path: &str) -> Result<(), XorcistError> {
+        let path = path.trim();
+        self.set_path_filter((!path.is_empty()).then(|| path.to_string()))
+    }
+
+    /// Path the log is filtered by, if any.
+    pub fn path_filter(&self) -> Option<&str> {
+        self.path_filter.as_deref()
+    }
+
+    /// Filter the log by a path (None = every path) and reload it.
+    ///
+    /// As with [`App::set_revset`], a failure keeps the previous filter.
+    pub fn set_path_filter(&mut self, path: Option<String>) -> Result<(), XorcistError> {
+        let previous = std::mem::replace(&mut self.path_filter, path);
+        let reloaded = self.reload_filtered_log();
+        if !matches!(reloaded, Ok(true)) {
+            self.path_filter = previous;
+        }
+        reloaded.map(|_| ())
+    }
+}
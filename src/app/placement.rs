@@ -0,0 +1,130 @@
+//! Placement menu for new changes (`i` / `I`), for App.
+//!
+//! Besides on top of the selected commit, a new change can be inserted
+//! between it and its children or between it and its parents, like
+//! `jj new --insert-after` / `--insert-before`.
+
+use crate::error::XorcistError;
+
+use super::{App, InputMode, ModalState, PendingAction};
+
+/// Where a new change goes, relative to the selected commit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NewPlacement {
+    /// On top of the commit (`jj new <rev>`).
+    #[default]
+    OnTop,
+    /// Between the commit and its children (`jj new --insert-after <rev>`).
+    InsertAfter,
+    /// Between the commit and its parents (`jj new --insert-before <rev>`).
+    InsertBefore,
+}
+
+impl NewPlacement {
+    /// All placements, in menu order.
+    pub const ALL: [NewPlacement; 3] = [
+        NewPlacement::OnTop,
+        NewPlacement::InsertAfter,
+        NewPlacement::InsertBefore,
+    ];
+
+    /// Menu label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            NewPlacement::OnTop => "On top (new head)",
+            NewPlacement::InsertAfter => "Insert after (children move onto it)",
+            NewPlacement::InsertBefore => "Insert before (it moves onto the new change)",
+        }
+    }
+
+    /// Shortcut key that picks the placement inside the menu.
+    pub fn key(&self) -> char {
+        match self {
+            NewPlacement::OnTop => 'n',
+            NewPlacement::InsertAfter => 'a',
+            NewPlacement::InsertBefore => 'b',
+        }
+    }
+
+    /// `jj new` flag placing the change (None = on top).
+    pub fn flag(&self) -> Option<&'static str> {
+        match self {
+            NewPlacement::OnTop => None,
+            NewPlacement::InsertAfter => Some("--insert-after"),
+            NewPlacement::InsertBefore => Some("--insert-before"),
+        }
+    }
+}
+
+/// State of an open placement menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlacementMenu {
+    /// Change the new change is placed next to.
+    pub change_id: String,
+    /// Whether a message is asked for after picking (`I`).
+    pub with_message: bool,
+    /// Highlighted placement.
+    pub selected: usize,
+}
+
+impl PlacementMenu {
+    /// Move the highlight down (wrapping).
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % NewPlacement::ALL.len();
+    }
+
+    /// Move the highlight up (wrapping).
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + NewPlacement::ALL.len() - 1) % NewPlacement::ALL.len();
+    }
+}
+
+impl App {
+    /// Open the placement menu for a new change next to the selected commit.
+    pub fn open_placement_menu(&mut self, with_message: bool) {
+        let Some(change_id) = self.selected_change_id() else {
+            return;
+        };
+        self.modal = ModalState::Placement(PlacementMenu {
+            change_id: change_id.to_string(),
+            with_message,
+            selected: 0,
+        });
+    }
+
+    /// Get the open placement menu, if any.
+    pub fn placement_menu_mut(&mut self) -> Option<&mut PlacementMenu> {
+        match &mut self.modal {
+            ModalState::Placement(menu) => Some(menu),
+            _ => None,
+        }
+    }
+
+    /// Pick the highlighted placement.
+    pub fn activate_placement(&mut self) -> Result<(), XorcistError> {
+        let ModalState::Placement(menu) = &self.modal else {
+            return Ok(());
+        };
+        let placement = NewPlacement::ALL[menu.selected % NewPlacement::ALL.len()];
+        self.pick_placement(placement)
+    }
+
+    /// Close the menu and create the new change with a placement: right
+    /// away, or after the message prompt when the menu was opened with `I`.
+    pub fn pick_placement(&mut self, placement: NewPlacement) -> Result<(), XorcistError> {
+        let ModalState::Placement(menu) = std::mem::take(&mut self.modal) else {
+            return Ok(());
+        };
+        self.close_modal();
+        if menu.with_message {
+            self.new_placement = placement;
+            self.start_input_mode(InputMode::NewWithMessage);
+            return Ok(());
+        }
+        self.request_confirm(PendingAction::New {
+            change_id: menu.change_id,
+            message: String::new(),
+            placement,
+        })
+    }
+}
@@ -0,0 +1,350 @@
+//! Preview pane beside the log (`v`) for App.
+//!
+//! The pane shows the detail or the full diff of the selected commit and
+//! follows the selection, so commits can be browsed without opening and
+//! closing the detail view for each one.
+//!
+//! Holding `j` would otherwise run `jj show` for every commit passed, so a
+//! commit is only fetched once the selection has rested on it for
+//! [`PREVIEW_DEBOUNCE`], on a worker thread. Moving on while a fetch runs
+//! drops its result. Commits whose outputs are in the output cache are shown
+//! at once.
+
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::XorcistError;
+use crate::jj::{
+    CacheKind, CachedOutput, DiffStat, GitDiff, JjRunner, ShowOutput, fetch_diff, fetch_diff_stat,
+    fetch_show,
+};
+
+use super::{App, DetailDiff, View};
+
+/// Share of the width, in percent, given to the log when the pane opens.
+const DEFAULT_LOG_PERCENT: u16 = 50;
+
+/// Smallest and largest share of the width given to the log.
+const LOG_PERCENT_RANGE: (u16, u16) = (20, 80);
+
+/// Change of the log's share per `<` / `>` press.
+const LOG_PERCENT_STEP: u16 = 10;
+
+/// Time the selection must rest on a commit before its preview is fetched.
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// What the preview pane shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewKind {
+    /// Commit details and changed files, like the detail view.
+    Detail,
+    /// Full diff of the commit.
+    Diff,
+}
+
+/// Preview of one commit.
+#[derive(Debug, Clone)]
+pub struct PreviewContent {
+    pub show_output: ShowOutput,
+    pub stat: DiffStat,
+    /// Full diff (only for [`PreviewKind::Diff`]).
+    pub diff: Option<DetailDiff>,
+}
+
+/// Change ID and kind of a preview.
+type PreviewKey = (String, PreviewKind);
+
+/// A fetched preview and the outputs it was built from, for the output cache.
+#[derive(Debug)]
+struct FetchedPreview {
+    content: PreviewContent,
+    /// Line counts, if they could be fetched.
+    stat: Option<DiffStat>,
+    /// Full diff, as printed by jj.
+    diff_output: Option<String>,
+}
+
+/// A preview waiting for the selection to settle.
+#[derive(Debug)]
+pub(super) struct PendingPreview {
+    key: PreviewKey,
+    /// When the selection moved to the commit.
+    pub(super) since: Instant,
+}
+
+/// A preview being fetched on a worker thread.
+#[derive(Debug)]
+struct PreviewFetch {
+    key: PreviewKey,
+    receiver: Receiver<Result<FetchedPreview, XorcistError>>,
+}
+
+/// State of the preview pane.
+#[derive(Debug)]
+pub struct PreviewState {
+    /// What the pane shows (None = closed).
+    pub kind: Option<PreviewKind>,
+    /// Share of the width, in percent, given to the log.
+    pub log_percent: u16,
+    /// Preview of the selected commit, or why it could not be fetched.
+    pub content: Option<Result<PreviewContent, String>>,
+    /// Change ID and kind the content was fetched for.
+    fetched: Option<PreviewKey>,
+    /// Preview to fetch once the selection has settled.
+    pub(super) pending: Option<PendingPreview>,
+    /// Fetch in progress.
+    fetching: Option<PreviewFetch>,
+}
+
+impl Default for PreviewState {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            log_percent: DEFAULT_LOG_PERCENT,
+            content: None,
+            fetched: None,
+            pending: None,
+            fetching: None,
+        }
+    }
+}
+
+impl PreviewState {
+    /// Whether the content was fetched as the `kind` preview of `change_id`.
+    pub(super) fn shows(&self, change_id: &str, kind: PreviewKind) -> bool {
+        self.fetched
+            .as_ref()
+            .is_some_and(|(id, fetched_kind)| id == change_id && *fetched_kind == kind)
+    }
+
+    /// Whether a preview is waiting for the selection to settle or being fetched.
+    pub fn is_loading(&self) -> bool {
+        self.pending.is_some() || self.fetching.is_some()
+    }
+
+    /// Time left until the pending preview is due, if one is waiting.
+    pub(super) fn time_until_due(&self) -> Option<Duration> {
+        self.pending
+            .as_ref()
+            .map(|pending| PREVIEW_DEBOUNCE.saturating_sub(pending.since.elapsed()))
+    }
+
+    /// Whether a fetch is running on a worker thread.
+    pub(super) fn is_fetching(&self) -> bool {
+        self.fetching.is_some()
+    }
+
+    fn clear(&mut self) {
+        self.content = None;
+        self.fetched = None;
+        self.pending = None;
+        self.fetching = None;
+    }
+
+    /// Show `content` as the preview `key`.
+    fn show_content(&mut self, key: PreviewKey, content: Result<PreviewContent, String>) {
+        self.content = Some(content);
+        self.fetched = Some(key);
+        self.pending = None;
+        self.fetching = None;
+    }
+
+    /// Take the result of the fetch in progress, if it has finished.
+    ///
+    /// Returns a successful fetch so that its outputs can be cached.
+    fn poll_fetch(&mut self) -> Option<(String, FetchedPreview)> {
+        let fetch = self.fetching.as_ref()?;
+        let result = match fetch.receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err(XorcistError::JjError(
+                "the preview fetch stopped".to_string(),
+            )),
+        };
+        let key = self.fetching.take()?.key;
+        match result {
+            Ok(fetched) => {
+                self.show_content(key.clone(), Ok(fetched.content.clone()));
+                Some((key.0, fetched))
+            }
+            Err(e) => {
+                self.show_content(key, Err(e.to_string()));
+                None
+            }
+        }
+    }
+}
+
+impl App {
+    /// Open the preview pane, switch it from detail to diff, or close it.
+    pub fn cycle_preview(&mut self) {
+        self.preview.kind = match self.preview.kind {
+            None => Some(PreviewKind::Detail),
+            Some(PreviewKind::Detail) => Some(PreviewKind::Diff),
+            Some(PreviewKind::Diff) => None,
+        };
+        self.preview.clear();
+    }
+
+    /// Give the log more (`grow`) or less of the width beside the preview.
+    pub fn resize_preview(&mut self, grow: bool) {
+        let (min, max) = LOG_PERCENT_RANGE;
+        let percent = &mut self.preview.log_percent;
+        *percent = if grow {
+            (*percent + LOG_PERCENT_STEP).min(max)
+        } else {
+            percent.saturating_sub(LOG_PERCENT_STEP).max(min)
+        };
+    }
+
+    /// The preview pane, if open.
+    pub fn preview(&self) -> Option<&PreviewState> {
+        self.preview.kind.is_some().then_some(&self.preview)
+    }
+
+    /// Bring the preview up to date with the selection.
+    ///
+    /// Called once per event loop pass. A newly selected commit is fetched
+    /// only after [`PREVIEW_DEBOUNCE`] without moving, and a fetch for a
+    /// commit no longer selected is abandoned. A failure is shown in the pane
+    /// instead of the status bar.
+    pub fn update_preview(&mut self) {
+        let Some(kind) = self.preview.kind else {
+            return;
+        };
+        if self.view != View::Log {
+            return;
+        }
+        let Some(change_id) = self.selected_change_id().map(str::to_string) else {
+            self.preview.clear();
+            return;
+        };
+        let key = (change_id, kind);
+        if let Some((change_id, fetched)) = self.preview.poll_fetch() {
+            self.cache_preview(&change_id, fetched);
+        }
+        if self.preview.shows(&key.0, kind) {
+            self.preview.pending = None;
+            return;
+        }
+        if let Some(content) = self.cached_preview(&key.0, kind) {
+            // Commits seen before are shown at once
+            self.preview.show_content(key, Ok(content));
+            return;
+        }
+        let preview = &mut self.preview;
+        if preview
+            .fetching
+            .as_ref()
+            .is_some_and(|fetch| fetch.key == key)
+        {
+            return;
+        }
+        // Dropping the receiver discards the result of a superseded fetch
+        preview.fetching = None;
+        match &preview.pending {
+            Some(pending) if pending.key == key => {
+                if pending.since.elapsed() >= PREVIEW_DEBOUNCE {
+                    preview.pending = None;
+                    self.start_preview_fetch(key);
+                }
+            }
+            _ => {
+                preview.pending = Some(PendingPreview {
+                    key,
+                    since: Instant::now(),
+                });
+            }
+        }
+    }
+
+    /// Drop the preview so it is fetched again (after the repository changed).
+    pub(super) fn invalidate_preview(&mut self) {
+        self.preview.fetched = None;
+        self.preview.fetching = None;
+    }
+
+    /// The `kind` preview of `change_id` built from the output cache, if it
+    /// holds everything needed.
+    fn cached_preview(&mut self, change_id: &str, kind: PreviewKind) -> Option<PreviewContent> {
+        let show_output = self.output_cache.show(change_id)?;
+        let (stat, diff) = match kind {
+            PreviewKind::Detail => (self.output_cache.stat(change_id)?, None),
+            PreviewKind::Diff => {
+                let output = self.output_cache.diff(change_id, &CacheKind::Diff)?;
+                let diff = DetailDiff {
+                    diff: self.runner.time_parse("diff", || GitDiff::parse(&output)),
+                    ..Default::default()
+                };
+                (DiffStat::default(), Some(diff))
+            }
+        };
+        Some(PreviewContent {
+            show_output,
+            stat,
+            diff,
+        })
+    }
+
+    /// Keep the outputs of a fetched preview in the output cache.
+    fn cache_preview(&mut self, change_id: &str, fetched: FetchedPreview) {
+        let show = Box::new(fetched.content.show_output);
+        self.cache_output(change_id, CacheKind::Show, CachedOutput::Show(show));
+        if let Some(stat) = fetched.stat {
+            self.cache_output(change_id, CacheKind::Stat, CachedOutput::Stat(stat));
+        }
+        if let Some(output) = fetched.diff_output {
+            self.cache_output(change_id, CacheKind::Diff, CachedOutput::Diff(output));
+        }
+    }
+
+    fn start_preview_fetch(&mut self, key: PreviewKey) {
+        let (sender, receiver) = mpsc::channel();
+        let runner = self.runner.clone();
+        let (change_id, kind) = key.clone();
+        thread::spawn(move || {
+            // The receiver is gone if the selection has moved on
+            let _ = sender.send(fetch_preview(&runner, &change_id, kind));
+        });
+        self.preview.fetching = Some(PreviewFetch { key, receiver });
+    }
+
+    /// Wait for the preview fetch in progress to finish (for tests).
+    #[cfg(test)]
+    pub(super) fn wait_for_preview(&mut self) {
+        while self.preview.fetching.is_some() {
+            if let Some((change_id, fetched)) = self.preview.poll_fetch() {
+                self.cache_preview(&change_id, fetched);
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+/// Fetch the `kind` preview of `change_id`.
+fn fetch_preview(
+    runner: &JjRunner,
+    change_id: &str,
+    kind: PreviewKind,
+) -> Result<FetchedPreview, XorcistError> {
+    let show_output = fetch_show(runner, change_id)?;
+    let (stat, diff_output) = match kind {
+        // Line counts are extra information, as in the detail view
+        PreviewKind::Detail => (fetch_diff_stat(runner, change_id).ok(), None),
+        PreviewKind::Diff => (None, Some(fetch_diff(runner, change_id)?)),
+    };
+    let diff = diff_output.as_ref().map(|output| DetailDiff {
+        diff: runner.time_parse("diff", || GitDiff::parse(output)),
+        ..Default::default()
+    });
+    Ok(FetchedPreview {
+        content: PreviewContent {
+            show_output,
+            stat: stat.clone().unwrap_or_default(),
+            diff,
+        },
+        stat,
+        diff_output,
+    })
+}
@@ -0,0 +1,245 @@
+//! Push dialog for choosing which bookmarks `jj git push` pushes, for App.
+
+use crate::config::PushConfig;
+use crate::error::XorcistError;
+use crate::jj::{BookmarkTracking, RepoMetadata};
+
+use super::tasks::AfterCommand;
+use super::{App, CommandResult, InputMode, ModalState};
+
+/// A local bookmark offered by the push dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushBookmark {
+    /// Bookmark name.
+    pub name: String,
+    /// Tracking state per tracked remote (empty = never pushed).
+    pub tracking: Vec<BookmarkTracking>,
+    /// Whether the bookmark is selected for pushing.
+    pub checked: bool,
+    /// Whether the bookmark matches `push.protected` (its name must be typed).
+    pub protected: bool,
+}
+
+impl PushBookmark {
+    /// Whether the bookmark does not exist on any remote yet (needs `--allow-new`).
+    pub fn is_new(&self) -> bool {
+        self.tracking.is_empty()
+    }
+
+    /// Whether the local bookmark has commits the remote does not.
+    pub fn is_ahead(&self) -> bool {
+        self.tracking.iter().any(|t| t.ahead > 0)
+    }
+
+    /// Whether the remote has commits the local bookmark does not, so that
+    /// pushing rewrites the remote bookmark (a force push).
+    pub fn is_behind(&self) -> bool {
+        self.tracking.iter().any(|t| t.behind > 0)
+    }
+}
+
+/// A push including protected bookmarks, waiting for their names to be typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectedPush {
+    /// Every bookmark to push.
+    pub names: Vec<String>,
+    /// Whether any of them is new on the remote.
+    pub allow_new: bool,
+    /// Protected bookmarks among them, to be typed space-separated.
+    pub protected: Vec<String>,
+    /// Whether a protected bookmark is behind its remote (force-pushed).
+    pub forced: bool,
+}
+
+impl ProtectedPush {
+    /// Title of the prompt asking for the names.
+    pub fn prompt_title(&self) -> String {
+        let push = if self.forced { "force-push" } else { "push" };
+        format!(" Protected: type {} to {push} ", self.protected.join(" "))
+    }
+}
+
+/// State of the push dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushDialog {
+    /// Local bookmarks, sorted by name.
+    pub bookmarks: Vec<PushBookmark>,
+    /// Highlighted bookmark.
+    pub selected: usize,
+}
+
+impl PushDialog {
+    /// Build the dialog from the repository's bookmarks.
+    ///
+    /// Bookmarks ahead of their remote start checked, unless protected;
+    /// new bookmarks must be picked explicitly.
+    pub fn from_metadata(metadata: &RepoMetadata, config: &PushConfig) -> Self {
+        let mut names: Vec<&str> = metadata
+            .commits
+            .values()
+            .flat_map(|commit| commit.bookmarks.iter().map(String::as_str))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        let bookmarks = names
+            .into_iter()
+            .map(|name| {
+                let mut bookmark = PushBookmark {
+                    name: name.to_string(),
+                    tracking: metadata.tracking_for(name).cloned().collect(),
+                    checked: false,
+                    protected: config.is_protected(name),
+                };
+                bookmark.checked = bookmark.is_ahead() && !bookmark.protected;
+                bookmark
+            })
+            .collect();
+        Self {
+            bookmarks,
+            selected: 0,
+        }
+    }
+
+    /// Move the highlight down (wrapping).
+    pub fn select_next(&mut self) {
+        if !self.bookmarks.is_empty() {
+            self.selected = (self.selected + 1) % self.bookmarks.len();
+        }
+    }
+
+    /// Move the highlight up (wrapping).
+    pub fn select_previous(&mut self) {
+        if !self.bookmarks.is_empty() {
+            self.selected = (self.selected + self.bookmarks.len() - 1) % self.bookmarks.len();
+        }
+    }
+
+    /// Check or uncheck the highlighted bookmark.
+    pub fn toggle_selected(&mut self) {
+        if let Some(bookmark) = self.bookmarks.get_mut(self.selected) {
+            bookmark.checked = !bookmark.checked;
+        }
+    }
+
+    /// Check every bookmark, or uncheck all if all are checked already.
+    pub fn toggle_all(&mut self) {
+        let check = !self.bookmarks.iter().all(|b| b.checked);
+        for bookmark in &mut self.bookmarks {
+            bookmark.checked = check;
+        }
+    }
+
+    /// Names of the checked bookmarks.
+    pub fn checked_names(&self) -> Vec<String> {
+        self.bookmarks
+            .iter()
+            .filter(|b| b.checked)
+            .map(|b| b.name.clone())
+            .collect()
+    }
+
+    /// The checked bookmarks that are protected.
+    fn checked_protected(&self) -> impl Iterator<Item = &PushBookmark> {
+        self.bookmarks.iter().filter(|b| b.checked && b.protected)
+    }
+
+    /// Whether any checked bookmark is new on the remote.
+    pub fn needs_allow_new(&self) -> bool {
+        self.bookmarks.iter().any(|b| b.checked && b.is_new())
+    }
+}
+
+impl App {
+    /// Open the push dialog listing the local bookmarks.
+    pub fn open_push_dialog(&mut self) {
+        self.refresh_metadata();
+        let dialog = self
+            .metadata()
+            .map(|metadata| PushDialog::from_metadata(metadata, &self.push_config))
+            .filter(|dialog| !dialog.bookmarks.is_empty());
+        match dialog {
+            Some(dialog) => self.modal = ModalState::Push(dialog),
+            None => {
+                self.report(CommandResult {
+                    success: false,
+                    message: "No local bookmarks to push".to_string(),
+                });
+            }
+        }
+    }
+
+    /// Get the open push dialog, if any.
+    pub fn push_dialog_mut(&mut self) -> Option<&mut PushDialog> {
+        match &mut self.modal {
+            ModalState::Push(dialog) => Some(dialog),
+            _ => None,
+        }
+    }
+
+    /// Push the bookmarks checked in the push dialog.
+    ///
+    /// If any of them is protected, their names must be typed first.
+    pub fn push_checked_bookmarks(&mut self) -> Result<(), XorcistError> {
+        let ModalState::Push(dialog) = &self.modal else {
+            return Ok(());
+        };
+        let names = dialog.checked_names();
+        if names.is_empty() {
+            self.report(CommandResult {
+                success: false,
+                message: "No bookmarks selected (Space: select)".to_string(),
+            });
+            return Ok(());
+        }
+        let protected: Vec<String> = dialog.checked_protected().map(|b| b.name.clone()).collect();
+        let forced = dialog.checked_protected().any(PushBookmark::is_behind);
+        let allow_new = dialog.needs_allow_new();
+        self.close_modal();
+        if !protected.is_empty() {
+            self.protected_push = Some(ProtectedPush {
+                names,
+                allow_new,
+                protected,
+                forced,
+            });
+            self.start_input_mode(InputMode::ProtectedPush);
+            return Ok(());
+        }
+        self.push_bookmarks(names, allow_new);
+        Ok(())
+    }
+
+    /// Get the push waiting for protected bookmark names, if any.
+    pub fn protected_push(&self) -> Option<&ProtectedPush> {
+        self.protected_push.as_ref()
+    }
+
+    /// Push the waiting bookmarks if `typed` names exactly the protected ones.
+    pub(super) fn submit_protected_push(&mut self, typed: &str) {
+        let Some(push) = self.protected_push.take() else {
+            return;
+        };
+        if !typed
+            .split_whitespace()
+            .eq(push.protected.iter().map(String::as_str))
+        {
+            self.report(CommandResult {
+                success: false,
+                message: format!(
+                    "Push cancelled: type {} to push protected bookmarks",
+                    push.protected.join(" ")
+                ),
+            });
+            return;
+        }
+        self.push_bookmarks(push.names, push.allow_new);
+    }
+
+    /// Run `jj git push -b …` in the background.
+    fn push_bookmarks(&mut self, names: Vec<String>, allow_new: bool) {
+        let remote = self.push_config.remote.clone();
+        self.run_in_background("jj git push", AfterCommand::Refresh, move |runner| {
+            runner.execute_git_push_bookmarks(&names, allow_new, remote.as_deref())
+        });
+    }
+}
@@ -0,0 +1,257 @@
+//! Periodic refresh methods for App.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::error::XorcistError;
+use crate::jj::{fetch_children, fetch_diff_summary_range, fetch_show, read_op_heads};
+
+use super::{App, CommandResult, View};
+
+/// Minimum time without key input before an automatic refresh may run.
+const AUTO_REFRESH_IDLE: Duration = Duration::from_secs(2);
+
+/// Poll interval used to keep the "synced Xs ago" indicator ticking.
+const AUTO_REFRESH_TICK: Duration = Duration::from_secs(1);
+
+/// Time before a failed refresh after an external change is tried again.
+const EXTERNAL_REFRESH_RETRY: Duration = Duration::from_secs(5);
+
+/// Poll interval while a background command runs (animates the spinner).
+const SPINNER_TICK: Duration = Duration::from_millis(100);
+
+impl App {
+    /// Set the auto-refresh interval (None disables auto-refresh).
+    pub fn set_auto_refresh_interval(&mut self, interval: Option<Duration>) {
+        self.auto_refresh_interval = interval.filter(|d| !d.is_zero());
+    }
+
+    /// Get the auto-refresh interval, if enabled.
+    pub fn auto_refresh_interval(&self) -> Option<Duration> {
+        self.auto_refresh_interval
+    }
+
+    /// Record that the user interacted with the app.
+    pub fn record_activity(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    /// Timeout for waiting on the next terminal event.
+    ///
+    /// The event loop wakes up regularly even without auto-refresh so that
+    /// the "synced Xs ago" indicator stays current, and more often while a
+    /// background command runs, the log is still being read or a preview
+    /// is being fetched. A preview waiting for the selection to settle wakes
+    /// it up when it is due.
+    pub fn poll_timeout(&self) -> Duration {
+        let tick =
+            if self.is_command_running() || self.is_log_streaming() || self.preview.is_fetching() {
+                SPINNER_TICK
+            } else {
+                AUTO_REFRESH_TICK
+            };
+        self.preview
+            .time_until_due()
+            .map_or(tick, |due| due.min(tick))
+    }
+
+    /// Check whether an automatic refresh is due.
+    ///
+    /// Refreshes only run while the log view is idle: no modal, input or help
+    /// overlay is open and no key has been pressed recently.
+    pub fn should_auto_refresh(&self) -> bool {
+        let Some(interval) = self.auto_refresh_interval else {
+            return false;
+        };
+        self.is_idle_in_log() && !self.is_log_streaming() && self.last_refresh.elapsed() >= interval
+    }
+
+    /// Whether the log view is shown without overlays and no key was pressed recently.
+    fn is_idle_in_log(&self) -> bool {
+        self.view == View::Log
+            && !self.is_modal_open()
+            && !self.is_input_mode()
+            && !self.show_help
+            && !self.is_output_pane_open()
+            && !self.is_command_history_open()
+            && !self.is_command_running()
+            && self.last_activity.elapsed() >= AUTO_REFRESH_IDLE
+    }
+
+    /// Handle an idle tick of the event loop (no event arrived before the timeout).
+    ///
+    /// A failed auto-refresh (e.g., the repository is locked by another jj)
+    /// is reported in the status bar and tried again after the next interval.
+    pub fn on_idle_tick(&mut self) {
+        if self.should_auto_refresh()
+            && let Err(e) = self.refresh_log_keep_selection()
+        {
+            self.last_refresh = Instant::now();
+            self.report(CommandResult {
+                success: false,
+                message: format!("Auto-refresh failed: {e}"),
+            });
+        }
+        self.check_external_changes();
+    }
+
+    /// Watch the operation heads in `op_heads_dir` for external changes.
+    pub fn set_op_heads_dir(&mut self, op_heads_dir: PathBuf) {
+        self.op_heads_dir = Some(op_heads_dir);
+        self.record_op_heads();
+    }
+
+    /// Remember the current operation heads as the state shown in the log.
+    pub(super) fn record_op_heads(&mut self) {
+        self.known_op_heads = self.op_heads_dir.as_deref().and_then(read_op_heads);
+        self.repo_changed = false;
+        self.external_refresh_failed = None;
+    }
+
+    /// Check whether another process (e.g., jj in another terminal) has run
+    /// an operation since the last refresh.
+    ///
+    /// Depending on the config this either flags the log as out of date or
+    /// refreshes it (only while idle in the log view, so a change seen while
+    /// the user is busy is applied once they are idle again).
+    ///
+    /// A failed refresh (e.g., the other process still holds the repository)
+    /// is reported and tried again after [`EXTERNAL_REFRESH_RETRY`].
+    pub fn check_external_changes(&mut self) {
+        let (Some(dir), Some(known)) = (&self.op_heads_dir, &self.known_op_heads) else {
+            return;
+        };
+        if !self.repo_changed {
+            if read_op_heads(dir).is_none_or(|heads| heads == *known) {
+                return;
+            }
+            self.repo_changed = true;
+        }
+        let retry_due = self
+            .external_refresh_failed
+            .is_none_or(|failed| failed.elapsed() >= EXTERNAL_REFRESH_RETRY);
+        if self.refresh_on_external_change
+            && self.is_idle_in_log()
+            && retry_due
+            && let Err(e) = self.refresh_log_keep_selection()
+        {
+            // The log stays flagged as out of date until a refresh works
+            self.external_refresh_failed = Some(Instant::now());
+            self.report(CommandResult {
+                success: false,
+                message: format!("Refresh after an external change failed: {e}"),
+            });
+        }
+    }
+
+    /// Whether the repository changed outside xorcist since the last refresh.
+    pub fn is_repo_changed(&self) -> bool {
+        self.repo_changed
+    }
+
+    /// Refresh the log, keeping the cursor on the same change if it still exists.
+    pub fn refresh_log_keep_selection(&mut self) -> Result<(), XorcistError> {
+        let change_id = self.selected_change_id().map(str::to_string);
+        self.refresh_log()?;
+        if let Some(change_id) = change_id {
+            self.select_change_id(&change_id);
+        }
+        Ok(())
+    }
+
+    /// Reload the log and any open detail/diff view (`R` / `F5`).
+    ///
+    /// The cursor stays on the same change and the scroll offsets are kept;
+    /// in the diff view the same file stays selected if it still exists.
+    pub fn reload_all(&mut self) -> Result<(), XorcistError> {
+        let scroll_offset = self.scroll_offset;
+        self.refresh_log_keep_selection()?;
+        // Rendering scrolls again if the selection moved out of view
+        self.scroll_offset = scroll_offset;
+
+        let result = match self.reload_views() {
+            Ok(()) => CommandResult {
+                success: true,
+                message: "Refreshed".to_string(),
+            },
+            Err(XorcistError::JjError(message)) => CommandResult {
+                success: false,
+                message,
+            },
+            Err(e) => return Err(e),
+        };
+        self.report(result);
+        Ok(())
+    }
+
+    /// Re-fetch the contents of the open detail and diff views.
+    pub(super) fn reload_views(&mut self) -> Result<(), XorcistError> {
+        if let Some(detail) = &self.detail_state {
+            let show_output = fetch_show(&self.runner, &detail.show_output.change_id)?;
+            let children =
+                fetch_children(&self.runner, &detail.show_output.change_id).unwrap_or_default();
+            if let Some(detail) = &mut self.detail_state {
+                let count = show_output.parents.len() + children.len();
+                detail.related = detail.related.min(count.saturating_sub(1));
+                detail.show_output = show_output;
+                detail.children = children;
+            }
+            self.reload_detail_diff()?;
+        }
+        // The file history may be open below the detail and diff views
+        self.refresh_file_history()?;
+        match self.view {
+            View::Diff => self.reload_diff()?,
+            View::OpLog => self.refresh_op_log()?,
+            View::Workspaces => self.refresh_workspaces()?,
+            View::Tags => self.refresh_tags()?,
+            View::Stacks => self.refresh_stacks()?,
+            View::Sparse => self.refresh_sparse()?,
+            View::Log | View::Detail | View::FileHistory => {}
+        }
+        Ok(())
+    }
+
+    /// Re-fetch the diff view, keeping the selected file and scroll offsets.
+    fn reload_diff(&mut self) -> Result<(), XorcistError> {
+        let state = &self.diff_state;
+        let selected_path = state.selected_file().map(|f| f.path.clone());
+        let (old_selected, diff_scroll, diff_h_scroll) =
+            (state.selected, state.diff_scroll, state.diff_h_scroll);
+
+        let files = match &state.range {
+            Some(range) => fetch_diff_summary_range(&self.runner, range)?,
+            None => fetch_show(&self.runner, &state.change_id)?.diff_summary,
+        };
+        let selected = selected_path
+            .and_then(|path| files.iter().position(|f| f.path == path))
+            .unwrap_or_else(|| old_selected.min(files.len().saturating_sub(1)));
+
+        self.diff_state
+            .marked_files
+            .retain(|path| files.iter().any(|f| &f.path == path));
+        self.diff_state.files = files;
+        self.diff_state.selected = selected;
+        self.refresh_diff_text()?;
+        // Rendering clamps the offsets to the new text
+        self.diff_state.diff_scroll = diff_scroll;
+        self.diff_state.diff_h_scroll = diff_h_scroll;
+        Ok(())
+    }
+
+    /// Time elapsed since the log was last refreshed.
+    pub fn since_last_refresh(&self) -> Duration {
+        self.last_refresh.elapsed()
+    }
+
+    /// Set the age after which the log is shown as stale (None or zero = never).
+    pub fn set_stale_after(&mut self, stale_after: Option<Duration>) {
+        self.stale_after = stale_after.filter(|d| !d.is_zero());
+    }
+
+    /// Check whether the log has not been refreshed for longer than the stale threshold.
+    pub fn is_log_stale(&self) -> bool {
+        self.stale_after
+            .is_some_and(|threshold| self.since_last_refresh() >= threshold)
+    }
+}
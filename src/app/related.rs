@@ -0,0 +1,61 @@
+//! Parents and children of the commit in the detail view, for App.
+
+use crate::error::XorcistError;
+use crate::jj::RelatedCommit;
+
+use super::App;
+
+impl App {
+    /// Parents followed by children of the commit in the detail view.
+    pub fn detail_related(&self) -> Vec<&RelatedCommit> {
+        self.detail_state
+            .as_ref()
+            .map(|state| {
+                state
+                    .show_output
+                    .parents
+                    .iter()
+                    .chain(&state.children)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Select the next parent or child (wrapping around).
+    pub fn detail_select_next_related(&mut self) {
+        let count = self.detail_related().len();
+        if let Some(state) = &mut self.detail_state
+            && count > 0
+        {
+            state.related = (state.related + 1) % count;
+        }
+    }
+
+    /// Select the previous parent or child (wrapping around).
+    pub fn detail_select_previous_related(&mut self) {
+        let count = self.detail_related().len();
+        if let Some(state) = &mut self.detail_state
+            && count > 0
+        {
+            state.related = (state.related + count - 1) % count;
+        }
+    }
+
+    /// Open the selected parent or child in the detail view.
+    ///
+    /// The current commit is kept, so going back returns to it.
+    pub fn open_related_detail(&mut self) -> Result<(), XorcistError> {
+        let Some(change_id) = self
+            .detail_state
+            .as_ref()
+            .and_then(|state| self.detail_related().get(state.related).copied())
+            .map(|commit| commit.change_id.clone())
+        else {
+            return Ok(());
+        };
+        let previous = self.detail_state.clone();
+        self.open_detail_for(&change_id)?;
+        self.detail_back.extend(previous);
+        Ok(())
+    }
+}
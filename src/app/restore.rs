@@ -0,0 +1,73 @@
+//! Restoring files of a revision into another one (`jj restore`), for App.
+
+use super::{App, InputMode, ModalState, PendingAction};
+
+impl App {
+    /// Show confirmation dialog for restoring the marked files (or the
+    /// selected file) of the diff view's revision into the working copy.
+    ///
+    /// In the diff view of the working copy itself, the changes to the files
+    /// are discarded instead (restored from its parents).
+    pub fn show_restore_files_confirm(&mut self) {
+        let Some(paths) = self.paths_for_file_action("Restore") else {
+            return;
+        };
+        let revision = self.diff_state.change_id.clone();
+        let action = if self.is_working_copy(&revision) {
+            PendingAction::RestoreFiles {
+                from: None,
+                into: revision,
+                paths,
+            }
+        } else {
+            PendingAction::RestoreFiles {
+                from: Some(revision),
+                into: "@".to_string(),
+                paths,
+            }
+        };
+        self.ask_confirm(action);
+    }
+
+    /// Prompt for the revision to restore the files into, from the restore
+    /// confirmation.
+    pub fn start_restore_target_input(&mut self) {
+        let ModalState::Confirm(PendingAction::RestoreFiles { from, into, paths }) =
+            std::mem::take(&mut self.modal)
+        else {
+            return;
+        };
+        let source = from.unwrap_or(into);
+        self.pending_restore = Some((source, paths));
+        self.start_input_mode(InputMode::RestoreInto);
+    }
+
+    /// Ask to restore the pending files into the entered revision.
+    pub(super) fn submit_restore_into(&mut self, target: &str) {
+        let target = target.trim();
+        let Some((source, paths)) = self.pending_restore.take() else {
+            return;
+        };
+        if target.is_empty() {
+            return;
+        }
+        // Restoring a revision into itself discards its changes to the files
+        let from = (target != source).then_some(source);
+        self.ask_confirm(PendingAction::RestoreFiles {
+            from,
+            into: target.to_string(),
+            paths,
+        });
+    }
+
+    /// Check if a change is the working-copy commit, according to the log.
+    pub(super) fn is_working_copy(&self, change_id: &str) -> bool {
+        self.graph_log.lines.iter().any(|line| {
+            line.flags.working_copy
+                && line
+                    .change_id
+                    .as_deref()
+                    .is_some_and(|id| id.starts_with(change_id) || change_id.starts_with(id))
+        })
+    }
+}
@@ -0,0 +1,195 @@
+//! Incremental text search in the log view for App.
+
+use crate::jj::GraphLine;
+
+use super::{App, CommandResult, InputMode};
+
+/// An active search over the loaded log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchState {
+    /// Search text.
+    pub query: String,
+    /// Indices (in commit order) of the commits matching the query, ascending.
+    pub matches: Vec<usize>,
+    /// Selection when the prompt was opened, restored if the search is cancelled.
+    origin: usize,
+}
+
+impl SearchState {
+    /// Position of a commit among the matches (0-based), if it matches.
+    pub fn match_position(&self, commit_index: usize) -> Option<usize> {
+        self.matches.binary_search(&commit_index).ok()
+    }
+}
+
+/// Whether a commit line matches the query.
+///
+/// Descriptions, change IDs, authors and bookmarks are searched. The search
+/// ignores case unless the query contains an uppercase letter.
+fn line_matches(line: &GraphLine, query: &str) -> bool {
+    let case_sensitive = query.chars().any(char::is_uppercase);
+    let contains = |text: &str| {
+        if case_sensitive {
+            text.contains(query)
+        } else {
+            text.to_lowercase().contains(&query.to_lowercase())
+        }
+    };
+    [
+        line.description.as_deref(),
+        line.change_id.as_deref(),
+        line.author.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .any(contains)
+        || line.bookmarks.iter().any(|b| contains(b))
+}
+
+impl App {
+    /// Open the `/` search prompt.
+    pub fn start_search(&mut self) {
+        self.start_input_mode(InputMode::Search);
+        self.search = Some(SearchState {
+            origin: self.selected,
+            ..Default::default()
+        });
+    }
+
+    /// Get the active search, if any.
+    pub fn search(&self) -> Option<&SearchState> {
+        self.search.as_ref()
+    }
+
+    /// Whether a search is active (n/N jump between matches).
+    pub fn has_search(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Update the search from the prompt as the user types.
+    ///
+    /// The selection moves to the first match at or after the commit that
+    /// was selected when the prompt opened.
+    pub fn update_search(&mut self) {
+        let query = self.input.value().to_string();
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.query = query;
+        let origin = search.origin;
+        self.refresh_search_matches();
+        let Some(search) = &self.search else {
+            return;
+        };
+        self.selected = search
+            .matches
+            .iter()
+            .find(|&&idx| idx >= origin)
+            .or_else(|| search.matches.first())
+            .copied()
+            .unwrap_or(origin);
+    }
+
+    /// Recompute the matches of the active search against the loaded log.
+    pub fn refresh_search_matches(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.matches = if search.query.is_empty() {
+            Vec::new()
+        } else {
+            self.graph_log
+                .commit_line_indices
+                .iter()
+                .enumerate()
+                .filter(|(_, line_idx)| {
+                    line_matches(&self.graph_log.lines[**line_idx], &search.query)
+                })
+                .map(|(commit_idx, _)| commit_idx)
+                .collect()
+        };
+    }
+
+    /// Finish typing the search: keep it active for n/N, or drop it if empty.
+    pub(super) fn submit_search(&mut self, query: &str) {
+        if query.is_empty() {
+            self.search = None;
+            return;
+        }
+        if self.search.as_ref().is_some_and(|s| s.matches.is_empty()) {
+            self.report(CommandResult {
+                success: false,
+                message: format!("Pattern not found: {query}"),
+            });
+        }
+    }
+
+    /// Cancel the search prompt, restoring the original selection.
+    pub(super) fn cancel_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.selected = search.origin;
+        }
+    }
+
+    /// End the search (the selection stays where it is).
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Select the next match after the selected commit, wrapping to the top.
+    pub fn search_next(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let next = search.matches.iter().find(|&&idx| idx > self.selected);
+        let (target, wrapped) = match next {
+            Some(&idx) => (Some(idx), false),
+            None => (search.matches.first().copied(), true),
+        };
+        self.jump_to_match(target, wrapped, "search hit BOTTOM, continuing at TOP");
+    }
+
+    /// Select the previous match before the selected commit, wrapping to the bottom.
+    pub fn search_previous(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let previous = search
+            .matches
+            .iter()
+            .rev()
+            .find(|&&idx| idx < self.selected);
+        let (target, wrapped) = match previous {
+            Some(&idx) => (Some(idx), false),
+            None => (search.matches.last().copied(), true),
+        };
+        self.jump_to_match(target, wrapped, "search hit TOP, continuing at BOTTOM");
+    }
+
+    fn jump_to_match(&mut self, target: Option<usize>, wrapped: bool, wrap_message: &str) {
+        let Some(target) = target else {
+            let query = self.search.as_ref().map_or("", |s| s.query.as_str());
+            self.report(CommandResult {
+                success: false,
+                message: format!("Pattern not found: {query}"),
+            });
+            return;
+        };
+        self.selected = target;
+        self.last_command_result = wrapped.then(|| CommandResult {
+            success: true,
+            message: wrap_message.to_string(),
+        });
+    }
+
+    /// Whether the graph line at `line_idx` is a commit matching the active search.
+    pub fn is_search_match(&self, line_idx: usize) -> bool {
+        let Some(search) = &self.search else {
+            return false;
+        };
+        self.graph_log
+            .commit_line_indices
+            .binary_search(&line_idx)
+            .is_ok_and(|commit_idx| search.match_position(commit_idx).is_some())
+    }
+}
@@ -0,0 +1,59 @@
+//! Signing revisions (`jj sign` / `jj unsign`) for App.
+
+use crate::jj::Capability;
+
+use super::tasks::AfterCommand;
+use super::{App, CommandResult, View};
+
+impl App {
+    /// Revision to sign: the one in the detail view, or the selected commit.
+    fn sign_target(&self) -> Option<String> {
+        if self.view == View::Detail
+            && let Some(state) = &self.detail_state
+        {
+            return Some(state.show_output.change_id.clone());
+        }
+        self.selected_change_id().map(str::to_string)
+    }
+
+    /// Whether this jj can sign; reports the version needed if not.
+    fn check_sign_command(&mut self) -> bool {
+        if self.runner.supports(Capability::SignCommand) {
+            return true;
+        }
+        self.report(CommandResult {
+            success: false,
+            message: format!(
+                "jj sign needs jj {} or newer",
+                Capability::SignCommand.min_version()
+            ),
+        });
+        false
+    }
+
+    /// Run `jj sign -r <rev>` in the background (needs `signing.backend` configured).
+    pub fn execute_sign(&mut self) {
+        let Some(change_id) = self.sign_target() else {
+            return;
+        };
+        if !self.check_sign_command() {
+            return;
+        }
+        self.run_in_background("jj sign", AfterCommand::ReloadViews, move |runner| {
+            runner.execute_sign(&change_id)
+        });
+    }
+
+    /// Run `jj unsign -r <rev>` in the background.
+    pub fn execute_unsign(&mut self) {
+        let Some(change_id) = self.sign_target() else {
+            return;
+        };
+        if !self.check_sign_command() {
+            return;
+        }
+        self.run_in_background("jj unsign", AfterCommand::ReloadViews, move |runner| {
+            runner.execute_unsign(&change_id)
+        });
+    }
+}
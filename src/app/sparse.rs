@@ -0,0 +1,129 @@
+//! Sparse patterns view (`jj sparse list`) methods for App.
+
+use crate::error::XorcistError;
+use crate::jj::fetch_sparse_patterns;
+
+use super::tasks::AfterCommand;
+use super::{App, InputMode, PendingAction, View};
+
+/// State of the sparse patterns view.
+#[derive(Debug, Clone, Default)]
+pub struct SparseState {
+    /// Sparse patterns of the working copy.
+    pub patterns: Vec<String>,
+    /// Selected pattern index.
+    pub selected: usize,
+    /// Scroll offset (in patterns).
+    pub scroll: usize,
+}
+
+impl App {
+    /// Open the sparse patterns view.
+    pub fn open_sparse(&mut self) -> Result<(), XorcistError> {
+        self.sparse_state = SparseState {
+            patterns: fetch_sparse_patterns(&self.runner)?,
+            selected: 0,
+            scroll: 0,
+        };
+        self.push_view(View::Sparse);
+        Ok(())
+    }
+
+    /// Close the sparse patterns view.
+    pub fn close_sparse(&mut self) {
+        self.pop_view();
+    }
+
+    /// Get the sparse patterns view state.
+    pub fn sparse(&self) -> &SparseState {
+        &self.sparse_state
+    }
+
+    /// Re-fetch the sparse patterns, keeping the selected one if it still exists.
+    pub(super) fn refresh_sparse(&mut self) -> Result<(), XorcistError> {
+        let patterns = fetch_sparse_patterns(&self.runner)?;
+        let state = &mut self.sparse_state;
+        let selected_pattern = state.patterns.get(state.selected).cloned();
+        state.selected = selected_pattern
+            .and_then(|pattern| patterns.iter().position(|p| *p == pattern))
+            .unwrap_or_else(|| state.selected.min(patterns.len().saturating_sub(1)));
+        state.patterns = patterns;
+        Ok(())
+    }
+
+    /// Get the selected sparse pattern.
+    pub fn selected_sparse_pattern(&self) -> Option<&str> {
+        self.sparse_state
+            .patterns
+            .get(self.sparse_state.selected)
+            .map(String::as_str)
+    }
+
+    /// Select the next sparse pattern.
+    pub fn sparse_select_next(&mut self) {
+        let state = &mut self.sparse_state;
+        if state.selected + 1 < state.patterns.len() {
+            state.selected += 1;
+        }
+    }
+
+    /// Select the previous sparse pattern.
+    pub fn sparse_select_previous(&mut self) {
+        self.sparse_state.selected = self.sparse_state.selected.saturating_sub(1);
+    }
+
+    /// Select the first sparse pattern.
+    pub fn sparse_select_first(&mut self) {
+        self.sparse_state.selected = 0;
+    }
+
+    /// Select the last sparse pattern.
+    pub fn sparse_select_last(&mut self) {
+        self.sparse_state.selected = self.sparse_state.patterns.len().saturating_sub(1);
+    }
+
+    /// Adjust the scroll offset so the selected pattern is visible.
+    pub fn ensure_sparse_visible(&mut self, visible_height: usize) {
+        let state = &mut self.sparse_state;
+        if visible_height == 0 {
+            return;
+        }
+        if state.selected < state.scroll {
+            state.scroll = state.selected;
+        } else if state.selected >= state.scroll + visible_height {
+            state.scroll = state.selected + 1 - visible_height;
+        }
+    }
+
+    /// Prompt for a sparse pattern to add.
+    pub fn start_sparse_add(&mut self) {
+        self.start_input_mode(InputMode::SparseAdd);
+    }
+
+    /// Run `jj sparse set --add <pattern>` in the background.
+    pub(super) fn submit_sparse_add(&mut self, pattern: &str) {
+        let pattern = pattern.trim().to_string();
+        if pattern.is_empty() {
+            return;
+        }
+        self.run_in_background("jj sparse set", AfterCommand::Refresh, move |runner| {
+            runner.execute_sparse_add(&pattern)
+        });
+    }
+
+    /// Show confirmation dialog for removing the selected sparse pattern.
+    ///
+    /// The files under the pattern are removed from the working copy.
+    pub fn show_sparse_remove_confirm(&mut self) -> Result<(), XorcistError> {
+        let Some(pattern) = self.selected_sparse_pattern() else {
+            return Ok(());
+        };
+        let pattern = pattern.to_string();
+        self.request_confirm(PendingAction::SparseRemove { pattern })
+    }
+
+    /// Show confirmation dialog for checking out the whole repository again.
+    pub fn show_sparse_reset_confirm(&mut self) -> Result<(), XorcistError> {
+        self.request_confirm(PendingAction::SparseReset)
+    }
+}
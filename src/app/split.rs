@@ -0,0 +1,109 @@
+//! Splitting a revision by file (`jj split`) and squashing some of its
+//! files into the parent, for App.
+
+use crate::error::XorcistError;
+
+use super::{App, CommandResult, PendingAction};
+
+impl App {
+    /// Open the diff view to pick the files to split out of the detail revision.
+    pub fn start_split(&mut self) -> Result<(), XorcistError> {
+        self.open_diff_view()?;
+        self.report(CommandResult {
+            success: true,
+            message: "Mark files with Space, then press s to split them out".to_string(),
+        });
+        Ok(())
+    }
+
+    /// Toggle the split mark on the selected file and move to the next one.
+    pub fn toggle_file_mark(&mut self) -> Result<(), XorcistError> {
+        let state = &mut self.diff_state;
+        let Some(path) = state.selected_file().map(|f| f.path.clone()) else {
+            return Ok(());
+        };
+        if !state.marked_files.remove(&path) {
+            state.marked_files.insert(path);
+        }
+        let previous = state.selected;
+        self.diff_select_next();
+        if self.diff_state.selected != previous {
+            self.refresh_diff_text()?;
+        }
+        Ok(())
+    }
+
+    /// Check if a file in the diff view is marked for splitting.
+    pub fn is_file_marked(&self, path: &str) -> bool {
+        self.diff_state.marked_files.contains(path)
+    }
+
+    /// Paths of the marked files, in file list order.
+    pub fn marked_files(&self) -> Vec<String> {
+        self.diff_state
+            .files
+            .iter()
+            .filter(|f| self.diff_state.marked_files.contains(&f.path))
+            .map(|f| f.path.clone())
+            .collect()
+    }
+
+    /// Show confirmation dialog for splitting the marked files out of the revision.
+    ///
+    /// Without marks, the selected file is split out.
+    pub fn show_split_confirm(&mut self) {
+        let Some(paths) = self.paths_for_file_action("Split") else {
+            return;
+        };
+        let state = &self.diff_state;
+        if paths.len() == state.files.len() {
+            self.report(CommandResult {
+                success: false,
+                message: "Leave at least one file for the second commit".to_string(),
+            });
+            return;
+        }
+        let description = self
+            .detail_state
+            .as_ref()
+            .filter(|detail| detail.show_output.change_id == state.change_id)
+            .map(|detail| detail.show_output.description.clone())
+            .unwrap_or_default();
+        self.ask_confirm(PendingAction::Split {
+            change_id: state.change_id.clone(),
+            description,
+            paths,
+        });
+    }
+
+    /// Show confirmation dialog for squashing the marked files into the parent.
+    ///
+    /// Without marks, the selected file is squashed.
+    pub fn show_squash_files_confirm(&mut self) {
+        let Some(paths) = self.paths_for_file_action("Squash") else {
+            return;
+        };
+        self.ask_confirm(PendingAction::SquashFiles {
+            change_id: self.diff_state.change_id.clone(),
+            paths,
+        });
+    }
+
+    /// Marked files (or the selected file) for a by-file action on the diff
+    /// view's revision; None when there is none or a comparison is shown.
+    pub(super) fn paths_for_file_action(&mut self, action: &str) -> Option<Vec<String>> {
+        if self.diff_state.range.is_some() {
+            self.report(CommandResult {
+                success: false,
+                message: format!("{action} works on a single revision, not a comparison"),
+            });
+            return None;
+        }
+        let paths = self.marked_files();
+        if !paths.is_empty() {
+            return Some(paths);
+        }
+        let file = self.diff_state.selected_file()?;
+        Some(vec![file.path.clone()])
+    }
+}
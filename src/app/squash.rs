@@ -0,0 +1,75 @@
+//! Squashing a change into a commit picked in the log, for App.
+
+use crate::error::XorcistError;
+
+use super::{App, CommandResult, ModalState, PendingAction};
+
+impl App {
+    /// Pick the commit to squash the selected change into.
+    ///
+    /// From the squash confirmation, the change being squashed is kept. The
+    /// user then moves to the target in the log and presses Enter.
+    pub fn start_squash_target_pick(&mut self) {
+        let source = match std::mem::take(&mut self.modal) {
+            ModalState::Confirm(PendingAction::Squash {
+                change_id,
+                description,
+            }) => Some((change_id, description)),
+            ModalState::None => self
+                .selected_change_id()
+                .map(str::to_string)
+                .map(|change_id| (change_id, self.selected_description().unwrap_or_default())),
+            other => {
+                self.modal = other;
+                return;
+            }
+        };
+        let Some((change_id, description)) = source else {
+            return;
+        };
+        self.report(CommandResult {
+            success: true,
+            message: format!(
+                "Squash {change_id} into: select the target and press Enter (Esc cancels)"
+            ),
+        });
+        self.squash_source = Some((change_id, description));
+    }
+
+    /// Change being squashed while a target is picked, if any.
+    pub fn squash_source(&self) -> Option<&str> {
+        self.squash_source
+            .as_ref()
+            .map(|(change_id, _)| change_id.as_str())
+    }
+
+    /// Stop picking a squash target.
+    pub fn cancel_squash_target_pick(&mut self) {
+        self.squash_source = None;
+        self.last_command_result = None;
+    }
+
+    /// Ask to squash the picked change into the selected commit.
+    pub fn finish_squash_target_pick(&mut self) -> Result<(), XorcistError> {
+        let Some(target) = self.selected_change_id().map(str::to_string) else {
+            return Ok(());
+        };
+        if self.squash_source() == Some(target.as_str()) {
+            self.report(CommandResult {
+                success: false,
+                message: "Pick a different commit to squash into".to_string(),
+            });
+            return Ok(());
+        }
+        let Some((change_id, description)) = self.squash_source.take() else {
+            return Ok(());
+        };
+        self.last_command_result = None;
+        let target_description = self.selected_description().unwrap_or_default();
+        self.request_confirm(PendingAction::SquashInto {
+            change_id,
+            description,
+            into: (target, target_description),
+        })
+    }
+}
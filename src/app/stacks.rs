@@ -0,0 +1,163 @@
+//! Stacks view: mutable commits on top of trunk, grouped by head, for App.
+
+use crate::error::XorcistError;
+use crate::jj::{Stack, fetch_stacks};
+
+use super::tasks::AfterCommand;
+use super::{App, CommandResult, PendingAction, View};
+
+/// Revset stacks are rebased onto.
+pub(super) const STACK_DESTINATION: &str = "trunk()";
+
+/// State of the stacks view.
+#[derive(Debug, Clone, Default)]
+pub struct StacksState {
+    /// Stacks of the repository, in log order of their heads.
+    pub entries: Vec<Stack>,
+    /// Selected stack index.
+    pub selected: usize,
+    /// Scroll offset (in stacks).
+    pub scroll: usize,
+}
+
+impl StacksState {
+    /// Screen rows of a stack: its header and one row per commit.
+    pub fn stack_height(stack: &Stack) -> usize {
+        1 + stack.commits.len()
+    }
+}
+
+impl App {
+    /// Open the stacks view.
+    pub fn open_stacks(&mut self) -> Result<(), XorcistError> {
+        self.stacks_state = StacksState {
+            entries: fetch_stacks(&self.runner)?,
+            selected: 0,
+            scroll: 0,
+        };
+        self.push_view(View::Stacks);
+        Ok(())
+    }
+
+    /// Close the stacks view.
+    pub fn close_stacks(&mut self) {
+        self.pop_view();
+    }
+
+    /// Get the stacks view state.
+    pub fn stacks(&self) -> &StacksState {
+        &self.stacks_state
+    }
+
+    /// Re-fetch the stacks, keeping the selected one if its head still exists.
+    pub(super) fn refresh_stacks(&mut self) -> Result<(), XorcistError> {
+        let entries = fetch_stacks(&self.runner)?;
+        let state = &mut self.stacks_state;
+        let selected_head = state
+            .entries
+            .get(state.selected)
+            .map(|stack| stack.head().change_id.clone());
+        state.selected = selected_head
+            .and_then(|head| {
+                entries
+                    .iter()
+                    .position(|stack| stack.head().change_id == head)
+            })
+            .unwrap_or_else(|| state.selected.min(entries.len().saturating_sub(1)));
+        state.entries = entries;
+        Ok(())
+    }
+
+    /// Get the selected stack.
+    pub fn selected_stack(&self) -> Option<&Stack> {
+        self.stacks_state.entries.get(self.stacks_state.selected)
+    }
+
+    /// Select the next stack.
+    pub fn stacks_select_next(&mut self) {
+        let state = &mut self.stacks_state;
+        if state.selected + 1 < state.entries.len() {
+            state.selected += 1;
+        }
+    }
+
+    /// Select the previous stack.
+    pub fn stacks_select_previous(&mut self) {
+        self.stacks_state.selected = self.stacks_state.selected.saturating_sub(1);
+    }
+
+    /// Select the first stack.
+    pub fn stacks_select_first(&mut self) {
+        self.stacks_state.selected = 0;
+    }
+
+    /// Select the last stack.
+    pub fn stacks_select_last(&mut self) {
+        self.stacks_state.selected = self.stacks_state.entries.len().saturating_sub(1);
+    }
+
+    /// Adjust the scroll offset so the whole selected stack is visible
+    /// (or at least its top, when it is taller than the view).
+    pub fn ensure_stacks_visible(&mut self, visible_height: usize) {
+        let state = &mut self.stacks_state;
+        if visible_height == 0 || state.entries.is_empty() {
+            return;
+        }
+        if state.selected < state.scroll {
+            state.scroll = state.selected;
+            return;
+        }
+        let rows = |range: std::ops::RangeInclusive<usize>| -> usize {
+            state.entries[range]
+                .iter()
+                .map(StacksState::stack_height)
+                .sum()
+        };
+        while state.scroll < state.selected && rows(state.scroll..=state.selected) > visible_height
+        {
+            state.scroll += 1;
+        }
+    }
+
+    /// Open the detail view for the head of the selected stack.
+    pub fn open_stack_detail(&mut self) -> Result<(), XorcistError> {
+        let Some(head) = self
+            .selected_stack()
+            .map(|stack| stack.head().change_id.clone())
+        else {
+            return Ok(());
+        };
+        self.open_detail_for(&head)
+    }
+
+    /// Show confirmation dialog for rebasing the selected stack onto trunk.
+    pub fn show_rebase_stack_confirm(&mut self) -> Result<(), XorcistError> {
+        let Some(stack) = self.selected_stack() else {
+            return Ok(());
+        };
+        if !stack.needs_rebase {
+            self.report(CommandResult {
+                success: true,
+                message: format!("{} is already on top of trunk", stack.name()),
+            });
+            return Ok(());
+        }
+        let action = PendingAction::RebaseStack {
+            head: stack.head().change_id.clone(),
+            name: stack.name(),
+            revisions: stack
+                .commits
+                .iter()
+                .map(|commit| (commit.change_id.clone(), commit.description.clone()))
+                .collect(),
+        };
+        self.request_confirm(action)
+    }
+
+    /// Rebase a stack onto trunk in the background (`jj rebase -b <head> -d trunk()`).
+    pub(super) fn execute_rebase_stack(&mut self, head: String) {
+        self.run_in_background("jj rebase", AfterCommand::Refresh, move |runner| {
+            runner.execute_rebase_branch(&head, STACK_DESTINATION)
+        });
+    }
+}
@@ -0,0 +1,109 @@
+//! Tags view (`jj tag list`) methods for App.
+
+use crate::error::XorcistError;
+use crate::jj::{TagEntry, fetch_tags};
+
+use super::{App, CommandResult, View};
+
+/// State of the tags view.
+#[derive(Debug, Clone, Default)]
+pub struct TagsState {
+    /// Tags of the repository, by name.
+    pub entries: Vec<TagEntry>,
+    /// Selected tag index.
+    pub selected: usize,
+    /// Scroll offset (in entries).
+    pub scroll: usize,
+}
+
+impl App {
+    /// Open the tags view.
+    pub fn open_tags(&mut self) -> Result<(), XorcistError> {
+        self.tags_state = TagsState {
+            entries: fetch_tags(&self.runner)?,
+            selected: 0,
+            scroll: 0,
+        };
+        self.push_view(View::Tags);
+        Ok(())
+    }
+
+    /// Close the tags view.
+    pub fn close_tags(&mut self) {
+        self.pop_view();
+    }
+
+    /// Get the tags view state.
+    pub fn tags(&self) -> &TagsState {
+        &self.tags_state
+    }
+
+    /// Re-fetch the tags, keeping the selected one if it still exists.
+    pub(super) fn refresh_tags(&mut self) -> Result<(), XorcistError> {
+        let entries = fetch_tags(&self.runner)?;
+        let state = &mut self.tags_state;
+        let selected_name = state.entries.get(state.selected).map(|e| e.name.clone());
+        state.selected = selected_name
+            .and_then(|name| entries.iter().position(|e| e.name == name))
+            .unwrap_or_else(|| state.selected.min(entries.len().saturating_sub(1)));
+        state.entries = entries;
+        Ok(())
+    }
+
+    /// Get the selected tag.
+    pub fn selected_tag(&self) -> Option<&TagEntry> {
+        self.tags_state.entries.get(self.tags_state.selected)
+    }
+
+    /// Select the next tag.
+    pub fn tags_select_next(&mut self) {
+        let state = &mut self.tags_state;
+        if state.selected + 1 < state.entries.len() {
+            state.selected += 1;
+        }
+    }
+
+    /// Select the previous tag.
+    pub fn tags_select_previous(&mut self) {
+        self.tags_state.selected = self.tags_state.selected.saturating_sub(1);
+    }
+
+    /// Select the first tag.
+    pub fn tags_select_first(&mut self) {
+        self.tags_state.selected = 0;
+    }
+
+    /// Select the last tag.
+    pub fn tags_select_last(&mut self) {
+        self.tags_state.selected = self.tags_state.entries.len().saturating_sub(1);
+    }
+
+    /// Adjust the scroll offset so the selected tag is visible.
+    pub fn ensure_tags_visible(&mut self, visible_height: usize) {
+        let state = &mut self.tags_state;
+        if visible_height == 0 {
+            return;
+        }
+        if state.selected < state.scroll {
+            state.scroll = state.selected;
+        } else if state.selected >= state.scroll + visible_height {
+            state.scroll = state.selected + 1 - visible_height;
+        }
+    }
+
+    /// Open the detail view for the selected tag's commit.
+    pub fn open_tag_detail(&mut self) -> Result<(), XorcistError> {
+        let Some(tag) = self.selected_tag() else {
+            return Ok(());
+        };
+        if tag.conflict {
+            self.report(CommandResult {
+                success: false,
+                message: format!("Tag {} is conflicted", tag.name),
+            });
+            return Ok(());
+        }
+        let change_id = tag.change_id.clone();
+        self.open_detail_for(&change_id)
+    }
+}
@@ -0,0 +1,162 @@
+//! Background execution of jj commands for App.
+//!
+//! Commands that change the repository run on a worker thread so that a
+//! slow `jj git fetch` or `jj git push` does not freeze the UI. The event
+//! loop polls for the result and refreshes the log once the command ends.
+
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::XorcistError;
+use crate::jj::JjRunner;
+
+use super::{App, CommandResult, View};
+
+/// Follow-up work once a background command has finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum AfterCommand {
+    /// Only refresh the log.
+    Refresh,
+    /// Re-fetch only these changes, their descendants and `@` if the command
+    /// succeeded.
+    ///
+    /// For commands that rewrite commits in place, like `jj describe`.
+    RefreshRevisions(Vec<String>),
+    /// Clear the marks if the command succeeded, then refresh.
+    ClearMarksOnSuccess,
+    /// Refresh the log and re-fetch the open detail and diff views.
+    ReloadViews,
+    /// Refresh the log and open the output pane on the command's summary.
+    ShowOutput,
+}
+
+/// A jj command running on a worker thread.
+#[derive(Debug)]
+pub(super) struct RunningCommand {
+    /// Command shown in the status bar (e.g., `jj git fetch`).
+    label: String,
+    started: Instant,
+    after: AfterCommand,
+    receiver: Receiver<Result<CommandResult, XorcistError>>,
+}
+
+impl App {
+    /// Run a jj command on a worker thread.
+    ///
+    /// Only one command runs at a time; starting another while one is
+    /// running is refused with a status message.
+    pub(super) fn run_in_background<F>(&mut self, label: &str, after: AfterCommand, command: F)
+    where
+        F: FnOnce(&JjRunner) -> Result<CommandResult, XorcistError> + Send + 'static,
+    {
+        if let Some(running) = &self.running_command {
+            self.report(CommandResult {
+                success: false,
+                message: format!("Wait for `{}` to finish", running.label),
+            });
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let runner = self.runner.clone();
+        thread::spawn(move || {
+            // The receiver is gone only if the app has quit
+            let _ = sender.send(command(&runner));
+        });
+        // Reads meanwhile must not snapshot the working copy the command uses
+        self.runner.set_ignore_working_copy(true);
+        self.running_command = Some(RunningCommand {
+            label: label.to_string(),
+            started: Instant::now(),
+            after,
+            receiver,
+        });
+    }
+
+    /// Label and elapsed time of the command running in the background, if any.
+    pub fn running_command(&self) -> Option<(&str, Duration)> {
+        self.running_command
+            .as_ref()
+            .map(|running| (running.label.as_str(), running.started.elapsed()))
+    }
+
+    /// Whether a command is running in the background.
+    pub fn is_command_running(&self) -> bool {
+        self.running_command.is_some()
+    }
+
+    /// Collect the result of the background command if it has finished.
+    ///
+    /// Returns `true` when a command finished (and the log was refreshed).
+    pub fn poll_background_command(&mut self) -> Result<bool, XorcistError> {
+        let Some(running) = &self.running_command else {
+            return Ok(false);
+        };
+        let result = match running.receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return Ok(false),
+            Err(TryRecvError::Disconnected) => Err(XorcistError::JjError(format!(
+                "`{}` stopped unexpectedly",
+                running.label
+            ))),
+        };
+        let Some(RunningCommand { label, after, .. }) = self.running_command.take() else {
+            return Ok(false);
+        };
+        self.runner.set_ignore_working_copy(false);
+        self.record_command_output(&label, &result);
+
+        let success = result.as_ref().is_ok_and(|r| r.success);
+        self.handle_command_result(result);
+        if !success {
+            self.abort_macro_replay();
+        }
+        if after == AfterCommand::ClearMarksOnSuccess && success {
+            self.clear_marks();
+        }
+        if after == AfterCommand::ShowOutput {
+            self.open_output_pane();
+        }
+        match &after {
+            AfterCommand::RefreshRevisions(change_ids) if success => {
+                self.refresh_revisions(change_ids)?;
+            }
+            _ => self.refresh_log()?,
+        }
+        if after == AfterCommand::ReloadViews {
+            // Keep the command's own result unless the views cannot be reloaded
+            match self.reload_views() {
+                Ok(()) => {}
+                Err(XorcistError::JjError(message)) => {
+                    self.report(CommandResult {
+                        success: false,
+                        message,
+                    });
+                }
+                Err(e) => return Err(e),
+            }
+        } else if self.view == View::OpLog {
+            self.refresh_op_log()?;
+        } else if self.view == View::Workspaces {
+            self.refresh_workspaces()?;
+        } else if self.view == View::Tags {
+            self.refresh_tags()?;
+        } else if self.view == View::Stacks {
+            self.refresh_stacks()?;
+        } else if self.view == View::Sparse {
+            self.refresh_sparse()?;
+        }
+        Ok(true)
+    }
+
+    /// Wait for the background command to finish (for tests).
+    #[cfg(test)]
+    pub(super) fn wait_for_background_command(&mut self) -> Result<(), XorcistError> {
+        while self.is_command_running() {
+            if !self.poll_background_command()? {
+                thread::sleep(Duration::from_millis(5));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,14 @@
 //! Tests for App.
 
+use super::menu::MenuAction;
+use super::messages::MESSAGE_HISTORY_LEN;
+use super::yank::YankTarget;
 use super::*;
-use crate::jj::GraphLog;
+use crate::config::{PushConfig, StartupView};
+use crate::jj::stack::StackCommit;
+use crate::jj::{CacheKind, CachedOutput, DiffStat, GraphLog, OpLogEntry, RelatedCommit, Stack};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 fn make_graph_log(count: usize) -> GraphLog {
     // Create a simple graph log with N commits
@@ -33,6 +39,8 @@ fn expected_change_id(i: usize) -> String {
     index_to_change_id(i)
 }
 
+/// Runner for a directory that is not a jj repository: every jj command
+/// fails, whether or not jj is installed.
 fn make_runner() -> JjRunner {
     JjRunner::new().with_work_dir(Path::new("/tmp"))
 }
@@ -131,9 +139,17 @@ fn test_view_transitions() {
             description: "Test".to_string(),
             bookmarks: vec![],
             diff_summary: vec![],
+            signature: None,
+            parents: Vec::new(),
+            divergent: false,
         },
         scroll: 5,
         content_height: 20,
+        full_diff: None,
+        stat: Default::default(),
+        children: Vec::new(),
+        related: 0,
+        working_copy: false,
     });
 
     app.close_detail();
@@ -158,9 +174,17 @@ fn test_detail_scroll() {
             description: "Test".to_string(),
             bookmarks: vec![],
             diff_summary: vec![],
+            signature: None,
+            parents: Vec::new(),
+            divergent: false,
         },
         scroll: 5,
         content_height: 20,
+        full_diff: None,
+        stat: Default::default(),
+        children: Vec::new(),
+        related: 0,
+        working_copy: false,
     });
 
     app.detail_scroll_down(3);
@@ -191,9 +215,17 @@ fn test_set_detail_content_height() {
             description: "Test".to_string(),
             bookmarks: vec![],
             diff_summary: vec![],
+            signature: None,
+            parents: Vec::new(),
+            divergent: false,
         },
         scroll: 50,
         content_height: 0,
+        full_diff: None,
+        stat: Default::default(),
+        children: Vec::new(),
+        related: 0,
+        working_copy: false,
     });
 
     // Setting height should clamp scroll
@@ -342,9 +374,85 @@ fn test_ensure_selected_visible() {
     );
 }
 
+#[test]
+fn test_ensure_selected_visible_rows() {
+    let graph_log = make_graph_log(20);
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+    // Every line wraps onto a second row
+    let heights = vec![2; app.line_count()];
+
+    app.selected = 10;
+    app.ensure_selected_visible_rows(10, &heights);
+    let line_idx = app.selected_line_index().unwrap();
+    // Both rows of the selected commit end the viewport
+    assert_eq!(app.scroll_offset, line_idx * 2 + 2 - 10);
+
+    // Moving up past the top scrolls to the commit's first row
+    app.selected = 1;
+    app.ensure_selected_visible_rows(10, &heights);
+    let line_idx = app.selected_line_index().unwrap();
+    assert_eq!(app.scroll_offset, line_idx * 2);
+
+    // A commit taller than the viewport shows its first row
+    let mut heights = vec![1; app.line_count()];
+    app.selected = 12;
+    let line_idx = app.selected_line_index().unwrap();
+    heights[line_idx] = 5;
+    app.ensure_selected_visible_rows(3, &heights);
+    assert_eq!(app.scroll_offset, line_idx);
+}
+
+#[test]
+fn test_toggle_wrap_descriptions() {
+    let graph_log = make_graph_log(5);
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+    app.scroll_offset = 3;
+
+    app.toggle_wrap_descriptions();
+    assert!(app.wrap_descriptions);
+    assert_eq!(app.scroll_offset, 0);
+
+    app.toggle_wrap_descriptions();
+    assert!(!app.wrap_descriptions);
+}
+
+#[test]
+fn test_absolute_timestamps_widen_column() {
+    use crate::config::LogColumnKind;
+    use crate::jj::{ABSOLUTE_TIMESTAMP_WIDTH, TimestampFormat};
+
+    let timestamp_width = |app: &App| {
+        app.log_columns()
+            .iter()
+            .find(|column| column.kind == LogColumnKind::Timestamp)
+            .map(|column| column.width)
+    };
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    assert_eq!(timestamp_width(&app), Some(4));
+    app.runner.set_timestamp_format(TimestampFormat::Absolute);
+    assert_eq!(timestamp_width(&app), Some(ABSOLUTE_TIMESTAMP_WIDTH));
+
+    // A custom template decides the timestamps itself
+    let runner = make_runner().with_log_template("custom".to_string());
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), runner);
+    app.toggle_timestamp_format().unwrap();
+    assert_eq!(app.runner.timestamp_format(), TimestampFormat::Relative);
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+}
+
 // === DiffState tests ===
 
-use crate::jj::{DiffEntry, DiffStatus};
+use crate::jj::{DiffEntry, DiffStatus, GitDiff};
+
+/// Build a single-file diff with exactly `line_count` display lines.
+fn make_diff(line_count: usize) -> GitDiff {
+    let body = line_count.saturating_sub(3);
+    let mut text = format!("diff --git a/f.rs b/f.rs\n--- a/f.rs\n@@ -1,{body} +1,{body} @@\n");
+    for i in 0..body {
+        text.push_str(&format!(" line {i}\n"));
+    }
+    GitDiff::parse(&text)
+}
 
 fn make_diff_entries(count: usize) -> Vec<DiffEntry> {
     (0..count)
@@ -364,7 +472,7 @@ fn test_diff_state_new() {
     assert_eq!(state.files.len(), 3);
     assert_eq!(state.selected, 0);
     assert_eq!(state.file_scroll, 0);
-    assert!(state.diff_lines.is_empty());
+    assert!(state.diff.is_empty());
     assert_eq!(state.diff_scroll, 0);
 }
 
@@ -427,7 +535,7 @@ fn test_diff_select_navigation() {
 fn test_diff_scroll() {
     let graph_log = GraphLog::default();
     let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
-    app.diff_state.diff_lines = vec!["line1".to_string(); 100];
+    app.diff_state.diff = make_diff(100);
     app.diff_state.diff_scroll = 0;
 
     app.diff_scroll_down(10);
@@ -448,7 +556,7 @@ fn test_diff_scroll() {
 fn test_clamp_diff_scroll() {
     let graph_log = GraphLog::default();
     let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
-    app.diff_state.diff_lines = vec!["line".to_string(); 50];
+    app.diff_state.diff = make_diff(50);
     app.diff_state.diff_scroll = 100; // Beyond content
 
     // Visible height 20, content 50 -> max_scroll = 30
@@ -499,3 +607,2523 @@ fn test_ensure_diff_file_visible_zero_height() {
     app.ensure_diff_file_visible(0);
     assert_eq!(app.diff_state.file_scroll, 5);
 }
+
+// === Auto-refresh tests ===
+
+#[test]
+fn test_auto_refresh_disabled_by_default() {
+    let app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    assert!(app.auto_refresh_interval().is_none());
+    assert!(!app.should_auto_refresh());
+}
+
+#[test]
+fn test_auto_refresh_zero_interval_disables() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.set_auto_refresh_interval(Some(std::time::Duration::ZERO));
+    assert!(app.auto_refresh_interval().is_none());
+}
+
+#[test]
+fn test_auto_refresh_waits_for_idle() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.set_auto_refresh_interval(Some(std::time::Duration::from_millis(1)));
+
+    // Interval elapsed, but the user just pressed a key
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    app.record_activity();
+    assert!(!app.should_auto_refresh());
+}
+
+#[test]
+fn test_select_change_id() {
+    let mut app = App::new(make_graph_log(5), "/repo".to_string(), make_runner());
+
+    assert!(app.select_change_id(&expected_change_id(3)));
+    assert_eq!(app.selected, 3);
+
+    // Unknown change_id keeps the selection
+    assert!(!app.select_change_id("zzzzzzzz"));
+    assert_eq!(app.selected, 3);
+}
+
+#[test]
+fn test_clamp_diff_h_scroll_ignores_ansi_codes() {
+    let graph_log = GraphLog::default();
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+    // 20 visible characters wrapped in color codes (longer than the hunk header)
+    app.diff_state.diff =
+        GitDiff::parse("@@ -0,0 +1 @@\n\x1b[38;5;2m+abcdefghijklmnopqrs\x1b[39m\n");
+    assert_eq!(app.diff_state.diff.line_count(), 2);
+    app.diff_state.diff_h_scroll = 100;
+
+    app.clamp_diff_h_scroll(4);
+    assert_eq!(app.diff_state.diff_h_scroll, 16);
+}
+
+// === View stack tests ===
+
+fn make_detail_state(change_id: &str) -> DetailState {
+    DetailState {
+        show_output: ShowOutput {
+            change_id: change_id.to_string(),
+            change_id_prefix: change_id[..4].to_string(),
+            change_id_rest: change_id[4..].to_string(),
+            commit_id: "def456".to_string(),
+            commit_id_prefix: "def".to_string(),
+            commit_id_rest: "456".to_string(),
+            author: "Test".to_string(),
+            timestamp: "now".to_string(),
+            description: "Test".to_string(),
+            bookmarks: vec![],
+            diff_summary: make_diff_entries(5),
+            signature: None,
+            parents: Vec::new(),
+            divergent: false,
+        },
+        scroll: 0,
+        content_height: 0,
+        full_diff: None,
+        stat: Default::default(),
+        children: Vec::new(),
+        related: 0,
+        working_copy: false,
+    }
+}
+
+#[test]
+fn test_detail_related_navigation() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    let related = |change_id: &str| RelatedCommit {
+        change_id: change_id.to_string(),
+        description: String::new(),
+    };
+    let mut state = make_detail_state("abcdefgh");
+    state.show_output.parents = vec![related("parentaa")];
+    state.children = vec![related("childaaa"), related("childbbb")];
+    app.detail_state = Some(state);
+    app.push_view(View::Detail);
+
+    // Tab walks the parents, then the children, and wraps around
+    app.detail_select_next_related();
+    app.detail_select_next_related();
+    assert_eq!(app.detail_related()[2].change_id, "childbbb");
+    app.detail_select_next_related();
+    assert_eq!(app.detail_state.as_ref().unwrap().related, 0);
+    app.detail_select_previous_related();
+    assert_eq!(app.detail_state.as_ref().unwrap().related, 2);
+
+    // Back from a drilled-down commit returns to the one it was opened from
+    app.detail_back.push(app.detail_state.clone().unwrap());
+    app.detail_state = Some(make_detail_state("childbbb"));
+    app.push_view(View::Detail);
+    app.close_detail();
+    assert_eq!(app.view, View::Detail);
+    let state = app.detail_state.as_ref().unwrap();
+    assert_eq!(state.show_output.change_id, "abcdefgh");
+    assert_eq!(state.related, 2);
+    app.close_detail();
+    assert_eq!(app.view, View::Log);
+    assert!(app.detail_state.is_none());
+}
+
+#[test]
+fn test_view_stack_restores_log_position() {
+    let mut app = App::new(make_graph_log(30), "/repo".to_string(), make_runner());
+    app.selected = 12;
+    app.scroll_offset = 7;
+
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.push_view(View::Detail);
+    assert_eq!(app.view, View::Detail);
+    assert_eq!(app.view_stack.len(), 1);
+
+    // Something moved the log underneath (e.g. a refresh)
+    app.scroll_offset = 0;
+
+    app.close_detail();
+    assert_eq!(app.view, View::Log);
+    assert_eq!(app.selected, 12);
+    assert_eq!(app.scroll_offset, 7);
+    assert!(app.view_stack.is_empty());
+
+    // A reload with two commits fewer above: the same commit is selected
+    let selected = app.selected_change_id().unwrap().to_string();
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.push_view(View::Detail);
+    let output: String = (2..30)
+        .map(|i| format!("@  {} Author {i}h Entry {i}\n", index_to_change_id(i)))
+        .collect();
+    app.graph_log = GraphLog::from_output(&output);
+    app.close_detail();
+    assert_eq!(app.selected, 10);
+    assert_eq!(app.selected_change_id(), Some(selected.as_str()));
+
+    // The commit is gone: the index is kept within the log
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.push_view(View::Detail);
+    app.graph_log = make_graph_log(5);
+    app.close_detail();
+    assert_eq!(app.selected, 4);
+}
+
+#[test]
+fn test_view_stack_restores_detail_scroll_after_diff() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.push_view(View::Detail);
+    app.detail_scroll_down(4);
+
+    app.diff_state = DiffState::new("abcdefgh".to_string(), make_diff_entries(5));
+    app.push_view(View::Diff);
+    app.diff_state.selected = 3;
+    app.diff_state.diff_scroll = 9;
+
+    app.close_diff();
+    assert_eq!(app.view, View::Detail);
+    assert_eq!(app.detail_state.as_ref().unwrap().scroll, 4);
+
+    // The diff position is remembered for the same change
+    let remembered = app.remembered_position(View::Diff, "abcdefgh").unwrap();
+    assert_eq!(remembered.selected, 3);
+    assert_eq!(remembered.scroll, 9);
+    assert!(app.remembered_position(View::Diff, "zzzzzzzz").is_none());
+}
+
+#[test]
+fn test_view_stack_reopen_detail_restores_scroll() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.push_view(View::Detail);
+    app.detail_scroll_down(6);
+    app.close_detail();
+
+    // Reopening the same revision restores the scroll offset
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.push_view(View::Detail);
+    assert_eq!(app.detail_state.as_ref().unwrap().scroll, 6);
+    app.close_detail();
+
+    // A different revision starts at the top
+    app.detail_state = Some(make_detail_state("zyxwvuts"));
+    app.push_view(View::Detail);
+    assert_eq!(app.detail_state.as_ref().unwrap().scroll, 0);
+}
+
+// === Command line tests ===
+
+#[test]
+fn test_command_line_completion() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.start_input_mode(InputMode::Command);
+    app.input = Input::new("desc".to_string());
+
+    app.complete_command_line();
+    assert_eq!(app.input.value(), "describe ");
+    assert_eq!(app.input.cursor(), "describe ".len());
+
+    // Nothing to complete once arguments are being typed
+    app.input = Input::new("describe fi".to_string());
+    app.complete_command_line();
+    assert_eq!(app.input.value(), "describe fi");
+}
+
+#[test]
+fn test_command_line_reports_parse_errors() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.start_input_mode(InputMode::Command);
+    app.input = Input::new("frobnicate".to_string());
+
+    app.submit_input().unwrap();
+    assert!(!app.is_input_mode());
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("unknown command"));
+}
+
+#[test]
+fn test_command_line_opens_confirmation_and_quits() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+
+    app.run_command_line("abandon").unwrap();
+    assert!(matches!(
+        app.modal,
+        ModalState::Confirm(PendingAction::Abandon { .. })
+    ));
+    app.close_modal();
+
+    // :push goes through the push dialog (no bookmarks are loaded here),
+    // never a bare `jj git push`
+    app.run_command_line("push").unwrap();
+    assert!(!app.is_modal_open());
+    assert!(!app.is_command_running());
+    assert_eq!(
+        app.last_command_result.as_ref().unwrap().message,
+        "No local bookmarks to push"
+    );
+
+    app.run_command_line("q").unwrap();
+    assert!(app.should_quit);
+}
+
+// === Mark tests ===
+
+#[test]
+fn test_toggle_mark_moves_down_and_keeps_log_order() {
+    let mut app = App::new(make_graph_log(5), "/repo".to_string(), make_runner());
+    app.selected = 3;
+    app.toggle_mark();
+    assert_eq!(app.selected, 4);
+    app.selected = 1;
+    app.toggle_mark();
+
+    assert_eq!(app.mark_count(), 2);
+    assert_eq!(
+        app.marked_change_ids(),
+        vec![expected_change_id(1), expected_change_id(3)]
+    );
+
+    // Toggling again unmarks
+    app.selected = 1;
+    app.toggle_mark();
+    assert_eq!(app.marked_change_ids(), vec![expected_change_id(3)]);
+
+    app.clear_marks();
+    assert!(!app.has_marks());
+}
+
+#[test]
+fn test_rebase_with_marks_confirms_every_change() {
+    let mut app = App::new(make_graph_log(5), "/repo".to_string(), make_runner());
+    app.selected = 2;
+    app.toggle_mark();
+    app.selected = 0;
+    app.toggle_mark();
+
+    app.execute_rebase("main").unwrap();
+
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation dialog");
+    };
+    let PendingAction::RebaseMarked {
+        revisions,
+        destination,
+    } = action
+    else {
+        panic!("expected a bulk rebase");
+    };
+    assert_eq!(destination, "main");
+    let ids: Vec<&str> = revisions.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, vec![expected_change_id(0), expected_change_id(2)]);
+    assert_eq!(action.confirm_message(), "Rebase 2 changes onto \"main\"?");
+    assert_eq!(action.details().len(), 2);
+    assert!(action.details()[0].starts_with(&expected_change_id(0)));
+}
+
+#[test]
+fn test_housekeeping_confirms() {
+    let mut app = App::new(make_graph_log(5), "/repo".to_string(), make_runner());
+    app.selected = 1;
+    app.show_simplify_parents_confirm().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation dialog");
+    };
+    let id = expected_change_id(1);
+    assert_eq!(
+        action.command_args(),
+        ["simplify-parents", "-r", id.as_str()]
+    );
+    assert_eq!(action.command(), "simplify_parents");
+    assert_eq!(
+        action.confirm_message(),
+        "Remove redundant parents of 1 change?"
+    );
+    app.close_modal();
+
+    // Marks take precedence over the selection
+    app.selected = 3;
+    app.toggle_mark();
+    app.selected = 0;
+    app.toggle_mark();
+    app.start_input_mode(InputMode::RebaseSkipEmptiedDestination);
+    app.input = Input::new("main".to_string());
+    app.submit_input().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation dialog");
+    };
+    let (first, last) = (expected_change_id(0), expected_change_id(3));
+    assert_eq!(
+        action.command_args(),
+        [
+            "rebase",
+            "-r",
+            first.as_str(),
+            "-r",
+            last.as_str(),
+            "-d",
+            "main",
+            "--skip-emptied"
+        ]
+    );
+    assert_eq!(action.details().len(), 2);
+}
+
+#[test]
+fn test_visual_mode_marks_range() {
+    let mut app = App::new(make_graph_log(6), "/repo".to_string(), make_runner());
+    app.selected = 4;
+    app.toggle_visual_mode();
+    assert!(app.is_visual_mode());
+    app.select_previous();
+    app.select_previous();
+    assert_eq!(app.visual_range_len(), 3);
+    assert!(app.in_visual_range(&expected_change_id(3)));
+    assert!(!app.in_visual_range(&expected_change_id(1)));
+    assert!(!app.has_marks());
+
+    app.toggle_visual_mode();
+    assert!(!app.is_visual_mode());
+    assert_eq!(
+        app.marked_change_ids(),
+        vec![
+            expected_change_id(2),
+            expected_change_id(3),
+            expected_change_id(4)
+        ]
+    );
+
+    // Cancelling leaves the marks alone
+    app.toggle_visual_mode();
+    app.select_first();
+    app.cancel_visual_mode();
+    assert_eq!(app.mark_count(), 3);
+}
+
+#[test]
+fn test_abandon_and_squash_with_marks() {
+    let mut app = App::new(make_graph_log(5), "/repo".to_string(), make_runner());
+    app.selected = 1;
+    app.toggle_mark();
+    app.toggle_mark();
+
+    app.show_abandon_confirm().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation dialog");
+    };
+    assert!(matches!(action, PendingAction::AbandonMarked { revisions } if revisions.len() == 2));
+    assert_eq!(action.confirm_message(), "Abandon 2 changes?");
+    app.close_modal();
+
+    // The selected commit is the squash destination and must not be marked
+    app.selected = 1;
+    app.show_squash_confirm().unwrap();
+    assert!(!app.is_modal_open());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    app.selected = 4;
+    app.show_squash_confirm().unwrap();
+    let ModalState::Confirm(PendingAction::SquashMarked { revisions, into }) = &app.modal else {
+        panic!("expected a bulk squash");
+    };
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(into.0, expected_change_id(4));
+}
+
+#[test]
+fn test_abandon_empty_confirm() {
+    let mut app = App::new(make_graph_log(4), "/tmp".to_string(), JjRunner::new());
+    app.show_abandon_empty_confirm().unwrap();
+    assert!(!app.is_modal_open());
+    assert!(app.last_command_result.as_ref().unwrap().success);
+
+    // The working copy and immutable commits are never offered
+    for line in &mut app.graph_log.lines {
+        line.flags.empty = true;
+    }
+    app.graph_log.lines[0].flags.working_copy = true;
+    app.graph_log.lines[3].flags.immutable = true;
+    app.quick_mode = true;
+    app.show_abandon_empty_confirm().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation dialog, even in quick mode");
+    };
+    let PendingAction::AbandonEmpty { revisions } = action else {
+        panic!("expected an empty-commit abandon");
+    };
+    let ids: Vec<String> = revisions.iter().map(|(id, _)| id.clone()).collect();
+    assert_eq!(ids, [expected_change_id(1), expected_change_id(2)]);
+    assert_eq!(action.confirm_message(), "Abandon 2 empty changes?");
+}
+
+// === Compare mode tests ===
+
+#[test]
+fn test_compare_selected_sets_and_clears_base() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.selected = 1;
+
+    app.compare_selected().unwrap();
+    assert_eq!(app.compare_base(), Some(expected_change_id(1).as_str()));
+    assert!(app.last_command_result.as_ref().unwrap().success);
+
+    // Pressing c on the base again clears it without opening a diff
+    app.compare_selected().unwrap();
+    assert_eq!(app.compare_base(), None);
+    assert_eq!(app.view, View::Log);
+}
+
+#[test]
+fn test_diff_state_for_range() {
+    let range = DiffRange {
+        from: "abcdefgh".to_string(),
+        to: "zyxwvuts".to_string(),
+    };
+    let state = DiffState::for_range(range.clone(), make_diff_entries(2));
+    assert_eq!(state.range, Some(range));
+    assert_eq!(state.change_id, "abcdefgh..zyxwvuts");
+    assert_eq!(state.files.len(), 2);
+}
+
+#[test]
+fn test_diff_prompts_prefill_and_reject_empty_revision() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.selected = 2;
+
+    app.start_diff_prompts();
+    assert_eq!(app.input_mode, Some(InputMode::DiffFrom));
+    assert_eq!(app.input.value(), expected_change_id(2));
+
+    // An empty revision is rejected and the prompt stays open
+    app.input = Input::new("  ".to_string());
+    app.submit_input().unwrap();
+    assert_eq!(app.input_mode, Some(InputMode::DiffFrom));
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("invalid revision"));
+
+    app.cancel_input_mode();
+    assert!(!app.is_input_mode());
+}
+
+#[test]
+fn test_context_menu_opens_for_selected_commit() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.selected = 1;
+
+    app.open_context_menu();
+    let ModalState::Menu(menu) = &app.modal else {
+        panic!("expected context menu");
+    };
+    assert_eq!(menu.change_id, expected_change_id(1));
+    assert!(app.is_modal_open());
+
+    // Choosing push opens a confirmation for that change
+    app.execute_menu_action(MenuAction::PushChange).unwrap();
+    let ModalState::Confirm(PendingAction::PushChange { change_id, .. }) = &app.modal else {
+        panic!("expected push confirmation");
+    };
+    assert_eq!(change_id, &expected_change_id(1));
+
+    // Confirming keeps other modals untouched
+    app.modal = ModalState::Menu(ContextMenu::for_commit(
+        "abcdefgh".to_string(),
+        Default::default(),
+    ));
+    app.confirm_action().unwrap();
+    assert!(matches!(app.modal, ModalState::Menu(_)));
+}
+
+#[test]
+fn test_select_at_row_maps_clicks_to_commits() {
+    let output = "@  aaaaaaaa A 1h First\n│  graph only\n○  bbbbbbbb B 2h Second\n";
+    let mut app = App::new(
+        GraphLog::from_output(output),
+        "/repo".to_string(),
+        make_runner(),
+    );
+    // List drawn from screen row 1, three rows high, with line 0 wrapped
+    app.set_log_layout(1, 3, vec![2, 1, 1]);
+
+    assert!(!app.select_at_row(0));
+    assert!(app.select_at_row(2));
+    assert_eq!(app.selected, 0);
+    // A graph-only line selects the commit above
+    assert!(app.select_at_row(3));
+    assert_eq!(app.selected, 0);
+
+    app.scroll_offset = 1;
+    assert!(app.select_at_row(3));
+    assert_eq!(app.selected, 1);
+    assert!(!app.select_at_row(4));
+
+    // Without wrapping, every line is one row
+    app.set_log_layout(1, 3, Vec::new());
+    app.scroll_offset = 0;
+    assert!(app.select_at_row(3));
+    assert_eq!(app.selected, 1);
+    assert!(app.select_at_row(2));
+    assert_eq!(app.selected, 0);
+    app.scroll_offset = 1;
+    assert!(!app.select_at_row(3));
+}
+
+#[test]
+fn test_confirm_focus_defaults_to_no() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+
+    app.show_abandon_confirm().unwrap();
+    assert_eq!(app.confirm_focus, ConfirmButton::No);
+    app.activate_confirm_focus().unwrap();
+    assert!(!app.is_modal_open());
+
+    // Focus moves to Yes and is reset once the dialog is done
+    app.show_undo_confirm();
+    app.toggle_confirm_focus();
+    assert_eq!(app.confirm_focus, ConfirmButton::Yes);
+    let _ = app.activate_confirm_focus();
+    assert!(!app.is_modal_open());
+    assert_eq!(app.confirm_focus, ConfirmButton::No);
+
+    app.show_undo_confirm();
+    app.toggle_confirm_focus();
+    app.close_modal();
+    assert_eq!(app.confirm_focus, ConfirmButton::No);
+}
+
+#[test]
+fn test_undo_confirmation_shows_the_operation() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+
+    // Without jj the operation log cannot be read, but undo can still be confirmed
+    app.show_undo_confirm();
+    let ModalState::Confirm(action @ PendingAction::Undo { operation: None }) = &app.modal else {
+        panic!("expected an undo confirmation");
+    };
+    assert_eq!(action.details(), ["(could not read the operation log)"]);
+
+    let action = PendingAction::Undo {
+        operation: Some(OpLogEntry {
+            id: "0123abcd".to_string(),
+            is_current: true,
+            user: "me@host".to_string(),
+            time: "5 minutes ago".to_string(),
+            description: "rebase commit 4567".to_string(),
+            tags: "args: jj rebase -d main".to_string(),
+        }),
+    };
+    assert_eq!(
+        action.details(),
+        [
+            "0123abcd rebase commit 4567",
+            "5 minutes ago by me@host",
+            "args: jj rebase -d main",
+        ]
+    );
+}
+
+#[test]
+fn test_quick_mode_requires_config_and_keeps_push_confirmation() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+
+    // Disabled unless allowed by the config file
+    app.toggle_quick_mode();
+    assert!(!app.quick_mode());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    app.apply_confirm_config(&ConfirmConfig {
+        allow_quick_mode: true,
+        quick_mode_push: false,
+        ..Default::default()
+    });
+    app.toggle_quick_mode();
+    assert!(app.quick_mode());
+
+    // Abandon runs immediately instead of opening the dialog
+    let _ = app.show_abandon_confirm();
+    assert!(!app.is_modal_open());
+
+    // Push still asks
+    app.show_push_change_confirm().unwrap();
+    assert!(matches!(
+        app.modal,
+        ModalState::Confirm(PendingAction::PushChange { .. })
+    ));
+    app.close_modal();
+
+    app.toggle_quick_mode();
+    assert!(!app.quick_mode());
+    app.show_squash_confirm().unwrap();
+    assert!(app.is_modal_open());
+}
+
+#[test]
+fn test_stale_log_indicator() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    // The event loop keeps ticking without auto-refresh
+    assert!(!app.poll_timeout().is_zero());
+    assert!(!app.is_log_stale());
+
+    app.set_stale_after(Some(std::time::Duration::from_millis(1)));
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert!(app.is_log_stale());
+
+    app.set_stale_after(Some(std::time::Duration::ZERO));
+    assert!(!app.is_log_stale());
+}
+
+#[test]
+fn test_external_change_sets_banner_until_refresh() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let heads_dir = temp.path().join("heads");
+    std::fs::create_dir(&heads_dir).unwrap();
+    std::fs::write(heads_dir.join("aaaa"), "").unwrap();
+
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.set_op_heads_dir(heads_dir.clone());
+    app.check_external_changes();
+    assert!(!app.is_repo_changed());
+
+    // Another jj process replaced the operation head
+    std::fs::remove_file(heads_dir.join("aaaa")).unwrap();
+    std::fs::write(heads_dir.join("bbbb"), "").unwrap();
+    app.check_external_changes();
+    assert!(app.is_repo_changed());
+
+    // A refresh records the new head and clears the banner
+    app.record_op_heads();
+    assert!(!app.is_repo_changed());
+    app.check_external_changes();
+    assert!(!app.is_repo_changed());
+}
+
+#[test]
+fn test_export_command_writes_loaded_log() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let path = temp.path().join("log.tsv");
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+
+    app.run_command_line(&format!("export {}", path.display()))
+        .unwrap();
+    assert!(app.last_command_result.as_ref().unwrap().success);
+    let tsv = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(tsv.lines().count(), 4); // header + 3 commits
+
+    app.export_log_to(&temp.path().join("missing").join("log.txt"));
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+}
+
+fn make_op_log_state(count: usize) -> OpLogState {
+    let entries = (0..count)
+        .map(|i| OpLogEntry {
+            id: format!("op{i:09}"),
+            is_current: i == 0,
+            user: "alice@host".to_string(),
+            time: format!("{i} minutes ago"),
+            description: format!("operation {i}"),
+            tags: String::new(),
+        })
+        .collect();
+    OpLogState {
+        entries,
+        selected: 0,
+        scroll: 0,
+    }
+}
+
+#[test]
+fn test_op_log_navigation_and_view_stack() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.op_log_state = make_op_log_state(3);
+    app.push_view(View::OpLog);
+
+    app.op_log_select_previous();
+    assert_eq!(app.op_log_state.selected, 0);
+    app.op_log_select_last();
+    assert_eq!(app.op_log_state.selected, 2);
+    app.op_log_select_next();
+    assert_eq!(app.op_log_state.selected, 2);
+
+    app.ensure_op_log_visible(2);
+    assert_eq!(app.op_log_state.scroll, 1);
+    app.op_log_select_first();
+    app.ensure_op_log_visible(2);
+    assert_eq!(app.op_log_state.scroll, 0);
+
+    app.close_op_log();
+    assert_eq!(app.view, View::Log);
+}
+
+#[test]
+fn test_op_restore_requires_confirmation() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.op_log_state = make_op_log_state(3);
+    app.push_view(View::OpLog);
+
+    // The current operation cannot be restored
+    app.show_op_restore_confirm();
+    assert!(!app.is_modal_open());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    app.op_log_select_next();
+    app.show_op_restore_confirm();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation dialog");
+    };
+    assert!(matches!(
+        action,
+        PendingAction::OpRestore { op_id, description }
+            if op_id == "op000000001" && description == "operation 1"
+    ));
+    assert!(action.confirm_message().contains("op000000001"));
+}
+
+#[test]
+fn test_commands_run_in_background_one_at_a_time() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    let idle_timeout = app.poll_timeout();
+
+    app.execute_git_fetch().unwrap();
+    assert!(app.is_command_running());
+    assert_eq!(app.running_command().unwrap().0, "jj git fetch");
+    assert!(app.poll_timeout() < idle_timeout);
+    // Reads meanwhile leave the working copy to the command
+    assert!(app.runner.ignores_working_copy());
+
+    // Navigation keeps working while the command runs
+    app.select_next();
+    assert_eq!(app.selected, 1);
+
+    // A second command is refused until the first one finishes
+    app.execute_new().unwrap();
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(
+        result.message.contains("jj git fetch"),
+        "{}",
+        result.message
+    );
+
+    // The work dir is not a jj repository, so the command fails and so does
+    // the refresh
+    assert!(app.wait_for_background_command().is_err());
+    assert!(!app.is_command_running());
+    assert!(!app.runner.ignores_working_copy());
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert!(!result.message.contains("Wait for"), "{}", result.message);
+    assert_eq!(app.poll_timeout(), idle_timeout);
+}
+
+#[test]
+fn test_search_moves_selection_as_you_type() {
+    let graph_log = make_graph_log(30);
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+    app.selected = 5;
+
+    app.start_search();
+    assert_eq!(app.input_mode, Some(InputMode::Search));
+    for len in 1..="entry 2".len() {
+        app.input = Input::new("entry 2"[..len].to_string());
+        app.update_search();
+    }
+    // "Entry 2", "Entry 20".."Entry 29" match; the first at or after 5 is 20
+    let search = app.search().unwrap();
+    assert_eq!(search.matches.len(), 11);
+    assert_eq!(app.selected, 20);
+    assert_eq!(search.match_position(20), Some(1));
+
+    // Smart case: an uppercase letter makes the search case-sensitive
+    app.input = Input::new("ENTRY".to_string());
+    app.update_search();
+    assert!(app.search().unwrap().matches.is_empty());
+    assert_eq!(app.selected, 5);
+}
+
+#[test]
+fn test_nearest_loaded_matches_prefixes() {
+    let mut app = App::new(make_graph_log(10), "/repo".to_string(), make_runner());
+    app.selected = 5;
+    // jj's short ids are longer than the ids shown in the log
+    let ids = [
+        format!("{}xyzw", expected_change_id(1)),
+        format!("{}xyzw", expected_change_id(7)),
+    ];
+    assert_eq!(app.nearest_loaded(&ids), Some(7));
+    assert_eq!(app.nearest_loaded(&ids[..1]), Some(1));
+    assert_eq!(app.nearest_loaded(&["zzzzzzzzzzzz".to_string()]), None);
+    assert_eq!(app.nearest_loaded(&[]), None);
+}
+
+#[test]
+fn test_finder_ranks_and_jumps() {
+    let graph_log = make_graph_log(30);
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+
+    app.open_finder();
+    assert_eq!(app.input_mode, Some(InputMode::Finder));
+    assert_eq!(app.finder().unwrap().results.len(), 30);
+
+    // "en2" matches "Entry 2" and "Entry 20".."Entry 29"; ties keep log order
+    app.input = Input::new("en2".to_string());
+    app.update_finder();
+    let results = &app.finder().unwrap().results;
+    assert_eq!(results[..2], [2, 20]);
+    assert!(!results.contains(&3));
+
+    app.finder_select_next();
+    let second = app.finder().unwrap().results[1];
+    app.submit_input().unwrap();
+    assert_eq!(app.selected, second);
+    assert!(app.finder().is_none());
+    assert!(!app.is_input_mode());
+
+    // Esc leaves the selection alone
+    app.open_finder();
+    app.input = Input::new("Entry 1".to_string());
+    app.update_finder();
+    app.cancel_input_mode();
+    assert_eq!(app.selected, second);
+    assert!(app.finder().is_none());
+}
+
+#[test]
+fn test_search_next_previous_wrap() {
+    let graph_log = make_graph_log(30);
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+
+    app.start_search();
+    app.input = Input::new("entry 1".to_string());
+    app.update_search();
+    app.submit_input().unwrap();
+    assert!(app.has_search());
+    assert_eq!(app.selected, 1);
+    let line_idx = app.graph_log.commit_line_indices[1];
+    assert!(app.is_search_match(line_idx));
+    assert!(!app.is_search_match(app.graph_log.commit_line_indices[2]));
+
+    app.search_next();
+    assert_eq!(app.selected, 10);
+    app.search_previous();
+    assert_eq!(app.selected, 1);
+    app.search_previous();
+    assert_eq!(app.selected, 19);
+    assert!(
+        app.last_command_result
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("hit TOP")
+    );
+    app.search_next();
+    assert_eq!(app.selected, 1);
+    assert!(
+        app.last_command_result
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("hit BOTTOM")
+    );
+
+    // Clearing keeps the selection
+    app.clear_search();
+    assert!(!app.has_search());
+    assert_eq!(app.selected, 1);
+}
+
+#[test]
+fn test_search_cancel_restores_selection() {
+    let graph_log = make_graph_log(30);
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+    app.selected = 3;
+
+    app.start_search();
+    app.input = Input::new("entry 25".to_string());
+    app.update_search();
+    assert_eq!(app.selected, 25);
+
+    app.cancel_input_mode();
+    assert!(!app.has_search());
+    assert_eq!(app.selected, 3);
+}
+
+#[test]
+fn test_search_not_found() {
+    let graph_log = make_graph_log(5);
+    let mut app = App::new(graph_log, "/repo".to_string(), make_runner());
+
+    app.start_search();
+    app.input = Input::new("nothing".to_string());
+    app.update_search();
+    app.submit_input().unwrap();
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert_eq!(result.message, "Pattern not found: nothing");
+    assert_eq!(app.selected, 0);
+}
+
+#[test]
+fn test_split_marked_files() {
+    let mut app = App::new(GraphLog::default(), "/repo".to_string(), make_runner());
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.diff_state = DiffState::new("abcdefgh".to_string(), make_diff_entries(5));
+
+    // Toggling the last file does not move (and so does not fetch a diff)
+    app.diff_state.selected = 4;
+    app.toggle_file_mark().unwrap();
+    assert!(app.is_file_marked("src/file4.rs"));
+    app.diff_state
+        .marked_files
+        .insert("src/file1.rs".to_string());
+
+    app.show_split_confirm();
+    match &app.modal {
+        ModalState::Confirm(PendingAction::Split {
+            change_id,
+            description,
+            paths,
+        }) => {
+            assert_eq!(change_id, "abcdefgh");
+            assert_eq!(description, "Test");
+            // Paths follow the file list order
+            assert_eq!(paths, &["src/file1.rs", "src/file4.rs"]);
+        }
+        other => panic!("expected split confirmation, got {other:?}"),
+    }
+
+    // Unmarking removes the mark again
+    app.modal = ModalState::None;
+    app.toggle_file_mark().unwrap();
+    assert!(!app.is_file_marked("src/file4.rs"));
+    assert_eq!(app.marked_files(), vec!["src/file1.rs".to_string()]);
+}
+
+#[test]
+fn test_split_without_marks_uses_selected_file() {
+    let mut app = App::new(GraphLog::default(), "/repo".to_string(), make_runner());
+    app.diff_state = DiffState::new("abcdefgh".to_string(), make_diff_entries(3));
+    app.diff_state.selected = 2;
+
+    app.show_split_confirm();
+    assert!(matches!(
+        &app.modal,
+        ModalState::Confirm(PendingAction::Split { paths, .. }) if paths == &["src/file2.rs"]
+    ));
+}
+
+#[test]
+fn test_split_refuses_all_files_and_comparisons() {
+    let mut app = App::new(GraphLog::default(), "/repo".to_string(), make_runner());
+    app.diff_state = DiffState::new("abcdefgh".to_string(), make_diff_entries(2));
+    app.diff_state.marked_files = ["src/file0.rs", "src/file1.rs"].map(str::to_string).into();
+    app.show_split_confirm();
+    assert!(!app.is_modal_open());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    let range = DiffRange {
+        from: "aaaa".to_string(),
+        to: "bbbb".to_string(),
+    };
+    app.diff_state = DiffState::for_range(range, make_diff_entries(2));
+    app.show_split_confirm();
+    assert!(!app.is_modal_open());
+    assert!(
+        app.last_command_result
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("comparison")
+    );
+}
+
+#[test]
+fn test_detail_diff_file_navigation_and_collapse() {
+    let mut app = App::new(GraphLog::default(), "/repo".to_string(), make_runner());
+    let mut state = make_detail_state("abcdefgh");
+    state.full_diff = Some(DetailDiff {
+        diff: GitDiff::parse(
+            "diff --git a/a b/a\n@@ -1 +1 @@\n-x\n+y\ndiff --git a/b b/b\n@@ -1 +1 @@\n-x\n+y\n",
+        ),
+        ..Default::default()
+    });
+    app.detail_state = Some(state);
+    app.set_detail_file_offsets(vec![15, 20]);
+    let scroll = |app: &App| app.detail_state.as_ref().unwrap().scroll;
+    let collapsed = |app: &App| {
+        let mut files: Vec<usize> = app
+            .detail_state
+            .as_ref()
+            .unwrap()
+            .full_diff
+            .as_ref()
+            .unwrap()
+            .collapsed
+            .iter()
+            .copied()
+            .collect();
+        files.sort();
+        files
+    };
+
+    app.detail_next_file();
+    assert_eq!(scroll(&app), 15);
+    app.detail_next_file();
+    assert_eq!(scroll(&app), 20);
+    app.detail_next_file();
+    assert_eq!(scroll(&app), 20);
+    app.detail_previous_file();
+    assert_eq!(scroll(&app), 15);
+
+    // Collapsing from inside a file targets it and scrolls back to its header
+    app.detail_scroll_down(3);
+    app.toggle_detail_file_collapse();
+    assert_eq!(collapsed(&app), vec![0]);
+    assert_eq!(scroll(&app), 15);
+    app.toggle_detail_file_collapse();
+    assert!(collapsed(&app).is_empty());
+
+    app.toggle_detail_collapse_all();
+    assert_eq!(collapsed(&app), vec![0, 1]);
+    app.toggle_detail_collapse_all();
+    assert!(collapsed(&app).is_empty());
+}
+
+#[test]
+fn test_click_modal_buttons_and_menu_items() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    let region = |x, y, width, target| ModalRegion {
+        x,
+        y,
+        width,
+        target,
+    };
+
+    // Clicking No closes the confirmation; clicks elsewhere do nothing
+    app.show_undo_confirm();
+    app.set_modal_regions(vec![
+        region(10, 5, 7, ModalTarget::Yes),
+        region(19, 5, 6, ModalTarget::No),
+    ]);
+    assert_eq!(app.modal_target_at(16, 5), Some(ModalTarget::Yes));
+    assert_eq!(app.modal_target_at(17, 5), None);
+    app.click_modal(17, 5).unwrap();
+    assert!(app.is_modal_open());
+    app.click_modal(19, 5).unwrap();
+    assert!(!app.is_modal_open());
+
+    // Clicking a menu item runs it; clicking outside closes the menu
+    app.open_context_menu();
+    let toggle_mark = match &app.modal {
+        ModalState::Menu(menu) => menu
+            .items
+            .iter()
+            .position(|&a| a == MenuAction::ToggleMark)
+            .unwrap(),
+        _ => panic!("menu not open"),
+    };
+    app.set_modal_regions(vec![region(
+        4,
+        2 + toggle_mark as u16,
+        30,
+        ModalTarget::MenuItem(toggle_mark),
+    )]);
+    app.click_modal(10, 2 + toggle_mark as u16).unwrap();
+    assert!(app.is_marked(&expected_change_id(0)));
+
+    app.open_context_menu();
+    app.click_modal(0, 0).unwrap();
+    assert!(!app.is_modal_open());
+}
+
+#[test]
+fn test_scroll_wheel_moves_each_view() {
+    let mut app = App::new(make_graph_log(10), "/repo".to_string(), make_runner());
+
+    app.scroll_wheel(true);
+    assert_eq!(app.selected, 3);
+    app.scroll_wheel(false);
+    app.scroll_wheel(false);
+    assert_eq!(app.selected, 0);
+
+    app.diff_state = DiffState::new("abcd1234".to_string(), make_diff_entries(1));
+    app.view = View::Diff;
+    app.scroll_wheel(true);
+    assert_eq!(app.diff_state.diff_scroll, 3);
+    assert_eq!(app.selected, 0);
+}
+
+#[test]
+fn test_yank_prefix_and_detail_text() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+
+    app.start_yank();
+    assert!(app.is_yank_pending());
+    // Any other key cancels without copying
+    app.finish_yank(Some('x')).unwrap();
+    assert!(!app.is_yank_pending());
+    assert!(app.last_command_result.is_none());
+
+    app.detail_state = Some(make_detail_state("abcdefgh"));
+    app.push_view(View::Detail);
+    let text = |app: &App, ch| app.yank_text(YankTarget::from_key(ch).unwrap()).unwrap();
+    assert_eq!(text(&app, 'y').as_deref(), Some("abcdefgh"));
+    assert_eq!(text(&app, 'c').as_deref(), Some("def456"));
+    assert_eq!(text(&app, 'g').as_deref(), Some("def456"));
+    assert_eq!(text(&app, 'd').as_deref(), Some("Test"));
+
+    // The full commit ID is copied, not the shortest unique prefix
+    let full = "def4567890abcdef1234567890abcdef12345678";
+    let output = format!("abc\x00d\x00def\x00\x00{full}\x00Alice\x00now\x00Test\x00\x00");
+    app.detail_state.as_mut().unwrap().show_output =
+        crate::jj::show::parse_show_output(&output).unwrap();
+    assert_eq!(text(&app, 'c').as_deref(), Some(full));
+    assert_eq!(text(&app, 'g').as_deref(), Some(full));
+
+    // The git SHA is only offered in colocated repositories
+    app.finish_yank(Some('g')).unwrap();
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("colocated"), "{}", result.message);
+}
+
+#[test]
+fn test_external_describe_request() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+
+    // Text typed at the describe prompt is carried over to the editor
+    app.start_input_mode_with(InputMode::Describe, "wip: parser");
+    app.start_external_describe().unwrap();
+    assert!(!app.is_input_mode());
+    let request = app.take_editor_request().unwrap();
+    assert_eq!(request.change_id, expected_change_id(0));
+    assert_eq!(request.text, "wip: parser");
+    assert!(!request.is_current);
+    assert!(app.take_editor_request().is_none());
+
+    let current = EditorRequest {
+        change_id: expected_change_id(0),
+        text: "fix: typo\n".to_string(),
+        is_current: true,
+    };
+    app.finish_external_describe(current.clone(), Ok("fix: typo".to_string()));
+    assert_eq!(
+        app.last_command_result.as_ref().unwrap().message,
+        "Description unchanged"
+    );
+    assert!(!app.is_command_running());
+
+    app.finish_external_describe(current, Err(std::io::Error::other("vi not found")));
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("vi not found"));
+}
+
+#[test]
+fn test_describe_prompt_prefilled() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+
+    // Only the subject is edited at the prompt; the body is kept
+    app.open_describe_prompt("fix: tpyo\n\nLonger explanation.".to_string());
+    assert_eq!(app.input.value(), "fix: tpyo");
+    assert!(app.describe_keeps_body());
+    app.submit_input().unwrap();
+    assert_eq!(
+        app.last_command_result.as_ref().unwrap().message,
+        "Description unchanged"
+    );
+    assert!(!app.is_command_running());
+
+    app.open_describe_prompt("fix: tpyo\n\nLonger explanation.".to_string());
+    app.input = Input::new("fix: typo".to_string());
+    app.start_external_describe().unwrap();
+    let request = app.take_editor_request().unwrap();
+    assert_eq!(request.text, "fix: typo\n\nLonger explanation.");
+    assert!(!request.is_current);
+
+    // An empty description is never "unchanged"
+    app.open_describe_prompt(String::new());
+    assert_eq!(app.input.value(), "");
+    assert!(!app.describe_keeps_body());
+    app.cancel_input_mode();
+    assert!(!app.describe_keeps_body());
+}
+
+#[test]
+fn test_refreshed_revisions_include_descendants() {
+    // Rewriting a commit rebases its descendants, changing their commit IDs
+    let ids = ["abc".to_string(), "def".to_string()];
+    assert_eq!(commands::revisions_revset(&ids), "(abc | def):: | @");
+    assert_eq!(commands::revisions_revset(&[]), "@");
+}
+
+#[test]
+fn test_conventional_describe_builder() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.graph_log.lines[1].description = Some("feat(ui): add x".to_string());
+
+    app.start_conventional_describe();
+    assert_eq!(app.input_mode, Some(InputMode::ConventionalType));
+    assert_eq!(app.finder().unwrap().results.len(), 11);
+    app.input = Input::new("fix".to_string());
+    app.update_finder();
+    app.submit_input().unwrap();
+
+    // Scopes used in the log are listed after "no scope"; a new one is typed
+    assert_eq!(app.input_mode, Some(InputMode::ConventionalScope));
+    let draft = app.conventional_draft().unwrap();
+    assert_eq!(draft.options[1].0, "ui");
+    assert_eq!(app.finder().unwrap().results, [0, 1]);
+    app.input = Input::new("parser".to_string());
+    app.update_finder();
+    assert_eq!(app.finder().unwrap().results, [0]);
+    app.submit_input().unwrap();
+    assert_eq!(app.input_mode, Some(InputMode::ConventionalSubject));
+    assert_eq!(app.conventional_draft().unwrap().prefix(), "fix(parser): ");
+
+    app.input = Input::new("handle eof".to_string());
+    app.submit_input().unwrap();
+    assert!(app.conventional_draft().is_none());
+    assert!(app.is_command_running());
+
+    // Breaking type, no scope; an empty subject is refused
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.start_conventional_describe();
+    app.input = Input::new("feat!".to_string());
+    app.update_finder();
+    app.submit_input().unwrap();
+    app.submit_input().unwrap();
+    assert_eq!(app.conventional_draft().unwrap().prefix(), "feat!: ");
+    app.submit_input().unwrap();
+    assert!(!app.is_input_mode());
+    assert_eq!(
+        app.last_command_result.as_ref().unwrap().message,
+        "Subject cannot be empty"
+    );
+
+    // The typed query is offered last, and must be a valid type
+    app.start_conventional_describe();
+    app.input = Input::new("Fix".to_string());
+    app.update_finder();
+    let results = app.finder().unwrap().results.clone();
+    assert_eq!(results.last(), Some(&0));
+    for _ in 1..results.len() {
+        app.finder_select_next();
+    }
+    app.submit_input().unwrap();
+    assert!(!app.is_input_mode());
+    assert!(
+        app.last_command_result
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Invalid commit type 'Fix'")
+    );
+}
+
+#[test]
+fn test_author_filter() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.graph_log.lines[1].author = Some("Jane \"JD\" Doe".to_string());
+
+    app.open_author_filter();
+    assert_eq!(app.input_mode, Some(InputMode::AuthorFilter));
+    let names: Vec<&str> = app
+        .author_choices()
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(names, ["", "Author", "Jane \"JD\" Doe"]);
+    assert_eq!(app.author_choices()[1].1, 2);
+    assert_eq!(app.finder().unwrap().results, [0, 1, 2]);
+    app.input = Input::new("jane".to_string());
+    app.update_finder();
+    assert_eq!(app.finder().unwrap().results, [2]);
+    app.cancel_input_mode();
+    assert!(app.author_choices().is_empty());
+
+    // The author filter is combined with the revset filter
+    assert_eq!(app.log_revset(), None);
+    app.author_filter = Some("Jane \"JD\" Doe".to_string());
+    assert_eq!(
+        app.log_revset().as_deref(),
+        Some(r#"author(exact:"Jane \"JD\" Doe")"#)
+    );
+    app.revset = Some("mine() | main".to_string());
+    assert_eq!(
+        app.log_revset().as_deref(),
+        Some(r#"(mine() | main) & author(exact:"Jane \"JD\" Doe")"#)
+    );
+}
+
+#[test]
+fn test_path_filter() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.path_filter = Some("src/ui.rs".to_string());
+    app.start_path_filter();
+    assert_eq!(app.input_mode, Some(InputMode::PathFilter));
+    assert_eq!(app.input.value(), "src/ui.rs");
+    app.cancel_input_mode();
+
+    // The path filter is combined with the revset and author filters
+    assert_eq!(app.log_revset().as_deref(), Some(r#"files("src/ui.rs")"#));
+    app.author_filter = Some("Jane".to_string());
+    app.revset = Some("mine()".to_string());
+    assert_eq!(
+        app.log_revset().as_deref(),
+        Some(r#"(mine()) & author(exact:"Jane") & files("src/ui.rs")"#)
+    );
+
+    // A failed reload (the work dir is not a jj repository) keeps the
+    // previous filter
+    assert!(app.set_path_filter(Some("docs/".to_string())).is_err());
+    assert_eq!(app.path_filter(), Some("src/ui.rs"));
+}
+
+#[test]
+fn test_expand_elided_revisions() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.expand_elided().unwrap();
+    assert!(!app.has_elided_expansions());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    let output = "@  qzmtztvn Author 1h Entry\n~  (elided revisions)\n○  rvzpxnov Author 2h Base\n";
+    let mut app = App::new(
+        GraphLog::from_output(output),
+        "/repo".to_string(),
+        make_runner(),
+    );
+    app.revset = Some("mine()".to_string());
+    // A failed reload (the work dir is not a jj repository) drops the
+    // expansion
+    assert!(app.expand_elided_line(1).is_err());
+    assert!(!app.has_elided_expansions());
+    assert_eq!(app.log_revset().as_deref(), Some("mine()"));
+
+    // Expansions are added to the filtered revset, or to jj's default one
+    app.elided_expansions = vec!["heads((mine()) & ::qzmtztvn-)::qzmtztvn-".to_string()];
+    assert_eq!(
+        app.log_revset().as_deref(),
+        Some("(mine()) | heads((mine()) & ::qzmtztvn-)::qzmtztvn-")
+    );
+    app.revset = None;
+    app.default_log_revset = Some("@ | trunk()".to_string());
+    assert_eq!(
+        app.log_revset().as_deref(),
+        Some("(@ | trunk()) | heads((mine()) & ::qzmtztvn-)::qzmtztvn-")
+    );
+}
+
+#[test]
+fn test_toggle_hidden_commits() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    // Without jj the operations cannot be listed, and nothing changes
+    assert!(app.toggle_hidden_commits().is_err());
+    assert!(!app.shows_hidden_commits());
+    assert_eq!(app.log_revset(), None);
+
+    app.hidden_revset = Some("(at_operation(a1b2c3, mutable())) ~ all()".to_string());
+    app.default_log_revset = Some("@ | trunk()".to_string());
+    assert_eq!(
+        app.log_revset().as_deref(),
+        Some("(@ | trunk()) | (at_operation(a1b2c3, mutable())) ~ all()")
+    );
+}
+
+#[test]
+fn test_select_trunk() {
+    let mut app = App::new(make_graph_log(5), "/repo".to_string(), make_runner());
+    app.select_trunk();
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    let trunk = format!("{}xyz", expected_change_id(3));
+    let mut metadata = crate::jj::RepoMetadata::default();
+    metadata.commits.insert(
+        trunk.clone(),
+        crate::jj::metadata::CommitMetadata {
+            trunk: true,
+            ..Default::default()
+        },
+    );
+    app.metadata = crate::jj::MetadataCache::with_metadata(metadata.clone());
+    app.last_command_result = None;
+    app.select_trunk();
+    assert_eq!(app.selected, 3);
+    assert!(app.last_command_result.is_none());
+    assert!(app.is_trunk(&expected_change_id(3)));
+    assert!(!app.is_trunk(&expected_change_id(2)));
+
+    // Not loaded (e.g. beyond the log limit)
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.metadata = crate::jj::MetadataCache::with_metadata(metadata);
+    app.select_trunk();
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(result.message.contains("not loaded"), "{}", result.message);
+}
+
+#[test]
+fn test_start_shell() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    assert!(app.take_external_command().is_none());
+    app.run_command_line("sh").unwrap();
+    let command = app.take_external_command().unwrap();
+    assert_eq!(command.dir.as_deref(), Some(Path::new("/tmp")));
+    assert!(app.take_external_command().is_none());
+}
+
+#[test]
+fn test_open_diff_tool() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.diff_state = DiffState::new("abcd1234".to_string(), make_diff_entries(2));
+    app.diff_state.selected = 1;
+
+    // Without diff.tool nothing runs
+    app.open_diff_tool();
+    assert!(app.take_external_command().is_none());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    app.apply_diff_config(&crate::config::DiffConfig {
+        tool: Some("difft".to_string()),
+        ..Default::default()
+    });
+    app.open_diff_tool();
+    let command = app.take_external_command().unwrap();
+    assert_eq!(
+        command.display(),
+        "jj diff --tool difft -r abcd1234 -- src/file1.rs"
+    );
+    assert_eq!(command.dir.as_deref(), Some(Path::new("/tmp")));
+
+    app.diff_state.range = Some(DiffRange {
+        from: "main".to_string(),
+        to: "@".to_string(),
+    });
+    app.open_diff_tool();
+    let command = app.take_external_command().unwrap();
+    assert_eq!(
+        command.display(),
+        "jj diff --tool difft --from main --to @ -- src/file1.rs"
+    );
+}
+
+#[test]
+fn test_duplicate_confirm_destination() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.select_next();
+
+    app.start_input_mode(InputMode::DuplicateDestination);
+    app.input = Input::new("main".to_string());
+    app.submit_input().unwrap();
+    match &app.modal {
+        ModalState::Confirm(
+            action @ PendingAction::Duplicate {
+                change_id,
+                destination,
+                ..
+            },
+        ) => {
+            assert_eq!(change_id, &expected_change_id(1));
+            assert_eq!(destination.as_deref(), Some("main"));
+            assert_eq!(
+                action.confirm_message(),
+                "Duplicate change: \"Entry 1\" onto \"main\"?"
+            );
+        }
+        _ => panic!("duplicate confirmation not shown"),
+    }
+
+    // An empty destination keeps the original parents
+    app.close_modal();
+    app.run_command_line("duplicate").unwrap();
+    assert!(matches!(
+        app.modal,
+        ModalState::Confirm(PendingAction::Duplicate {
+            destination: None,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_file_history_navigation() {
+    let mut app = App::new(make_graph_log(10), "/repo".to_string(), make_runner());
+    app.file_history = Some(FileHistoryState {
+        path: "src/main.rs".to_string(),
+        graph_log: make_graph_log(4),
+        ..Default::default()
+    });
+    app.push_view(View::FileHistory);
+
+    app.file_history_select_last();
+    app.file_history_select_next();
+    assert_eq!(app.file_history().unwrap().selected, 3);
+    app.ensure_file_history_visible(2);
+    assert_eq!(app.file_history().unwrap().scroll, 2);
+    assert_eq!(
+        app.file_history().unwrap().selected_change_id(),
+        Some(expected_change_id(3).as_str())
+    );
+    app.file_history_select_first();
+    app.file_history_select_previous();
+    assert_eq!(app.file_history().unwrap().selected, 0);
+
+    app.close_file_history();
+    assert_eq!(app.view, View::Log);
+    assert!(app.file_history().is_none());
+}
+
+#[test]
+fn test_output_pane_keeps_recent_commands() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    for i in 0..output::OUTPUT_HISTORY_LEN + 5 {
+        app.record_command_output(
+            &format!("jj new {i}"),
+            &Ok(CommandResult {
+                success: true,
+                message: "Working copy now at: abc\nParent commit: def".to_string(),
+            }),
+        );
+    }
+    app.record_command_output("jj git push", &Err(XorcistError::JjNotFound));
+
+    let entries = &app.output_pane().entries;
+    assert_eq!(entries.len(), output::OUTPUT_HISTORY_LEN);
+    assert_eq!(entries[0].label, "jj git push");
+    assert!(!entries[0].success);
+    assert_eq!(entries[1].output.lines().count(), 2);
+
+    app.toggle_output_pane();
+    assert!(app.is_output_pane_open());
+    app.output_scroll_down(50);
+    app.clamp_output_scroll(30, 10);
+    assert_eq!(app.output_pane().scroll, 20);
+    app.toggle_output_pane();
+    assert!(!app.is_output_pane_open());
+    assert_eq!(app.output_pane().scroll, 0);
+}
+
+#[test]
+fn test_command_history_rerun() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.toggle_command_history();
+    app.rerun_selected_command().unwrap();
+    assert!(!app.is_modal_open());
+
+    // The work dir is not a jj repository, so the command fails but is
+    // still recorded
+    assert!(app.start_external_describe().is_err());
+    let history = app.command_history();
+    assert!(!history.is_empty());
+    assert!(!history[0].success);
+    assert_ne!(history[0].status, "exit 0");
+
+    app.command_history_select_next();
+    assert_eq!(app.command_history_panel().selected, 0);
+    app.rerun_selected_command().unwrap();
+    assert!(!app.is_command_history_open());
+    let ModalState::Confirm(PendingAction::Rerun { args }) = &app.modal else {
+        panic!("expected a rerun confirmation");
+    };
+    assert_eq!(args, &history[0].args);
+}
+
+#[test]
+fn test_failed_auto_refresh_is_reported() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.set_auto_refresh_interval(Some(Duration::from_secs(1)));
+    app.last_activity = Instant::now() - Duration::from_secs(5);
+    app.last_refresh = Instant::now() - Duration::from_secs(5);
+    assert!(app.should_auto_refresh());
+
+    // The refresh fails (/tmp is not a repository), but the app keeps running
+    app.on_idle_tick();
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert!(result.message.starts_with("Auto-refresh failed"));
+    assert_eq!(app.commit_count(), 3);
+    // ... and it is tried again after the next interval, not on every tick
+    assert!(!app.should_auto_refresh());
+}
+
+#[test]
+fn test_external_change_refresh_waits_for_idle() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let heads_dir = temp.path().join("heads");
+    std::fs::create_dir(&heads_dir).unwrap();
+    std::fs::write(heads_dir.join("aaaa"), "").unwrap();
+
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.set_op_heads_dir(heads_dir.clone());
+    app.refresh_on_external_change = true;
+    std::fs::remove_file(heads_dir.join("aaaa")).unwrap();
+    std::fs::write(heads_dir.join("bbbb"), "").unwrap();
+
+    // A key was just pressed, so the refresh is deferred
+    app.record_activity();
+    app.check_external_changes();
+    assert!(app.is_repo_changed());
+    assert!(app.last_command_result.is_none());
+
+    // Once idle, the pending refresh runs; it fails (/tmp is not a
+    // repository), which is reported and leaves the log flagged
+    app.last_activity = Instant::now() - Duration::from_secs(5);
+    app.check_external_changes();
+    let result = app.last_command_result.take().unwrap();
+    assert!(!result.success);
+    assert!(
+        result
+            .message
+            .starts_with("Refresh after an external change failed")
+    );
+    assert!(app.is_repo_changed());
+    assert_eq!(app.commit_count(), 2);
+
+    // It is not retried on every tick
+    app.check_external_changes();
+    assert!(app.last_command_result.is_none());
+    app.external_refresh_failed = Some(Instant::now() - Duration::from_secs(10));
+    app.check_external_changes();
+    assert!(app.last_command_result.is_some());
+    assert!(app.is_repo_changed());
+}
+
+#[test]
+fn test_push_dialog_from_metadata() {
+    let metadata = push_metadata();
+    let mut dialog = PushDialog::from_metadata(&metadata, &PushConfig::default());
+    let names: Vec<&str> = dialog.bookmarks.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["dev", "feature", "main"]);
+    // Only the bookmark ahead of its remote starts checked
+    assert_eq!(dialog.checked_names(), ["main"]);
+    assert!(!dialog.needs_allow_new());
+
+    dialog.select_next();
+    dialog.toggle_selected();
+    assert!(dialog.bookmarks[1].is_new());
+    assert_eq!(dialog.checked_names(), ["feature", "main"]);
+    assert!(dialog.needs_allow_new());
+
+    dialog.toggle_all();
+    assert_eq!(dialog.checked_names().len(), 3);
+    dialog.toggle_all();
+    assert!(dialog.checked_names().is_empty());
+
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.modal = ModalState::Push(dialog);
+    app.push_checked_bookmarks().unwrap();
+    assert!(app.is_modal_open());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+}
+
+#[test]
+fn test_push_dialog_protected_bookmarks_need_their_name() {
+    let config = PushConfig {
+        protected: vec!["ma*".to_string()],
+        remote: Some("upstream".to_string()),
+    };
+    let mut dialog = PushDialog::from_metadata(&push_metadata(), &config);
+    // The protected bookmark is ahead but does not start checked
+    assert!(dialog.bookmarks[2].protected);
+    assert!(dialog.checked_names().is_empty());
+    dialog.toggle_all();
+
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.apply_push_config(&config);
+    app.modal = ModalState::Push(dialog.clone());
+    app.push_checked_bookmarks().unwrap();
+    assert!(!app.is_modal_open());
+    assert_eq!(app.input_mode, Some(InputMode::ProtectedPush));
+    let push = app.protected_push().unwrap();
+    assert_eq!(push.names, ["dev", "feature", "main"]);
+    assert_eq!(push.prompt_title(), " Protected: type main to push ");
+
+    // A wrong name cancels the push
+    app.input = Input::new("mian".to_string());
+    app.submit_input().unwrap();
+    assert!(!app.is_command_running());
+    assert!(app.protected_push().is_none());
+    let message = &app.last_command_result.as_ref().unwrap().message;
+    assert_eq!(
+        message,
+        "Push cancelled: type main to push protected bookmarks"
+    );
+
+    app.modal = ModalState::Push(dialog);
+    app.push_checked_bookmarks().unwrap();
+    app.input = Input::new("main".to_string());
+    app.submit_input().unwrap();
+    assert!(app.is_command_running());
+    assert_eq!(app.running_command().unwrap().0, "jj git push");
+}
+
+#[test]
+fn test_fetch_and_push_use_configured_remote() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.apply_push_config(&PushConfig {
+        protected: Vec::new(),
+        remote: Some("upstream".to_string()),
+    });
+    app.show_push_change_confirm().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected push confirmation");
+    };
+    let change_id = expected_change_id(0);
+    assert_eq!(
+        action.command_args(),
+        [
+            "git", "push", "--change", &change_id, "--remote", "upstream"
+        ]
+    );
+    assert_eq!(
+        action.confirm_message(),
+        format!("Push change {change_id} to upstream?")
+    );
+}
+
+/// Bookmarks `dev` (in sync), `feature` (new) and `main` (2 ahead).
+fn push_metadata() -> crate::jj::RepoMetadata {
+    let mut metadata = crate::jj::RepoMetadata::default();
+    for (change_id, bookmarks) in [("aaaa", vec!["main", "dev"]), ("bbbb", vec!["feature"])] {
+        metadata.commits.insert(
+            change_id.to_string(),
+            crate::jj::metadata::CommitMetadata {
+                bookmarks: bookmarks.into_iter().map(String::from).collect(),
+                ..Default::default()
+            },
+        );
+    }
+    for (name, ahead) in [("main", 2), ("dev", 0)] {
+        metadata.tracking.push(crate::jj::BookmarkTracking {
+            name: name.to_string(),
+            remote: "origin".to_string(),
+            ahead,
+            behind: 0,
+        });
+    }
+    metadata
+}
+
+#[test]
+fn test_squash_into_picked_target() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    let source = expected_change_id(0);
+    app.show_squash_confirm().unwrap();
+    app.start_squash_target_pick();
+    assert!(!app.is_modal_open());
+    assert_eq!(app.squash_source(), Some(source.as_str()));
+
+    // The change itself is not a valid target
+    app.finish_squash_target_pick().unwrap();
+    assert!(!app.is_modal_open());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    app.select_next();
+    app.select_next();
+    app.finish_squash_target_pick().unwrap();
+    assert!(app.squash_source().is_none());
+    let ModalState::Confirm(PendingAction::SquashInto {
+        change_id, into, ..
+    }) = &app.modal
+    else {
+        panic!("expected a squash-into confirmation");
+    };
+    assert_eq!(change_id, &source);
+    assert_eq!(into, &(expected_change_id(2), "Entry 2".to_string()));
+}
+
+#[test]
+fn test_restore_files_into_working_copy_or_target() {
+    let raw =
+        "@  wwwwwwww\x1fA\x1f1m\x1f\x1fw\x1fworking copy\n○  abcdefgh\x1fA\x1f2d\x1f\x1f\x1fold";
+    let mut app = App::new(
+        GraphLog::from_output(raw),
+        "/repo".to_string(),
+        make_runner(),
+    );
+    app.diff_state = DiffState::new("abcdefgh".to_string(), make_diff_entries(2));
+    app.diff_state.marked_files = ["src/file1.rs"].map(str::to_string).into();
+
+    app.show_restore_files_confirm();
+    let ModalState::Confirm(action @ PendingAction::RestoreFiles { from, into, paths }) =
+        &app.modal
+    else {
+        panic!("expected a restore confirmation");
+    };
+    assert_eq!(from.as_deref(), Some("abcdefgh"));
+    assert_eq!(into, "@");
+    assert_eq!(paths, &["src/file1.rs"]);
+    assert_eq!(action.details()[0], "src/file1.rs");
+
+    // t: restore into a revision typed in
+    app.start_restore_target_input();
+    assert_eq!(app.input_mode, Some(InputMode::RestoreInto));
+    app.input = "xyz".into();
+    app.submit_input().unwrap();
+    let ModalState::Confirm(PendingAction::RestoreFiles { from, into, .. }) = &app.modal else {
+        panic!("expected a restore confirmation");
+    };
+    assert_eq!(from.as_deref(), Some("abcdefgh"));
+    assert_eq!(into, "xyz");
+
+    // In the working copy, the changes are discarded
+    app.modal = ModalState::None;
+    app.diff_state = DiffState::new("wwwwwwww".to_string(), make_diff_entries(1));
+    app.show_restore_files_confirm();
+    let ModalState::Confirm(action @ PendingAction::RestoreFiles { from, into, .. }) = &app.modal
+    else {
+        panic!("expected a restore confirmation");
+    };
+    assert_eq!(from, &None);
+    assert_eq!(into, "wwwwwwww");
+    assert!(action.confirm_message().starts_with("Discard"));
+}
+
+#[test]
+fn test_squash_marked_files_into_parent() {
+    let mut app = App::new(GraphLog::default(), "/repo".to_string(), make_runner());
+    app.diff_state = DiffState::new("abcdefgh".to_string(), make_diff_entries(2));
+    // Unlike split, every file may be moved
+    app.diff_state.marked_files = ["src/file0.rs", "src/file1.rs"].map(str::to_string).into();
+
+    app.show_squash_files_confirm();
+    let ModalState::Confirm(PendingAction::SquashFiles { change_id, paths }) = &app.modal else {
+        panic!("expected a squash confirmation");
+    };
+    assert_eq!(change_id, "abcdefgh");
+    assert_eq!(paths, &["src/file0.rs", "src/file1.rs"]);
+
+    app.modal = ModalState::None;
+    app.diff_state = DiffState::for_range(
+        DiffRange {
+            from: "aaaa".to_string(),
+            to: "bbbb".to_string(),
+        },
+        make_diff_entries(2),
+    );
+    app.show_squash_files_confirm();
+    assert!(!app.is_modal_open());
+}
+
+#[test]
+fn test_tags_view_navigation() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    let tag = |name: &str, conflict| crate::jj::TagEntry {
+        name: name.to_string(),
+        conflict,
+        change_id: String::new(),
+        commit_id: String::new(),
+        description: String::new(),
+    };
+    app.tags_state.entries = vec![tag("v0.1.0", false), tag("v0.2.0", true)];
+    app.push_view(View::Tags);
+
+    app.tags_select_last();
+    assert_eq!(app.selected_tag().unwrap().name, "v0.2.0");
+    app.tags_select_next();
+    assert_eq!(app.tags().selected, 1);
+
+    // A conflicted tag has no single commit to show
+    app.open_tag_detail().unwrap();
+    assert_eq!(app.view, View::Tags);
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    app.tags_select_first();
+    assert_eq!(app.selected_tag().unwrap().name, "v0.1.0");
+    app.close_tags();
+    assert_eq!(app.view, View::Log);
+}
+
+#[test]
+fn test_workspaces_forget_confirm() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    let workspace = |name: &str, is_current| crate::jj::WorkspaceEntry {
+        name: name.to_string(),
+        is_current,
+        change_id: "qzmtztvn".to_string(),
+        commit_id: "a1b2c3d4".to_string(),
+        description: String::new(),
+    };
+    app.workspaces_state.entries = vec![workspace("default", true), workspace("feature", false)];
+
+    // The workspace xorcist runs in is refused
+    app.show_workspace_forget_confirm().unwrap();
+    assert!(!app.is_modal_open());
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+
+    app.workspaces_select_next();
+    app.workspaces_select_next();
+    assert_eq!(app.selected_workspace().unwrap().name, "feature");
+    app.show_workspace_forget_confirm().unwrap();
+    assert!(matches!(
+        &app.modal,
+        ModalState::Confirm(PendingAction::WorkspaceForget { name }) if name == "feature"
+    ));
+    app.close_modal();
+
+    // An empty path does not start a command
+    app.start_workspace_add();
+    assert_eq!(app.input_mode, Some(InputMode::WorkspaceAdd));
+    app.submit_input().unwrap();
+    assert!(!app.is_command_running());
+}
+
+#[test]
+fn test_sparse_remove_and_reset_confirm() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.sparse_state.patterns = vec!["libs/common".to_string(), "services/api".to_string()];
+    app.sparse_select_last();
+    app.sparse_select_next();
+    assert_eq!(app.selected_sparse_pattern(), Some("services/api"));
+
+    app.show_sparse_remove_confirm().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation, got {:?}", app.modal);
+    };
+    assert_eq!(
+        action.command_preview(),
+        "jj sparse set --remove services/api"
+    );
+    assert_eq!(action.command(), "sparse");
+    app.close_modal();
+
+    app.show_sparse_reset_confirm().unwrap();
+    assert!(matches!(
+        &app.modal,
+        ModalState::Confirm(PendingAction::SparseReset)
+    ));
+    app.close_modal();
+
+    // An empty pattern does not start a command
+    app.start_sparse_add();
+    assert_eq!(app.input_mode, Some(InputMode::SparseAdd));
+    app.submit_input().unwrap();
+    assert!(!app.is_command_running());
+}
+
+#[test]
+fn test_resolve_divergence_confirm() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.show_resolve_divergence_confirm().unwrap();
+    assert!(matches!(app.modal, ModalState::None));
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(result.message.contains("not divergent"));
+
+    app.modal = ModalState::Confirm(PendingAction::ResolveDivergence {
+        keep: ("0f9e8d7c".to_string(), "feat: a".to_string()),
+        others: vec![("1a2b3c4d".to_string(), "feat: a".to_string())],
+        squash: false,
+    });
+    let ModalState::Confirm(action) = &app.modal else {
+        unreachable!();
+    };
+    assert_eq!(action.command_preview(), "jj abandon 1a2b3c4d");
+    assert_eq!(action.command(), "abandon");
+    assert!(action.details().last().unwrap().starts_with("t: squash"));
+
+    app.toggle_divergence_resolution();
+    let ModalState::Confirm(action) = &app.modal else {
+        unreachable!();
+    };
+    assert_eq!(
+        action.command_preview(),
+        "jj squash --from 1a2b3c4d --into 0f9e8d7c"
+    );
+    assert_eq!(
+        action.confirm_message(),
+        "Squash the other commit into 0f9e8d7c (\"feat: a\")?"
+    );
+}
+
+#[test]
+fn test_set_author_rejects_malformed_authors() {
+    let mut app = App::new(make_graph_log(2), "/repo".to_string(), make_runner());
+    app.start_set_author();
+    assert_eq!(app.input_mode, Some(InputMode::SetAuthor));
+    // Without jj the current author is unknown, so the prompt starts empty
+    assert_eq!(app.input.value(), "");
+
+    app.input = Input::new("Jane Doe".to_string());
+    app.submit_input().unwrap();
+    assert!(!app.is_command_running());
+    let result = app.last_command_result.as_ref().unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("Name <email>"));
+}
+
+#[test]
+fn test_patch_export_prefills_file_or_series_directory() {
+    let mut app = App::new(make_graph_log(5), "/repo".to_string(), make_runner());
+    app.selected = 1;
+    app.start_patch_export();
+    assert_eq!(app.input_mode, Some(InputMode::PatchPath));
+    assert_eq!(app.input.value(), "0001-Entry-1.patch");
+    app.cancel_input_mode();
+
+    app.toggle_mark();
+    app.selected = 3;
+    app.toggle_mark();
+    app.start_patch_export();
+    assert_eq!(app.input.value(), "patches/");
+    // The series is written oldest first
+    assert_eq!(
+        app.patch_revisions(),
+        vec![expected_change_id(3), expected_change_id(1)]
+    );
+}
+
+#[test]
+fn test_palette_filters_and_runs_actions() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.open_palette();
+    assert_eq!(app.input_mode, Some(InputMode::Palette));
+    assert_eq!(app.finder().unwrap().results.len(), PALETTE_ENTRIES.len());
+
+    app.input = Input::new("rebas".to_string());
+    app.update_finder();
+    let best = app.finder().unwrap().results[0];
+    assert_eq!(PALETTE_ENTRIES[best].name, "Rebase");
+
+    app.submit_input().unwrap();
+    assert!(app.finder().is_none());
+    assert_eq!(app.input_mode, Some(InputMode::RebaseDestination));
+}
+
+#[test]
+fn test_cycle_theme_walks_presets() {
+    let mut app = App::new(make_graph_log(1), "/repo".to_string(), make_runner());
+    app.set_theme(Theme::dark());
+    app.cycle_theme();
+    assert_eq!(*app.theme(), Theme::light());
+    app.cycle_theme();
+    app.cycle_theme();
+    assert_eq!(*app.theme(), Theme::dark());
+}
+
+#[test]
+fn test_commit_input_runs_jj_commit() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.start_input_mode(InputMode::Commit);
+    app.input = Input::new("feat: finish".to_string());
+    app.submit_input().unwrap();
+
+    assert!(app.input_mode.is_none());
+    assert_eq!(app.running_command().unwrap().0, "jj commit");
+    // The work dir is not a jj repository, so the command fails
+    assert!(app.wait_for_background_command().is_err());
+}
+
+#[test]
+fn test_confirm_policies_override_defaults() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    let mut config = ConfirmConfig::default();
+    config
+        .policies
+        .insert("new", crate::config::ConfirmPolicy::Always);
+    config
+        .policies
+        .insert("abandon", crate::config::ConfirmPolicy::Never);
+    app.apply_confirm_config(&config);
+
+    // `jj new` normally runs right away
+    app.execute_new().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation dialog");
+    };
+    assert_eq!(action.command(), "new");
+    app.close_modal();
+
+    // `jj abandon` normally asks
+    app.show_abandon_confirm().unwrap();
+    assert!(!app.is_modal_open());
+    assert_eq!(app.running_command().unwrap().0, "jj abandon");
+    assert!(app.wait_for_background_command().is_err());
+
+    // Commands without a policy keep their default
+    app.execute_edit().unwrap();
+    assert!(!app.is_modal_open());
+    assert_eq!(app.running_command().unwrap().0, "jj edit");
+}
+
+#[test]
+fn test_help_scroll() {
+    let mut app = App::new(make_graph_log(1), "/repo".to_string(), make_runner());
+    app.toggle_help();
+    app.help_scroll_down(50);
+    app.clamp_help_scroll(30, 10);
+    assert_eq!(app.help_scroll(), 20);
+    app.help_scroll_up(5);
+    assert_eq!(app.help_scroll(), 15);
+
+    // Reopening starts at the top
+    app.toggle_help();
+    app.toggle_help();
+    assert!(app.show_help);
+    assert_eq!(app.help_scroll(), 0);
+}
+
+#[test]
+fn test_macro_record_and_replay() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    let key = |ch| KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+
+    // Qa j j Q
+    app.toggle_macro_recording();
+    assert!(app.is_macro_prompt_pending());
+    app.finish_macro_prompt(key('a'));
+    assert_eq!(app.recording_macro(), Some('a'));
+    for ch in ['j', 'j', 'Q'] {
+        app.record_macro_key(key(ch));
+    }
+    app.toggle_macro_recording();
+    assert_eq!(app.recording_macro(), None);
+    assert!(
+        app.last_command_result
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("2 keys")
+    );
+
+    app.start_macro_replay();
+    app.finish_macro_prompt(key('a'));
+    assert_eq!(app.next_macro_key(), Some(key('j')));
+    assert!(app.stop_macro_replay());
+    assert_eq!(app.next_macro_key(), None);
+    assert!(!app.stop_macro_replay());
+
+    app.start_macro_replay();
+    app.finish_macro_prompt(key('b'));
+    assert_eq!(app.next_macro_key(), None);
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+}
+
+#[test]
+fn test_diff_marked_pair_needs_two_marks() {
+    let mut app = App::new(make_graph_log(4), "/repo".to_string(), make_runner());
+    app.toggle_mark();
+    app.diff_marked_pair().unwrap();
+    let result = app.last_command_result.take().unwrap();
+    assert!(!result.success);
+    assert!(result.message.contains("1 marked"), "{}", result.message);
+
+    // With two marks, c diffs them instead of setting a compare base
+    // (the work dir is not a jj repository, so fetching the diff fails)
+    app.toggle_mark();
+    assert!(app.compare_selected().is_err());
+    assert_eq!(app.compare_base(), None);
+    assert_eq!(app.view, View::Log);
+}
+
+#[test]
+fn test_placement_menu() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.selected = 1;
+    app.open_placement_menu(true);
+    let menu = app.placement_menu_mut().unwrap();
+    assert_eq!(menu.change_id, expected_change_id(1));
+    menu.select_previous();
+    assert_eq!(menu.selected, 2);
+
+    // With a message, the placement is kept for the message prompt
+    app.activate_placement().unwrap();
+    assert!(matches!(app.modal, ModalState::None));
+    assert_eq!(app.input_mode, Some(InputMode::NewWithMessage));
+    assert_eq!(app.new_placement, NewPlacement::InsertBefore);
+    app.cancel_input_mode();
+    assert_eq!(app.new_placement, NewPlacement::OnTop);
+
+    let action = PendingAction::New {
+        change_id: "abcdefgh".to_string(),
+        message: "wip".to_string(),
+        placement: NewPlacement::InsertAfter,
+    };
+    assert_eq!(
+        action.confirm_message(),
+        "Create a new change \"wip\" after abcdefgh?"
+    );
+}
+
+fn make_stack(head: &str, len: usize, needs_rebase: bool) -> Stack {
+    let commits = (0..len)
+        .map(|i| StackCommit {
+            change_id: if i == 0 {
+                head.to_string()
+            } else {
+                format!("{head}{i}")
+            },
+            bookmarks: Vec::new(),
+            conflict: false,
+            empty: false,
+            description: format!("commit {i}"),
+        })
+        .collect();
+    Stack {
+        commits,
+        needs_rebase,
+    }
+}
+
+#[test]
+fn test_open_startup_view() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.open_startup_view(StartupView::Log);
+    assert_eq!(app.view, View::Log);
+    assert!(app.last_command_result.is_none());
+
+    // The working copy is selected; outside a jj repository the view cannot
+    // open
+    app.graph_log.lines[2].flags.working_copy = true;
+    app.open_startup_view(StartupView::Detail);
+    assert_eq!(app.selected, 2);
+    assert_eq!(app.view, View::Log);
+    assert!(!app.last_command_result.as_ref().unwrap().success);
+}
+
+#[test]
+fn test_stacks_scroll_and_rebase_confirm() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.stacks_state = StacksState {
+        entries: vec![
+            make_stack("aaaaaaaa", 3, false),
+            make_stack("bbbbbbbb", 2, true),
+            make_stack("cccccccc", 4, true),
+        ],
+        selected: 0,
+        scroll: 0,
+    };
+    app.view = View::Stacks;
+
+    // A stack up to date with trunk is not rebased
+    app.show_rebase_stack_confirm().unwrap();
+    assert!(matches!(app.modal, ModalState::None));
+    assert!(app.last_command_result.take().unwrap().success);
+
+    // Stacks take a header row plus a row per commit: 4 + 3 + 5
+    app.stacks_select_last();
+    app.ensure_stacks_visible(8);
+    assert_eq!(app.stacks().scroll, 1);
+    app.ensure_stacks_visible(4);
+    assert_eq!(app.stacks().scroll, 2);
+    app.stacks_select_first();
+    app.ensure_stacks_visible(8);
+    assert_eq!(app.stacks().scroll, 0);
+
+    app.stacks_select_next();
+    app.show_rebase_stack_confirm().unwrap();
+    let ModalState::Confirm(action) = &app.modal else {
+        panic!("expected a confirmation");
+    };
+    assert_eq!(
+        action.confirm_message(),
+        "Rebase stack \"bbbbbbbb\" (2 changes) onto trunk()?"
+    );
+    assert_eq!(action.details().len(), 2);
+}
+
+#[test]
+fn test_pending_action_command_preview() {
+    let rebase = PendingAction::Rebase {
+        change_id: "abc".to_string(),
+        description: "wip".to_string(),
+        destination: "main".to_string(),
+    };
+    assert_eq!(rebase.command_preview(), "jj rebase -r abc -d main");
+
+    let squash = PendingAction::SquashMarked {
+        revisions: vec![
+            ("aaa".to_string(), String::new()),
+            ("bbb".to_string(), String::new()),
+        ],
+        into: ("ccc".to_string(), String::new()),
+    };
+    assert_eq!(
+        squash.command_preview(),
+        "jj squash --from aaa --from bbb --into ccc"
+    );
+
+    let new = PendingAction::New {
+        change_id: "abc".to_string(),
+        message: "fix: it's done".to_string(),
+        placement: NewPlacement::InsertBefore,
+    };
+    assert_eq!(
+        new.command_preview(),
+        r"jj new --insert-before abc -m 'fix: it'\''s done'"
+    );
+
+    let restore = PendingAction::RestoreFiles {
+        from: None,
+        into: "abc".to_string(),
+        paths: vec!["src/main.rs".to_string()],
+    };
+    assert_eq!(
+        restore.command_args(),
+        ["restore", "--changes-in", "abc", "--", "src/main.rs"]
+    );
+}
+
+#[test]
+fn test_status_messages_are_kept() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    for i in 0..110 {
+        app.report(CommandResult {
+            success: i % 2 == 0,
+            message: format!("message {i}\nsecond line"),
+        });
+    }
+    let entries = &app.messages_panel().entries;
+    assert_eq!(entries.len(), MESSAGE_HISTORY_LEN);
+    assert_eq!(entries[0].message, "message 109\nsecond line");
+    assert!(!entries[0].success);
+    assert_eq!(
+        app.last_command_result.as_ref().unwrap().message,
+        entries[0].message
+    );
+
+    app.toggle_messages_panel();
+    assert!(app.is_messages_panel_open());
+    app.messages_scroll_down(500);
+    app.clamp_messages_scroll(200, 20);
+    assert_eq!(app.messages_panel().scroll, 180);
+    app.toggle_messages_panel();
+    assert_eq!(app.messages_panel().scroll, 0);
+}
+
+#[test]
+fn test_preview_pane_follows_selection() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    assert!(app.preview().is_none());
+    // Nothing is fetched while the pane is closed
+    app.update_preview();
+    assert!(app.preview.content.is_none());
+
+    app.cycle_preview();
+    assert_eq!(app.preview().unwrap().kind, Some(PreviewKind::Detail));
+    // The work dir is not a jj repository, so the failure is kept in the pane
+    settle_preview(&mut app);
+    assert!(matches!(app.preview.content, Some(Err(_))));
+    let first = expected_change_id(0);
+    assert!(app.preview.shows(&first, PreviewKind::Detail));
+
+    // Moving the selection fetches the newly selected commit
+    app.select_next();
+    settle_preview(&mut app);
+    assert!(
+        app.preview
+            .shows(&expected_change_id(1), PreviewKind::Detail)
+    );
+    assert!(!app.preview.shows(&first, PreviewKind::Detail));
+
+    // A refresh keeps the shown preview until it is fetched again
+    app.invalidate_preview();
+    assert!(app.preview.content.is_some());
+    assert!(
+        !app.preview
+            .shows(&expected_change_id(1), PreviewKind::Detail)
+    );
+
+    // The split stays within bounds
+    for _ in 0..10 {
+        app.resize_preview(true);
+    }
+    assert_eq!(app.preview().unwrap().log_percent, 80);
+    for _ in 0..10 {
+        app.resize_preview(false);
+    }
+    assert_eq!(app.preview().unwrap().log_percent, 20);
+
+    app.cycle_preview();
+    assert_eq!(app.preview().unwrap().kind, Some(PreviewKind::Diff));
+    assert!(app.preview.content.is_none());
+    app.cycle_preview();
+    assert!(app.preview().is_none());
+}
+
+/// Let the selection settle and wait for the preview to be fetched.
+fn settle_preview(app: &mut App) {
+    app.update_preview();
+    if let Some(pending) = &mut app.preview.pending {
+        pending.since -= Duration::from_secs(1);
+    }
+    app.update_preview();
+    app.wait_for_preview();
+    app.update_preview();
+}
+
+#[test]
+fn test_preview_waits_for_the_selection_to_settle() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    app.cycle_preview();
+
+    // Moving through commits only schedules a fetch of the last one
+    app.update_preview();
+    app.select_next();
+    app.update_preview();
+    app.select_next();
+    app.update_preview();
+    assert!(app.preview().unwrap().is_loading());
+    assert!(app.preview.content.is_none());
+    assert!(app.poll_timeout() <= Duration::from_millis(100));
+
+    settle_preview(&mut app);
+    assert!(!app.preview().unwrap().is_loading());
+    assert!(
+        app.preview
+            .shows(&expected_change_id(2), PreviewKind::Detail)
+    );
+    assert!(
+        !app.preview
+            .shows(&expected_change_id(1), PreviewKind::Detail)
+    );
+
+    // A fetch for a commit no longer selected is dropped
+    app.select_previous();
+    app.update_preview();
+    app.preview.pending.as_mut().unwrap().since -= Duration::from_secs(1);
+    app.update_preview();
+    assert!(app.preview.is_fetching());
+    app.select_previous();
+    app.update_preview();
+    assert!(!app.preview.is_fetching());
+    assert!(
+        app.preview
+            .shows(&expected_change_id(2), PreviewKind::Detail)
+    );
+}
+
+#[test]
+fn test_cached_output_is_used_without_jj() {
+    let mut app = App::new(make_graph_log(3), "/repo".to_string(), make_runner());
+    let change_id = expected_change_id(1);
+    let show = make_detail_state(&change_id).show_output;
+    app.cache_output(
+        &change_id,
+        CacheKind::Show,
+        CachedOutput::Show(Box::new(show)),
+    );
+    app.cache_output(
+        &change_id,
+        CacheKind::Stat,
+        CachedOutput::Stat(DiffStat::default()),
+    );
+
+    // The work dir is not a jj repository, so only cached output can open
+    // the detail view
+    app.open_detail_for(&change_id).unwrap();
+    assert_eq!(app.view, View::Detail);
+    assert_eq!(
+        app.detail_state.as_ref().unwrap().show_output.change_id,
+        change_id
+    );
+    assert!(app.open_detail_for(&expected_change_id(2)).is_err());
+    app.close_detail();
+
+    // The preview of a cached commit is shown without waiting
+    app.cycle_preview();
+    app.select_next();
+    app.update_preview();
+    assert!(matches!(app.preview.content, Some(Ok(_))));
+    assert!(!app.preview().unwrap().is_loading());
+
+    // A new operation empties the cache
+    app.output_cache.set_op_id("1a2b3c4d5e6f");
+    assert!(app.cached_show(&change_id).is_err());
+}
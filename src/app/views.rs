@@ -0,0 +1,224 @@
+//! View stack methods for App.
+
+use crate::config::StartupView;
+
+use super::{App, View, ViewEntry, ViewPosition};
+
+/// Number of per-revision view positions remembered.
+const SAVED_POSITIONS_LEN: usize = 100;
+
+impl App {
+    /// Open the configured startup view on top of the log (`startup.view`).
+    ///
+    /// The detail and diff views show the working copy (or the first commit,
+    /// if the revset leaves it out). Errors are reported in the status bar,
+    /// leaving the log open.
+    pub fn open_startup_view(&mut self, view: StartupView) {
+        if matches!(view, StartupView::Detail | StartupView::Diff)
+            && let Some(index) = self
+                .graph_log
+                .lines
+                .iter()
+                .position(|line| line.flags.working_copy)
+        {
+            self.selected = index;
+        }
+        let result = match view {
+            StartupView::Log => Ok(()),
+            StartupView::Detail => self.open_detail(),
+            StartupView::Diff => self.open_detail().and_then(|()| self.open_diff_view()),
+            StartupView::OpLog => self.open_op_log(),
+            StartupView::Stacks => self.open_stacks(),
+        };
+        if let Err(e) = result {
+            self.handle_command_result(Err(e));
+        }
+    }
+
+    /// Get the scroll/selection position of the current view.
+    pub fn current_position(&self) -> ViewPosition {
+        match self.view {
+            View::Log => ViewPosition {
+                selected: self.selected,
+                scroll: self.scroll_offset,
+                h_scroll: 0,
+                change_id: self.selected_change_id().map(str::to_string),
+            },
+            View::Detail => ViewPosition {
+                selected: 0,
+                scroll: self.detail_state.as_ref().map(|s| s.scroll).unwrap_or(0),
+                h_scroll: 0,
+                change_id: None,
+            },
+            View::Diff => ViewPosition {
+                selected: self.diff_state.selected,
+                scroll: self.diff_state.diff_scroll,
+                h_scroll: self.diff_state.diff_h_scroll,
+                change_id: None,
+            },
+            View::OpLog => ViewPosition {
+                selected: self.op_log_state.selected,
+                scroll: self.op_log_state.scroll,
+                h_scroll: 0,
+                change_id: None,
+            },
+            View::Workspaces => ViewPosition {
+                selected: self.workspaces_state.selected,
+                scroll: self.workspaces_state.scroll,
+                h_scroll: 0,
+                change_id: None,
+            },
+            View::Tags => ViewPosition {
+                selected: self.tags_state.selected,
+                scroll: self.tags_state.scroll,
+                h_scroll: 0,
+                change_id: None,
+            },
+            View::Stacks => ViewPosition {
+                selected: self.stacks_state.selected,
+                scroll: self.stacks_state.scroll,
+                h_scroll: 0,
+                change_id: None,
+            },
+            View::Sparse => ViewPosition {
+                selected: self.sparse_state.selected,
+                scroll: self.sparse_state.scroll,
+                h_scroll: 0,
+                change_id: None,
+            },
+            View::FileHistory => ViewPosition {
+                selected: self.file_history.as_ref().map_or(0, |s| s.selected),
+                scroll: self.file_history.as_ref().map_or(0, |s| s.scroll),
+                h_scroll: 0,
+                change_id: None,
+            },
+        }
+    }
+
+    /// Restore a saved position into the current view.
+    ///
+    /// For the diff view this only restores the scroll offsets; the selected
+    /// file is restored by `open_diff_view` since it requires a fetch.
+    fn apply_position(&mut self, position: ViewPosition) {
+        match self.view {
+            View::Log => {
+                // The log may have been reloaded underneath, moving the commit
+                let found = position
+                    .change_id
+                    .as_deref()
+                    .is_some_and(|change_id| self.select_change_id(change_id));
+                if !found {
+                    self.selected = position.selected.min(self.commit_count().saturating_sub(1));
+                }
+                self.scroll_offset = position.scroll;
+            }
+            View::Detail => {
+                if let Some(state) = &mut self.detail_state {
+                    state.scroll = position.scroll;
+                }
+            }
+            View::Diff => {
+                self.diff_state.diff_scroll = position.scroll;
+                self.diff_state.diff_h_scroll = position.h_scroll;
+            }
+            View::OpLog => {
+                self.op_log_state.selected = position.selected;
+                self.op_log_state.scroll = position.scroll;
+            }
+            View::Workspaces => {
+                self.workspaces_state.selected = position.selected;
+                self.workspaces_state.scroll = position.scroll;
+            }
+            View::Tags => {
+                self.tags_state.selected = position.selected;
+                self.tags_state.scroll = position.scroll;
+            }
+            View::Stacks => {
+                self.stacks_state.selected = position.selected;
+                self.stacks_state.scroll = position.scroll;
+            }
+            View::Sparse => {
+                self.sparse_state.selected = position.selected;
+                self.sparse_state.scroll = position.scroll;
+            }
+            View::FileHistory => {
+                if let Some(state) = &mut self.file_history {
+                    state.selected = position.selected;
+                    state.scroll = position.scroll;
+                }
+            }
+        }
+    }
+
+    /// Change ID the current view is showing, if it is revision-specific.
+    fn current_view_change_id(&self) -> Option<String> {
+        match self.view {
+            View::Log
+            | View::OpLog
+            | View::FileHistory
+            | View::Workspaces
+            | View::Tags
+            | View::Stacks
+            | View::Sparse => None,
+            View::Detail => self
+                .detail_state
+                .as_ref()
+                .map(|s| s.show_output.change_id.clone()),
+            View::Diff => Some(self.diff_state.change_id.clone()),
+        }
+    }
+
+    /// Get the position a view was last left at for the given change.
+    pub fn remembered_position(&self, view: View, change_id: &str) -> Option<ViewPosition> {
+        self.saved_positions
+            .iter()
+            .find(|((saved_view, saved_id), _)| *saved_view == view && saved_id == change_id)
+            .map(|(_, position)| position.clone())
+    }
+
+    /// Remember where `view` was left for `change_id`, forgetting the oldest
+    /// position once [`SAVED_POSITIONS_LEN`] are kept.
+    fn remember_position(&mut self, view: View, change_id: String, position: ViewPosition) {
+        let key = (view, change_id);
+        self.saved_positions.retain(|(saved, _)| *saved != key);
+        if self.saved_positions.len() >= SAVED_POSITIONS_LEN {
+            self.saved_positions.pop_front();
+        }
+        self.saved_positions.push_back((key, position));
+    }
+
+    /// Switch to a new view, saving the current one on the view stack.
+    ///
+    /// If the new view was previously left for the same revision, its
+    /// scroll position is restored.
+    pub(super) fn push_view(&mut self, view: View) {
+        self.view_stack.push(ViewEntry {
+            view: self.view,
+            position: self.current_position(),
+        });
+        self.view = view;
+        if let Some(change_id) = self.current_view_change_id()
+            && let Some(position) = self.remembered_position(view, &change_id)
+        {
+            self.apply_position(position);
+        }
+    }
+
+    /// Return to the previous view, restoring its saved position.
+    ///
+    /// The position of the view being left is remembered for the next
+    /// time it is opened for the same revision.
+    pub(super) fn pop_view(&mut self) {
+        if let Some(change_id) = self.current_view_change_id() {
+            let position = self.current_position();
+            self.remember_position(self.view, change_id, position);
+        }
+        match self.view_stack.pop() {
+            Some(entry) => {
+                self.view = entry.view;
+                self.apply_position(entry.position);
+            }
+            None => self.view = View::Log,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Workspaces view (`jj workspace list`) methods for App.
+
+use crate::error::XorcistError;
+use crate::jj::{WorkspaceEntry, fetch_workspaces};
+
+use super::tasks::AfterCommand;
+use super::{App, CommandResult, InputMode, PendingAction, View};
+
+/// State of the workspaces view.
+#[derive(Debug, Clone, Default)]
+pub struct WorkspacesState {
+    /// Workspaces of the repository.
+    pub entries: Vec<WorkspaceEntry>,
+    /// Selected workspace index.
+    pub selected: usize,
+    /// Scroll offset (in entries).
+    pub scroll: usize,
+}
+
+impl App {
+    /// Open the workspaces view with the current workspace selected.
+    pub fn open_workspaces(&mut self) -> Result<(), XorcistError> {
+        let entries = fetch_workspaces(&self.runner)?;
+        let selected = entries.iter().position(|e| e.is_current).unwrap_or(0);
+        self.workspaces_state = WorkspacesState {
+            entries,
+            selected,
+            scroll: 0,
+        };
+        self.push_view(View::Workspaces);
+        Ok(())
+    }
+
+    /// Close the workspaces view.
+    pub fn close_workspaces(&mut self) {
+        self.pop_view();
+    }
+
+    /// Get the workspaces view state.
+    pub fn workspaces(&self) -> &WorkspacesState {
+        &self.workspaces_state
+    }
+
+    /// Re-fetch the workspaces, keeping the selected one if it still exists.
+    pub(super) fn refresh_workspaces(&mut self) -> Result<(), XorcistError> {
+        let entries = fetch_workspaces(&self.runner)?;
+        let state = &mut self.workspaces_state;
+        let selected_name = state.entries.get(state.selected).map(|e| e.name.clone());
+        state.selected = selected_name
+            .and_then(|name| entries.iter().position(|e| e.name == name))
+            .unwrap_or_else(|| state.selected.min(entries.len().saturating_sub(1)));
+        state.entries = entries;
+        Ok(())
+    }
+
+    /// Get the selected workspace.
+    pub fn selected_workspace(&self) -> Option<&WorkspaceEntry> {
+        self.workspaces_state
+            .entries
+            .get(self.workspaces_state.selected)
+    }
+
+    /// Select the next workspace.
+    pub fn workspaces_select_next(&mut self) {
+        let state = &mut self.workspaces_state;
+        if state.selected + 1 < state.entries.len() {
+            state.selected += 1;
+        }
+    }
+
+    /// Select the previous workspace.
+    pub fn workspaces_select_previous(&mut self) {
+        self.workspaces_state.selected = self.workspaces_state.selected.saturating_sub(1);
+    }
+
+    /// Select the first workspace.
+    pub fn workspaces_select_first(&mut self) {
+        self.workspaces_state.selected = 0;
+    }
+
+    /// Select the last workspace.
+    pub fn workspaces_select_last(&mut self) {
+        self.workspaces_state.selected = self.workspaces_state.entries.len().saturating_sub(1);
+    }
+
+    /// Adjust the scroll offset so the selected workspace is visible.
+    pub fn ensure_workspaces_visible(&mut self, visible_height: usize) {
+        let state = &mut self.workspaces_state;
+        if visible_height == 0 {
+            return;
+        }
+        if state.selected < state.scroll {
+            state.scroll = state.selected;
+        } else if state.selected >= state.scroll + visible_height {
+            state.scroll = state.selected + 1 - visible_height;
+        }
+    }
+
+    /// Open the detail view for the selected workspace's working-copy commit.
+    pub fn open_workspace_detail(&mut self) -> Result<(), XorcistError> {
+        let Some(change_id) = self.selected_workspace().map(|e| e.change_id.clone()) else {
+            return Ok(());
+        };
+        self.open_detail_for(&change_id)
+    }
+
+    /// Prompt for the path of a new workspace.
+    pub fn start_workspace_add(&mut self) {
+        self.start_input_mode(InputMode::WorkspaceAdd);
+    }
+
+    /// Run `jj workspace add <path>` in the background.
+    pub(super) fn submit_workspace_add(&mut self, path: &str) {
+        let path = path.trim().to_string();
+        if path.is_empty() {
+            return;
+        }
+        self.run_in_background("jj workspace add", AfterCommand::Refresh, move |runner| {
+            runner.execute_workspace_add(&path)
+        });
+    }
+
+    /// Show confirmation dialog for forgetting the selected workspace.
+    ///
+    /// The workspace xorcist runs in cannot be forgotten from here.
+    pub fn show_workspace_forget_confirm(&mut self) -> Result<(), XorcistError> {
+        let Some(entry) = self.selected_workspace() else {
+            return Ok(());
+        };
+        if entry.is_current {
+            self.report(CommandResult {
+                success: false,
+                message: "Cannot forget the workspace xorcist is running in".to_string(),
+            });
+            return Ok(());
+        }
+        let name = entry.name.clone();
+        self.request_confirm(PendingAction::WorkspaceForget { name })
+    }
+
+    /// Run `jj workspace update-stale` for the current workspace in the background.
+    pub fn execute_workspace_update_stale(&mut self) {
+        self.run_in_background(
+            "jj workspace update-stale",
+            AfterCommand::Refresh,
+            |runner| runner.execute_workspace_update_stale(),
+        );
+    }
+}
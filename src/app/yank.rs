@@ -0,0 +1,127 @@
+//! Copying IDs and descriptions to the clipboard (`y` prefix) for App.
+
+use crate::clipboard::copy_to_clipboard;
+use crate::error::XorcistError;
+use crate::jj::{ShowOutput, fetch_show};
+
+use super::{App, CommandResult, View};
+
+/// Key hint shown while the `y` prefix waits for its second key.
+const YANK_HINT: &str = "Copy: y change ID  c commit ID  g git SHA  d description";
+
+/// What to copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YankTarget {
+    /// Full change ID (`yy`).
+    ChangeId,
+    /// Full commit ID (`yc`).
+    CommitId,
+    /// Git commit SHA of a colocated repository (`yg`).
+    GitSha,
+    /// Full description (`yd`).
+    Description,
+}
+
+impl YankTarget {
+    /// Target for the key pressed after `y`.
+    pub fn from_key(ch: char) -> Option<Self> {
+        match ch {
+            'y' => Some(Self::ChangeId),
+            'c' => Some(Self::CommitId),
+            'g' => Some(Self::GitSha),
+            'd' => Some(Self::Description),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::ChangeId => "change ID",
+            Self::CommitId => "commit ID",
+            Self::GitSha => "git SHA",
+            Self::Description => "description",
+        }
+    }
+
+    fn text(self, show: &ShowOutput) -> &str {
+        match self {
+            Self::ChangeId => &show.change_id,
+            // The git backend uses the git commit's SHA as the commit ID
+            Self::CommitId | Self::GitSha => &show.commit_id,
+            Self::Description => &show.description,
+        }
+    }
+}
+
+impl App {
+    /// Wait for the second key of a `y` copy command.
+    pub fn start_yank(&mut self) {
+        self.yank_pending = true;
+        self.report(CommandResult {
+            success: true,
+            message: YANK_HINT.to_string(),
+        });
+    }
+
+    /// Whether `y` was pressed and the next key picks what to copy.
+    pub fn is_yank_pending(&self) -> bool {
+        self.yank_pending
+    }
+
+    /// Finish a `y` command with its second key.
+    ///
+    /// Any key other than `y`, `c`, `g` or `d` cancels the command.
+    pub fn finish_yank(&mut self, key: Option<char>) -> Result<(), XorcistError> {
+        self.yank_pending = false;
+        let Some(target) = key.and_then(YankTarget::from_key) else {
+            self.last_command_result = None;
+            return Ok(());
+        };
+        self.yank(target)
+    }
+
+    /// Copy `target` of the shown or selected revision to the clipboard.
+    pub fn yank(&mut self, target: YankTarget) -> Result<(), XorcistError> {
+        if target == YankTarget::GitSha && !self.colocated {
+            self.report(CommandResult {
+                success: false,
+                message: "Not a colocated git repository (yc copies the commit ID)".to_string(),
+            });
+            return Ok(());
+        }
+        let Some(text) = self.yank_text(target)? else {
+            self.last_command_result = None;
+            return Ok(());
+        };
+        self.report(match copy_to_clipboard(&text) {
+            Ok(()) => CommandResult {
+                success: true,
+                message: format!(
+                    "Copied {}: {}",
+                    target.label(),
+                    text.lines().next().unwrap_or_default()
+                ),
+            },
+            Err(e) => CommandResult {
+                success: false,
+                message: format!("Failed to copy {}: {e}", target.label()),
+            },
+        });
+        Ok(())
+    }
+
+    /// Text to copy for the revision shown in the detail view, or the
+    /// commit selected in the log.
+    pub(super) fn yank_text(&self, target: YankTarget) -> Result<Option<String>, XorcistError> {
+        if self.view == View::Detail
+            && let Some(state) = &self.detail_state
+        {
+            return Ok(Some(target.text(&state.show_output).to_string()));
+        }
+        let Some(change_id) = self.selected_change_id() else {
+            return Ok(None);
+        };
+        let show = fetch_show(&self.runner, change_id)?;
+        Ok(Some(target.text(&show).to_string()))
+    }
+}
@@ -0,0 +1,82 @@
+//! Copying text to the system clipboard with the OSC 52 escape sequence.
+//!
+//! OSC 52 asks the terminal itself to set the clipboard, so it also works
+//! over SSH and needs no platform clipboard library. Most modern terminals
+//! support it (some only after enabling it in their settings).
+
+use std::env;
+use std::io::{self, Write};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Copy `text` to the clipboard through the terminal.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let inside_tmux = env::var_os("TMUX").is_some();
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52_sequence(text, inside_tmux).as_bytes())?;
+    stdout.flush()
+}
+
+/// Build the OSC 52 sequence setting the clipboard to `text`.
+///
+/// Inside tmux the sequence is wrapped in a DCS passthrough so that it
+/// reaches the outer terminal.
+fn osc52_sequence(text: &str, inside_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()));
+    if inside_tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Encode bytes as standard base64 with padding.
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let indices = [
+            b[0] >> 2,
+            (b[0] & 0x03) << 4 | b[1] >> 4,
+            (b[1] & 0x0f) << 2 | b[2] >> 6,
+            b[2] & 0x3f,
+        ];
+        for (i, &index) in indices.iter().enumerate() {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_encode("✨ feat".as_bytes()), "4pyoIGZlYXQ=");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("foo", false), "\x1b]52;c;Zm9v\x07");
+        assert_eq!(
+            osc52_sequence("foo", true),
+            "\x1bPtmux;\x1b\x1b]52;c;Zm9v\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,1018 @@
+//! User configuration loaded from `~/.config/xorcist/config.toml`, with
+//! per-repository overrides from `.xorcist.toml` at the repository root.
+//!
+//! The files are TOML. Settings are looked up by their dotted
+//! `section.key` names, so `[log]` + `limit = 10` and `log.limit = 10` are
+//! the same.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::XorcistError;
+use crate::jj::{TimestampFormat, check_log_template};
+use crate::theme::{PRESET_NAMES, Theme, parse_color};
+
+/// Name of the per-repository configuration file, at the repository root.
+pub const REPO_CONFIG_FILE: &str = ".xorcist.toml";
+
+/// Settings a repository's [`REPO_CONFIG_FILE`] may set. They describe the
+/// repository; everything else, and the safeguards (confirmations, quick
+/// mode, the journal) in particular, stays under the user's control.
+const REPO_KEYS: [&str; 5] = [
+    "log.revset",
+    "log.limit",
+    "log.batch_size",
+    "push.remote",
+    "push.protected",
+];
+
+/// Repository settings added to the user's instead of replacing them.
+const ADDITIVE_REPO_KEYS: [&str; 1] = ["push.protected"];
+
+/// Default number of log entries loaded at startup.
+pub const DEFAULT_LOG_LIMIT: usize = 500;
+
+/// Default threshold for triggering load more (entries from end).
+pub const DEFAULT_LOAD_MORE_THRESHOLD: usize = 50;
+
+/// Default age (in seconds) after which the log is shown as stale.
+pub const DEFAULT_STALE_AFTER_SECS: u64 = 300;
+
+/// Default width of the author column in the log view.
+pub const DEFAULT_AUTHOR_WIDTH: usize = 16;
+
+/// Default width of the timestamp column in the log view.
+pub const DEFAULT_TIMESTAMP_WIDTH: usize = 4;
+
+/// Default width of the commit ID column in the log view.
+pub const DEFAULT_COMMIT_ID_WIDTH: usize = 12;
+
+/// A field shown in a right-aligned column after the description in the log view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogColumnKind {
+    Author,
+    Timestamp,
+    /// Short commit ID (the git SHA with the git backend).
+    CommitId,
+}
+
+impl LogColumnKind {
+    /// Name used in the `log.columns` setting.
+    fn name(self) -> &'static str {
+        match self {
+            LogColumnKind::Author => "author",
+            LogColumnKind::Timestamp => "timestamp",
+            LogColumnKind::CommitId => "commit_id",
+        }
+    }
+}
+
+/// A column of the log view and its width in display columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogColumn {
+    pub kind: LogColumnKind,
+    pub width: usize,
+}
+
+/// Columns shown when `log.columns` is not set.
+pub const DEFAULT_LOG_COLUMNS: [LogColumn; 2] = [
+    LogColumn {
+        kind: LogColumnKind::Author,
+        width: DEFAULT_AUTHOR_WIDTH,
+    },
+    LogColumn {
+        kind: LogColumnKind::Timestamp,
+        width: DEFAULT_TIMESTAMP_WIDTH,
+    },
+];
+
+/// Every column `log.columns` can name, with its default width.
+const LOG_COLUMN_CHOICES: [LogColumn; 3] = [
+    DEFAULT_LOG_COLUMNS[0],
+    DEFAULT_LOG_COLUMNS[1],
+    LogColumn {
+        kind: LogColumnKind::CommitId,
+        width: DEFAULT_COMMIT_ID_WIDTH,
+    },
+];
+
+/// `[log]` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    /// Number of entries loaded at startup (overridden by `-n`).
+    pub limit: usize,
+    /// Revset shown at startup (overridden by `-r`; None = jj's default log).
+    pub revset: Option<String>,
+    /// Number of entries fetched per incremental load (None = same as limit).
+    pub batch_size: Option<usize>,
+    /// Load more entries when the selection is this close to the end.
+    pub load_more_threshold: usize,
+    /// Wrap long descriptions onto continuation rows instead of truncating.
+    pub wrap_descriptions: bool,
+    /// Seconds after which the "synced" age is highlighted as stale (0 = never).
+    pub stale_after: u64,
+    /// Refresh automatically when another process changes the repository
+    /// (instead of only showing a banner).
+    pub refresh_on_external_change: bool,
+    /// jj template for log lines (None = the built-in template).
+    pub template: Option<String>,
+    /// Timestamps of the built-in template (toggled with `Ctrl+t`).
+    pub timestamps: TimestampFormat,
+    /// Columns after the description, left to right.
+    pub columns: Vec<LogColumn>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LOG_LIMIT,
+            revset: None,
+            batch_size: None,
+            load_more_threshold: DEFAULT_LOAD_MORE_THRESHOLD,
+            wrap_descriptions: false,
+            stale_after: DEFAULT_STALE_AFTER_SECS,
+            refresh_on_external_change: false,
+            template: None,
+            timestamps: TimestampFormat::default(),
+            columns: DEFAULT_LOG_COLUMNS.to_vec(),
+        }
+    }
+}
+
+/// Commands whose confirmation can be set with `confirm.<command>`.
+pub const CONFIRM_COMMANDS: [&str; 16] = [
+    "abandon",
+    "squash",
+    "duplicate",
+    "rebase",
+    "new",
+    "edit",
+    "fetch",
+    "push",
+    "undo",
+    "op_restore",
+    "workspace_forget",
+    "sparse",
+    "split",
+    "restore",
+    "rerun",
+    "simplify_parents",
+];
+
+/// Whether a command asks for confirmation (`confirm.<command>`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfirmPolicy {
+    /// xorcist's built-in behavior (destructive commands ask, quick mode may skip).
+    #[default]
+    Default,
+    /// Always ask, even in quick mode.
+    Always,
+    /// Never ask.
+    Never,
+}
+
+impl ConfirmPolicy {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "default" => Some(Self::Default),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+}
+
+/// `[confirm]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfirmConfig {
+    /// Whether quick mode (skipping confirmations, toggled with `!`) may be enabled.
+    pub allow_quick_mode: bool,
+    /// Whether quick mode also skips the confirmation for pushes.
+    pub quick_mode_push: bool,
+    /// Per-command policies that differ from the default, keyed by
+    /// [`CONFIRM_COMMANDS`] name.
+    pub policies: HashMap<&'static str, ConfirmPolicy>,
+}
+
+impl ConfirmConfig {
+    /// Policy for a command (one of [`CONFIRM_COMMANDS`]).
+    pub fn policy(&self, command: &str) -> ConfirmPolicy {
+        self.policies.get(command).copied().unwrap_or_default()
+    }
+}
+
+/// `[diff]` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffConfig {
+    /// Syntax-highlight file contents in hunks (by file extension).
+    pub syntax_highlight: bool,
+    /// External diff tool for `t` in the diff view (`jj diff --tool <tool>`).
+    pub tool: Option<String>,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            syntax_highlight: true,
+            tool: None,
+        }
+    }
+}
+
+/// `[push]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushConfig {
+    /// Bookmark patterns whose name must be typed to push them (`*` matches
+    /// any characters, e.g. `release/*`).
+    pub protected: Vec<String>,
+    /// Remote passed to `jj git fetch` and `jj git push` (None = jj's default).
+    pub remote: Option<String>,
+}
+
+impl PushConfig {
+    /// Whether a bookmark matches one of the protected patterns.
+    pub fn is_protected(&self, bookmark: &str) -> bool {
+        self.protected
+            .iter()
+            .any(|pattern| wildcard_match(pattern, bookmark))
+    }
+}
+
+/// Match `text` against `pattern`, where `*` matches any characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*`: the whole text must match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// View opened on top of the log at startup (`startup.view`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartupView {
+    #[default]
+    Log,
+    /// Detail view of the working copy.
+    Detail,
+    /// Diff view of the working copy.
+    Diff,
+    OpLog,
+    Stacks,
+}
+
+impl StartupView {
+    /// Names accepted by `startup.view`.
+    pub const NAMES: [&'static str; 5] = ["log", "detail", "diff", "op_log", "stacks"];
+
+    /// Parse a `startup.view` name.
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "log" => Some(Self::Log),
+            "detail" => Some(Self::Detail),
+            "diff" => Some(Self::Diff),
+            "op_log" => Some(Self::OpLog),
+            "stacks" => Some(Self::Stacks),
+            _ => None,
+        }
+    }
+}
+
+/// `[startup]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StartupConfig {
+    /// View opened once the log is loaded.
+    pub view: StartupView,
+    /// Auto-refresh interval in seconds (overridden by `--refresh`; 0 = off).
+    pub refresh: u64,
+}
+
+/// `[journal]` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalConfig {
+    /// Record executed jj commands in `.jj/xorcist-journal.log`.
+    pub enabled: bool,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Complete xorcist configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Log loading settings.
+    pub log: LogConfig,
+    /// Startup view and behavior.
+    pub startup: StartupConfig,
+    /// Confirmation dialog settings.
+    pub confirm: ConfirmConfig,
+    /// Diff view settings.
+    pub diff: DiffConfig,
+    /// Command journal settings.
+    pub journal: JournalConfig,
+    /// Push and fetch settings.
+    pub push: PushConfig,
+    /// UI colors (`[theme]`: a preset plus per-role overrides).
+    pub theme: Theme,
+}
+
+impl Config {
+    /// Load the user configuration file with the overrides of the
+    /// repository at `root`, falling back to defaults for missing files.
+    ///
+    /// Settings in the repository's [`REPO_CONFIG_FILE`] (only [`REPO_KEYS`])
+    /// replace the user's, except that protected bookmarks are added.
+    pub fn load_for_repo(root: &Path) -> Result<Self, XorcistError> {
+        Self::load_files(config_path().as_deref(), &root.join(REPO_CONFIG_FILE))
+    }
+
+    /// Load the user file, then the repository file over it (either may be missing).
+    fn load_files(user_path: Option<&Path>, repo_path: &Path) -> Result<Self, XorcistError> {
+        let mut table = match user_path {
+            Some(path) if path.is_file() => read_table(path)?,
+            _ => Table::default(),
+        };
+        if repo_path.is_file() {
+            let repo_table = read_table(repo_path)?;
+            if let Some(key) = repo_table
+                .values
+                .keys()
+                .filter(|key| !REPO_KEYS.contains(&key.as_str()))
+                .min()
+            {
+                return Err(XorcistError::Config(format!(
+                    "{}: {key} can only be set in the user config file (a repository may set {})",
+                    repo_path.display(),
+                    REPO_KEYS.join(", ")
+                )));
+            }
+            for (key, value) in repo_table.values {
+                if ADDITIVE_REPO_KEYS.contains(&key.as_str())
+                    && let (Some(Value::Array(user)), Value::Array(repo)) =
+                        (table.values.get_mut(&key), &value)
+                {
+                    user.extend(repo.iter().cloned());
+                    continue;
+                }
+                table.values.insert(key, value);
+            }
+        }
+        // Both files are valid on their own, and so is the merged table
+        Self::from_table(&table).map_err(XorcistError::Config)
+    }
+
+    /// Parse configuration from TOML text.
+    #[cfg(test)]
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::from_table(&Table::parse(text)?)
+    }
+
+    /// Build the configuration from a parsed table.
+    fn from_table(table: &Table) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Some(limit) = table.get_usize("log.limit")? {
+            config.log.limit = limit;
+        }
+        if let Some(revset) = table.get_str("log.revset")? {
+            if revset.trim().is_empty() {
+                return Err("log.revset: expected a revset".to_string());
+            }
+            config.log.revset = Some(revset.to_string());
+        }
+        if let Some(batch_size) = table.get_usize("log.batch_size")? {
+            config.log.batch_size = Some(batch_size);
+        }
+        if let Some(threshold) = table.get_usize("log.load_more_threshold")? {
+            config.log.load_more_threshold = threshold;
+        }
+        if let Some(wrap) = table.get_bool("log.wrap_descriptions")? {
+            config.log.wrap_descriptions = wrap;
+        }
+        if let Some(stale_after) = table.get_usize("log.stale_after")? {
+            config.log.stale_after = stale_after as u64;
+        }
+        if let Some(refresh) = table.get_bool("log.refresh_on_external_change")? {
+            config.log.refresh_on_external_change = refresh;
+        }
+        if let Some(template) = table.get_str("log.template")? {
+            check_log_template(template).map_err(|e| format!("log.template: {e}"))?;
+            config.log.template = Some(template.to_string());
+        }
+        if let Some(timestamps) = table.get_str("log.timestamps")? {
+            config.log.timestamps = TimestampFormat::parse(timestamps).ok_or_else(|| {
+                format!(
+                    "log.timestamps: unknown format `{timestamps}` (expected relative or absolute)"
+                )
+            })?;
+        }
+        config.log.columns = parse_log_columns(table)?;
+        if let Some(view) = table.get_str("startup.view")? {
+            config.startup.view = StartupView::parse(view).ok_or_else(|| {
+                format!(
+                    "startup.view: unknown view `{view}` (expected one of: {})",
+                    StartupView::NAMES.join(", ")
+                )
+            })?;
+        }
+        if let Some(refresh) = table.get_usize("startup.refresh")? {
+            config.startup.refresh = refresh as u64;
+        }
+        if let Some(allow) = table.get_bool("confirm.allow_quick_mode")? {
+            config.confirm.allow_quick_mode = allow;
+        }
+        if let Some(push) = table.get_bool("confirm.quick_mode_push")? {
+            config.confirm.quick_mode_push = push;
+        }
+        for command in CONFIRM_COMMANDS {
+            let key = format!("confirm.{command}");
+            if let Some(value) = table.get_str(&key)? {
+                let policy = ConfirmPolicy::parse(value).ok_or_else(|| {
+                    format!("{key}: unknown policy `{value}` (expected always, never or default)")
+                })?;
+                config.confirm.policies.insert(command, policy);
+            }
+        }
+        if let Some(highlight) = table.get_bool("diff.syntax_highlight")? {
+            config.diff.syntax_highlight = highlight;
+        }
+        if let Some(tool) = table.get_str("diff.tool")? {
+            if tool.trim().is_empty() {
+                return Err("diff.tool: expected a tool name".to_string());
+            }
+            config.diff.tool = Some(tool.to_string());
+        }
+        if let Some(enabled) = table.get_bool("journal.enabled")? {
+            config.journal.enabled = enabled;
+        }
+        if let Some(patterns) = table.get_str_array("push.protected")? {
+            config.push.protected = patterns.into_iter().map(str::to_string).collect();
+        }
+        if let Some(remote) = table.get_str("push.remote")? {
+            if remote.trim().is_empty() {
+                return Err("push.remote: expected a remote name".to_string());
+            }
+            config.push.remote = Some(remote.to_string());
+        }
+        if let Some(preset) = table.get_str("theme.preset")? {
+            config.theme = Theme::preset(preset).ok_or_else(|| {
+                format!(
+                    "theme.preset: unknown preset `{preset}` (expected one of: {})",
+                    PRESET_NAMES.join(", ")
+                )
+            })?;
+        }
+        for role in Theme::ROLES {
+            let key = format!("theme.{role}");
+            if let Some(color) = table.get_str(&key)? {
+                let color = parse_color(color).map_err(|e| format!("{key}: {e}"))?;
+                if let Some(slot) = config.theme.role_mut(role) {
+                    *slot = color;
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Parse `log.columns` with the `log.<column>_width` widths.
+fn parse_log_columns(table: &Table) -> Result<Vec<LogColumn>, String> {
+    let mut columns = DEFAULT_LOG_COLUMNS.to_vec();
+    if let Some(names) = table.get_str_array("log.columns")? {
+        columns = names
+            .into_iter()
+            .map(|name| {
+                LOG_COLUMN_CHOICES
+                    .into_iter()
+                    .find(|column| column.kind.name() == name)
+                    .ok_or_else(|| {
+                        format!(
+                            "log.columns: unknown column `{name}` (expected author, timestamp or commit_id)"
+                        )
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    for column in &mut columns {
+        let key = format!("log.{}_width", column.kind.name());
+        match table.get_usize(&key)? {
+            Some(0) => return Err(format!("{key}: expected a positive integer")),
+            Some(width) => column.width = width,
+            None => {}
+        }
+    }
+    Ok(columns)
+}
+
+/// Read and check a configuration file, naming it in errors.
+fn read_table(path: &Path) -> Result<Table, XorcistError> {
+    let text = std::fs::read_to_string(path)?;
+    let table = Table::parse(&text)
+        .and_then(|table| Config::from_table(&table).map(|_| table))
+        .map_err(|msg| XorcistError::Config(format!("{}: {msg}", path.display())))?;
+    Ok(table)
+}
+
+/// Path of the user configuration file.
+///
+/// Uses `$XDG_CONFIG_HOME/xorcist/config.toml`, falling back to
+/// `~/.config/xorcist/config.toml`.
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("xorcist").join("config.toml"))
+}
+
+/// A parsed TOML value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    /// Convert a parsed TOML value found at `key`.
+    fn from_toml(key: &str, value: toml::Value) -> Result<Self, String> {
+        match value {
+            toml::Value::String(s) => Ok(Value::String(s)),
+            toml::Value::Integer(n) => Ok(Value::Integer(n)),
+            toml::Value::Boolean(b) => Ok(Value::Boolean(b)),
+            toml::Value::Array(items) => items
+                .into_iter()
+                .map(|item| Self::from_toml(key, item))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            other => Err(format!(
+                "{key}: {} values are not supported",
+                other.type_str()
+            )),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Integer(_) => "integer",
+            Value::Boolean(_) => "boolean",
+            Value::Array(_) => "array",
+        }
+    }
+}
+
+/// A TOML syntax error, starting with the line it was found on.
+fn syntax_error(text: &str, error: &toml::de::Error) -> String {
+    let message = error.message();
+    match error.span() {
+        Some(span) => {
+            let line = text[..span.start.min(text.len())].matches('\n').count() + 1;
+            format!("line {line}: {message}")
+        }
+        None => message.to_string(),
+    }
+}
+
+/// Flat key/value table with dotted `section.key` names.
+#[derive(Debug, Default)]
+struct Table {
+    values: HashMap<String, Value>,
+}
+
+impl Table {
+    /// Parse TOML text into a flat table.
+    ///
+    /// Only strings, integers, booleans and arrays of them are used by
+    /// xorcist; other values are rejected with the key they were found at.
+    fn parse(text: &str) -> Result<Self, String> {
+        let parsed: toml::Table = text.parse().map_err(|e| syntax_error(text, &e))?;
+        let mut table = Self::default();
+        table.insert_all("", parsed)?;
+        Ok(table)
+    }
+
+    /// Insert the values of `parsed`, naming them `prefix` + their key.
+    fn insert_all(&mut self, prefix: &str, parsed: toml::Table) -> Result<(), String> {
+        for (key, value) in parsed {
+            let key = format!("{prefix}{key}");
+            match value {
+                toml::Value::Table(section) => self.insert_all(&format!("{key}."), section)?,
+                value => {
+                    let value = Value::from_toml(&key, value)?;
+                    self.values.insert(key, value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn get_usize(&self, key: &str) -> Result<Option<usize>, String> {
+        match self.values.get(key) {
+            None => Ok(None),
+            Some(Value::Integer(n)) => usize::try_from(*n)
+                .map(Some)
+                .map_err(|_| format!("{key}: expected a non-negative integer")),
+            Some(other) => Err(format!(
+                "{key}: expected integer, found {}",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn get_str(&self, key: &str) -> Result<Option<&str>, String> {
+        match self.values.get(key) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(other) => Err(format!(
+                "{key}: expected string, found {}",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn get_str_array(&self, key: &str) -> Result<Option<Vec<&str>>, String> {
+        match self.values.get(key) {
+            None => Ok(None),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| match item {
+                    Value::String(s) => Ok(s.as_str()),
+                    other => Err(format!(
+                        "{key}: expected an array of strings, found {}",
+                        other.type_name()
+                    )),
+                })
+                .collect::<Result<_, _>>()
+                .map(Some),
+            Some(other) => Err(format!(
+                "{key}: expected array, found {}",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn get_bool(&self, key: &str) -> Result<Option<bool>, String> {
+        match self.values.get(key) {
+            None => Ok(None),
+            Some(Value::Boolean(b)) => Ok(Some(*b)),
+            Some(other) => Err(format!(
+                "{key}: expected boolean, found {}",
+                other.type_name()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_parse_empty_uses_defaults() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.log.limit, DEFAULT_LOG_LIMIT);
+        assert_eq!(config.log.batch_size, None);
+        assert_eq!(config.log.load_more_threshold, DEFAULT_LOAD_MORE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_parse_log_section() {
+        let text = r#"
+# Tuned for a slow network filesystem
+[log]
+limit = 200
+batch_size = 1_000  # fetch more per round trip
+load_more_threshold = 100
+wrap_descriptions = true
+stale_after = 0
+"#;
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.log.limit, 200);
+        assert_eq!(config.log.batch_size, Some(1000));
+        assert_eq!(config.log.load_more_threshold, 100);
+        assert!(config.log.wrap_descriptions);
+        assert_eq!(config.log.stale_after, 0);
+    }
+
+    #[test]
+    fn test_parse_startup_section() {
+        let text = r#"
+[log]
+revset = "mine() | trunk()"
+
+[startup]
+view = "op_log"
+refresh = 30
+"#;
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.log.revset.as_deref(), Some("mine() | trunk()"));
+        assert_eq!(config.startup.view, StartupView::OpLog);
+        assert_eq!(config.startup.refresh, 30);
+        assert_eq!(Config::default().startup.view, StartupView::Log);
+
+        let err = Config::parse("[startup]\nview = \"graph\"\n").unwrap_err();
+        assert!(err.contains("startup.view"), "{err}");
+        assert!(Config::parse("[log]\nrevset = \"\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_push_section() {
+        let text = r#"
+[push]
+protected = ["main", "release/*"]
+remote = "upstream"
+"#;
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.push.remote.as_deref(), Some("upstream"));
+        assert!(config.push.is_protected("main"));
+        assert!(config.push.is_protected("release/1.2"));
+        assert!(!config.push.is_protected("mainline"));
+        assert!(!config.push.is_protected("feature/release/1"));
+        assert!(!Config::default().push.is_protected("main"));
+        assert!(Config::parse("[push]\nremote = \"\"\n").is_err());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "a-b-b-c"));
+        assert!(wildcard_match("*-wip", "feature-wip"));
+        assert!(!wildcard_match("a*b", "ab-"));
+        assert!(!wildcard_match("ab", "abc"));
+    }
+
+    #[test]
+    fn test_load_files_repo_overrides_user() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        let user = dir.join("config.toml");
+        let repo = dir.join(REPO_CONFIG_FILE);
+        std::fs::write(
+            &user,
+            "[log]\nlimit = 100\nrevset = \"mine()\"\n[push]\nprotected = [\"release-*\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &repo,
+            "[log]\nrevset = \"trunk()..\"\n[push]\nprotected = [\"main\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load_files(Some(&user), &repo).unwrap();
+        assert_eq!(config.log.limit, 100);
+        assert_eq!(config.log.revset.as_deref(), Some("trunk().."));
+        // Protected bookmarks add up
+        assert!(config.push.is_protected("main"));
+        assert!(config.push.is_protected("release-1.0"));
+
+        // Errors name the file; repositories cannot pick programs to run
+        std::fs::write(&repo, "[log]\nlimit = \"many\"\n").unwrap();
+        let err = Config::load_files(Some(&user), &repo)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(REPO_CONFIG_FILE), "{err}");
+        std::fs::write(&repo, "[diff]\ntool = \"./run-me\"\n").unwrap();
+        let err = Config::load_files(Some(&user), &repo)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("diff.tool"), "{err}");
+        // ... nor turn off the user's safeguards
+        for setting in [
+            "[confirm]\npush = \"never\"",
+            "[confirm]\nallow_quick_mode = true",
+            "[journal]\nenabled = false",
+        ] {
+            std::fs::write(&repo, setting).unwrap();
+            let err = Config::load_files(Some(&user), &repo)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("can only be set in the user config"), "{err}");
+        }
+
+        let missing = dir.join("missing.toml");
+        assert_eq!(
+            Config::load_files(None, &missing).unwrap(),
+            Config::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_log_template() {
+        let text = r#"
+[log]
+template = 'change_id.shortest(8) ++ "\x1f" ++ author.email() ++ "\x1f" ++ author.timestamp() ++ "\x1f" ++ bookmarks.join(",") ++ "\x1f" ++ "\x1f" ++ description.first_line()'
+"#;
+        let config = Config::parse(text).unwrap();
+        let template = config.log.template.unwrap();
+        assert!(template.contains("author.email()"), "{template}");
+        assert!(template.contains(r#""\x1f""#), "{template}");
+
+        let err = Config::parse("[log]\ntemplate = 'description'\n").unwrap_err();
+        assert!(err.contains("log.template"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_log_timestamps() {
+        assert_eq!(Config::default().log.timestamps, TimestampFormat::Relative);
+        let config = Config::parse("[log]\ntimestamps = \"absolute\"\n").unwrap();
+        assert_eq!(config.log.timestamps, TimestampFormat::Absolute);
+        let err = Config::parse("[log]\ntimestamps = \"iso\"\n").unwrap_err();
+        assert!(err.contains("log.timestamps"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_log_columns() {
+        let text = "[log]\ncolumns = [\"timestamp\", \"author\"]\ntimestamp_width = 10\n";
+        let config = Config::parse(text).unwrap();
+        assert_eq!(
+            config.log.columns,
+            vec![
+                LogColumn {
+                    kind: LogColumnKind::Timestamp,
+                    width: 10
+                },
+                LogColumn {
+                    kind: LogColumnKind::Author,
+                    width: DEFAULT_AUTHOR_WIDTH
+                },
+            ]
+        );
+        assert!(
+            Config::parse("[log]\ncolumns = []\n")
+                .unwrap()
+                .log
+                .columns
+                .is_empty()
+        );
+        let config =
+            Config::parse("[log]\ncolumns = [\"commit_id\"]\ncommit_id_width = 8\n").unwrap();
+        assert_eq!(
+            config.log.columns,
+            vec![LogColumn {
+                kind: LogColumnKind::CommitId,
+                width: 8
+            }]
+        );
+
+        let err = Config::parse("[log]\ncolumns = [\"email\"]\n").unwrap_err();
+        assert!(err.contains("unknown column `email`"), "{err}");
+        let err = Config::parse("[log]\nauthor_width = 0\n").unwrap_err();
+        assert!(err.contains("log.author_width"), "{err}");
+        let err = Config::parse("[log]\ncolumns = \"author\"\n").unwrap_err();
+        assert!(err.contains("expected array"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_theme_section() {
+        let text = r##"
+[theme]
+preset = "light"
+selection_bg = "#e0e0e0"
+key = "blue"
+"##;
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.theme.selection_bg, Color::Rgb(0xe0, 0xe0, 0xe0));
+        assert_eq!(config.theme.key, Color::Blue);
+        assert_eq!(config.theme.added_bg, Theme::light().added_bg);
+        assert_eq!(Config::parse("").unwrap().theme, Theme::dark());
+
+        let err = Config::parse("[theme]\npreset = \"neon\"\n").unwrap_err();
+        assert!(err.contains("unknown preset"), "{err}");
+        let err = Config::parse("[theme]\naccent = \"sparkly\"\n").unwrap_err();
+        assert!(err.contains("theme.accent"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_confirm_section() {
+        let config = Config::parse("[confirm]\nallow_quick_mode = true\n").unwrap();
+        assert!(config.confirm.allow_quick_mode);
+        assert!(!config.confirm.quick_mode_push);
+        assert!(config.journal.enabled);
+
+        let config = Config::parse("[journal]\nenabled = false\n").unwrap();
+        assert!(!config.journal.enabled);
+        assert!(config.diff.syntax_highlight);
+
+        let config = Config::parse("[diff]\nsyntax_highlight = false\n").unwrap();
+        assert!(!config.diff.syntax_highlight);
+        assert_eq!(config.diff.tool, None);
+
+        let config = Config::parse("[diff]\ntool = \"difft\"\n").unwrap();
+        assert_eq!(config.diff.tool.as_deref(), Some("difft"));
+        assert!(Config::parse("[diff]\ntool = \" \"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_confirm_policies() {
+        let text = "[confirm]\nabandon = \"never\"\nedit = \"always\"\npush = \"default\"\n";
+        let config = Config::parse(text).unwrap();
+        assert_eq!(config.confirm.policy("abandon"), ConfirmPolicy::Never);
+        assert_eq!(config.confirm.policy("edit"), ConfirmPolicy::Always);
+        assert_eq!(config.confirm.policy("push"), ConfirmPolicy::Default);
+        assert_eq!(config.confirm.policy("new"), ConfirmPolicy::Default);
+
+        let err = Config::parse("[confirm]\nnew = \"sometimes\"\n").unwrap_err();
+        assert!(err.contains("confirm.new"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_type_error() {
+        let err = Config::parse("[log]\nlimit = \"many\"\n").unwrap_err();
+        assert!(err.contains("log.limit"), "{err}");
+
+        let err = Config::parse("[log]\nwrap_descriptions = 1\n").unwrap_err();
+        assert!(err.contains("expected boolean"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_negative_integer_rejected() {
+        assert!(Config::parse("[log]\nlimit = -1\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_syntax_error_reports_line() {
+        let err = Config::parse("[log]\nlimit 200\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_values() {
+        let table = Table::parse(
+            r#"a = "a # b" # note
+b = 'lit\eral'
+c = "q\"x"
+d = true
+e = 42
+f = ["main", 'release/*']
+g = []
+"#,
+        )
+        .unwrap();
+        let value = |key: &str| table.values.get(key).cloned().unwrap();
+        assert_eq!(value("a"), Value::String("a # b".into()));
+        assert_eq!(value("b"), Value::String("lit\\eral".into()));
+        assert_eq!(value("c"), Value::String("q\"x".into()));
+        assert_eq!(value("d"), Value::Boolean(true));
+        assert_eq!(value("e"), Value::Integer(42));
+        assert_eq!(
+            value("f"),
+            Value::Array(vec![
+                Value::String("main".into()),
+                Value::String("release/*".into())
+            ])
+        );
+        assert_eq!(value("g"), Value::Array(vec![]));
+        assert!(Table::parse("a = \"open").is_err());
+        assert!(Table::parse("a = 1 2").is_err());
+
+        let err = Table::parse("[log]\nratio = 0.5\n").unwrap_err();
+        assert_eq!(err, "log.ratio: float values are not supported");
+    }
+
+    #[test]
+    fn test_parse_multi_line_values_and_dotted_keys() {
+        let config = Config::parse(
+            r#"startup.refresh = 10
+
+[log]
+columns = [
+    "commit_id",
+    "author", # the trailing comma is allowed
+]
+template = '''
+change_id.shortest(8) ++ "\x1f" ++ author.name() ++ "\x1f"
+++ "\x1f" ++ "\x1f" ++ "\x1f" ++ description.first_line()'''
+
+[push]
+remote = """upstream"""
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.startup.refresh, 10);
+        let kinds: Vec<_> = config.log.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, [LogColumnKind::CommitId, LogColumnKind::Author]);
+        let template = config.log.template.unwrap();
+        assert!(template.starts_with("change_id.shortest(8)"), "{template}");
+        assert!(template.contains('\n'));
+        assert_eq!(config.push.remote.as_deref(), Some("upstream"));
+    }
+}
@@ -1,7 +1,8 @@
-//! Conventional Commits parsing and emoji formatting.
+//! Conventional Commits parsing, writing and emoji formatting.
 //!
 //! Parses commit messages following the Conventional Commits specification
-//! and converts them to emoji-prefixed display format.
+//! and converts them to emoji-prefixed display format. The describe builder
+//! goes the other way, formatting a picked type and scope into a message.
 //!
 //! See: <https://www.conventionalcommits.org/en/v1.0.0/>
 
@@ -68,7 +69,7 @@ impl<'a> ConventionalCommit<'a> {
         };
 
         // Validate commit_type: must be lowercase alphanumeric
-        if commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_lowercase()) {
+        if !is_valid_type(commit_type) {
             return None;
         }
 
@@ -80,6 +81,21 @@ impl<'a> ConventionalCommit<'a> {
         })
     }
 
+    /// Format the commit as a message: `type(scope)!: description`.
+    ///
+    /// The inverse of [`ConventionalCommit::parse`].
+    pub fn to_message(&self) -> String {
+        let scope = self
+            .scope
+            .map(|scope| format!("({scope})"))
+            .unwrap_or_default();
+        let breaking = if self.breaking { "!" } else { "" };
+        format!(
+            "{}{scope}{breaking}: {}",
+            self.commit_type, self.description
+        )
+    }
+
     /// Get the emoji for this commit type.
     pub fn emoji(&self) -> &'static str {
         type_to_emoji(self.commit_type)
@@ -107,6 +123,49 @@ impl<'a> ConventionalCommit<'a> {
     }
 }
 
+/// Commit types offered by the describe builder, with a short summary each.
+pub const COMMIT_TYPES: &[(&str, &str)] = &[
+    ("feat", "A new feature"),
+    ("fix", "A bug fix"),
+    ("docs", "Documentation only"),
+    ("style", "Formatting, no code change"),
+    ("refactor", "Neither a fix nor a feature"),
+    ("perf", "A performance improvement"),
+    ("test", "Adding or fixing tests"),
+    ("build", "Build system or dependencies"),
+    ("ci", "CI configuration"),
+    ("chore", "Other changes"),
+    ("revert", "Reverts a previous commit"),
+];
+
+/// Whether `commit_type` can start a conventional message (lowercase letters).
+pub fn is_valid_type(commit_type: &str) -> bool {
+    !commit_type.is_empty() && commit_type.chars().all(|c| c.is_ascii_lowercase())
+}
+
+/// Scopes used by the conventional messages among `messages`, most used first.
+///
+/// Ties keep the order in which the scopes first appear.
+pub fn collect_scopes<'a>(messages: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for scope in messages
+        .into_iter()
+        .filter_map(|message| ConventionalCommit::parse(message)?.scope)
+        .filter(|scope| !scope.is_empty())
+    {
+        match counts.iter_mut().find(|(seen, _)| *seen == scope) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((scope, 1)),
+        }
+    }
+    // Stable sort keeps first-appearance order among equal counts
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    counts
+        .into_iter()
+        .map(|(scope, _)| scope.to_string())
+        .collect()
+}
+
 /// Convert a conventional commit type to its corresponding emoji.
 fn type_to_emoji(commit_type: &str) -> &'static str {
     match commit_type {
@@ -250,6 +309,45 @@ mod tests {
         assert_eq!(format_commit_message("WIP stuff"), "WIP stuff");
     }
 
+    #[test]
+    fn test_to_message_round_trip() {
+        for message in [
+            "feat: add x",
+            "fix(api): handle null",
+            "refactor(core)!: rewrite",
+        ] {
+            let cc = ConventionalCommit::parse(message).unwrap();
+            assert_eq!(cc.to_message(), message);
+        }
+        let cc = ConventionalCommit {
+            commit_type: "docs",
+            scope: None,
+            breaking: true,
+            description: "drop the old guide",
+        };
+        assert_eq!(cc.to_message(), "docs!: drop the old guide");
+    }
+
+    #[test]
+    fn test_collect_scopes() {
+        let messages = [
+            "fix(ui): a",
+            "feat(jj): b",
+            "feat(ui): c",
+            "plain message",
+            "chore: no scope",
+            "fix(): empty scope",
+            "docs(readme): d",
+        ];
+        assert_eq!(collect_scopes(messages), ["ui", "jj", "readme"]);
+        assert!(
+            COMMIT_TYPES
+                .iter()
+                .all(|(commit_type, _)| is_valid_type(commit_type))
+        );
+        assert!(!is_valid_type("Feat"));
+    }
+
     #[test]
     fn test_emoji_mapping() {
         assert_eq!(type_to_emoji("feat"), "✨");
@@ -0,0 +1,75 @@
+//! Editing text in the user's external editor.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::external::ExternalCommand;
+
+/// Editor used when none of the editor variables are set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Instructions appended below the text, removed again after editing.
+const DESCRIPTION_FOOTER: &str =
+    "\nJJ: Enter a description for the change.\nJJ: Lines starting with \"JJ:\" are removed.\n";
+
+/// Editor command line, looked up like jj does: `$JJ_EDITOR`, `$VISUAL`, then `$EDITOR`.
+pub fn editor_command() -> String {
+    ["JJ_EDITOR", "VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Open `description` in the external editor and return the edited description.
+///
+/// The terminal must already be released by the caller. The editor command
+/// may include arguments (e.g., `code --wait`).
+pub fn edit_description(description: &str) -> io::Result<String> {
+    let path = temp_path();
+    fs::write(&path, format!("{description}\n{DESCRIPTION_FOOTER}"))?;
+    let result = run_editor(&editor_command(), &path).and_then(|()| fs::read_to_string(&path));
+    let _ = fs::remove_file(&path);
+    Ok(strip_comments(&result?))
+}
+
+fn temp_path() -> PathBuf {
+    env::temp_dir().join(format!("xorcist-{}.jjdescription", std::process::id()))
+}
+
+fn run_editor(command: &str, path: &Path) -> io::Result<()> {
+    let editor = ExternalCommand::parse(command, DEFAULT_EDITOR).arg(path.to_string_lossy());
+    let status = editor.run()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "`{command}` exited with {status}"
+        )))
+    }
+}
+
+/// Remove `JJ:` lines, trailing whitespace and leading/trailing blank lines.
+fn strip_comments(text: &str) -> String {
+    let kept: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.starts_with("JJ:"))
+        .map(str::trim_end)
+        .collect();
+    kept.join("\n").trim_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments() {
+        let edited = format!("feat: add thing\n\nLonger body  \n{DESCRIPTION_FOOTER}");
+        assert_eq!(strip_comments(&edited), "feat: add thing\n\nLonger body");
+        assert_eq!(strip_comments(DESCRIPTION_FOOTER), "");
+        assert_eq!(strip_comments("\n\nfix\nJJ: note\n\n"), "fix");
+    }
+}
@@ -17,10 +17,18 @@ pub enum XorcistError {
     #[error("jj command failed: {0}")]
     JjError(String),
 
+    /// A revset did not resolve to exactly one revision.
+    #[error("invalid revision: {0}")]
+    InvalidRevision(String),
+
     /// IO error.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Invalid configuration file.
+    #[error("invalid config: {0}")]
+    Config(String),
+
     /// UTF-8 decode error.
     #[error("invalid UTF-8 in jj output")]
     InvalidUtf8,
@@ -0,0 +1,431 @@
+//! Exporting the loaded log as text, TSV or JSON, and revisions as patches.
+//!
+//! Used by `xor --print` and the `:export` command to share a snapshot of
+//! the log outside the TUI, or to feed it to other tools, and by `:patch`
+//! to write mailable patches.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::conventional::format_commit_message;
+use crate::error::XorcistError;
+use crate::jj::{GraphLine, GraphLog, JjRunner, LogEntry, Patch, ShowOutput, fetch_log_entries};
+use crate::json::Json;
+
+/// Output format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// The graph as shown in the log view, without colors.
+    #[default]
+    Text,
+    /// One tab-separated row per commit, with a header row.
+    Tsv,
+    /// Array of entries with full ids, parents, flags and descriptions.
+    Json,
+}
+
+impl ExportFormat {
+    /// Pick the format from a file extension (`.tsv`, `.json`), defaulting to text.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("tsv") => Self::Tsv,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Render the loaded log in the given format.
+///
+/// Text and TSV are rendered from `graph_log` as loaded. JSON needs data
+/// that the graph does not carry (full ids, parents, whole descriptions),
+/// so the same commits are fetched again from `revset`.
+pub fn export_log(
+    runner: &JjRunner,
+    graph_log: &GraphLog,
+    revset: Option<&str>,
+    format: ExportFormat,
+) -> Result<String, XorcistError> {
+    Ok(match format {
+        ExportFormat::Text => export_text(graph_log),
+        ExportFormat::Tsv => export_tsv(graph_log),
+        ExportFormat::Json => {
+            let entries = fetch_log_entries(runner, revset, Some(graph_log.commit_count()))?;
+            entries_json(&entries)
+        }
+    })
+}
+
+/// Render one revision's details and changed files in the given format.
+pub fn export_detail(show: &ShowOutput, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Text => {
+            let mut out = format!(
+                "Change ID: {}\nCommit ID: {}\nAuthor:    {} ({})\n",
+                show.change_id, show.commit_id, show.author, show.timestamp
+            );
+            if !show.bookmarks.is_empty() {
+                let _ = writeln!(out, "Bookmarks: {}", show.bookmarks.join(", "));
+            }
+            out.push('\n');
+            for line in show.description.lines() {
+                let _ = writeln!(out, "    {line}");
+            }
+            out.push('\n');
+            for file in &show.diff_summary {
+                let _ = writeln!(out, "{:<9} {}", file.status.name(), file.path);
+            }
+            out
+        }
+        ExportFormat::Tsv => {
+            let mut out = String::from("status\tpath\n");
+            for file in &show.diff_summary {
+                let _ = writeln!(out, "{}\t{}", file.status.name(), file.path);
+            }
+            out
+        }
+        ExportFormat::Json => {
+            let files = show
+                .diff_summary
+                .iter()
+                .map(|file| {
+                    Json::Object(vec![
+                        ("status", Json::str(file.status.name())),
+                        ("path", Json::str(&file.path)),
+                    ])
+                })
+                .collect();
+            let json = Json::Object(vec![
+                ("change_id", Json::str(&show.change_id)),
+                ("commit_id", Json::str(&show.commit_id)),
+                ("author", Json::str(&show.author)),
+                ("timestamp", Json::str(&show.timestamp)),
+                ("bookmarks", Json::strings(&show.bookmarks)),
+                ("description", Json::str(&show.description)),
+                ("files", Json::Array(files)),
+            ]);
+            json.to_pretty_string() + "\n"
+        }
+    }
+}
+
+/// Longest subject slug in patch file names, as in `git format-patch`.
+const PATCH_SLUG_MAX_LEN: usize = 52;
+
+/// Render a patch in `git format-patch` form, so that `git am` can apply it.
+///
+/// `number` and `total` are the patch's position in a series; a lone patch
+/// (`total == 1`) is labeled `[PATCH]`.
+pub fn format_patch(patch: &Patch, number: usize, total: usize) -> String {
+    let (subject, body) = split_description(&patch.description);
+    let label = if total > 1 {
+        format!("[PATCH {number}/{total}]")
+    } else {
+        "[PATCH]".to_string()
+    };
+    let mut out = format!(
+        "From {} Mon Sep 17 00:00:00 2001\nFrom: {} <{}>\nDate: {}\nSubject: {label} {subject}\n\n",
+        patch.commit_id, patch.author_name, patch.author_email, patch.date
+    );
+    if !body.is_empty() {
+        out.push_str(body);
+        out.push_str("\n\n");
+    }
+    out.push_str("---\n");
+    out.push_str(&patch.diff);
+    if !patch.diff.is_empty() && !patch.diff.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// File name of a patch in a series: `0001-fix-the-typo.patch`.
+pub fn patch_file_name(number: usize, description: &str) -> String {
+    let (subject, _) = split_description(description);
+    let mut slug = String::new();
+    for ch in subject.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '.' || ch == '_' {
+            slug.push(ch);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= PATCH_SLUG_MAX_LEN {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches(['-', '.']);
+    if slug.is_empty() {
+        format!("{number:04}.patch")
+    } else {
+        format!("{number:04}-{slug}.patch")
+    }
+}
+
+/// Subject (first line) and body (the rest, trimmed) of a description.
+fn split_description(description: &str) -> (&str, &str) {
+    let description = description.trim();
+    if description.is_empty() {
+        return ("(no description)", "");
+    }
+    match description.split_once('\n') {
+        Some((subject, body)) => (subject.trim(), body.trim()),
+        None => (description, ""),
+    }
+}
+
+/// JSON array of log entries.
+fn entries_json(entries: &[LogEntry]) -> String {
+    let items = entries
+        .iter()
+        .map(|entry| {
+            Json::Object(vec![
+                ("change_id", Json::str(&entry.change_id)),
+                ("commit_id", Json::str(&entry.commit_id)),
+                ("parents", Json::strings(&entry.parents)),
+                ("author", Json::str(&entry.author)),
+                ("email", Json::str(&entry.email)),
+                ("timestamp", Json::str(&entry.timestamp)),
+                ("description", Json::str(&entry.description)),
+                ("bookmarks", Json::strings(&entry.bookmarks)),
+                ("immutable", Json::Bool(entry.flags.immutable)),
+                ("conflict", Json::Bool(entry.flags.conflict)),
+                ("empty", Json::Bool(entry.flags.empty)),
+                ("divergent", Json::Bool(entry.flags.divergent)),
+                ("working_copy", Json::Bool(entry.flags.working_copy)),
+            ])
+        })
+        .collect();
+    Json::Array(items).to_pretty_string() + "\n"
+}
+
+/// Graph lines with commit lines laid out as `graph id [bookmarks] description (author, time)`.
+fn export_text(graph_log: &GraphLog) -> String {
+    let mut out = String::new();
+    for line in &graph_log.lines {
+        match line.id_prefix_char_count() {
+            Some(prefix_chars) if line.is_commit_line() => {
+                let prefix: String = line.plain.chars().take(prefix_chars).collect();
+                let _ = write!(out, "{prefix}");
+                if !line.bookmarks.is_empty() {
+                    let _ = write!(out, " [{}]", line.bookmarks.join(", "));
+                }
+                let _ = write!(out, " {}", display_description(line));
+                let details: Vec<&str> = [line.author.as_deref(), line.timestamp.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                if !details.is_empty() {
+                    let _ = write!(out, " ({})", details.join(", "));
+                }
+                out.push('\n');
+            }
+            _ => {
+                out.push_str(line.plain.trim_end());
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// One row per commit: change id, author, timestamp, bookmarks, flags, description.
+fn export_tsv(graph_log: &GraphLog) -> String {
+    let mut out = String::from("change_id\tauthor\ttimestamp\tbookmarks\tflags\tdescription\n");
+    for line in graph_log.lines.iter().filter(|l| l.is_commit_line()) {
+        let fields = [
+            line.change_id.clone().unwrap_or_default(),
+            line.author.clone().unwrap_or_default(),
+            line.timestamp.clone().unwrap_or_default(),
+            line.bookmarks.join(","),
+            line.flags.names().join(","),
+            format_commit_message(line.description.as_deref().unwrap_or_default()),
+        ];
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|f| f.replace(['\t', '\n'], " "))
+            .collect();
+        out.push_str(&fields.join("\t"));
+        out.push('\n');
+    }
+    out
+}
+
+/// Description as displayed in the log view (emoji-formatted, or `(no desc)`).
+fn display_description(line: &GraphLine) -> String {
+    match line.description.as_deref() {
+        Some(desc) if !desc.is_empty() => format_commit_message(desc),
+        _ => "(no desc)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEP: char = '\x1f';
+
+    fn sample_log() -> GraphLog {
+        let output = format!(
+            "@  qzmtztvn{SEP}Alice{SEP}2h{SEP}main{SEP}w{SEP}feat: add export\n\
+             │\n\
+             ◆  rvzpxnov{SEP}Bob{SEP}1d{SEP}{SEP}i{SEP}\n"
+        );
+        GraphLog::from_output(&output)
+    }
+
+    fn runner() -> JjRunner {
+        JjRunner::new()
+    }
+
+    #[test]
+    fn test_export_text() {
+        let text = export_log(&runner(), &sample_log(), None, ExportFormat::Text).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("@  qzmtztvn [main] "), "{}", lines[0]);
+        assert!(lines[0].contains("add export"), "{}", lines[0]);
+        assert!(lines[0].ends_with(" (Alice, 2h)"), "{}", lines[0]);
+        assert_eq!(lines[1], "│");
+        assert_eq!(lines[2], "◆  rvzpxnov (no desc) (Bob, 1d)");
+    }
+
+    #[test]
+    fn test_export_tsv() {
+        let tsv = export_log(&runner(), &sample_log(), None, ExportFormat::Tsv).unwrap();
+        let lines: Vec<&str> = tsv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("change_id\t"));
+        let row: Vec<&str> = lines[1].split('\t').collect();
+        assert_eq!(
+            &row[..5],
+            ["qzmtztvn", "Alice", "2h", "main", "working_copy"]
+        );
+        assert_eq!(lines[2], "rvzpxnov\tBob\t1d\t\timmutable\t");
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("log.TSV")),
+            ExportFormat::Tsv
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("log.txt")),
+            ExportFormat::Text
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("log")),
+            ExportFormat::Text
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("log.json")),
+            ExportFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_export_detail() {
+        let show = ShowOutput {
+            change_id: "qzmtztvn".to_string(),
+            change_id_prefix: "q".to_string(),
+            change_id_rest: "zmtztvn".to_string(),
+            commit_id: "abc123".to_string(),
+            commit_id_prefix: "a".to_string(),
+            commit_id_rest: "bc123".to_string(),
+            author: "Alice".to_string(),
+            timestamp: "2 hours ago".to_string(),
+            description: "fix: typo".to_string(),
+            bookmarks: vec!["main".to_string()],
+            diff_summary: vec![crate::jj::DiffEntry {
+                status: crate::jj::DiffStatus::Modified,
+                path: "src/main.rs".to_string(),
+            }],
+            signature: None,
+            parents: Vec::new(),
+            divergent: false,
+        };
+        assert_eq!(
+            export_detail(&show, ExportFormat::Tsv),
+            "status\tpath\nmodified\tsrc/main.rs\n"
+        );
+        let json = export_detail(&show, ExportFormat::Json);
+        assert!(json.contains(r#""commit_id": "abc123","#), "{json}");
+        assert!(json.contains(r#""status": "modified","#), "{json}");
+        let text = export_detail(&show, ExportFormat::Text);
+        assert!(text.contains("Bookmarks: main\n"), "{text}");
+        assert!(text.ends_with("modified  src/main.rs\n"), "{text}");
+    }
+
+    fn sample_patch(description: &str) -> Patch {
+        Patch {
+            commit_id: "0123abcd".to_string(),
+            author_name: "Alice".to_string(),
+            author_email: "alice@example.com".to_string(),
+            date: "Tue, 14 Oct 2025 09:30:00 +0200".to_string(),
+            description: description.to_string(),
+            diff: "diff --git a/x b/x\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_patch() {
+        let patch = sample_patch("fix: typo\n\nLonger body.");
+        assert_eq!(
+            format_patch(&patch, 2, 3),
+            "From 0123abcd Mon Sep 17 00:00:00 2001\n\
+             From: Alice <alice@example.com>\n\
+             Date: Tue, 14 Oct 2025 09:30:00 +0200\n\
+             Subject: [PATCH 2/3] fix: typo\n\
+             \n\
+             Longer body.\n\
+             \n\
+             ---\n\
+             diff --git a/x b/x\n"
+        );
+        let single = format_patch(&sample_patch(""), 1, 1);
+        assert!(
+            single.contains("Subject: [PATCH] (no description)\n\n---\n"),
+            "{single}"
+        );
+    }
+
+    #[test]
+    fn test_patch_file_name() {
+        assert_eq!(
+            patch_file_name(1, "feat(ui): Add the *patch* export!\n\nbody"),
+            "0001-feat-ui-Add-the-patch-export.patch"
+        );
+        assert_eq!(patch_file_name(12, ""), "0012-no-description.patch");
+        assert_eq!(patch_file_name(2, "日本語"), "0002.patch");
+        assert_eq!(
+            patch_file_name(3, &"x".repeat(80)).len(),
+            "0003-.patch".len() + 52
+        );
+    }
+
+    #[test]
+    fn test_entries_json() {
+        let entry = LogEntry {
+            change_id: "qzmtztvn".to_string(),
+            commit_id: "abc123".to_string(),
+            parents: vec!["def456".to_string()],
+            author: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            timestamp: "2026-01-31T09:15:02+09:00".to_string(),
+            bookmarks: Vec::new(),
+            flags: Default::default(),
+            description: "feat: \"quoted\"\n\nbody".to_string(),
+        };
+        let json = entries_json(&[entry]);
+        assert!(json.starts_with("[\n  {\n    \"change_id\": \"qzmtztvn\","));
+        assert!(json.contains(
+            r#""parents": [
+      "def456"
+    ],"#
+        ));
+        assert!(json.contains(r#""description": "feat: \"quoted\"\n\nbody","#));
+        assert!(json.contains(r#""bookmarks": [],"#));
+        assert!(json.ends_with("\"working_copy\": false\n  }\n]\n"));
+    }
+}
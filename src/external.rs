@@ -0,0 +1,133 @@
+//! External programs run in the foreground while the TUI is suspended.
+//!
+//! The event loop releases the terminal (raw mode, alternate screen, mouse
+//! capture) around [`ExternalCommand::run`], so the program gets the
+//! terminal and stdin to itself. [`ExternalCommand::spawn_detached`] starts
+//! one in the background instead (e.g., a web browser).
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+/// Shell used when `$SHELL` is not set.
+const DEFAULT_SHELL: &str = "sh";
+
+/// A program to run with the terminal released (a shell, an editor, …).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalCommand {
+    /// Program to run.
+    pub program: String,
+    /// Arguments.
+    pub args: Vec<String>,
+    /// Working directory (None = xorcist's own).
+    pub dir: Option<PathBuf>,
+}
+
+impl ExternalCommand {
+    /// Parse a command line from the environment (e.g., `code --wait`),
+    /// running `fallback` if it is blank.
+    ///
+    /// Arguments are split on whitespace, as editors in `$EDITOR` expect.
+    pub fn parse(command_line: &str, fallback: &str) -> Self {
+        let mut parts = command_line.split_whitespace();
+        Self {
+            program: parts.next().unwrap_or(fallback).to_string(),
+            args: parts.map(str::to_string).collect(),
+            dir: None,
+        }
+    }
+
+    /// The user's shell (`$SHELL`), started in `dir`.
+    pub fn shell(dir: Option<&Path>) -> Self {
+        let shell = env::var("SHELL").unwrap_or_default();
+        Self {
+            dir: dir.map(Path::to_path_buf),
+            ..Self::parse(&shell, DEFAULT_SHELL)
+        }
+    }
+
+    /// jj itself with `args`, run in `dir` (for commands that take over the
+    /// terminal, like `jj diff --tool`).
+    pub fn jj(dir: Option<&Path>, args: Vec<String>) -> Self {
+        Self {
+            program: "jj".to_string(),
+            args,
+            dir: dir.map(Path::to_path_buf),
+        }
+    }
+
+    /// Append an argument.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Command line for messages.
+    pub fn display(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Run the program in the foreground and wait for it to exit.
+    ///
+    /// The terminal must already be released by the caller.
+    pub fn run(&self) -> io::Result<ExitStatus> {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        if let Some(dir) = &self.dir {
+            command.current_dir(dir);
+        }
+        command.status()
+    }
+
+    /// Start the program in the background with its input and output
+    /// detached from the terminal.
+    ///
+    /// The child is waited for on a worker thread so that it does not
+    /// linger as a zombie once it exits.
+    pub fn spawn_detached(&self) -> io::Result<()> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(dir) = &self.dir {
+            command.current_dir(dir);
+        }
+        let mut child = command.spawn()?;
+        thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command_line() {
+        let command = ExternalCommand::parse("code --wait", "vi").arg("/tmp/x");
+        assert_eq!(command.program, "code");
+        assert_eq!(command.args, ["--wait", "/tmp/x"]);
+        assert_eq!(command.display(), "code --wait /tmp/x");
+        assert_eq!(ExternalCommand::parse("  ", "vi").display(), "vi");
+
+        let shell = ExternalCommand::shell(Some(Path::new("/repo")));
+        assert!(!shell.program.is_empty());
+        assert_eq!(shell.dir.as_deref(), Some(Path::new("/repo")));
+    }
+
+    #[test]
+    fn test_spawn_detached_reports_a_missing_program() {
+        let command = ExternalCommand::parse("xorcist-no-such-browser --new-window", "vi");
+        assert!(command.spawn_detached().is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Web pages of commits on code forges (GitHub, GitLab, …).
+
+use std::env;
+use std::io;
+
+use crate::external::ExternalCommand;
+
+/// Web URL of a commit on the forge hosting `remote_url`.
+///
+/// Accepts HTTPS (`https://github.com/owner/repo.git`), scp-like SSH
+/// (`git@github.com:owner/repo.git`) and `ssh://` remotes. GitLab and
+/// Bitbucket use their own commit paths; other hosts (GitHub, Gitea,
+/// Forgejo, Codeberg, …) use `/commit/<sha>`. Returns `None` for remotes
+/// that are not on a web host (e.g., local paths).
+pub fn commit_url(remote_url: &str, commit_id: &str) -> Option<String> {
+    let (host, path) = split_remote(remote_url.trim())?;
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    let commit_path = if host.contains("gitlab") {
+        "-/commit"
+    } else if host == "bitbucket.org" {
+        "commits"
+    } else {
+        "commit"
+    };
+    Some(format!("https://{host}/{path}/{commit_path}/{commit_id}"))
+}
+
+/// Split a remote URL into its host (without user or port) and repository path.
+fn split_remote(url: &str) -> Option<(&str, &str)> {
+    if let Some((scheme, rest)) = url.split_once("://") {
+        if !matches!(scheme, "https" | "http" | "ssh" | "git" | "git+ssh") {
+            return None;
+        }
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        return Some((host, path));
+    }
+    // scp-like syntax: [user@]host:path
+    let (authority, path) = url.split_once(':')?;
+    if authority.contains('/') {
+        return None;
+    }
+    let host = authority.rsplit('@').next()?;
+    Some((host, path))
+}
+
+/// Open `url` in the web browser (`$BROWSER`, else the platform's opener).
+///
+/// The browser is started in the background with its output discarded so
+/// that it does not draw over the TUI.
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    browser_command(&env::var("BROWSER").unwrap_or_default(), url).spawn_detached()
+}
+
+/// Command opening `url` with `browser`, which may include arguments
+/// (e.g., `firefox --new-window`).
+fn browser_command(browser: &str, url: &str) -> ExternalCommand {
+    ExternalCommand::parse(browser, default_opener()).arg(url)
+}
+
+fn default_opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_url() {
+        let sha = "0123abcd";
+        for remote in [
+            "https://github.com/fpdy/xorcist.git",
+            "git@github.com:fpdy/xorcist.git",
+            "ssh://git@github.com/fpdy/xorcist",
+            "https://user@github.com/fpdy/xorcist/",
+        ] {
+            assert_eq!(
+                commit_url(remote, sha).as_deref(),
+                Some("https://github.com/fpdy/xorcist/commit/0123abcd"),
+                "{remote}"
+            );
+        }
+        assert_eq!(
+            commit_url("git@gitlab.example.com:group/sub/repo.git", sha).as_deref(),
+            Some("https://gitlab.example.com/group/sub/repo/-/commit/0123abcd")
+        );
+        assert_eq!(
+            commit_url("ssh://git@bitbucket.org:22/team/repo.git", sha).as_deref(),
+            Some("https://bitbucket.org/team/repo/commits/0123abcd")
+        );
+        assert_eq!(commit_url("/srv/git/repo.git", sha), None);
+        assert_eq!(commit_url("file:///srv/git/repo.git", sha), None);
+    }
+
+    #[test]
+    fn test_browser_command() {
+        let url = "https://github.com/fpdy/xorcist";
+        let command = browser_command("firefox --new-window", url);
+        assert_eq!(command.program, "firefox");
+        assert_eq!(command.args, ["--new-window", url]);
+        assert_eq!(browser_command(" ", url).program, default_opener());
+    }
+}
@@ -0,0 +1,617 @@
+//! Lightweight syntax highlighting for file contents in diffs.
+//!
+//! Languages are picked by file extension. Each line is split into
+//! keyword, type, constant, string, number and comment tokens with a small
+//! hand-written lexer; block comments are tracked across the lines of a hunk.
+//! Colors are assigned by the UI.
+
+use std::path::Path;
+
+use crate::jj::{DiffLineKind, GitDiff};
+
+/// Kind of a highlighted token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    /// Type names (capitalized identifiers in most languages).
+    Type,
+    /// Literal constants such as `true`, `None` or `nil`.
+    Constant,
+    String,
+    Number,
+    Comment,
+}
+
+/// A highlighted byte range of a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Lexical rules of a language.
+#[derive(Debug)]
+pub struct Syntax {
+    /// Display name.
+    pub name: &'static str,
+    keywords: &'static [&'static str],
+    constants: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// Capitalized identifiers are types.
+    capitalized_types: bool,
+    /// `'a` is a lifetime or label rather than the start of a character literal.
+    lifetimes: bool,
+}
+
+const RUST: Syntax = Syntax {
+    name: "Rust",
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "type",
+        "unsafe", "use", "where", "while",
+    ],
+    constants: &["true", "false", "None", "Some", "Ok", "Err"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    capitalized_types: true,
+    lifetimes: true,
+};
+
+const PYTHON: Syntax = Syntax {
+    name: "Python",
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+        "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with",
+        "yield",
+    ],
+    constants: &["True", "False", "None"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    capitalized_types: true,
+    lifetimes: false,
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    name: "JavaScript",
+    keywords: &[
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "export",
+        "extends",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "of",
+        "return",
+        "static",
+        "switch",
+        "this",
+        "throw",
+        "try",
+        "type",
+        "typeof",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    constants: &["true", "false", "null", "undefined", "NaN"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    capitalized_types: true,
+    lifetimes: false,
+};
+
+const GO: Syntax = Syntax {
+    name: "Go",
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "type",
+        "var",
+    ],
+    constants: &["true", "false", "nil", "iota"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    capitalized_types: false,
+    lifetimes: false,
+};
+
+const C: Syntax = Syntax {
+    name: "C/C++",
+    keywords: &[
+        "auto",
+        "break",
+        "case",
+        "class",
+        "const",
+        "constexpr",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "extern",
+        "for",
+        "goto",
+        "if",
+        "inline",
+        "namespace",
+        "new",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "sizeof",
+        "static",
+        "struct",
+        "switch",
+        "template",
+        "this",
+        "typedef",
+        "typename",
+        "union",
+        "using",
+        "virtual",
+        "void",
+        "volatile",
+        "while",
+        "#include",
+        "#define",
+        "#ifdef",
+        "#ifndef",
+        "#endif",
+        "#if",
+        "#else",
+        "#pragma",
+    ],
+    constants: &["true", "false", "NULL", "nullptr"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    capitalized_types: true,
+    lifetimes: false,
+};
+
+const JAVA: Syntax = Syntax {
+    name: "Java/Kotlin",
+    keywords: &[
+        "abstract",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "continue",
+        "data",
+        "default",
+        "do",
+        "else",
+        "enum",
+        "extends",
+        "final",
+        "finally",
+        "for",
+        "fun",
+        "if",
+        "implements",
+        "import",
+        "interface",
+        "new",
+        "object",
+        "override",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "throws",
+        "try",
+        "val",
+        "var",
+        "void",
+        "when",
+        "while",
+    ],
+    constants: &["true", "false", "null"],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    capitalized_types: true,
+    lifetimes: false,
+};
+
+const SHELL: Syntax = Syntax {
+    name: "Shell",
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "until", "while",
+    ],
+    constants: &["true", "false"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    capitalized_types: false,
+    lifetimes: false,
+};
+
+const RUBY: Syntax = Syntax {
+    name: "Ruby",
+    keywords: &[
+        "alias", "and", "begin", "break", "case", "class", "def", "do", "else", "elsif", "end",
+        "ensure", "for", "if", "in", "module", "next", "not", "or", "redo", "rescue", "retry",
+        "return", "self", "super", "then", "unless", "until", "when", "while", "yield",
+    ],
+    constants: &["true", "false", "nil"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    capitalized_types: true,
+    lifetimes: false,
+};
+
+/// Configuration-style files: only comments, strings, numbers and booleans.
+const CONFIG: Syntax = Syntax {
+    name: "Config",
+    keywords: &[],
+    constants: &["true", "false", "null", "yes", "no"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    capitalized_types: false,
+    lifetimes: false,
+};
+
+/// Syntax for a file path, picked by extension (None = not highlighted).
+pub fn syntax_for_path(path: &str) -> Option<&'static Syntax> {
+    let path = Path::new(path);
+    let ext = path.extension().and_then(|ext| ext.to_str())?;
+    Some(match ext.to_ascii_lowercase().as_str() {
+        "rs" => &RUST,
+        "py" | "pyi" => &PYTHON,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => &JAVASCRIPT,
+        "go" => &GO,
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" => &C,
+        "java" | "kt" | "kts" => &JAVA,
+        "sh" | "bash" | "zsh" => &SHELL,
+        "rb" => &RUBY,
+        "toml" | "yaml" | "yml" => &CONFIG,
+        _ => return None,
+    })
+}
+
+/// Line-by-line lexer keeping block comment state between lines.
+#[derive(Debug)]
+pub struct Highlighter {
+    syntax: &'static Syntax,
+    in_block_comment: bool,
+}
+
+impl Highlighter {
+    /// Create a highlighter for a language.
+    pub fn new(syntax: &'static Syntax) -> Self {
+        Self {
+            syntax,
+            in_block_comment: false,
+        }
+    }
+
+    /// Forget state carried over from previous lines (e.g. at a hunk boundary).
+    pub fn reset(&mut self) {
+        self.in_block_comment = false;
+    }
+
+    /// Tokens of one line of code.
+    pub fn highlight_line(&mut self, code: &str) -> Vec<Token> {
+        let syntax = self.syntax;
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+
+        if self.in_block_comment {
+            let (_, close) = syntax
+                .block_comment
+                .expect("block comment state without syntax");
+            pos = match code.find(close) {
+                Some(idx) => {
+                    self.in_block_comment = false;
+                    idx + close.len()
+                }
+                None => code.len(),
+            };
+            push(&mut tokens, TokenKind::Comment, 0, pos);
+        }
+
+        while let Some(ch) = code[pos..].chars().next() {
+            let rest = &code[pos..];
+            if syntax.line_comments.iter().any(|p| rest.starts_with(p)) {
+                push(&mut tokens, TokenKind::Comment, pos, code.len());
+                break;
+            }
+            if let Some((open, close)) = syntax.block_comment
+                && rest.starts_with(open)
+            {
+                let end = match rest[open.len()..].find(close) {
+                    Some(idx) => pos + open.len() + idx + close.len(),
+                    None => {
+                        self.in_block_comment = true;
+                        code.len()
+                    }
+                };
+                push(&mut tokens, TokenKind::Comment, pos, end);
+                pos = end;
+                continue;
+            }
+            if syntax.quotes.contains(&ch) && !(syntax.lifetimes && ch == '\'' && is_lifetime(rest))
+            {
+                let end = pos + string_len(rest, ch);
+                push(&mut tokens, TokenKind::String, pos, end);
+                pos = end;
+                continue;
+            }
+            if ch.is_ascii_digit() {
+                let end = pos + word_len(rest, |c| c.is_ascii_alphanumeric() || "_.".contains(c));
+                push(&mut tokens, TokenKind::Number, pos, end);
+                pos = end;
+                continue;
+            }
+            if is_ident_start(ch) || (ch == '#' && !syntax.line_comments.contains(&"#")) {
+                let end = pos + ch.len_utf8() + word_len(&rest[ch.len_utf8()..], is_ident_char);
+                let word = &code[pos..end];
+                let kind = if syntax.keywords.contains(&word) {
+                    Some(TokenKind::Keyword)
+                } else if syntax.constants.contains(&word) {
+                    Some(TokenKind::Constant)
+                } else if syntax.capitalized_types && word.starts_with(|c: char| c.is_uppercase()) {
+                    Some(TokenKind::Type)
+                } else {
+                    None
+                };
+                if let Some(kind) = kind {
+                    push(&mut tokens, kind, pos, end);
+                }
+                pos = end;
+                continue;
+            }
+            pos += ch.len_utf8();
+        }
+        tokens
+    }
+}
+
+/// Highlight the hunk lines of a diff, one token list per display line.
+///
+/// The old and new sides are lexed separately so that block comments
+/// opened in removed lines do not leak into added ones.
+pub fn highlight_diff(diff: &GitDiff, syntax: &'static Syntax) -> Vec<Vec<Token>> {
+    let mut old_side = Highlighter::new(syntax);
+    let mut new_side = Highlighter::new(syntax);
+    diff.lines()
+        .map(|line| {
+            let Some(code) = line.text.get(1..) else {
+                return Vec::new();
+            };
+            let tokens = match line.kind {
+                DiffLineKind::Added => new_side.highlight_line(code),
+                DiffLineKind::Removed => old_side.highlight_line(code),
+                DiffLineKind::Context => {
+                    old_side.highlight_line(code);
+                    new_side.highlight_line(code)
+                }
+                DiffLineKind::HunkHeader | DiffLineKind::FileHeader => {
+                    old_side.reset();
+                    new_side.reset();
+                    return Vec::new();
+                }
+                DiffLineKind::NoNewline => return Vec::new(),
+            };
+            // Shift past the +/-/space prefix
+            tokens
+                .into_iter()
+                .map(|t| Token {
+                    start: t.start + 1,
+                    end: t.end + 1,
+                    ..t
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn push(tokens: &mut Vec<Token>, kind: TokenKind, start: usize, end: usize) {
+    if start < end {
+        tokens.push(Token { kind, start, end });
+    }
+}
+
+fn is_ident_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+fn is_ident_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Byte length of the leading run of characters matching `pred`.
+fn word_len(s: &str, pred: impl Fn(char) -> bool) -> usize {
+    s.find(|c: char| !pred(c)).unwrap_or(s.len())
+}
+
+/// Byte length of a string literal starting at `s` (to the end of the line if unterminated).
+fn string_len(s: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (idx, ch) in s.char_indices().skip(1) {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return idx + c.len_utf8(),
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+/// Whether `'` at the start of `s` begins a lifetime or label (`'a`, `'static`).
+fn is_lifetime(s: &str) -> bool {
+    let mut chars = s.chars().skip(1);
+    match (chars.next(), chars.next()) {
+        (Some(first), Some(second)) => is_ident_start(first) && second != '\'',
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds<'a>(syntax: &'static Syntax, code: &'a str) -> Vec<(TokenKind, &'a str)> {
+        let mut highlighter = Highlighter::new(syntax);
+        highlighter
+            .highlight_line(code)
+            .into_iter()
+            .map(|t| (t.kind, &code[t.start..t.end]))
+            .collect()
+    }
+
+    #[test]
+    fn test_syntax_for_path() {
+        assert_eq!(syntax_for_path("src/main.rs").unwrap().name, "Rust");
+        assert_eq!(syntax_for_path("a/b.PY").unwrap().name, "Python");
+        assert!(syntax_for_path("Makefile").is_none());
+        assert!(syntax_for_path("image.png").is_none());
+    }
+
+    #[test]
+    fn test_highlight_rust_line() {
+        assert_eq!(
+            kinds(&RUST, r#"let s: String = "a \"b\""; // note"#),
+            [
+                (TokenKind::Keyword, "let"),
+                (TokenKind::Type, "String"),
+                (TokenKind::String, r#""a \"b\"""#),
+                (TokenKind::Comment, "// note"),
+            ]
+        );
+        assert_eq!(
+            kinds(&RUST, "fn f<'a>(x: &'a str) -> char { 'x' }"),
+            [(TokenKind::Keyword, "fn"), (TokenKind::String, "'x'"),]
+        );
+        assert_eq!(
+            kinds(&RUST, "x = 0x1f + 1_000;"),
+            [(TokenKind::Number, "0x1f"), (TokenKind::Number, "1_000")]
+        );
+    }
+
+    #[test]
+    fn test_block_comment_spans_lines() {
+        let mut highlighter = Highlighter::new(&RUST);
+        let first = highlighter.highlight_line("let x; /* start");
+        assert_eq!(first.last().unwrap().kind, TokenKind::Comment);
+        let second = highlighter.highlight_line("still */ fn");
+        assert_eq!(
+            second,
+            [
+                Token {
+                    kind: TokenKind::Comment,
+                    start: 0,
+                    end: 8
+                },
+                Token {
+                    kind: TokenKind::Keyword,
+                    start: 9,
+                    end: 11
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_highlight_python_and_config() {
+        assert_eq!(
+            kinds(&PYTHON, "def f(): return None  # done"),
+            [
+                (TokenKind::Keyword, "def"),
+                (TokenKind::Keyword, "return"),
+                (TokenKind::Constant, "None"),
+                (TokenKind::Comment, "# done"),
+            ]
+        );
+        assert_eq!(
+            kinds(&CONFIG, "enabled = true"),
+            [(TokenKind::Constant, "true")]
+        );
+    }
+
+    #[test]
+    fn test_highlight_diff_offsets_past_prefix() {
+        let diff = GitDiff::parse(
+            "diff --git a/x.rs b/x.rs\n\
+             @@ -1,2 +1,2 @@\n\
+             -let a = 1;\n\
+             +let a = 2;\n \
+             }\n",
+        );
+        let highlights = highlight_diff(&diff, &RUST);
+        let lines: Vec<_> = diff.lines().collect();
+        assert_eq!(highlights.len(), lines.len());
+        let removed = lines
+            .iter()
+            .position(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+        let keyword = highlights[removed][0];
+        assert_eq!(&lines[removed].text[keyword.start..keyword.end], "let");
+        assert!(highlights[0].is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+//! Least-recently-used cache of `jj show` and `jj diff` output.
+//!
+//! A commit's details and diffs never change: rewriting a commit gives it a
+//! new commit id. Entries are keyed by commit id, so revisiting a commit in
+//! the detail view or going back and forth between the files of the diff
+//! view does not run jj again. Revisions are looked up through the commit
+//! id their [`ShowOutput`] was cached with.
+//!
+//! What a change id or `@` resolves to moves with the operation, so the
+//! cache is emptied whenever the operation id changes.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::jj::{DiffStat, ShowOutput};
+
+/// Number of outputs kept.
+pub const OUTPUT_CACHE_CAPACITY: usize = 64;
+
+/// What was fetched for a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CacheKind {
+    /// `jj show` fields ([`ShowOutput`]).
+    Show,
+    /// Per-file line counts ([`DiffStat`]).
+    Stat,
+    /// Full diff, as printed by jj.
+    Diff,
+    /// Diff of one file, as printed by jj.
+    FileDiff(String),
+}
+
+/// A cached output.
+#[derive(Debug, Clone)]
+pub enum CachedOutput {
+    Show(Box<ShowOutput>),
+    Stat(DiffStat),
+    Diff(String),
+}
+
+/// LRU cache of outputs keyed by (commit id, kind), for one operation.
+#[derive(Debug)]
+pub struct OutputCache {
+    /// Operation the entries were fetched at.
+    op_id: Option<String>,
+    capacity: usize,
+    /// Entries, least recently used first.
+    entries: VecDeque<((String, CacheKind), CachedOutput)>,
+    /// Commit id of each revision a [`ShowOutput`] was cached for.
+    commit_ids: HashMap<String, String>,
+}
+
+impl Default for OutputCache {
+    fn default() -> Self {
+        Self::new(OUTPUT_CACHE_CAPACITY)
+    }
+}
+
+impl OutputCache {
+    /// Empty cache holding up to `capacity` outputs.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            op_id: None,
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            commit_ids: HashMap::new(),
+        }
+    }
+
+    /// Record the current operation, emptying the cache if it changed.
+    pub fn set_op_id(&mut self, op_id: &str) {
+        if self.op_id.as_deref() != Some(op_id) {
+            self.clear();
+            self.op_id = Some(op_id.to_string());
+        }
+    }
+
+    /// Forget every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.commit_ids.clear();
+    }
+
+    /// Cached output of `kind` for `revision`, marking it recently used.
+    pub fn get(&mut self, revision: &str, kind: &CacheKind) -> Option<&CachedOutput> {
+        let commit_id = self.commit_ids.get(revision)?;
+        let idx = self
+            .entries
+            .iter()
+            .position(|((id, entry_kind), _)| id == commit_id && entry_kind == kind)?;
+        let entry = self.entries.remove(idx)?;
+        self.entries.push_back(entry);
+        self.entries.back().map(|(_, output)| output)
+    }
+
+    /// Cache `output` of `kind` for `revision`.
+    ///
+    /// A [`ShowOutput`] also records the revision's commit id; other outputs
+    /// are dropped if the revision's [`ShowOutput`] was not cached first.
+    pub fn insert(&mut self, revision: &str, kind: CacheKind, output: CachedOutput) {
+        if let CachedOutput::Show(show) = &output {
+            for name in [revision, show.change_id.as_str(), show.commit_id.as_str()] {
+                self.commit_ids
+                    .insert(name.to_string(), show.commit_id.clone());
+            }
+        }
+        let Some(commit_id) = self.commit_ids.get(revision).cloned() else {
+            return;
+        };
+        let key = (commit_id, kind);
+        self.entries.retain(|(entry_key, _)| *entry_key != key);
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, output));
+    }
+
+    /// Cached [`ShowOutput`] of `revision`.
+    pub fn show(&mut self, revision: &str) -> Option<ShowOutput> {
+        match self.get(revision, &CacheKind::Show)? {
+            CachedOutput::Show(show) => Some(show.as_ref().clone()),
+            _ => None,
+        }
+    }
+
+    /// Cached [`DiffStat`] of `revision`.
+    pub fn stat(&mut self, revision: &str) -> Option<DiffStat> {
+        match self.get(revision, &CacheKind::Stat)? {
+            CachedOutput::Stat(stat) => Some(stat.clone()),
+            _ => None,
+        }
+    }
+
+    /// Cached diff output of `kind` ([`CacheKind::Diff`] or
+    /// [`CacheKind::FileDiff`]) for `revision`.
+    pub fn diff(&mut self, revision: &str, kind: &CacheKind) -> Option<String> {
+        match self.get(revision, kind)? {
+            CachedOutput::Diff(output) => Some(output.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show(change_id: &str, commit_id: &str) -> ShowOutput {
+        ShowOutput {
+            change_id: change_id.to_string(),
+            commit_id: commit_id.to_string(),
+            ..ShowOutput::default()
+        }
+    }
+
+    #[test]
+    fn test_output_cache_keys_by_commit_id() {
+        let mut cache = OutputCache::new(8);
+        // Nothing is cached for a revision without its show output
+        cache.insert(
+            "qz",
+            CacheKind::Diff,
+            CachedOutput::Diff("diff".to_string()),
+        );
+        assert_eq!(cache.diff("qz", &CacheKind::Diff), None);
+
+        cache.insert(
+            "qz",
+            CacheKind::Show,
+            CachedOutput::Show(Box::new(show("qzmtztvn", "0f9e"))),
+        );
+        cache.insert(
+            "qz",
+            CacheKind::Diff,
+            CachedOutput::Diff("diff".to_string()),
+        );
+        let file = CacheKind::FileDiff("src/main.rs".to_string());
+        cache.insert("qz", file.clone(), CachedOutput::Diff("main".to_string()));
+        // The full change id and the commit id find the same entries
+        assert_eq!(cache.show("qzmtztvn").unwrap().commit_id, "0f9e");
+        assert_eq!(
+            cache.diff("0f9e", &CacheKind::Diff).as_deref(),
+            Some("diff")
+        );
+        assert_eq!(cache.diff("qz", &file).as_deref(), Some("main"));
+        assert_eq!(
+            cache.diff("qz", &CacheKind::FileDiff("x".to_string())),
+            None
+        );
+        assert!(cache.stat("qz").is_none());
+
+        // A new operation empties the cache
+        cache.set_op_id("1");
+        assert!(cache.show("qz").is_none());
+        cache.insert(
+            "qz",
+            CacheKind::Show,
+            CachedOutput::Show(Box::new(show("qzmtztvn", "0f9e"))),
+        );
+        cache.set_op_id("1");
+        assert!(cache.show("qz").is_some());
+    }
+
+    #[test]
+    fn test_output_cache_evicts_least_recently_used() {
+        let mut cache = OutputCache::new(2);
+        cache.insert(
+            "a",
+            CacheKind::Show,
+            CachedOutput::Show(Box::new(show("a", "1"))),
+        );
+        cache.insert(
+            "b",
+            CacheKind::Show,
+            CachedOutput::Show(Box::new(show("b", "2"))),
+        );
+        // Using `a` makes `b` the least recently used
+        assert!(cache.show("a").is_some());
+        cache.insert(
+            "c",
+            CacheKind::Show,
+            CachedOutput::Show(Box::new(show("c", "3"))),
+        );
+        assert!(cache.show("a").is_some());
+        assert!(cache.show("b").is_none());
+        assert!(cache.show("c").is_some());
+    }
+}
@@ -0,0 +1,430 @@
+//! Structured model of `jj diff --git` output.
+//!
+//! The raw output is parsed into files → hunks → lines, keeping the original
+//! (ANSI-colored) text of every line for display alongside its plain text,
+//! kind and old/new line numbers.
+
+use crate::jj::strip_ansi;
+
+/// Kind of a line in a git-format diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// File header (`diff --git`, `index`, `---`, `+++`, mode lines, ...).
+    FileHeader,
+    /// Hunk header (`@@ -a,b +c,d @@`).
+    HunkHeader,
+    /// Unchanged context line.
+    Context,
+    /// Added line.
+    Added,
+    /// Removed line.
+    Removed,
+    /// `\ No newline at end of file` marker.
+    NoNewline,
+}
+
+/// A single line of diff output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    /// Line kind.
+    pub kind: DiffLineKind,
+    /// Line number in the old file (context and removed lines).
+    #[allow(dead_code)] // Not rendered: the hunk header shows the ranges
+    pub old_no: Option<usize>,
+    /// Line number in the new file (context and added lines).
+    #[allow(dead_code)] // Not rendered: the hunk header shows the ranges
+    pub new_no: Option<usize>,
+    /// Plain text of the full line (including the +/-/space prefix).
+    pub text: String,
+    /// Original line with ANSI color codes.
+    pub raw: String,
+}
+
+/// A hunk within a file diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// Start line in the old file.
+    #[allow(dead_code)] // Not rendered: the hunk header shows the ranges
+    pub old_start: usize,
+    /// Start line in the new file.
+    #[allow(dead_code)] // Not rendered: the hunk header shows the ranges
+    pub new_start: usize,
+    /// The `@@` header line.
+    pub header: DiffLine,
+    /// Body lines of the hunk.
+    pub lines: Vec<DiffLine>,
+}
+
+/// Diff of a single file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDiff {
+    /// Path before the change (None for added files).
+    pub old_path: Option<String>,
+    /// Path after the change (None for deleted files).
+    pub new_path: Option<String>,
+    /// Header lines preceding the first hunk.
+    pub header: Vec<DiffLine>,
+    /// Hunks in order.
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileDiff {
+    /// Iterate over all display lines of this file in order.
+    pub fn lines(&self) -> impl Iterator<Item = &DiffLine> {
+        self.header.iter().chain(
+            self.hunks
+                .iter()
+                .flat_map(|h| std::iter::once(&h.header).chain(h.lines.iter())),
+        )
+    }
+}
+
+/// Parsed `jj diff --git` output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitDiff {
+    /// Per-file diffs in output order.
+    pub files: Vec<FileDiff>,
+}
+
+impl GitDiff {
+    /// Parse `jj diff --git` output (with or without ANSI color codes).
+    pub fn parse(output: &str) -> Self {
+        let mut parser = Parser::default();
+        for raw in output.lines() {
+            parser.push(raw);
+        }
+        Self {
+            files: parser.files,
+        }
+    }
+
+    /// Iterate over all display lines in order.
+    pub fn lines(&self) -> impl Iterator<Item = &DiffLine> {
+        self.files.iter().flat_map(FileDiff::lines)
+    }
+
+    /// Total number of display lines.
+    pub fn line_count(&self) -> usize {
+        self.files
+            .iter()
+            .map(|f| f.header.len() + f.hunks.iter().map(|h| h.lines.len() + 1).sum::<usize>())
+            .sum()
+    }
+
+    /// Check if the diff has no lines.
+    pub fn is_empty(&self) -> bool {
+        self.line_count() == 0
+    }
+}
+
+/// Incremental line parser state.
+#[derive(Default)]
+struct Parser {
+    files: Vec<FileDiff>,
+    /// Remaining (old, new) lines in the current hunk.
+    remaining: (usize, usize),
+    /// Next (old, new) line numbers in the current hunk.
+    next_no: (usize, usize),
+}
+
+impl Parser {
+    fn current_file(&mut self) -> &mut FileDiff {
+        if self.files.is_empty() {
+            self.files.push(FileDiff::default());
+        }
+        self.files.last_mut().expect("files is not empty")
+    }
+
+    fn in_hunk(&self) -> bool {
+        self.remaining != (0, 0)
+    }
+
+    fn push(&mut self, raw: &str) {
+        let text = strip_ansi(raw);
+        let mut line = DiffLine {
+            kind: DiffLineKind::FileHeader,
+            old_no: None,
+            new_no: None,
+            text,
+            raw: raw.to_string(),
+        };
+
+        if line.text.starts_with("diff --git ") {
+            self.remaining = (0, 0);
+            let (old_path, new_path) = parse_diff_git_paths(&line.text);
+            self.files.push(FileDiff {
+                old_path,
+                new_path,
+                header: vec![line],
+                hunks: Vec::new(),
+            });
+            return;
+        }
+
+        if line.text.starts_with("@@")
+            && let Some((old_start, old_count, new_start, new_count)) =
+                parse_hunk_header(&line.text)
+        {
+            line.kind = DiffLineKind::HunkHeader;
+            self.remaining = (old_count, new_count);
+            self.next_no = (old_start, new_start);
+            self.current_file().hunks.push(Hunk {
+                old_start,
+                new_start,
+                header: line,
+                lines: Vec::new(),
+            });
+            return;
+        }
+
+        let has_hunk = self.files.last().is_some_and(|f| !f.hunks.is_empty());
+        if has_hunk && line.text.starts_with('\\') {
+            line.kind = DiffLineKind::NoNewline;
+            self.push_hunk_line(line);
+            return;
+        }
+
+        if has_hunk && self.in_hunk() {
+            let (old_no, new_no) = self.next_no;
+            match line.text.chars().next() {
+                Some('+') => {
+                    line.kind = DiffLineKind::Added;
+                    line.new_no = Some(new_no);
+                    self.next_no.1 += 1;
+                    self.remaining.1 = self.remaining.1.saturating_sub(1);
+                }
+                Some('-') => {
+                    line.kind = DiffLineKind::Removed;
+                    line.old_no = Some(old_no);
+                    self.next_no.0 += 1;
+                    self.remaining.0 = self.remaining.0.saturating_sub(1);
+                }
+                _ => {
+                    line.kind = DiffLineKind::Context;
+                    line.old_no = Some(old_no);
+                    line.new_no = Some(new_no);
+                    self.next_no = (old_no + 1, new_no + 1);
+                    self.remaining.0 = self.remaining.0.saturating_sub(1);
+                    self.remaining.1 = self.remaining.1.saturating_sub(1);
+                }
+            }
+            self.push_hunk_line(line);
+            return;
+        }
+
+        // Header line outside of any hunk
+        let file = self.current_file();
+        if let Some(path) = line.text.strip_prefix("--- ") {
+            file.old_path = parse_header_path(path, "a/");
+        } else if let Some(path) = line.text.strip_prefix("+++ ") {
+            file.new_path = parse_header_path(path, "b/");
+        }
+        file.header.push(line);
+    }
+
+    fn push_hunk_line(&mut self, line: DiffLine) {
+        if let Some(hunk) = self.current_file().hunks.last_mut() {
+            hunk.lines.push(line);
+        }
+    }
+}
+
+/// Parse old/new paths from a `diff --git a/old b/new` line.
+fn parse_diff_git_paths(text: &str) -> (Option<String>, Option<String>) {
+    let rest = text.trim_start_matches("diff --git ");
+    match rest.strip_prefix("a/").and_then(|r| r.split_once(" b/")) {
+        Some((old, new)) => (Some(old.to_string()), Some(new.to_string())),
+        None => (None, None),
+    }
+}
+
+/// Parse a path from a `---`/`+++` header (None for /dev/null).
+fn parse_header_path(path: &str, prefix: &str) -> Option<String> {
+    let path = path.trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
+/// Parse `@@ -a,b +c,d @@` into (old_start, old_count, new_start, new_count).
+fn parse_hunk_header(text: &str) -> Option<(usize, usize, usize, usize)> {
+    let inner = text.strip_prefix("@@ ")?;
+    let end = inner.find(" @@")?;
+    let (old, new) = inner[..end].split_once(' ')?;
+    let (old_start, old_count) = parse_range(old.strip_prefix('-')?)?;
+    let (new_start, new_count) = parse_range(new.strip_prefix('+')?)?;
+    Some((old_start, old_count, new_start, new_count))
+}
+
+/// Parse `start[,count]` (count defaults to 1).
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,4 @@
+ fn main() {
+-    println!(\"old\");
++    println!(\"new\");
++    println!(\"more\");
+ }
+diff --git a/new.txt b/new.txt
+new file mode 100644
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++hello
+\\ No newline at end of file
+";
+
+    #[test]
+    fn test_parse_files_and_paths() {
+        let diff = GitDiff::parse(SAMPLE);
+
+        assert_eq!(diff.files.len(), 2);
+        assert_eq!(diff.files[0].old_path.as_deref(), Some("src/main.rs"));
+        assert_eq!(diff.files[0].new_path.as_deref(), Some("src/main.rs"));
+        assert_eq!(diff.files[1].old_path, None);
+        assert_eq!(diff.files[1].new_path.as_deref(), Some("new.txt"));
+    }
+
+    #[test]
+    fn test_parse_hunk_lines_and_numbers() {
+        let diff = GitDiff::parse(SAMPLE);
+        let hunk = &diff.files[0].hunks[0];
+
+        assert_eq!(hunk.old_start, 1);
+        assert_eq!(hunk.new_start, 1);
+        let kinds: Vec<DiffLineKind> = hunk.lines.iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffLineKind::Context,
+                DiffLineKind::Removed,
+                DiffLineKind::Added,
+                DiffLineKind::Added,
+                DiffLineKind::Context,
+            ]
+        );
+
+        assert_eq!(
+            (hunk.lines[0].old_no, hunk.lines[0].new_no),
+            (Some(1), Some(1))
+        );
+        assert_eq!(
+            (hunk.lines[1].old_no, hunk.lines[1].new_no),
+            (Some(2), None)
+        );
+        assert_eq!(
+            (hunk.lines[2].old_no, hunk.lines[2].new_no),
+            (None, Some(2))
+        );
+        assert_eq!(
+            (hunk.lines[3].old_no, hunk.lines[3].new_no),
+            (None, Some(3))
+        );
+        assert_eq!(
+            (hunk.lines[4].old_no, hunk.lines[4].new_no),
+            (Some(3), Some(4))
+        );
+    }
+
+    #[test]
+    fn test_parse_numbers_across_hunks() {
+        let diff = GitDiff::parse(
+            "diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -2,2 +2,1 @@
+ a
+-b
+@@ -10 +9,3 @@ fn x()
+ c
++d
++e
+diff --git a/g b/g
+--- a/g
++++ b/g
+@@ -5,2 +5,2 @@
+-x
++y
+ z
+",
+        );
+        let numbers = |file: usize, hunk: usize| -> Vec<(Option<usize>, Option<usize>)> {
+            let hunk = &diff.files[file].hunks[hunk];
+            hunk.lines.iter().map(|l| (l.old_no, l.new_no)).collect()
+        };
+
+        let hunks = &diff.files[0].hunks;
+        assert_eq!((hunks[1].old_start, hunks[1].new_start), (10, 9));
+        assert_eq!(numbers(0, 0), [(Some(2), Some(2)), (Some(3), None)]);
+        assert_eq!(
+            numbers(0, 1),
+            [(Some(10), Some(9)), (None, Some(10)), (None, Some(11))]
+        );
+        // Numbering starts again in the next file
+        assert_eq!(
+            numbers(1, 0),
+            [(Some(5), None), (None, Some(5)), (Some(6), Some(6))]
+        );
+    }
+
+    #[test]
+    fn test_parse_no_newline_marker() {
+        let diff = GitDiff::parse(SAMPLE);
+        let hunk = &diff.files[1].hunks[0];
+
+        assert_eq!(hunk.lines.len(), 2);
+        assert_eq!(hunk.lines[0].kind, DiffLineKind::Added);
+        assert_eq!(hunk.lines[1].kind, DiffLineKind::NoNewline);
+    }
+
+    #[test]
+    fn test_line_count_matches_lines() {
+        let diff = GitDiff::parse(SAMPLE);
+        assert_eq!(diff.line_count(), SAMPLE.lines().count());
+        assert_eq!(diff.lines().count(), diff.line_count());
+    }
+
+    #[test]
+    fn test_parse_keeps_ansi_raw_text() {
+        let output = "\x1b[1mdiff --git a/f b/f\x1b[0m\n@@ -1 +1 @@\n\x1b[38;5;1m-a\x1b[39m\n\x1b[38;5;2m+b\x1b[39m\n";
+        let diff = GitDiff::parse(output);
+        let lines: Vec<&DiffLine> = diff.lines().collect();
+
+        assert_eq!(lines[0].text, "diff --git a/f b/f");
+        assert_eq!(lines[2].kind, DiffLineKind::Removed);
+        assert_eq!(lines[2].text, "-a");
+        assert_eq!(lines[2].raw, "\x1b[38;5;1m-a\x1b[39m");
+        assert_eq!(lines[3].kind, DiffLineKind::Added);
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let diff = GitDiff::parse("");
+        assert!(diff.is_empty());
+        assert!(diff.files.is_empty());
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(parse_hunk_header("@@ -1,3 +1,4 @@"), Some((1, 3, 1, 4)));
+        assert_eq!(
+            parse_hunk_header("@@ -5 +7,2 @@ fn x()"),
+            Some((5, 1, 7, 2))
+        );
+        assert_eq!(parse_hunk_header("@@ bogus @@"), None);
+    }
+}
@@ -0,0 +1,70 @@
+//! Divergent changes: several visible commits with one change ID.
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Template for [`fetch_divergent_commits`]: `commit_id\x1fsubject`, one commit per `\x1e`.
+///
+/// The commit IDs are shortened like the log's, so they compare equal.
+const DIVERGENT_TEMPLATE: &str =
+    r#"commit_id.shortest(8) ++ "\x1f" ++ description.first_line() ++ "\x1e""#;
+
+/// One of the commits of a divergent change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivergentCommit {
+    /// Shortest commit ID (at least 8 characters).
+    pub commit_id: String,
+    /// First line of the description.
+    pub description: String,
+}
+
+/// Fetch every visible commit sharing the change ID of `revision`, newest first.
+pub fn fetch_divergent_commits(
+    runner: &JjRunner,
+    revision: &str,
+) -> Result<Vec<DivergentCommit>, XorcistError> {
+    let change_id =
+        runner.run_capture(&["log", "-r", revision, "--no-graph", "-T", "change_id"])?;
+    let revset = format!("change_id({})", change_id.trim());
+    let output =
+        runner.run_capture(&["log", "-r", &revset, "--no-graph", "-T", DIVERGENT_TEMPLATE])?;
+    Ok(parse_divergent_commits(&output))
+}
+
+/// Parse the output of [`DIVERGENT_TEMPLATE`].
+fn parse_divergent_commits(output: &str) -> Vec<DivergentCommit> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let (commit_id, description) = record.trim_start_matches('\n').split_once('\x1f')?;
+            Some(DivergentCommit {
+                commit_id: commit_id.to_string(),
+                description: description.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_divergent_commits() {
+        let commits = parse_divergent_commits("0f9e8d7c\x1ffeat: a\x1e\n1a2b3c4d\x1f\x1e");
+        assert_eq!(
+            commits,
+            [
+                DivergentCommit {
+                    commit_id: "0f9e8d7c".to_string(),
+                    description: "feat: a".to_string(),
+                },
+                DivergentCommit {
+                    commit_id: "1a2b3c4d".to_string(),
+                    description: String::new(),
+                },
+            ]
+        );
+        assert!(parse_divergent_commits("").is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! Structured log entries for machine-readable output.
+
+use crate::error::XorcistError;
+use crate::jj::graph_log::{CommitFlags, DEFAULT_REVSET};
+use crate::jj::runner::JjRunner;
+
+/// Separator between fields of an entry.
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// Terminator of an entry (descriptions may span several lines).
+const RECORD_SEPARATOR: char = '\x1e';
+
+/// Template for [`fetch_log_entries`]; fields are separated by
+/// [`FIELD_SEPARATOR`] and each entry ends with [`RECORD_SEPARATOR`].
+const ENTRY_TEMPLATE: &str = r#"change_id ++ "\x1f" ++ commit_id ++ "\x1f" ++ parents.map(|c| c.commit_id()).join(",") ++ "\x1f" ++ author.name() ++ "\x1f" ++ author.email() ++ "\x1f" ++ author.timestamp().format("%Y-%m-%dT%H:%M:%S%:z") ++ "\x1f" ++ bookmarks.map(|b| b.name()).join(",") ++ "\x1f" ++ if(immutable, "i") ++ if(conflict, "c") ++ if(empty, "e") ++ if(current_working_copy, "w") ++ if(divergent, "d") ++ "\x1f" ++ description ++ "\x1e""#;
+
+/// Number of fields produced by [`ENTRY_TEMPLATE`].
+const ENTRY_FIELD_COUNT: usize = 9;
+
+/// One log entry with full ids and the complete description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Change ID (full).
+    pub change_id: String,
+    /// Commit ID (full).
+    pub commit_id: String,
+    /// Commit IDs of the parents.
+    pub parents: Vec<String>,
+    /// Author name.
+    pub author: String,
+    /// Author email.
+    pub email: String,
+    /// Author timestamp (RFC 3339).
+    pub timestamp: String,
+    /// Bookmarks pointing at the commit.
+    pub bookmarks: Vec<String>,
+    /// State flags of the commit.
+    pub flags: CommitFlags,
+    /// Full description (without the trailing newline).
+    pub description: String,
+}
+
+/// Fetch structured entries for `revset` (None = [`DEFAULT_REVSET`]), newest first.
+pub fn fetch_log_entries(
+    runner: &JjRunner,
+    revset: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, XorcistError> {
+    let mut args = vec![
+        "log",
+        "--no-graph",
+        "--ignore-working-copy",
+        "-T",
+        ENTRY_TEMPLATE,
+        "-r",
+        revset.unwrap_or(DEFAULT_REVSET),
+    ];
+    let limit_str;
+    if let Some(n) = limit {
+        limit_str = n.to_string();
+        args.push("-n");
+        args.push(&limit_str);
+    }
+    let output = runner.run_capture(&args)?;
+    runner.time_parse("log entries", || parse_log_entries(&output))
+}
+
+/// Parse the output of [`ENTRY_TEMPLATE`].
+fn parse_log_entries(output: &str) -> Result<Vec<LogEntry>, XorcistError> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .map(parse_entry)
+        .collect()
+}
+
+fn parse_entry(record: &str) -> Result<LogEntry, XorcistError> {
+    let fields: Vec<&str> = record
+        .trim_start_matches('\n')
+        .splitn(ENTRY_FIELD_COUNT, FIELD_SEPARATOR)
+        .collect();
+    let [
+        change_id,
+        commit_id,
+        parents,
+        author,
+        email,
+        timestamp,
+        bookmarks,
+        flags,
+        description,
+    ] = fields[..]
+    else {
+        return Err(XorcistError::JjError(format!(
+            "unexpected log entry format: expected {ENTRY_FIELD_COUNT} fields, got {}",
+            fields.len()
+        )));
+    };
+    Ok(LogEntry {
+        change_id: change_id.to_string(),
+        commit_id: commit_id.to_string(),
+        parents: super::parse_bookmarks_field(parents),
+        author: author.to_string(),
+        email: email.to_string(),
+        timestamp: timestamp.to_string(),
+        bookmarks: super::parse_bookmarks_field(bookmarks),
+        flags: CommitFlags::parse(flags),
+        description: description.trim_end_matches('\n').to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_entries() {
+        let output = "qzmt\x1fabc123\x1fdef456,789abc\x1fAlice\x1falice@example.com\x1f\
+                      2026-01-31T09:15:02+09:00\x1fmain,dev\x1fw\x1ffeat: x\n\nbody\n\x1e\
+                      rvzp\x1fdef456\x1f\x1fBob\x1f\x1f2026-01-30T00:00:00+00:00\x1f\x1fie\x1f\x1e";
+        let entries = parse_log_entries(output).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].change_id, "qzmt");
+        assert_eq!(entries[0].parents, ["def456", "789abc"]);
+        assert_eq!(entries[0].bookmarks, ["main", "dev"]);
+        assert!(entries[0].flags.working_copy);
+        assert_eq!(entries[0].description, "feat: x\n\nbody");
+        assert!(entries[1].parents.is_empty());
+        assert!(entries[1].flags.immutable && entries[1].flags.empty);
+        assert_eq!(entries[1].description, "");
+    }
+
+    #[test]
+    fn test_parse_log_entries_rejects_short_records() {
+        assert!(parse_log_entries("qzmt\x1fabc\x1e").is_err());
+        assert_eq!(parse_log_entries("").unwrap(), Vec::new());
+    }
+}
@@ -7,17 +7,133 @@ use regex::Regex;
 use std::sync::LazyLock;
 
 use crate::error::XorcistError;
-use crate::jj::runner::JjRunner;
+use crate::jj::runner::{JjRunner, LineStream};
+
+/// Separator between fields of [`GRAPH_LOG_TEMPLATE`] (ASCII unit separator).
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// Separator between the bookmarks and the tags in the bookmarks field.
+const TAG_SEPARATOR: char = '\x1d';
+
+/// Separator between the flags and the commit ID in the flags field.
+const COMMIT_ID_SEPARATOR: char = '\x1d';
+
+/// Build a graph log template around a timestamp expression.
+macro_rules! graph_log_template {
+    ($timestamp:literal) => {
+        concat!(
+            r#"change_id.shortest(8) ++ if(divergent, label("divergent", "??") ++ " " ++ commit_id.shortest(8), if(hidden, " " ++ label("hidden", "hidden") ++ " " ++ commit_id.shortest(8))) ++ "\x1f" ++ author.name() ++ "\x1f" ++ "#,
+            $timestamp,
+            r#" ++ "\x1f" ++ bookmarks.map(|b| b.name()).join(",") ++ "\x1d" ++ tags.map(|t| t.name()).join(",") ++ "\x1f" ++ if(immutable, "i") ++ if(conflict, "c") ++ if(empty, "e") ++ if(current_working_copy, "w") ++ if(divergent, "d") ++ if(hidden, "h") ++ "\x1d" ++ commit_id.short() ++ "\x1f" ++ description.first_line()"#
+        )
+    };
+}
 
 /// Template for graph log output with shortened timestamps and bookmarks.
 ///
-/// Format: `change_id author timestamp [bookmarks] description`
-/// - change_id: 8-character shortest unique prefix
-/// - author: author name
+/// Format: `change_id\x1fauthor\x1ftimestamp\x1fbookmarks\x1fflags\x1fdescription`
+/// - change_id: 8-character shortest unique prefix; a divergent commit adds
+///   `?? ` and its commit ID, and a hidden one ` hidden ` and its commit ID,
+///   which then stands for it (the change ID is shared or points elsewhere)
+/// - author: author name (may contain spaces)
 /// - timestamp: shortened format (e.g., "12h" instead of "12 hours ago")
-/// - bookmarks: comma-separated bookmark names wrapped in brackets (if any)
+/// - bookmarks: comma-separated bookmark names, then `\x1d` and comma-separated
+///   tag names (both may be empty)
+/// - flags: one letter per state, see [`CommitFlags::parse`], then `\x1d`
+///   and the 12-character commit ID (the git SHA with the git backend)
 /// - description: first line of commit message
-const GRAPH_LOG_TEMPLATE: &str = r#"separate(" ", change_id.shortest(8), author.name(), author.timestamp().ago().replace(regex:"\\s+seconds? ago", "s").replace(regex:"\\s+minutes? ago", "m").replace(regex:"\\s+hours? ago", "h").replace(regex:"\\s+days? ago", "d").replace(regex:"\\s+weeks? ago", "w").replace(regex:"\\s+months? ago", "mo").replace(regex:"\\s+years? ago", "y"), if(bookmarks, "[" ++ bookmarks.map(|b| b.name()).join(",") ++ "]"), description.first_line())"#;
+///
+/// The fields are laid out by the UI, so the separator never reaches the screen.
+/// `log.template` in the config file replaces it (see [`check_log_template`]).
+pub(crate) const GRAPH_LOG_TEMPLATE: &str = graph_log_template!(
+    r#"author.timestamp().ago().replace(regex:"\\s+seconds? ago", "s").replace(regex:"\\s+minutes? ago", "m").replace(regex:"\\s+hours? ago", "h").replace(regex:"\\s+days? ago", "d").replace(regex:"\\s+weeks? ago", "w").replace(regex:"\\s+months? ago", "mo").replace(regex:"\\s+years? ago", "y")"#
+);
+
+/// [`GRAPH_LOG_TEMPLATE`] with absolute local timestamps ("2026-10-14 09:30").
+const GRAPH_LOG_TEMPLATE_ABSOLUTE: &str =
+    graph_log_template!(r#"author.timestamp().local().format("%Y-%m-%d %H:%M")"#);
+
+/// Log template for when the built-in one fails (e.g., with an older jj).
+///
+/// It keeps to template features jj has had for long, at the cost of tags
+/// and shortened timestamps.
+pub const MINIMAL_LOG_TEMPLATE: &str = r#"change_id.shortest(8) ++ if(divergent, "?? " ++ commit_id.shortest(8), if(hidden, " hidden " ++ commit_id.shortest(8))) ++ "\x1f" ++ author.name() ++ "\x1f" ++ author.timestamp().ago() ++ "\x1f" ++ bookmarks.join(",") ++ "\x1f" ++ if(immutable, "i") ++ if(conflict, "c") ++ if(empty, "e") ++ if(current_working_copy, "w") ++ if(divergent, "d") ++ if(hidden, "h") ++ "\x1d" ++ commit_id.short() ++ "\x1f" ++ description.first_line()"#;
+
+/// Width of the timestamps of [`GRAPH_LOG_TEMPLATE_ABSOLUTE`].
+pub const ABSOLUTE_TIMESTAMP_WIDTH: usize = 16;
+
+/// How the built-in log template shows commit timestamps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Shortened age ("3mo").
+    #[default]
+    Relative,
+    /// Local date and time ("2026-10-14 09:30").
+    Absolute,
+}
+
+impl TimestampFormat {
+    /// Parse a `log.timestamps` value.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "relative" => Some(Self::Relative),
+            "absolute" => Some(Self::Absolute),
+            _ => None,
+        }
+    }
+
+    /// The other format.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Relative => Self::Absolute,
+            Self::Absolute => Self::Relative,
+        }
+    }
+
+    /// Built-in log template showing timestamps in this format.
+    pub(crate) fn template(self) -> &'static str {
+        match self {
+            Self::Relative => GRAPH_LOG_TEMPLATE,
+            Self::Absolute => GRAPH_LOG_TEMPLATE_ABSOLUTE,
+        }
+    }
+}
+
+/// Number of fields a log template must produce.
+const LOG_TEMPLATE_FIELD_COUNT: usize = 6;
+
+/// Check that a custom log template produces the fields xorcist parses.
+///
+/// The template must join six fields with `"\x1f"`, in the order of
+/// [`GRAPH_LOG_TEMPLATE`], starting with `change_id.shortest(8)`. What each
+/// of the other fields contains is up to the template; anything after the
+/// fifth separator is shown as the description.
+pub fn check_log_template(template: &str) -> Result<(), String> {
+    let separators = template.matches(r#""\x1f""#).count();
+    if separators < LOG_TEMPLATE_FIELD_COUNT - 1 {
+        return Err(format!(
+            "expected {LOG_TEMPLATE_FIELD_COUNT} fields separated by \"\\x1f\", found {}",
+            separators + 1
+        ));
+    }
+    if !template.trim_start().starts_with("change_id") {
+        return Err("the first field must be the change ID (change_id.shortest(8))".to_string());
+    }
+    Ok(())
+}
+
+/// Largest share, in percent, of the template's lines that may fail to parse
+/// before the log is considered unreadable (see [`GraphLog::unparsed_percent`]).
+pub const MAX_UNPARSED_PERCENT: usize = 10;
+
+/// jj settings forced when the user's configuration breaks log parsing, as
+/// `(name, TOML value)`: node symbols that are letters (e.g.,
+/// `builtin_log_node_ascii`) hide the change ID from the regexes, and word
+/// wrapping splits the fields over several lines.
+pub const LOG_CONFIG_OVERRIDES: &[(&str, &str)] = &[
+    ("templates.log_node", r#""builtin_log_node""#),
+    ("ui.log-word-wrap", "false"),
+];
 
 /// Regex pattern for extracting change_id from graph output.
 /// Matches 8 lowercase letters after graph symbols.
@@ -27,8 +143,18 @@ static CHANGE_ID_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^[^a-z]*([a-z]{8})\s").expect("Invalid regex pattern")
 });
 
-/// Regex pattern for extracting all fields from a commit line.
+/// Regex pattern for the graph prefix and change_id before the first field separator.
+///
+/// The commit ID after `??` (divergent commits) or `hidden` is captured as well.
+static SEPARATED_ID_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[^a-z]*([a-z]{8,})(?:(?:\?\?| hidden) ([0-9a-f]{8,}))?$")
+        .expect("Invalid regex pattern")
+});
+
+/// Regex pattern for extracting all fields from a space-separated commit line.
 /// Format: `change_id author timestamp [bookmarks] description`
+///
+/// Used as a fallback for output that does not contain field separators.
 static COMMIT_LINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     // Match: graph_symbols change_id(8 letters) author timestamp [bookmarks]? description
     // - graph_symbols: non-letter characters at the start
@@ -41,6 +167,9 @@ static COMMIT_LINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
         .expect("Invalid regex pattern")
 });
 
+/// Label jj draws beside the `~` of revisions left out of the graph.
+const ELIDED_LABEL: &str = "(elided revisions)";
+
 /// Regex pattern to strip ANSI escape sequences.
 static ANSI_STRIP_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").expect("Invalid ANSI regex pattern"));
@@ -57,6 +186,18 @@ pub struct GraphLine {
     /// Description extracted from this line, if any.
     /// Empty string if the commit has no description.
     pub description: Option<String>,
+    /// Author name, if this is a commit line.
+    pub author: Option<String>,
+    /// Shortened relative timestamp (e.g., "12h"), if this is a commit line.
+    pub timestamp: Option<String>,
+    /// Short commit ID, if the log template includes it.
+    pub commit_id: Option<String>,
+    /// Bookmarks pointing at the commit.
+    pub bookmarks: Vec<String>,
+    /// Tags pointing at the commit.
+    pub tags: Vec<String>,
+    /// State flags of the commit (all false for non-commit lines).
+    pub flags: CommitFlags,
     /// Line index in the full output.
     pub line_index: usize,
 }
@@ -65,12 +206,18 @@ impl GraphLine {
     /// Create a new GraphLine from raw text.
     fn new(raw: String, line_index: usize) -> Self {
         let plain = strip_ansi(&raw);
-        let (change_id, description) = extract_commit_fields(&plain);
+        let fields = extract_commit_fields(&plain).unwrap_or_default();
         Self {
             raw,
             plain,
-            change_id,
-            description,
+            change_id: fields.change_id,
+            description: fields.description,
+            author: fields.author,
+            timestamp: fields.timestamp,
+            commit_id: fields.commit_id,
+            bookmarks: fields.bookmarks,
+            tags: fields.tags,
+            flags: fields.flags,
             line_index,
         }
     }
@@ -79,6 +226,89 @@ impl GraphLine {
     pub fn is_commit_line(&self) -> bool {
         self.change_id.is_some()
     }
+
+    /// Check if this line marks elided revisions (`~  (elided revisions)`).
+    pub fn is_elided(&self) -> bool {
+        !self.is_commit_line() && self.plain.contains(ELIDED_LABEL)
+    }
+
+    /// Column (in characters) of the commit node (`@`, `○`, …) of a commit line.
+    fn node_column(&self) -> Option<usize> {
+        self.change_id.as_ref()?;
+        self.plain
+            .chars()
+            .position(|ch| !matches!(ch, ' ' | '│' | '─' | '├' | '┤' | '╭' | '╮' | '╯' | '╰'))
+    }
+
+    /// Number of characters of the plain text up to the end of the change_id.
+    ///
+    /// This covers the graph symbols and the change_id itself, and is used to
+    /// keep jj's coloring for that part when the rest of the row is laid out.
+    pub fn id_prefix_char_count(&self) -> Option<usize> {
+        let change_id = self.change_id.as_deref()?;
+        let start = self.plain.find(change_id)?;
+        Some(self.plain[..start + change_id.len()].chars().count())
+    }
+}
+
+/// State flags of a commit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommitFlags {
+    /// The commit is immutable (e.g., on a remote trunk).
+    pub immutable: bool,
+    /// The commit has unresolved conflicts.
+    pub conflict: bool,
+    /// The commit does not change any files.
+    pub empty: bool,
+    /// The commit is the current working-copy commit.
+    pub working_copy: bool,
+    /// The commit shares its change ID with other visible commits.
+    pub divergent: bool,
+    /// The commit is hidden (abandoned or rewritten).
+    pub hidden: bool,
+}
+
+impl CommitFlags {
+    /// Parse the flags field: `i` immutable, `c` conflict, `e` empty, `w` working
+    /// copy, `d` divergent, `h` hidden.
+    pub(crate) fn parse(field: &str) -> Self {
+        Self {
+            immutable: field.contains('i'),
+            conflict: field.contains('c'),
+            empty: field.contains('e'),
+            working_copy: field.contains('w'),
+            divergent: field.contains('d'),
+            hidden: field.contains('h'),
+        }
+    }
+
+    /// Names of the set flags (e.g., `["immutable", "empty"]`).
+    pub fn names(&self) -> Vec<&'static str> {
+        [
+            (self.immutable, "immutable"),
+            (self.conflict, "conflict"),
+            (self.empty, "empty"),
+            (self.working_copy, "working_copy"),
+            (self.divergent, "divergent"),
+            (self.hidden, "hidden"),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect()
+    }
+}
+
+/// Fields parsed from a commit line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CommitFields {
+    change_id: Option<String>,
+    author: Option<String>,
+    timestamp: Option<String>,
+    commit_id: Option<String>,
+    bookmarks: Vec<String>,
+    tags: Vec<String>,
+    flags: CommitFlags,
+    description: Option<String>,
 }
 
 /// Complete graph log with all lines and selection metadata.
@@ -128,11 +358,106 @@ impl GraphLog {
         self.lines[line_idx].change_id.as_deref()
     }
 
+    /// Line index of the commit whose ancestors the elided line `line_idx` stands for.
+    ///
+    /// That is the nearest commit above drawn in the lane of the `~`, or the
+    /// nearest commit above if none is.
+    pub fn elided_owner(&self, line_idx: usize) -> Option<usize> {
+        let line = self.lines.get(line_idx).filter(|line| line.is_elided())?;
+        let column = line.plain.chars().position(|ch| ch == '~');
+        let mut above = self.lines[..line_idx]
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, line)| line.is_commit_line());
+        above
+            .clone()
+            .find(|(_, line)| column.is_some() && line.node_column() == column)
+            .or_else(|| above.next())
+            .map(|(idx, _)| idx)
+    }
+
+    /// Whether revisions are elided below the commit on line `line_idx`.
+    pub fn has_elided_below(&self, line_idx: usize) -> bool {
+        (line_idx + 1..self.lines.len()).any(|idx| self.elided_owner(idx) == Some(line_idx))
+    }
+
     /// Check if the log is empty.
     pub fn is_empty(&self) -> bool {
         self.commit_line_indices.is_empty()
     }
 
+    /// Share, in percent, of the lines produced by the log template (those
+    /// holding a field separator) that could not be parsed as commits.
+    ///
+    /// Output without a single commit or template line is entirely unparsed.
+    pub fn unparsed_percent(&self) -> usize {
+        let template_lines = self
+            .lines
+            .iter()
+            .filter(|line| line.plain.contains(FIELD_SEPARATOR))
+            .count();
+        if template_lines == 0 {
+            return if self.lines.is_empty() || !self.is_empty() {
+                0
+            } else {
+                100
+            };
+        }
+        let unparsed = self
+            .lines
+            .iter()
+            .filter(|line| line.plain.contains(FIELD_SEPARATOR) && !line.is_commit_line())
+            .count();
+        unparsed * 100 / template_lines
+    }
+
+    /// Append one line of raw jj output (see [`stream_graph_log`]).
+    pub fn push_line(&mut self, raw: String) {
+        let idx = self.lines.len();
+        let line = GraphLine::new(raw, idx);
+        if line.is_commit_line() {
+            self.commit_line_indices.push(idx);
+        }
+        self.lines.push(line);
+    }
+
+    /// Replace the commit lines of the changes in `output` (from
+    /// [`fetch_commit_lines`]), keeping the graph drawn before each.
+    ///
+    /// Changes that are not loaded are skipped. Returns `false`, leaving the
+    /// log unchanged, if a line cannot be parsed or a change is loaded more
+    /// than once (divergent).
+    pub fn patch_commits(&mut self, output: &str) -> bool {
+        let mut patches = Vec::new();
+        for raw in output.lines() {
+            let Some(change_id) = extract_commit_fields(&strip_ansi(raw)).and_then(|f| f.change_id)
+            else {
+                return false;
+            };
+            let mut matching = self
+                .lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.change_id.as_deref() == Some(change_id.as_str()));
+            let Some((idx, line)) = matching.next() else {
+                continue;
+            };
+            if matching.next().is_some() {
+                return false;
+            }
+            let Some(graph_width) = line.id_prefix_char_count().map(|n| n - change_id.len()) else {
+                return false;
+            };
+            let graph = &line.raw[..visible_prefix_len(&line.raw, graph_width)];
+            patches.push((idx, format!("{graph}\x1b[0m{raw}")));
+        }
+        for (idx, raw) in patches {
+            self.lines[idx] = GraphLine::new(raw, idx);
+        }
+        true
+    }
+
     /// Extend this graph log with another one.
     ///
     /// This is used for incremental loading of more entries.
@@ -148,8 +473,31 @@ impl GraphLog {
     }
 }
 
+/// Byte length of the first `chars` visible characters of `raw`, skipping
+/// ANSI escape sequences (those right after them are not included).
+fn visible_prefix_len(raw: &str, chars: usize) -> usize {
+    let mut visible = 0;
+    let mut in_escape = false;
+    for (idx, ch) in raw.char_indices() {
+        if in_escape {
+            in_escape = ch != 'm';
+        } else if ch == '\x1b' {
+            if visible == chars {
+                return idx;
+            }
+            in_escape = true;
+        } else {
+            if visible == chars {
+                return idx;
+            }
+            visible += 1;
+        }
+    }
+    raw.len()
+}
+
 /// Strip ANSI escape sequences from a string.
-fn strip_ansi(s: &str) -> String {
+pub(crate) fn strip_ansi(s: &str) -> String {
     ANSI_STRIP_REGEX.replace_all(s, "").to_string()
 }
 
@@ -163,32 +511,80 @@ fn extract_change_id(plain: &str) -> Option<String> {
         .map(|cap| cap[1].to_string())
 }
 
-/// Extract change_id and description from a plain text commit line.
+/// Extract the commit fields from a plain text line.
 ///
-/// Returns (change_id, description) where description is Some for commit lines.
-/// Note: bookmarks (group 4) are handled by the template itself - they appear in the raw output.
-fn extract_commit_fields(plain: &str) -> (Option<String>, Option<String>) {
-    match COMMIT_LINE_REGEX.captures(plain) {
-        Some(cap) => {
-            let change_id = cap[1].to_string();
-            // Group 5 is the description (after optional [bookmarks])
-            let description = cap.get(5).map(|m| m.as_str().to_string());
-            (Some(change_id), description)
-        }
-        None => (None, None),
+/// Lines produced by [`GRAPH_LOG_TEMPLATE`] are split on [`FIELD_SEPARATOR`];
+/// space-separated lines are parsed with [`COMMIT_LINE_REGEX`] instead.
+/// Returns `None` for non-commit lines.
+fn extract_commit_fields(plain: &str) -> Option<CommitFields> {
+    if plain.contains(FIELD_SEPARATOR) {
+        let mut parts = plain.splitn(6, FIELD_SEPARATOR);
+        let cap = SEPARATED_ID_REGEX.captures(parts.next()?.trim_end())?;
+        let author = parts.next()?;
+        let timestamp = parts.next()?;
+        // Custom templates may leave out the tags
+        let refs = parts.next()?;
+        let (bookmarks, tags) = refs.split_once(TAG_SEPARATOR).unwrap_or((refs, ""));
+        // ... and the commit ID
+        let flags = parts.next()?;
+        let (flags, commit_id) = match flags.split_once(COMMIT_ID_SEPARATOR) {
+            Some((flags, commit_id)) => (flags, Some(commit_id.to_string())),
+            None => (flags, None),
+        };
+        let description = parts.next()?;
+        // A divergent change ID names several commits, and a hidden commit's
+        // may name its visible successor, so the commit ID is used
+        let id = cap
+            .get(2)
+            .unwrap_or_else(|| cap.get(1).expect("group 1 always matches"));
+        return Some(CommitFields {
+            change_id: Some(id.as_str().to_string()),
+            author: Some(author.to_string()),
+            timestamp: Some(timestamp.to_string()),
+            commit_id,
+            bookmarks: super::parse_bookmarks_field(bookmarks),
+            tags: super::parse_bookmarks_field(tags),
+            flags: CommitFlags::parse(flags),
+            description: Some(description.to_string()),
+        });
     }
+
+    let cap = COMMIT_LINE_REGEX.captures(plain)?;
+    Some(CommitFields {
+        change_id: Some(cap[1].to_string()),
+        author: Some(cap[2].to_string()),
+        timestamp: Some(cap[3].to_string()),
+        commit_id: None,
+        flags: CommitFlags::default(),
+        tags: Vec::new(),
+        bookmarks: cap
+            .get(4)
+            .map(|m| super::parse_bookmarks_field(m.as_str()))
+            .unwrap_or_default(),
+        // Group 5 is the description (after optional [bookmarks])
+        description: cap.get(5).map(|m| m.as_str().to_string()),
+    })
 }
 
+/// Default revset for the log view (all history).
+pub const DEFAULT_REVSET: &str = "::";
+
 /// Fetch graph log from jj with colored output.
-pub fn fetch_graph_log(runner: &JjRunner, limit: Option<usize>) -> Result<GraphLog, XorcistError> {
+///
+/// `revset` filters the log (None = [`DEFAULT_REVSET`]).
+pub fn fetch_graph_log(
+    runner: &JjRunner,
+    revset: Option<&str>,
+    limit: Option<usize>,
+) -> Result<GraphLog, XorcistError> {
     let mut args = vec![
         "log",
         "--color",
         "always",
         "-T",
-        GRAPH_LOG_TEMPLATE,
+        runner.log_template(),
         "-r",
-        "::",
+        revset.unwrap_or(DEFAULT_REVSET),
     ];
 
     let limit_str;
@@ -199,16 +595,57 @@ pub fn fetch_graph_log(runner: &JjRunner, limit: Option<usize>) -> Result<GraphL
     }
 
     let output = runner.run_capture(&args)?;
-    Ok(GraphLog::from_output(&output))
+    Ok(runner.time_parse("graph log", || GraphLog::from_output(&output)))
+}
+
+/// Fetch the log lines of `revset` without the graph, one per commit, for
+/// [`GraphLog::patch_commits`].
+pub fn fetch_commit_lines(runner: &JjRunner, revset: &str) -> Result<String, XorcistError> {
+    let template = format!("({}) ++ \"\\n\"", runner.log_template());
+    runner.run_capture(&[
+        "log",
+        "--color",
+        "always",
+        "--no-graph",
+        "-T",
+        &template,
+        "-r",
+        revset,
+    ])
+}
+
+/// Start fetching the whole graph log, with its lines read while jj runs.
+///
+/// Used for `--all`, where waiting for the full output of a large
+/// repository would delay the first screen.
+pub fn stream_graph_log(
+    runner: &JjRunner,
+    revset: Option<&str>,
+) -> Result<LineStream, XorcistError> {
+    runner.stream_lines(&[
+        "log",
+        "--color",
+        "always",
+        "-T",
+        runner.log_template(),
+        "-r",
+        revset.unwrap_or(DEFAULT_REVSET),
+    ])
 }
 
 /// Fetch additional graph log entries after a given change_id.
+///
+/// Only ancestors of the anchor that also match `revset` (if any) are returned.
 pub fn fetch_graph_log_after(
     runner: &JjRunner,
+    revset: Option<&str>,
     after_change_id: &str,
     limit: usize,
 ) -> Result<GraphLog, XorcistError> {
-    let revset = format!("::{after_change_id}-");
+    let revset = match revset {
+        Some(filter) => format!("({filter}) & ::{after_change_id}-"),
+        None => format!("::{after_change_id}-"),
+    };
     let limit_str = limit.to_string();
 
     let args = vec![
@@ -216,7 +653,7 @@ pub fn fetch_graph_log_after(
         "--color",
         "always",
         "-T",
-        GRAPH_LOG_TEMPLATE,
+        runner.log_template(),
         "-r",
         &revset,
         "-n",
@@ -224,13 +661,116 @@ pub fn fetch_graph_log_after(
     ];
 
     let output = runner.run_capture(&args)?;
-    Ok(GraphLog::from_output(&output))
+    Ok(runner.time_parse("graph log", || GraphLog::from_output(&output)))
+}
+
+/// Fetch the history of one file: every commit in `::` that touched `path`.
+///
+/// `path` is relative to the repository root, as shown in the diff view.
+pub fn fetch_file_history(
+    runner: &JjRunner,
+    path: &str,
+    limit: Option<usize>,
+) -> Result<GraphLog, XorcistError> {
+    let fileset = file_fileset(path);
+    let mut args = vec![
+        "log",
+        "--color",
+        "always",
+        "-T",
+        runner.log_template(),
+        "-r",
+        DEFAULT_REVSET,
+    ];
+
+    let limit_str;
+    if let Some(n) = limit {
+        limit_str = n.to_string();
+        args.push("-n");
+        args.push(&limit_str);
+    }
+    args.push("--");
+    args.push(&fileset);
+
+    let output = runner.run_capture(&args)?;
+    Ok(runner.time_parse("file history", || GraphLog::from_output(&output)))
+}
+
+/// Fileset matching exactly one file, quoted so that spaces and fileset
+/// operators in the name are taken literally.
+fn file_fileset(path: &str) -> String {
+    let escaped = path.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("root-file:\"{escaped}\"")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_push_line_matches_from_output() {
+        let output =
+            "@  qzmtztvn\x1fA\x1f1m\x1f\x1fw\x1fwip\n│\n○  rvzpxnov\x1fB\x1f2d\x1fmain\x1f\x1fbase";
+        let mut streamed = GraphLog::default();
+        for line in output.lines() {
+            streamed.push_line(line.to_string());
+        }
+        let parsed = GraphLog::from_output(output);
+        assert_eq!(streamed.commit_line_indices, parsed.commit_line_indices);
+        assert_eq!(streamed.change_id_for_selection(1), Some("rvzpxnov"));
+        assert_eq!(streamed.lines[2].line_index, 2);
+    }
+
+    #[test]
+    fn test_patch_commits_keeps_graph() {
+        let output = "\x1b[1m@\x1b[0m  qzmtztvn\x1fJane\x1f5m\x1f\x1fw\x1fold\n\
+                      │ ○  kxqpwsml\x1fJohn\x1f1d\x1f\x1f\x1fside\n\
+                      ├─╯\n\
+                      ◆  zsuskuln\x1fJohn\x1f3w\x1fmain\x1fi\x1frelease\n";
+        let mut log = GraphLog::from_output(output);
+        let patch = "\x1b[1mkxqpwsml\x1b[0m\x1fJohn\x1f1d\x1fside\x1f\x1fnew subject\n\
+                     qzmtztvn\x1fJane\x1f5m\x1f\x1fw\x1fnew message\n\
+                     unloaded\x1fJane\x1f1h\x1f\x1f\x1fskipped\n";
+        assert!(log.patch_commits(patch));
+
+        assert_eq!(log.lines[0].description.as_deref(), Some("new message"));
+        assert!(log.lines[0].plain.starts_with("@  qzmtztvn"));
+        assert!(log.lines[0].raw.starts_with("\x1b[1m@\x1b[0m  \x1b[0m"));
+        assert!(log.lines[0].flags.working_copy);
+        assert_eq!(log.lines[1].bookmarks, ["side"]);
+        assert!(log.lines[1].plain.starts_with("│ ○  kxqpwsml"));
+        assert_eq!(log.lines[3].description.as_deref(), Some("release"));
+        assert_eq!(log.commit_line_indices, [0, 1, 3]);
+
+        // Unparsable output leaves the log as it was
+        assert!(!log.patch_commits("not a commit line\n"));
+        assert_eq!(log.lines[0].description.as_deref(), Some("new message"));
+    }
+
+    #[test]
+    fn test_check_log_template() {
+        assert!(check_log_template(GRAPH_LOG_TEMPLATE).is_ok());
+        assert!(check_log_template(GRAPH_LOG_TEMPLATE_ABSOLUTE).is_ok());
+        assert!(check_log_template(MINIMAL_LOG_TEMPLATE).is_ok());
+        let email = GRAPH_LOG_TEMPLATE.replace("author.name()", "author.email()");
+        assert!(check_log_template(&email).is_ok());
+
+        let err =
+            check_log_template(r#"change_id.shortest(8) ++ "\x1f" ++ author.name()"#).unwrap_err();
+        assert!(err.contains("found 2"), "{err}");
+        let swapped = GRAPH_LOG_TEMPLATE.replacen("change_id.shortest(8)", "commit_id", 1);
+        assert!(check_log_template(&swapped).is_err());
+    }
+
+    #[test]
+    fn test_file_fileset_quotes_path() {
+        assert_eq!(file_fileset("src/main.rs"), r#"root-file:"src/main.rs""#);
+        assert_eq!(
+            file_fileset(r#"docs/a "b" (c).md"#),
+            r#"root-file:"docs/a \"b\" (c).md""#
+        );
+    }
+
     #[test]
     fn test_strip_ansi() {
         let input = "\x1b[1m\x1b[38;5;5mq\x1b[0m\x1b[38;5;8mzmtztvn\x1b[39m test";
@@ -315,19 +855,102 @@ mod tests {
     #[test]
     fn test_extract_commit_fields() {
         // Normal commit with description
-        let (cid, desc) = extract_commit_fields("@  qzmtztvn Author 1h feat: add feature");
-        assert_eq!(cid, Some("qzmtztvn".to_string()));
-        assert_eq!(desc, Some("feat: add feature".to_string()));
+        let fields = extract_commit_fields("@  qzmtztvn Author 1h feat: add feature").unwrap();
+        assert_eq!(fields.change_id, Some("qzmtztvn".to_string()));
+        assert_eq!(fields.author, Some("Author".to_string()));
+        assert_eq!(fields.timestamp, Some("1h".to_string()));
+        assert_eq!(fields.description, Some("feat: add feature".to_string()));
 
         // Commit with empty description
-        let (cid, desc) = extract_commit_fields("@  qzmtztvn Author 1h ");
-        assert_eq!(cid, Some("qzmtztvn".to_string()));
-        assert_eq!(desc, Some("".to_string()));
+        let fields = extract_commit_fields("@  qzmtztvn Author 1h ").unwrap();
+        assert_eq!(fields.change_id, Some("qzmtztvn".to_string()));
+        assert_eq!(fields.description, Some("".to_string()));
+
+        // Commit with bookmarks
+        let fields = extract_commit_fields("○  abcdefgh Author 1d [main,dev] init").unwrap();
+        assert_eq!(fields.bookmarks, vec!["main", "dev"]);
+        assert_eq!(fields.description, Some("init".to_string()));
 
         // Non-commit line (graph branch)
-        let (cid, desc) = extract_commit_fields("├─╮");
-        assert_eq!(cid, None);
-        assert_eq!(desc, None);
+        assert_eq!(extract_commit_fields("├─╮"), None);
+    }
+
+    #[test]
+    fn test_extract_commit_fields_separated() {
+        let fields =
+            extract_commit_fields("@  qzmtztvn\x1fJane Doe\x1f11m\x1fmain\x1fw\x1ffeat: a\x1fb")
+                .unwrap();
+        assert_eq!(fields.change_id, Some("qzmtztvn".to_string()));
+        assert_eq!(fields.author, Some("Jane Doe".to_string()));
+        assert_eq!(fields.timestamp, Some("11m".to_string()));
+        assert_eq!(fields.bookmarks, vec!["main"]);
+        assert!(fields.flags.working_copy);
+        assert!(!fields.flags.immutable);
+        assert_eq!(fields.description, Some("feat: a\x1fb".to_string()));
+        assert!(fields.tags.is_empty());
+        assert_eq!(fields.commit_id, None);
+
+        // Flags followed by the commit ID
+        let fields =
+            extract_commit_fields("@  qzmtztvn\x1fJane\x1f1h\x1f\x1fwe\x1d0f9e8d7c6b5a\x1fwip")
+                .unwrap();
+        assert_eq!(fields.flags, CommitFlags::parse("we"));
+        assert_eq!(fields.commit_id.as_deref(), Some("0f9e8d7c6b5a"));
+        assert_eq!(fields.description, Some("wip".to_string()));
+
+        // Bookmarks followed by tags
+        let fields = extract_commit_fields(
+            "◆  zsuskuln\x1fJane\x1f3w\x1fmain\x1dv0.1.2,stable\x1fi\x1frelease",
+        )
+        .unwrap();
+        assert_eq!(fields.bookmarks, vec!["main"]);
+        assert_eq!(fields.tags, vec!["v0.1.2", "stable"]);
+        let fields =
+            extract_commit_fields("◆  zsuskuln\x1fJane\x1f3w\x1f\x1dv0.1.2\x1fi\x1f").unwrap();
+        assert!(fields.bookmarks.is_empty());
+        assert_eq!(fields.tags, vec!["v0.1.2"]);
+
+        // Empty author, bookmarks and description
+        let fields = extract_commit_fields("│ ○  rvzpxnovq\x1f\x1f2d\x1f\x1f\x1f").unwrap();
+        assert_eq!(fields.change_id, Some("rvzpxnovq".to_string()));
+        assert_eq!(fields.author, Some("".to_string()));
+        assert!(fields.bookmarks.is_empty());
+        assert_eq!(fields.flags, CommitFlags::default());
+        assert_eq!(fields.description, Some("".to_string()));
+
+        // Divergent commits are identified by their commit ID
+        let fields =
+            extract_commit_fields("○  qzmtztvn?? 0f9e8d7c\x1fJane\x1f2d\x1f\x1fd\x1fwip").unwrap();
+        assert_eq!(fields.change_id, Some("0f9e8d7c".to_string()));
+        assert!(fields.flags.divergent);
+        let fields =
+            extract_commit_fields("○  qzmtztvn hidden 1a2b3c4d\x1fJane\x1f2d\x1f\x1fh\x1fwip")
+                .unwrap();
+        assert_eq!(fields.change_id, Some("1a2b3c4d".to_string()));
+        assert!(fields.flags.hidden);
+
+        // Too few fields
+        assert_eq!(extract_commit_fields("@  qzmtztvn\x1fJane"), None);
+    }
+
+    #[test]
+    fn test_commit_flags_parse() {
+        let flags = CommitFlags::parse("ice");
+        assert!(flags.immutable && flags.conflict && flags.empty);
+        assert!(!flags.working_copy && !flags.divergent);
+        assert!(CommitFlags::parse("d").divergent);
+        assert_eq!(flags.names(), ["immutable", "conflict", "empty"]);
+        assert_eq!(CommitFlags::parse(""), CommitFlags::default());
+    }
+
+    #[test]
+    fn test_id_prefix_char_count() {
+        let raw = "\x1b[1m@\x1b[0m  \x1b[1m\x1b[38;5;5mq\x1b[0mzmtztvn\x1fA\x1f1h\x1f\x1f\x1fx";
+        let line = GraphLine::new(raw.to_string(), 0);
+        assert_eq!(line.id_prefix_char_count(), Some(11));
+
+        let line = GraphLine::new("├─╮".to_string(), 1);
+        assert_eq!(line.id_prefix_char_count(), None);
     }
 
     #[test]
@@ -352,6 +975,51 @@ mod tests {
         assert_eq!(log.change_id_for_selection(4), None);
     }
 
+    #[test]
+    fn test_elided_owner() {
+        let graph_log = GraphLog::from_output(
+            "@  qzmtztvn\x1fJane\x1f5m\x1f\x1f\x1f\n\
+             │ ○  rvzpxnov\x1fJane\x1f2h\x1f\x1f\x1f\n\
+             ~ │  (elided revisions)\n\
+             │ ~  (elided revisions)\n\
+             ◆  zsuskuln\x1fJohn\x1f3w\x1f\x1f\x1f\n\
+             ~",
+        );
+        assert!(graph_log.lines[2].is_elided());
+        assert!(!graph_log.lines[5].is_elided());
+        // Each marker belongs to the commit drawn in its lane
+        assert_eq!(graph_log.elided_owner(2), Some(0));
+        assert_eq!(graph_log.elided_owner(3), Some(1));
+        assert_eq!(graph_log.elided_owner(4), None);
+        assert!(graph_log.has_elided_below(0));
+        assert!(graph_log.has_elided_below(1));
+        assert!(!graph_log.has_elided_below(4));
+    }
+
+    #[test]
+    fn test_unparsed_percent() {
+        let log = GraphLog::from_output(
+            "@  qzmtztvn\x1fJane\x1f5m\x1f\x1f\x1f\n\
+             ○  rvzpxnov\x1fJane\x1f2h\x1f\x1f\x1f\n\
+             ~",
+        );
+        assert_eq!(log.unparsed_percent(), 0);
+        assert_eq!(GraphLog::from_output("").unparsed_percent(), 0);
+
+        // ASCII node symbols are letters, which the change ID regexes skip over
+        let log = GraphLog::from_output(
+            "@  qzmtztvn\x1fJane\x1f5m\x1f\x1f\x1f\n\
+             o  rvzpxnov\x1fJane\x1f2h\x1f\x1f\x1f\n\
+             o  zsuskuln\x1fJohn\x1f3w\x1f\x1f\x1f",
+        );
+        assert_eq!(log.commit_count(), 1);
+        assert_eq!(log.unparsed_percent(), 66);
+        assert!(log.unparsed_percent() > MAX_UNPARSED_PERCENT);
+
+        // Nothing recognizable at all
+        assert_eq!(GraphLog::from_output("o  qz Jane").unparsed_percent(), 100);
+    }
+
     #[test]
     fn test_graph_log_empty() {
         let log = GraphLog::from_output("");
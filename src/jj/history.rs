@@ -0,0 +1,100 @@
+//! Session history of the jj commands xorcist executes.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::jj::journal::command_line;
+
+/// Number of commands kept in the history.
+pub const COMMAND_HISTORY_LEN: usize = 500;
+
+/// One jj command run this session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Arguments passed to `jj`.
+    pub args: Vec<String>,
+    /// How long the command took.
+    pub duration: Duration,
+    /// Whether the command exited successfully.
+    pub success: bool,
+    /// Outcome (e.g., `exit 0`, `killed`, `failed: …`).
+    pub status: String,
+    /// When the command finished.
+    pub finished: Instant,
+}
+
+impl HistoryEntry {
+    /// Command line as typed in a shell (e.g., `jj rebase -r abc -d main`).
+    pub fn command_line(&self) -> String {
+        let args: Vec<&str> = self.args.iter().map(String::as_str).collect();
+        command_line(&args)
+    }
+}
+
+/// Shared handle to the command history.
+///
+/// Clones share the same entries, so the runner in a background thread
+/// records into the history the app shows.
+#[derive(Debug, Clone, Default)]
+pub struct CommandHistory {
+    entries: Arc<Mutex<VecDeque<HistoryEntry>>>,
+}
+
+impl CommandHistory {
+    fn lock(&self) -> MutexGuard<'_, VecDeque<HistoryEntry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Record a finished command.
+    pub fn record(&self, args: &[&str], duration: Duration, success: bool, status: String) {
+        let mut entries = self.lock();
+        entries.push_front(HistoryEntry {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            duration,
+            success,
+            status,
+            finished: Instant::now(),
+        });
+        entries.truncate(COMMAND_HISTORY_LEN);
+    }
+
+    /// All recorded commands, newest first.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_is_shared_and_bounded() {
+        let history = CommandHistory::default();
+        let clone = history.clone();
+        for i in 0..COMMAND_HISTORY_LEN + 5 {
+            let rev = i.to_string();
+            clone.record(&["log", "-r", &rev], Duration::ZERO, true, "exit 0".into());
+        }
+        history.record(
+            &["describe", "-m", "it's"],
+            Duration::ZERO,
+            false,
+            "exit 1".into(),
+        );
+
+        assert_eq!(history.len(), COMMAND_HISTORY_LEN);
+        let entries = history.entries();
+        assert_eq!(entries[0].command_line(), "jj describe -m 'it'\\''s'");
+        assert!(!entries[0].success);
+        assert_eq!(entries[1].args, ["log", "-r", "504"]);
+    }
+}
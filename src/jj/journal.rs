@@ -0,0 +1,149 @@
+//! Audit journal of the jj commands xorcist executes.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File name of the journal inside the repository's `.jj` directory.
+pub const JOURNAL_FILE_NAME: &str = "xorcist-journal.log";
+
+/// Append-only log of mutating jj commands.
+///
+/// Each line holds the UTC timestamp, the working directory, the exit
+/// status and the command line, separated by tabs, e.g.
+/// `2026-01-31T09:15:02Z  /home/me/repo  exit 0  jj rebase -r qzmtztvn -d main`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Create a journal writing to `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Journal for the repository rooted at `repo_root`.
+    pub fn for_repo(repo_root: &Path) -> Self {
+        Self::new(repo_root.join(".jj").join(JOURNAL_FILE_NAME))
+    }
+
+    /// Append an entry for a command.
+    ///
+    /// `status` describes the outcome (e.g., `exit 0`). Write failures are
+    /// ignored: the journal must never get in the way of the command itself.
+    pub fn record(&self, work_dir: Option<&Path>, args: &[&str], status: &str) {
+        let line = format_entry(SystemTime::now(), work_dir, args, status);
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+}
+
+/// Format one journal line.
+fn format_entry(time: SystemTime, work_dir: Option<&Path>, args: &[&str], status: &str) -> String {
+    let work_dir = work_dir.map_or_else(|| ".".to_string(), |dir| dir.display().to_string());
+    let command = command_line(args);
+    format!("{}\t{work_dir}\t{status}\t{command}", format_utc(time))
+}
+
+/// Shell-quoted `jj …` command line for `args`.
+pub fn command_line(args: &[&str]) -> String {
+    std::iter::once("jj")
+        .chain(args.iter().copied())
+        .map(quote_arg)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quote an argument for display the way a POSIX shell would need it.
+fn quote_arg(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || "-_./:@=+,%^~".contains(ch));
+    if is_plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Format a time as an RFC 3339 UTC timestamp with second precision.
+fn format_utc(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+        assert_eq!(format_utc(time), "2024-02-29T12:34:56Z");
+    }
+
+    #[test]
+    fn test_format_entry_quotes_arguments() {
+        let line = format_entry(
+            UNIX_EPOCH,
+            Some(Path::new("/repo")),
+            &["describe", "-r", "abc", "-m", "it's done"],
+            "exit 0",
+        );
+        assert_eq!(
+            line,
+            "1970-01-01T00:00:00Z\t/repo\texit 0\tjj describe -r abc -m 'it'\\''s done'"
+        );
+    }
+
+    #[test]
+    fn test_record_appends_lines() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("journal.log");
+        let journal = Journal::new(path.clone());
+        journal.record(None, &["new"], "exit 0");
+        journal.record(None, &["undo"], "exit 1");
+
+        let text = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("\t.\texit 0\tjj new"));
+        assert!(lines[1].ends_with("\texit 1\tjj undo"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Batched auxiliary repository metadata.
+//!
+//! Bookmarks, tags, conflicts, the working-copy and trunk commits and
+//! bookmark tracking state would each be an
+//! extra jj call if fetched on demand. This module collects them with a fixed
+//! number of template queries and caches the result until the operation id
+//! changes.
+
+use std::collections::HashMap;
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Template for the current operation id.
+const OP_ID_TEMPLATE: &str = r#"id.short()"#;
+
+/// Revset of commits that carry any auxiliary metadata.
+const METADATA_REVSET: &str = "bookmarks() | tags() | conflicts() | @ | trunk()";
+
+/// Template for per-commit metadata.
+///
+/// Format: `change_id\x00bookmarks\x00tags\x00conflict\x00working_copy\x00trunk\n`
+///
+/// `trunk()` falls back to the root commit when no trunk is configured or
+/// found, so the root commit is never reported as trunk.
+const COMMIT_METADATA_TEMPLATE: &str = r#"change_id ++ "\x00" ++ local_bookmarks.map(|b| b.name()).join(",") ++ "\x00" ++ tags.map(|t| t.name()).join(",") ++ "\x00" ++ if(conflict, "1") ++ "\x00" ++ if(current_working_copy, "@") ++ "\x00" ++ if(self.contained_in("trunk()") && !root, "t") ++ "\n""#;
+
+/// Template for tracked remote bookmarks with ahead/behind counts.
+///
+/// Format: `name\x00remote\x00remote_ahead\x00remote_behind\n`
+/// Only tracked remote refs produce a line.
+const TRACKING_TEMPLATE: &str = r#"if(remote, if(tracked, name ++ "\x00" ++ remote ++ "\x00" ++ tracking_ahead_count().lower() ++ "\x00" ++ tracking_behind_count().lower() ++ "\n"))"#;
+
+/// Metadata attached to a single commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitMetadata {
+    /// Local bookmarks pointing at the commit.
+    pub bookmarks: Vec<String>,
+    /// Tags pointing at the commit.
+    pub tags: Vec<String>,
+    /// Whether the commit has conflicts.
+    pub conflict: bool,
+    /// Whether the commit is the working-copy commit (`@`).
+    pub working_copy: bool,
+    /// Whether the commit is `trunk()` (the main line, e.g. `main@origin`).
+    pub trunk: bool,
+}
+
+/// Tracking state of a local bookmark against one remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookmarkTracking {
+    /// Bookmark name.
+    pub name: String,
+    /// Remote name (e.g., "origin").
+    pub remote: String,
+    /// Commits on the local bookmark not yet on the remote (need push).
+    pub ahead: usize,
+    /// Commits on the remote not yet on the local bookmark.
+    pub behind: usize,
+}
+
+/// Auxiliary metadata for the whole repository at one operation.
+#[derive(Debug, Clone, Default)]
+pub struct RepoMetadata {
+    /// Operation id the metadata was fetched at.
+    pub op_id: String,
+    /// Per-commit metadata keyed by full change id.
+    pub commits: HashMap<String, CommitMetadata>,
+    /// Tracking state of bookmarks with tracked remotes.
+    pub tracking: Vec<BookmarkTracking>,
+}
+
+impl RepoMetadata {
+    /// Look up metadata for a change id (full id or unique prefix).
+    pub fn for_change(&self, change_id: &str) -> Option<&CommitMetadata> {
+        if let Some(meta) = self.commits.get(change_id) {
+            return Some(meta);
+        }
+        self.commits
+            .iter()
+            .find(|(id, _)| id.starts_with(change_id))
+            .map(|(_, meta)| meta)
+    }
+
+    /// Full change id of the working-copy commit.
+    pub fn working_copy_change_id(&self) -> Option<&str> {
+        self.commits
+            .iter()
+            .find(|(_, meta)| meta.working_copy)
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// Full change id of the `trunk()` commit, if the repository has one.
+    pub fn trunk_change_id(&self) -> Option<&str> {
+        self.commits
+            .iter()
+            .find(|(_, meta)| meta.trunk)
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// Tracking state for a local bookmark, one entry per tracked remote.
+    pub fn tracking_for(&self, bookmark: &str) -> impl Iterator<Item = &BookmarkTracking> {
+        self.tracking.iter().filter(move |t| t.name == bookmark)
+    }
+}
+
+/// Cache of [`RepoMetadata`] invalidated by operation id.
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    current: Option<RepoMetadata>,
+}
+
+impl MetadataCache {
+    /// Cache holding `metadata` (for tests, which cannot run jj).
+    #[cfg(test)]
+    pub fn with_metadata(metadata: RepoMetadata) -> Self {
+        Self {
+            current: Some(metadata),
+        }
+    }
+
+    /// Get the cached metadata, if any has been fetched.
+    pub fn get(&self) -> Option<&RepoMetadata> {
+        self.current.as_ref()
+    }
+
+    /// Refresh the cache.
+    ///
+    /// Only the (cheap) operation id is queried when nothing changed since
+    /// the last refresh.
+    pub fn refresh(&mut self, runner: &JjRunner) -> Result<&RepoMetadata, XorcistError> {
+        let op_id = fetch_op_id(runner)?;
+        let is_stale = self.current.as_ref().is_none_or(|m| m.op_id != op_id);
+        if is_stale {
+            self.current = Some(fetch_metadata(runner, op_id)?);
+        }
+        Ok(self.current.get_or_insert_default())
+    }
+}
+
+/// Fetch the id of the current operation.
+///
+/// Uses `--ignore-working-copy` so that polling does not itself create a
+/// snapshot operation.
+pub fn fetch_op_id(runner: &JjRunner) -> Result<String, XorcistError> {
+    let output = runner.run_capture(&[
+        "op",
+        "log",
+        "-n",
+        "1",
+        "--no-graph",
+        "--ignore-working-copy",
+        "-T",
+        OP_ID_TEMPLATE,
+    ])?;
+    Ok(output.trim().to_string())
+}
+
+/// Fetch all auxiliary metadata at the given operation.
+fn fetch_metadata(runner: &JjRunner, op_id: String) -> Result<RepoMetadata, XorcistError> {
+    let commit_output = runner.run_capture(&[
+        "log",
+        "--no-graph",
+        "--ignore-working-copy",
+        "-r",
+        METADATA_REVSET,
+        "-T",
+        COMMIT_METADATA_TEMPLATE,
+    ])?;
+    let tracking_output = runner.run_capture(&[
+        "bookmark",
+        "list",
+        "--all-remotes",
+        "--ignore-working-copy",
+        "-T",
+        TRACKING_TEMPLATE,
+    ])?;
+
+    Ok(RepoMetadata {
+        op_id,
+        commits: parse_commit_metadata(&commit_output),
+        tracking: parse_tracking(&tracking_output),
+    })
+}
+
+/// Parse the output of [`COMMIT_METADATA_TEMPLATE`].
+fn parse_commit_metadata(output: &str) -> HashMap<String, CommitMetadata> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\x00').collect();
+            if parts.len() < 4 || parts[0].is_empty() {
+                return None;
+            }
+            let meta = CommitMetadata {
+                bookmarks: super::parse_bookmarks_field(parts[1]),
+                tags: super::parse_bookmarks_field(parts[2]),
+                conflict: parts[3] == "1",
+                working_copy: parts.get(4) == Some(&"@"),
+                trunk: parts.get(5) == Some(&"t"),
+            };
+            Some((parts[0].to_string(), meta))
+        })
+        .collect()
+}
+
+/// Parse the output of [`TRACKING_TEMPLATE`].
+///
+/// jj reports counts from the remote ref's point of view, so they are
+/// swapped here to describe the local bookmark.
+fn parse_tracking(output: &str) -> Vec<BookmarkTracking> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\x00').collect();
+            if parts.len() < 4 {
+                return None;
+            }
+            // The "git" pseudo-remote mirrors the colocated git repo
+            if parts[1] == "git" {
+                return None;
+            }
+            Some(BookmarkTracking {
+                name: parts[0].to_string(),
+                remote: parts[1].to_string(),
+                ahead: parts[3].parse().unwrap_or(0),
+                behind: parts[2].parse().unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commit_metadata() {
+        let output = "qzmtztvnabcd\x00main,dev\x00v1.0\x00\x00@\x00\n\
+                      rvzpxnovabcd\x00\x00\x001\x00\x00t\n";
+        let commits = parse_commit_metadata(output);
+
+        assert_eq!(commits.len(), 2);
+        let meta = RepoMetadata {
+            commits: commits.clone(),
+            ..Default::default()
+        };
+        assert_eq!(meta.working_copy_change_id(), Some("qzmtztvnabcd"));
+        assert_eq!(meta.trunk_change_id(), Some("rvzpxnovabcd"));
+        let first = &commits["qzmtztvnabcd"];
+        assert_eq!(first.bookmarks, vec!["main", "dev"]);
+        assert_eq!(first.tags, vec!["v1.0"]);
+        assert!(!first.conflict);
+
+        let second = &commits["rvzpxnovabcd"];
+        assert!(second.bookmarks.is_empty());
+        assert!(second.tags.is_empty());
+        assert!(second.conflict);
+        assert!(!second.working_copy);
+    }
+
+    #[test]
+    fn test_parse_commit_metadata_skips_malformed() {
+        let commits = parse_commit_metadata("garbage\n\x00a\x00b\x00\n");
+        assert!(commits.is_empty());
+    }
+
+    #[test]
+    fn test_parse_tracking() {
+        let output = "main\x00origin\x001\x003\nmain\x00git\x000\x000\n";
+        let tracking = parse_tracking(output);
+
+        assert_eq!(
+            tracking,
+            vec![BookmarkTracking {
+                name: "main".to_string(),
+                remote: "origin".to_string(),
+                ahead: 3,
+                behind: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_for_change_prefix_lookup() {
+        let mut meta = RepoMetadata::default();
+        meta.commits.insert(
+            "qzmtztvnabcd".to_string(),
+            CommitMetadata {
+                conflict: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(meta.for_change("qzmtztvnabcd").unwrap().conflict);
+        assert!(meta.for_change("qzmtztvn").unwrap().conflict);
+        assert!(meta.for_change("rvzpxnov").is_none());
+    }
+}
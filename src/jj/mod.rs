@@ -1,15 +1,57 @@
 //! jj VCS integration module.
 
+pub mod cache;
+pub mod diff;
+pub mod divergence;
+pub mod entries;
 pub mod graph_log;
+pub mod history;
+pub mod journal;
+pub mod metadata;
+pub mod oplog;
+pub mod patch;
+pub mod remote;
 pub mod repo;
+pub mod revset;
 pub mod runner;
 pub mod show;
+pub mod sparse;
+pub mod stack;
+pub mod stat;
+pub mod tag;
+pub mod version;
+pub mod workspace;
 
-pub use graph_log::{GraphLog, fetch_graph_log, fetch_graph_log_after};
-pub use repo::find_jj_repo;
-pub use runner::JjRunner;
-pub(crate) use show::parse_diff_summary;
-pub use show::{DiffEntry, DiffStatus, ShowOutput, fetch_diff_file, fetch_show};
+pub use cache::{CacheKind, CachedOutput, OutputCache};
+pub use diff::{DiffLine, DiffLineKind, GitDiff};
+pub use divergence::fetch_divergent_commits;
+pub use entries::{LogEntry, fetch_log_entries};
+pub(crate) use graph_log::strip_ansi;
+pub use graph_log::{
+    ABSOLUTE_TIMESTAMP_WIDTH, CommitFlags, GraphLine, GraphLog, MAX_UNPARSED_PERCENT,
+    MINIMAL_LOG_TEMPLATE, TimestampFormat, check_log_template, fetch_commit_lines,
+    fetch_file_history, fetch_graph_log, fetch_graph_log_after, stream_graph_log,
+};
+pub use history::HistoryEntry;
+pub use journal::Journal;
+pub use metadata::{BookmarkTracking, MetadataCache, RepoMetadata};
+pub use oplog::{DEFAULT_OP_LOG_LIMIT, OpLogEntry, fetch_op_log};
+pub use patch::{Patch, fetch_patch};
+pub use remote::fetch_git_remotes;
+pub use repo::{JjRepo, find_jj_repo, read_op_heads};
+pub use revset::{fetch_default_log_revset, resolve_revisions, resolve_single_revision};
+pub use runner::{JjRunner, LineStream};
+pub use show::{
+    DiffEntry, DiffRange, DiffStatus, RelatedCommit, ShowOutput, fetch_author, fetch_children,
+    fetch_description, fetch_diff, fetch_diff_file, fetch_diff_file_range,
+    fetch_diff_summary_range, fetch_show,
+};
+pub use sparse::fetch_sparse_patterns;
+pub use stack::{Stack, fetch_stacks};
+pub use stat::{DiffStat, FileStat, fetch_diff_stat, fetch_diff_stat_range};
+pub use tag::{TagEntry, fetch_tags};
+pub use version::Capability;
+pub use workspace::{WorkspaceEntry, fetch_workspaces};
 
 pub(crate) fn parse_bookmarks_field(field: &str) -> Vec<String> {
     if field.is_empty() {
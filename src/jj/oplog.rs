@@ -0,0 +1,98 @@
+//! jj operation log.
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Number of operations loaded into the operation log view.
+pub const DEFAULT_OP_LOG_LIMIT: usize = 200;
+
+/// Template for [`fetch_op_log`]: `\x1f`-separated fields, one operation per `\x1e`.
+const OP_LOG_TEMPLATE: &str = r#"self.id().short() ++ "\x1f" ++ if(self.current_operation(), "@") ++ "\x1f" ++ self.user() ++ "\x1f" ++ self.time().start().ago() ++ "\x1f" ++ self.description().first_line() ++ "\x1f" ++ self.tags() ++ "\x1e""#;
+
+/// Number of fields produced by [`OP_LOG_TEMPLATE`].
+const OP_LOG_FIELD_COUNT: usize = 6;
+
+/// One operation in `jj op log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpLogEntry {
+    /// Short operation ID.
+    pub id: String,
+    /// Whether this is the repository's current operation.
+    pub is_current: bool,
+    /// `user@host` that ran the operation.
+    pub user: String,
+    /// Relative start time (e.g., "5 minutes ago").
+    pub time: String,
+    /// What the operation did (e.g., "rebase commit abc123").
+    pub description: String,
+    /// Operation tags, typically the command line (`args: jj rebase ...`).
+    pub tags: String,
+}
+
+/// Fetch the most recent `limit` operations, newest first.
+pub fn fetch_op_log(runner: &JjRunner, limit: usize) -> Result<Vec<OpLogEntry>, XorcistError> {
+    let limit = limit.to_string();
+    let output = runner.run_capture(&[
+        "op",
+        "log",
+        "--no-graph",
+        "-T",
+        OP_LOG_TEMPLATE,
+        "-n",
+        &limit,
+    ])?;
+    runner.time_parse("op log", || parse_op_log(&output))
+}
+
+/// Parse the output of [`OP_LOG_TEMPLATE`].
+fn parse_op_log(output: &str) -> Result<Vec<OpLogEntry>, XorcistError> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let fields: Vec<&str> = record
+                .trim_start_matches('\n')
+                .splitn(OP_LOG_FIELD_COUNT, '\x1f')
+                .collect();
+            let [id, current, user, time, description, tags] = fields[..] else {
+                return Err(XorcistError::JjError(format!(
+                    "unexpected op log format: expected {OP_LOG_FIELD_COUNT} fields, got {}",
+                    fields.len()
+                )));
+            };
+            Ok(OpLogEntry {
+                id: id.to_string(),
+                is_current: current == "@",
+                user: user.to_string(),
+                time: time.to_string(),
+                description: description.to_string(),
+                tags: tags.trim_end().replace('\n', " "),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_op_log() {
+        let output = "a1b2c3d4e5f6\x1f@\x1falice@host\x1f2 minutes ago\x1fdescribe commit 0123\x1fargs: jj describe -m 'x'\x1e\
+                      0f9e8d7c6b5a\x1f\x1falice@host\x1f1 hour ago\x1fsnapshot working copy\x1f\x1e";
+        let entries = parse_op_log(output).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "a1b2c3d4e5f6");
+        assert!(entries[0].is_current);
+        assert_eq!(entries[0].tags, "args: jj describe -m 'x'");
+        assert!(!entries[1].is_current);
+        assert_eq!(entries[1].description, "snapshot working copy");
+        assert_eq!(entries[1].tags, "");
+    }
+
+    #[test]
+    fn test_parse_op_log_rejects_short_records() {
+        assert!(parse_op_log("a1b2\x1f@\x1e").is_err());
+        assert_eq!(parse_op_log("").unwrap(), Vec::new());
+    }
+}
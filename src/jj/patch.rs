@@ -0,0 +1,78 @@
+//! Commits as git-style patches (`jj diff --git` plus mail headers).
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Template for the mail header of [`fetch_patch`]: `\x1f`-separated fields.
+const PATCH_HEADER_TEMPLATE: &str = r#"commit_id ++ "\x1f" ++ author.name() ++ "\x1f" ++ author.email() ++ "\x1f" ++ author.timestamp().format("%a, %d %b %Y %H:%M:%S %z") ++ "\x1f" ++ description"#;
+
+/// Number of fields produced by [`PATCH_HEADER_TEMPLATE`].
+const PATCH_HEADER_FIELD_COUNT: usize = 5;
+
+/// One revision's metadata and git diff, ready to be written as a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    /// Full commit ID.
+    pub commit_id: String,
+    /// Author name.
+    pub author_name: String,
+    /// Author email.
+    pub author_email: String,
+    /// Author timestamp in RFC 2822 form (e.g., `Tue, 14 Oct 2025 09:30:00 +0200`).
+    pub date: String,
+    /// Full description.
+    pub description: String,
+    /// Output of `jj diff -r <rev> --git`.
+    pub diff: String,
+}
+
+/// Fetch the patch of a revision.
+pub fn fetch_patch(runner: &JjRunner, revision: &str) -> Result<Patch, XorcistError> {
+    let header = runner.run_capture(&[
+        "log",
+        "-r",
+        revision,
+        "--no-graph",
+        "-T",
+        PATCH_HEADER_TEMPLATE,
+    ])?;
+    let diff = runner.run_capture(&["diff", "-r", revision, "--git", "--color=never"])?;
+    runner.time_parse("patch", || parse_patch(&header, diff))
+}
+
+/// Parse the output of [`PATCH_HEADER_TEMPLATE`] and attach the diff.
+fn parse_patch(header: &str, diff: String) -> Result<Patch, XorcistError> {
+    let fields: Vec<&str> = header.splitn(PATCH_HEADER_FIELD_COUNT, '\x1f').collect();
+    let [commit_id, author_name, author_email, date, description] = fields[..] else {
+        return Err(XorcistError::JjError(format!(
+            "unexpected patch header format: expected {PATCH_HEADER_FIELD_COUNT} fields, got {}",
+            fields.len()
+        )));
+    };
+    Ok(Patch {
+        commit_id: commit_id.to_string(),
+        author_name: author_name.to_string(),
+        author_email: author_email.to_string(),
+        date: date.to_string(),
+        description: description.trim_end_matches('\n').to_string(),
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_patch() {
+        let header = "0123abcd\x1fAlice\x1falice@example.com\x1fTue, 14 Oct 2025 09:30:00 +0200\x1ffix: typo\n\nLonger body\n";
+        let patch = parse_patch(header, "diff --git a/x b/x\n".to_string()).unwrap();
+        assert_eq!(patch.commit_id, "0123abcd");
+        assert_eq!(patch.author_email, "alice@example.com");
+        assert_eq!(patch.date, "Tue, 14 Oct 2025 09:30:00 +0200");
+        assert_eq!(patch.description, "fix: typo\n\nLonger body");
+        assert_eq!(patch.diff, "diff --git a/x b/x\n");
+
+        assert!(parse_patch("0123abcd\x1fAlice", String::new()).is_err());
+    }
+}
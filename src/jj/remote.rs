@@ -0,0 +1,49 @@
+//! Git remotes of the repository (`jj git remote list`).
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// One git remote: its name and URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitRemote {
+    pub name: String,
+    pub url: String,
+}
+
+/// Fetch the git remotes, in `jj git remote list` order.
+pub fn fetch_git_remotes(runner: &JjRunner) -> Result<Vec<GitRemote>, XorcistError> {
+    let output = runner.run_capture(&["git", "remote", "list"])?;
+    Ok(parse_git_remotes(&output))
+}
+
+/// Parse `jj git remote list` output: one `<name> <url>` per line.
+fn parse_git_remotes(output: &str) -> Vec<GitRemote> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, url) = line.trim().split_once(char::is_whitespace)?;
+            Some(GitRemote {
+                name: name.to_string(),
+                url: url.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_remotes() {
+        let output = "origin git@github.com:fpdy/xorcist.git\n\
+                      upstream https://gitlab.com/group/repo\n\
+                      \n";
+        let remotes = parse_git_remotes(output);
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(remotes[0].name, "origin");
+        assert_eq!(remotes[0].url, "git@github.com:fpdy/xorcist.git");
+        assert_eq!(remotes[1].name, "upstream");
+        assert_eq!(remotes[1].url, "https://gitlab.com/group/repo");
+    }
+}
@@ -8,10 +8,39 @@ pub struct JjRepo {
     /// Root directory of the repository (contains .jj).
     pub root: PathBuf,
     /// Whether this is a colocated repository (has both .jj and .git).
-    #[allow(dead_code)] // Will be used for future features
     pub colocated: bool,
 }
 
+impl JjRepo {
+    /// Directory holding one file per operation head.
+    ///
+    /// In secondary workspaces `.jj/repo` is a file containing the path of
+    /// the shared repository directory (relative to `.jj`).
+    pub fn op_heads_dir(&self) -> PathBuf {
+        let jj_dir = self.root.join(".jj");
+        let repo_link = jj_dir.join("repo");
+        let repo_dir = match std::fs::read_to_string(&repo_link) {
+            Ok(target) => jj_dir.join(target.trim()),
+            Err(_) => repo_link,
+        };
+        repo_dir.join("op_heads").join("heads")
+    }
+}
+
+/// Read the current operation heads (sorted ids) from `op_heads_dir`.
+///
+/// This is a directory listing rather than a jj invocation, so it is cheap
+/// enough to poll between events. Returns `None` if the directory cannot be read.
+pub fn read_op_heads(op_heads_dir: &Path) -> Option<Vec<String>> {
+    let mut heads: Vec<String> = std::fs::read_dir(op_heads_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    heads.sort_unstable();
+    Some(heads)
+}
+
 /// Find a jj repository by walking up from the given directory.
 ///
 /// Returns `None` if no `.jj` directory is found.
@@ -85,4 +114,40 @@ mod tests {
         let repo = result.unwrap();
         assert!(repo.colocated);
     }
+
+    #[test]
+    fn test_read_op_heads() {
+        let temp = TempDir::new().unwrap();
+        let repo = JjRepo {
+            root: temp.path().to_path_buf(),
+            colocated: false,
+        };
+        let heads_dir = repo.op_heads_dir();
+        assert_eq!(heads_dir, temp.path().join(".jj/repo/op_heads/heads"));
+        assert_eq!(read_op_heads(&heads_dir), None);
+
+        std::fs::create_dir_all(&heads_dir).unwrap();
+        std::fs::write(heads_dir.join("bbbb"), "").unwrap();
+        std::fs::write(heads_dir.join("aaaa"), "").unwrap();
+        assert_eq!(
+            read_op_heads(&heads_dir),
+            Some(vec!["aaaa".to_string(), "bbbb".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_op_heads_dir_in_secondary_workspace() {
+        let temp = TempDir::new().unwrap();
+        let jj_dir = temp.path().join("ws").join(".jj");
+        std::fs::create_dir_all(&jj_dir).unwrap();
+        std::fs::write(jj_dir.join("repo"), "../../main/.jj/repo").unwrap();
+        let repo = JjRepo {
+            root: temp.path().join("ws"),
+            colocated: false,
+        };
+        assert_eq!(
+            repo.op_heads_dir(),
+            jj_dir.join("../../main/.jj/repo/op_heads/heads")
+        );
+    }
 }
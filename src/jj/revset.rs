@@ -0,0 +1,87 @@
+//! Revset validation and resolution.
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Template printing one change id per line.
+const CHANGE_ID_TEMPLATE: &str = r#"change_id.short() ++ "\n""#;
+
+/// Fetch the revset `jj log` shows by default (`revsets.log`).
+pub fn fetch_default_log_revset(runner: &JjRunner) -> Result<String, XorcistError> {
+    let output = runner.run_capture(&["config", "get", "revsets.log"])?;
+    Ok(output.trim().to_string())
+}
+
+/// Resolve a revset that must name exactly one revision.
+///
+/// Returns the short change id of the revision. Invalid revsets report jj's
+/// own error; empty or multi-revision revsets are rejected with a message.
+pub fn resolve_single_revision(runner: &JjRunner, revset: &str) -> Result<String, XorcistError> {
+    let revset = revset.trim();
+    if revset.is_empty() {
+        return Err(XorcistError::InvalidRevision("empty revset".to_string()));
+    }
+    // Two results are enough to tell that the revset is ambiguous
+    let output = runner.run_capture(&[
+        "log",
+        "--no-graph",
+        "--ignore-working-copy",
+        "-r",
+        revset,
+        "-n",
+        "2",
+        "-T",
+        CHANGE_ID_TEMPLATE,
+    ])?;
+    single_revision(revset, &output)
+}
+
+/// Resolve a revset to the short change ids of its revisions, in log order.
+pub fn resolve_revisions(runner: &JjRunner, revset: &str) -> Result<Vec<String>, XorcistError> {
+    let output = runner.run_capture(&[
+        "log",
+        "--no-graph",
+        "--ignore-working-copy",
+        "-r",
+        revset,
+        "-T",
+        CHANGE_ID_TEMPLATE,
+    ])?;
+    Ok(output
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Check that `output` of [`CHANGE_ID_TEMPLATE`] lists exactly one revision.
+fn single_revision(revset: &str, output: &str) -> Result<String, XorcistError> {
+    let ids: Vec<&str> = output.lines().filter(|l| !l.is_empty()).collect();
+    match ids.as_slice() {
+        [id] => Ok(id.to_string()),
+        [] => Err(XorcistError::InvalidRevision(format!(
+            "\"{revset}\" resolved to no revisions"
+        ))),
+        _ => Err(XorcistError::InvalidRevision(format!(
+            "\"{revset}\" resolved to more than one revision"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_revision() {
+        assert_eq!(single_revision("@", "qzmtztvn\n").unwrap(), "qzmtztvn");
+
+        let err = single_revision("none()", "").unwrap_err().to_string();
+        assert!(err.contains("no revisions"), "{err}");
+
+        let err = single_revision("::@", "qzmtztvn\nrvzpxnov\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("more than one"), "{err}");
+    }
+}
@@ -1,22 +1,57 @@
 //! jj command execution wrapper.
 
+use std::io::{BufRead, BufReader};
 use std::path::Path;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Instant;
 
 use crate::app::CommandResult;
 use crate::error::XorcistError;
+use crate::jj::graph_log::{LOG_CONFIG_OVERRIDES, MINIMAL_LOG_TEMPLATE, TimestampFormat};
+use crate::jj::history::CommandHistory;
+use crate::jj::journal::Journal;
+use crate::jj::version::{Capability, JjVersion};
+use crate::profile::Profiler;
 
 /// Runner for executing jj commands.
 #[derive(Debug, Clone)]
 pub struct JjRunner {
     /// Working directory for jj commands.
     work_dir: Option<std::path::PathBuf>,
+    /// Journal recording mutating commands (None = not recorded).
+    journal: Option<Journal>,
+    /// Profiler timing commands and parsing (None = not profiled).
+    profiler: Option<Profiler>,
+    /// Every command run this session (shared by clones).
+    history: CommandHistory,
+    /// Log template from the config file (None = the built-in template).
+    log_template: Option<String>,
+    /// Timestamps of the built-in log template.
+    timestamp_format: TimestampFormat,
+    /// Installed jj release (None = unknown, assumed to have every feature).
+    version: Option<JjVersion>,
+    /// Whether [`LOG_CONFIG_OVERRIDES`] are passed to every command.
+    overrides_log_config: bool,
+    /// Whether reads pass `--ignore-working-copy` (while another command runs).
+    ignore_working_copy: bool,
 }
 
 impl JjRunner {
     /// Create a new JjRunner.
     pub fn new() -> Self {
-        Self { work_dir: None }
+        Self {
+            work_dir: None,
+            journal: None,
+            profiler: None,
+            history: CommandHistory::default(),
+            log_template: None,
+            timestamp_format: TimestampFormat::default(),
+            version: None,
+            overrides_log_config: false,
+            ignore_working_copy: false,
+        }
     }
 
     /// Set the working directory for commands.
@@ -25,9 +60,161 @@ impl JjRunner {
         self
     }
 
+    /// Working directory for commands, if set.
+    pub fn work_dir(&self) -> Option<&Path> {
+        self.work_dir.as_deref()
+    }
+
+    /// Record mutating commands in the given journal.
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Time commands and parsing with the given profiler.
+    pub fn with_profiler(mut self, profiler: Profiler) -> Self {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Use a custom template for the graph log (checked by `check_log_template`).
+    pub fn with_log_template(mut self, template: String) -> Self {
+        self.log_template = Some(template);
+        self
+    }
+
+    /// Switch to another template for the graph log (e.g., a fallback).
+    pub fn set_log_template(&mut self, template: String) {
+        self.log_template = Some(template);
+    }
+
+    /// Show timestamps in the given format (unless a custom template is used).
+    pub fn with_timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    /// Switch the timestamps of the graph log (unless a custom template is used).
+    pub fn set_timestamp_format(&mut self, format: TimestampFormat) {
+        self.timestamp_format = format;
+    }
+
+    /// Timestamps of the built-in log template.
+    pub fn timestamp_format(&self) -> TimestampFormat {
+        self.timestamp_format
+    }
+
+    /// Go back to xorcist's log template and override the jj settings it
+    /// relies on, after the user's configuration broke log parsing.
+    ///
+    /// [`MINIMAL_LOG_TEMPLATE`] is kept if it is in use.
+    pub fn force_builtin_log(&mut self) {
+        if self.log_template.as_deref() != Some(MINIMAL_LOG_TEMPLATE) {
+            self.log_template = None;
+        }
+        self.overrides_log_config = true;
+    }
+
+    /// Make reads not snapshot the working copy, so that they do not race a
+    /// mutating command running on another thread.
+    pub fn set_ignore_working_copy(&mut self, ignore: bool) {
+        self.ignore_working_copy = ignore;
+    }
+
+    /// Whether reads leave the working copy alone.
+    #[cfg(test)]
+    pub fn ignores_working_copy(&self) -> bool {
+        self.ignore_working_copy
+    }
+
+    /// Arguments passed before those of read commands (not of mutating ones).
+    fn read_args(&self) -> &'static [&'static str] {
+        if self.ignore_working_copy {
+            &["--ignore-working-copy"]
+        } else {
+            &[]
+        }
+    }
+
+    /// Arguments passed before those of every command: the forced settings,
+    /// as `--config` options or, for older jj, one `--config-toml`.
+    fn config_args(&self) -> Vec<String> {
+        if !self.overrides_log_config {
+            return Vec::new();
+        }
+        if self.supports(Capability::ConfigOption) {
+            LOG_CONFIG_OVERRIDES
+                .iter()
+                .flat_map(|(name, value)| ["--config".to_string(), format!("{name}={value}")])
+                .collect()
+        } else {
+            let toml: Vec<String> = LOG_CONFIG_OVERRIDES
+                .iter()
+                .map(|(name, value)| format!("{name} = {value}"))
+                .collect();
+            vec!["--config-toml".to_string(), toml.join("\n")]
+        }
+    }
+
+    /// Whether the graph log uses a template from the config file.
+    pub fn has_custom_log_template(&self) -> bool {
+        self.log_template.is_some()
+    }
+
+    /// Template used for the graph log.
+    ///
+    /// Shortened timestamps need `String.replace()`; older jj releases get
+    /// [`MINIMAL_LOG_TEMPLATE`] instead.
+    pub fn log_template(&self) -> &str {
+        if let Some(template) = &self.log_template {
+            return template;
+        }
+        match self.timestamp_format {
+            TimestampFormat::Relative if !self.supports(Capability::TemplateReplace) => {
+                MINIMAL_LOG_TEMPLATE
+            }
+            format => format.template(),
+        }
+    }
+
+    /// Assume the given jj release (see [`JjRunner::probe_version`]).
+    pub fn with_version(mut self, version: Option<JjVersion>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Installed jj release, if known.
+    pub fn version(&self) -> Option<JjVersion> {
+        self.version
+    }
+
+    /// Whether the installed jj has a feature (true if its version is unknown).
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.version
+            .is_none_or(|version| version >= capability.min_version())
+    }
+
+    /// Profiler in use, if profiling is enabled.
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Commands run this session.
+    pub fn history(&self) -> &CommandHistory {
+        &self.history
+    }
+
+    /// Run a parser, recording its duration when profiling.
+    pub fn time_parse<T>(&self, name: &'static str, parse: impl FnOnce() -> T) -> T {
+        match &self.profiler {
+            Some(profiler) => profiler.time_parse(name, parse),
+            None => parse(),
+        }
+    }
+
     /// Run a jj command and capture its output.
     pub fn run_capture(&self, args: &[&str]) -> Result<String, XorcistError> {
-        let output = self.execute(args)?;
+        let output = self.execute(self.read_args(), args)?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -38,30 +225,104 @@ impl JjRunner {
     }
 
     /// Execute a jj command and return the raw output.
-    fn execute(&self, args: &[&str]) -> Result<Output, XorcistError> {
+    ///
+    /// `flags` go before the command's arguments but are not recorded.
+    fn execute(&self, flags: &[&str], args: &[&str]) -> Result<Output, XorcistError> {
         let mut cmd = Command::new("jj");
-        cmd.args(args);
+        cmd.args(self.config_args()).args(flags).args(args);
 
         if let Some(dir) = &self.work_dir {
             cmd.current_dir(dir);
         }
 
-        cmd.output().map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                XorcistError::JjNotFound
-            } else {
-                XorcistError::Io(e)
+        let start = Instant::now();
+        let output = cmd.output().map_err(spawn_error);
+        self.record(args, start, &output);
+        output
+    }
+
+    /// Record a finished command in the profiler and the command history.
+    fn record(&self, args: &[&str], start: Instant, output: &Result<Output, XorcistError>) {
+        let elapsed = start.elapsed();
+        if let Some(profiler) = &self.profiler {
+            profiler.record_command(args, elapsed);
+        }
+        let success = output.as_ref().is_ok_and(|output| output.status.success());
+        self.history
+            .record(args, elapsed, success, exit_status(output));
+    }
+
+    /// Run a jj command, reading its standard output line by line on a
+    /// worker thread so that the lines can be used before the command ends.
+    ///
+    /// Dropping the returned stream stops the command.
+    pub fn stream_lines(&self, args: &[&str]) -> Result<LineStream, XorcistError> {
+        let mut cmd = Command::new("jj");
+        cmd.args(self.config_args())
+            .args(self.read_args())
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(dir) = &self.work_dir {
+            cmd.current_dir(dir);
+        }
+
+        let start = Instant::now();
+        let mut child = cmd.spawn().map_err(spawn_error)?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| XorcistError::Io(std::io::Error::other("jj stdout is not captured")))?;
+        let (sender, receiver) = mpsc::channel();
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let runner = self.clone();
+        thread::spawn(move || {
+            let mut read_error = None;
+            for line in BufReader::new(stdout).lines() {
+                let sent = match line {
+                    Ok(line) => sender.send(StreamEvent::Line(line)).is_ok(),
+                    Err(e) => {
+                        read_error = Some(if e.kind() == std::io::ErrorKind::InvalidData {
+                            XorcistError::InvalidUtf8
+                        } else {
+                            XorcistError::Io(e)
+                        });
+                        false
+                    }
+                };
+                if !sent {
+                    // Stopped by the reader (or unreadable output): end jj too
+                    let _ = child.kill();
+                    break;
+                }
             }
-        })
+            let output = child.wait_with_output().map_err(XorcistError::Io);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            runner.record(&args, start, &output);
+            let result = match (read_error, output) {
+                (Some(e), _) | (None, Err(e)) => Err(e),
+                (None, Ok(output)) if !output.status.success() => Err(XorcistError::JjError(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                )),
+                (None, Ok(_)) => Ok(()),
+            };
+            // The receiver is gone only if the stream was dropped
+            let _ = sender.send(StreamEvent::Done(result));
+        });
+        Ok(LineStream { receiver })
     }
 
-    /// Check if jj is available.
-    pub fn is_available(&self) -> bool {
-        Command::new("jj")
-            .arg("--version")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    /// Run `jj --version`, failing if jj is not available.
+    ///
+    /// Returns None if the version number cannot be read (e.g., a build
+    /// with an unusual version string).
+    pub fn probe_version(&self) -> Result<Option<JjVersion>, XorcistError> {
+        match Command::new("jj").arg("--version").output() {
+            Ok(output) if output.status.success() => {
+                Ok(JjVersion::parse(&String::from_utf8_lossy(&output.stdout)))
+            }
+            _ => Err(XorcistError::JjNotFound),
+        }
     }
 
     /// Execute `jj new` to create a new change.
@@ -78,6 +339,26 @@ impl JjRunner {
         self.run_command(&["new", parent, "-m", message])
     }
 
+    /// Execute `jj new --insert-after` / `--insert-before` (`flag`) to insert
+    /// a new change next to a revision (with a message unless it is empty).
+    pub fn execute_new_inserted(
+        &self,
+        flag: &str,
+        revision: &str,
+        message: &str,
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["new", flag, revision];
+        if !message.is_empty() {
+            args.extend(["-m", message]);
+        }
+        self.run_command(&args)
+    }
+
+    /// Execute `jj commit -m` to describe the working copy and start a new change on top.
+    pub fn execute_commit(&self, message: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["commit", "-m", message])
+    }
+
     /// Execute `jj edit` to edit a revision.
     pub fn execute_edit(&self, revision: &str) -> Result<CommandResult, XorcistError> {
         self.run_command(&["edit", revision])
@@ -106,19 +387,204 @@ impl JjRunner {
         self.run_command(&["abandon", revision])
     }
 
+    /// Execute `jj abandon <a> <b> ...` to abandon several changes at once.
+    pub fn execute_abandon_revisions(
+        &self,
+        revisions: &[String],
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["abandon"];
+        args.extend(revisions.iter().map(String::as_str));
+        self.run_command(&args)
+    }
+
+    /// Execute `jj squash --from <a> --from <b> ... --into <target>`.
+    pub fn execute_squash_into(
+        &self,
+        revisions: &[String],
+        target: &str,
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["squash"];
+        for revision in revisions {
+            args.push("--from");
+            args.push(revision);
+        }
+        args.push("--into");
+        args.push(target);
+        self.run_command(&args)
+    }
+
     /// Execute `jj squash` to squash a change into its parent.
     pub fn execute_squash(&self, revision: &str) -> Result<CommandResult, XorcistError> {
         self.run_command(&["squash", "-r", revision])
     }
 
+    /// Execute `jj squash -r <revision> -- <paths>` to move only those files into the parent.
+    pub fn execute_squash_paths(
+        &self,
+        revision: &str,
+        paths: &[String],
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["squash", "-r", revision, "--"];
+        args.extend(paths.iter().map(String::as_str));
+        self.run_command(&args)
+    }
+
+    /// Execute `jj restore` for some files of `into`.
+    ///
+    /// With `from`, the files get their contents in `from`; without it, the
+    /// changes `into` makes to them are discarded (`--changes-in`).
+    pub fn execute_restore_paths(
+        &self,
+        from: Option<&str>,
+        into: &str,
+        paths: &[String],
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = match from {
+            Some(from) => vec!["restore", "--from", from, "--into", into, "--"],
+            None => vec!["restore", "--changes-in", into, "--"],
+        };
+        args.extend(paths.iter().map(String::as_str));
+        self.run_command(&args)
+    }
+
+    /// Execute `jj split -r <revision> -- <paths>` to move files into a new first commit.
+    ///
+    /// The first commit keeps `message`; the rest of the change stays in the second.
+    pub fn execute_split(
+        &self,
+        revision: &str,
+        message: &str,
+        paths: &[String],
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["split", "-r", revision, "-m", message, "--"];
+        args.extend(paths.iter().map(String::as_str));
+        self.run_command(&args)
+    }
+
+    /// Execute `jj duplicate` to copy a change (onto `destination`, or onto
+    /// the same parents if None).
+    pub fn execute_duplicate(
+        &self,
+        revision: &str,
+        destination: Option<&str>,
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["duplicate", revision];
+        if let Some(destination) = destination {
+            args.extend(["-d", destination]);
+        }
+        self.run_command(&args)
+    }
+
+    /// Execute `jj git push -b <a> -b <b> ...` (with `--allow-new` for new bookmarks).
+    pub fn execute_git_push_bookmarks(
+        &self,
+        bookmarks: &[String],
+        allow_new: bool,
+        remote: Option<&str>,
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["git", "push"];
+        for bookmark in bookmarks {
+            args.push("-b");
+            args.push(bookmark);
+        }
+        if allow_new {
+            args.push("--allow-new");
+        }
+        if let Some(remote) = remote {
+            args.extend(["--remote", remote]);
+        }
+        self.run_command(&args)
+    }
+
+    /// Execute `jj workspace add <path>` to create a new workspace.
+    pub fn execute_workspace_add(&self, path: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["workspace", "add", path])
+    }
+
+    /// Execute `jj workspace forget <name>`.
+    pub fn execute_workspace_forget(&self, name: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["workspace", "forget", name])
+    }
+
+    /// Execute `jj workspace update-stale` for the current workspace.
+    pub fn execute_workspace_update_stale(&self) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["workspace", "update-stale"])
+    }
+
+    /// Execute `jj sparse set --add <pattern>` to check out more paths.
+    pub fn execute_sparse_add(&self, pattern: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["sparse", "set", "--add", pattern])
+    }
+
+    /// Execute `jj sparse set --remove <pattern>`.
+    pub fn execute_sparse_remove(&self, pattern: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["sparse", "set", "--remove", pattern])
+    }
+
+    /// Execute `jj sparse reset` to check out the whole repository again.
+    pub fn execute_sparse_reset(&self) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["sparse", "reset"])
+    }
+
+    /// Execute `jj sign -r <revision>` with the configured signing backend.
+    pub fn execute_sign(&self, revision: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["sign", "-r", revision])
+    }
+
+    /// Change the author of a revision with `jj metaedit --author <author>`.
+    ///
+    /// Without an author, the author is reset to the configured user with a
+    /// new timestamp. Releases without `jj metaedit` use `jj describe`.
+    pub fn execute_set_author(
+        &self,
+        revision: &str,
+        author: Option<&str>,
+    ) -> Result<CommandResult, XorcistError> {
+        let args: Vec<&str> = match (self.supports(Capability::MetaeditCommand), author) {
+            (true, Some(author)) => vec!["metaedit", "--author", author, "-r", revision],
+            (true, None) => vec![
+                "metaedit",
+                "--update-author",
+                "--update-author-timestamp",
+                "-r",
+                revision,
+            ],
+            (false, Some(author)) => vec!["describe", "--no-edit", "--author", author, revision],
+            (false, None) => vec!["describe", "--no-edit", "--reset-author", revision],
+        };
+        self.run_command(&args)
+    }
+
+    /// Execute `jj unsign -r <revision>` to drop a revision's signature.
+    pub fn execute_unsign(&self, revision: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["unsign", "-r", revision])
+    }
+
     /// Execute `jj git fetch` to fetch from remote.
-    pub fn execute_git_fetch(&self) -> Result<CommandResult, XorcistError> {
-        self.run_command(&["git", "fetch"])
+    pub fn execute_git_fetch(&self, remote: Option<&str>) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["git", "fetch"];
+        if let Some(remote) = remote {
+            args.extend(["--remote", remote]);
+        }
+        self.run_command(&args)
+    }
+
+    /// Execute `jj git push --change <revision>` to push a single change.
+    pub fn execute_git_push_change(
+        &self,
+        revision: &str,
+        remote: Option<&str>,
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["git", "push", "--change", revision];
+        if let Some(remote) = remote {
+            args.extend(["--remote", remote]);
+        }
+        self.run_command(&args)
     }
 
-    /// Execute `jj git push` to push to remote.
-    pub fn execute_git_push(&self) -> Result<CommandResult, XorcistError> {
-        self.run_command(&["git", "push"])
+    /// Execute `jj op restore` to restore the repository to an earlier operation.
+    pub fn execute_op_restore(&self, operation: &str) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["op", "restore", operation])
     }
 
     /// Execute `jj undo` to undo the last operation.
@@ -135,20 +601,156 @@ impl JjRunner {
         self.run_command(&["rebase", "-r", revision, "-d", destination])
     }
 
+    /// Execute `jj rebase -b <revision> -d <destination>`, moving the whole
+    /// branch of `revision` that is not already on the destination.
+    pub fn execute_rebase_branch(
+        &self,
+        revision: &str,
+        destination: &str,
+    ) -> Result<CommandResult, XorcistError> {
+        self.run_command(&["rebase", "-b", revision, "-d", destination])
+    }
+
+    /// Execute `jj rebase -r <a> -r <b> ... -d <destination>`.
+    pub fn execute_rebase_revisions(
+        &self,
+        revisions: &[String],
+        destination: &str,
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["rebase"];
+        for revision in revisions {
+            args.push("-r");
+            args.push(revision);
+        }
+        args.push("-d");
+        args.push(destination);
+        self.run_command(&args)
+    }
+
+    /// Execute `jj rebase -r <a> -r <b> ... -d <destination> --skip-emptied`.
+    pub fn execute_rebase_skip_emptied(
+        &self,
+        revisions: &[String],
+        destination: &str,
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["rebase"];
+        for revision in revisions {
+            args.push("-r");
+            args.push(revision);
+        }
+        args.extend(["-d", destination, "--skip-emptied"]);
+        self.run_command(&args)
+    }
+
+    /// Execute `jj simplify-parents -r <a> -r <b> ...`.
+    pub fn execute_simplify_parents(
+        &self,
+        revisions: &[String],
+    ) -> Result<CommandResult, XorcistError> {
+        let mut args = vec!["simplify-parents"];
+        for revision in revisions {
+            args.push("-r");
+            args.push(revision);
+        }
+        self.run_command(&args)
+    }
+
+    /// Run a command from the history again (`args` as recorded).
+    pub fn execute_args(&self, args: &[String]) -> Result<CommandResult, XorcistError> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_command(&args)
+    }
+
     /// Run a jj command and return a CommandResult.
+    ///
+    /// Every command run this way changes the repository, so it is recorded
+    /// in the journal (if any) together with its outcome.
     fn run_command(&self, args: &[&str]) -> Result<CommandResult, XorcistError> {
-        let output = self.execute(args)?;
+        let output = self.execute(&[], args);
+        if let Some(journal) = &self.journal {
+            journal.record(self.work_dir.as_deref(), args, &exit_status(&output));
+        }
+        let output = output?;
         let success = output.status.success();
+        // jj reports most of what it did on stderr, so keep both streams;
+        // the more relevant one comes first
         let message = if success {
-            String::from_utf8_lossy(&output.stdout).trim().to_string()
+            combined_output(&output.stdout, &output.stderr)
         } else {
-            String::from_utf8_lossy(&output.stderr).trim().to_string()
+            combined_output(&output.stderr, &output.stdout)
         };
 
         Ok(CommandResult { success, message })
     }
 }
 
+/// Message sent from the worker thread of [`JjRunner::stream_lines`].
+#[derive(Debug)]
+enum StreamEvent {
+    /// One line of standard output (without the line ending).
+    Line(String),
+    /// The command has ended.
+    Done(Result<(), XorcistError>),
+}
+
+/// Output of a jj command that is still running (see [`JjRunner::stream_lines`]).
+#[derive(Debug)]
+pub struct LineStream {
+    receiver: Receiver<StreamEvent>,
+}
+
+impl LineStream {
+    /// Take up to `max_lines` of the lines read so far, without waiting.
+    ///
+    /// The second value is set once the command has ended and every line
+    /// has been taken.
+    pub fn poll(&self, max_lines: usize) -> (Vec<String>, Option<Result<(), XorcistError>>) {
+        let mut lines = Vec::new();
+        while lines.len() < max_lines {
+            match self.receiver.try_recv() {
+                Ok(StreamEvent::Line(line)) => lines.push(line),
+                Ok(StreamEvent::Done(result)) => return (lines, Some(result)),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let stopped = XorcistError::JjError("jj stopped unexpectedly".to_string());
+                    return (lines, Some(Err(stopped)));
+                }
+            }
+        }
+        (lines, None)
+    }
+}
+
+/// Map an error starting jj, telling a missing `jj` binary apart.
+fn spawn_error(e: std::io::Error) -> XorcistError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        XorcistError::JjNotFound
+    } else {
+        XorcistError::Io(e)
+    }
+}
+
+/// Describe how a command ended (e.g., `exit 0`).
+fn exit_status(output: &Result<Output, XorcistError>) -> String {
+    match output {
+        Ok(output) => match output.status.code() {
+            Some(code) => format!("exit {code}"),
+            None => "killed".to_string(),
+        },
+        Err(e) => format!("failed: {e}"),
+    }
+}
+
+/// Join two output streams, skipping empty ones.
+fn combined_output(first: &[u8], second: &[u8]) -> String {
+    [first, second]
+        .iter()
+        .map(|stream| String::from_utf8_lossy(stream).trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl Default for JjRunner {
     fn default() -> Self {
         Self::new()
@@ -165,9 +767,66 @@ mod tests {
         assert!(runner.work_dir.is_none());
     }
 
+    #[test]
+    fn test_combined_output() {
+        assert_eq!(combined_output(b"out\n", b"  err\n"), "out\nerr");
+        assert_eq!(
+            combined_output(b"", b"Working copy now at: abc\n"),
+            "Working copy now at: abc"
+        );
+        assert_eq!(combined_output(b"\n", b""), "");
+    }
+
     #[test]
     fn test_runner_with_work_dir() {
         let runner = JjRunner::new().with_work_dir(Path::new("/tmp"));
         assert_eq!(runner.work_dir, Some(std::path::PathBuf::from("/tmp")));
     }
+
+    #[test]
+    fn test_runner_version_fallbacks() {
+        let runner = JjRunner::new();
+        assert!(runner.supports(Capability::TemplateReplace));
+        assert_eq!(runner.log_template(), TimestampFormat::Relative.template());
+
+        let mut runner = runner.with_version(Some(JjVersion::new(0, 24, 0)));
+        assert!(!runner.supports(Capability::SignatureTemplate));
+        assert_eq!(runner.log_template(), MINIMAL_LOG_TEMPLATE);
+        runner.set_timestamp_format(TimestampFormat::Absolute);
+        assert_eq!(runner.log_template(), TimestampFormat::Absolute.template());
+    }
+
+    #[test]
+    fn test_runner_force_builtin_log() {
+        let mut runner = JjRunner::new().with_log_template("change_id".to_string());
+        assert!(runner.config_args().is_empty());
+        assert!(runner.read_args().is_empty());
+        runner.set_ignore_working_copy(true);
+        assert_eq!(runner.read_args(), ["--ignore-working-copy"]);
+        runner.set_ignore_working_copy(false);
+        runner.force_builtin_log();
+        assert!(!runner.has_custom_log_template());
+        assert_eq!(
+            runner.config_args(),
+            [
+                "--config",
+                "templates.log_node=\"builtin_log_node\"",
+                "--config",
+                "ui.log-word-wrap=false",
+            ]
+        );
+
+        // Older jj only has --config-toml; the minimal template is kept
+        let mut runner = JjRunner::new().with_version(Some(JjVersion::new(0, 24, 0)));
+        runner.set_log_template(MINIMAL_LOG_TEMPLATE.to_string());
+        runner.force_builtin_log();
+        assert_eq!(runner.log_template(), MINIMAL_LOG_TEMPLATE);
+        assert_eq!(
+            runner.config_args(),
+            [
+                "--config-toml",
+                "templates.log_node = \"builtin_log_node\"\nui.log-word-wrap = false",
+            ]
+        );
+    }
 }
@@ -2,9 +2,10 @@
 
 use crate::error::XorcistError;
 use crate::jj::runner::JjRunner;
+use crate::jj::version::Capability;
 
 /// Output from jj show command.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ShowOutput {
     /// Change ID (full).
     pub change_id: String,
@@ -13,7 +14,6 @@ pub struct ShowOutput {
     /// Rest of change ID after the unique prefix.
     pub change_id_rest: String,
     /// Commit ID (full).
-    #[allow(dead_code)] // Will be used for copy-to-clipboard etc.
     pub commit_id: String,
     /// Shortest unique prefix of commit ID.
     pub commit_id_prefix: String,
@@ -29,6 +29,37 @@ pub struct ShowOutput {
     pub bookmarks: Vec<String>,
     /// Diff summary (list of changed files with status).
     pub diff_summary: Vec<DiffEntry>,
+    /// Cryptographic signature, if the commit is signed.
+    pub signature: Option<Signature>,
+    /// Parent commits, in jj's order.
+    pub parents: Vec<RelatedCommit>,
+    /// Whether other visible commits share the change ID.
+    pub divergent: bool,
+}
+
+/// A parent or child of a commit, as listed in the detail view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedCommit {
+    /// Short change ID.
+    pub change_id: String,
+    /// First line of the description.
+    pub description: String,
+}
+
+/// Cryptographic signature of a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    /// Verification result: `good`, `bad`, `unknown` or `invalid`.
+    pub status: String,
+    /// Signer as reported by the signing backend (e.g., name and email).
+    pub display: String,
+}
+
+impl Signature {
+    /// Whether the signature was verified successfully.
+    pub fn is_good(&self) -> bool {
+        self.status == "good"
+    }
 }
 
 /// A single file change entry.
@@ -50,103 +81,236 @@ pub enum DiffStatus {
     Copied,
 }
 
+impl DiffStatus {
+    /// Lowercase name (e.g., `"added"`) for machine-readable output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiffStatus::Added => "added",
+            DiffStatus::Modified => "modified",
+            DiffStatus::Deleted => "deleted",
+            DiffStatus::Renamed => "renamed",
+            DiffStatus::Copied => "copied",
+        }
+    }
+}
+
+/// Two revisions compared with `jj diff --from <from> --to <to>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRange {
+    /// Base revision (the "old" side).
+    pub from: String,
+    /// Compared revision (the "new" side).
+    pub to: String,
+}
+
+impl DiffRange {
+    /// Key identifying this comparison (e.g., for remembered positions).
+    pub fn key(&self) -> String {
+        format!("{}..{}", self.from, self.to)
+    }
+}
+
+/// Build a show template around a signature expression.
+macro_rules! show_template {
+    ($signature:literal) => {
+        concat!(
+            r#"change_id.shortest(4).prefix() ++ "\x00" ++ change_id.shortest(4).rest() ++ "\x00" ++ commit_id.shortest(4).prefix() ++ "\x00" ++ commit_id.shortest(4).rest() ++ "\x00" ++ commit_id ++ "\x00" ++ author.name() ++ "\x00" ++ committer.timestamp().ago() ++ "\x00" ++ description ++ "\x00" ++ bookmarks.join(",") ++ "\x00" ++ diff().summary() ++ "\x00" ++ "#,
+            $signature,
+            r#" ++ "\x00" ++ parents.map(|c| c.change_id().short() ++ "\x1f" ++ c.description().first_line() ++ "\x1e").join("") ++ "\x00" ++ if(divergent, "d")"#
+        )
+    };
+}
+
 /// Template for machine-readable show output.
 /// Fields are separated by \x00 (null byte) for reliable parsing.
-/// Uses shortest() to get unique prefix for change_id and commit_id.
-const SHOW_TEMPLATE: &str = r#"change_id.shortest(4).prefix() ++ "\x00" ++ change_id.shortest(4).rest() ++ "\x00" ++ commit_id.shortest(4).prefix() ++ "\x00" ++ commit_id.shortest(4).rest() ++ "\x00" ++ author.name() ++ "\x00" ++ committer.timestamp().ago() ++ "\x00" ++ description ++ "\x00" ++ bookmarks.join(",") ++ "\n""#;
+/// Uses shortest() to get unique prefix for change_id and commit_id, for
+/// highlighting; the full commit ID follows them.
+/// The diff summary is rendered by the template itself so that a single
+/// jj invocation is enough to populate the detail view. The signature
+/// (`status\x1fdisplay`, empty if unsigned), the parents (in the
+/// [`RELATED_TEMPLATE`] format) and `d` for a divergent change come last.
+const SHOW_TEMPLATE: &str =
+    show_template!(r#"if(signature, signature.status() ++ "\x1f" ++ signature.display())"#);
+
+/// [`SHOW_TEMPLATE`] for jj releases without `signature` (always unsigned).
+const SHOW_TEMPLATE_UNSIGNED: &str = show_template!(r#""""#);
+
+/// Template for [`fetch_children`]: `change_id\x1fsubject`, one commit per `\x1e`.
+const RELATED_TEMPLATE: &str =
+    r#"change_id.short() ++ "\x1f" ++ description.first_line() ++ "\x1e""#;
+
+/// Number of \x00-separated fields [`SHOW_TEMPLATE`] must produce (the
+/// trailing signature field is optional).
+const SHOW_FIELD_COUNT: usize = 10;
+
+/// Fetch the full description of a revision (without jj's trailing newline).
+pub fn fetch_description(runner: &JjRunner, revision: &str) -> Result<String, XorcistError> {
+    let output = runner.run_capture(&["log", "-r", revision, "--no-graph", "-T", "description"])?;
+    Ok(output.trim_end_matches('\n').to_string())
+}
+
+/// Fetch the author of a revision as `Name <email>`.
+pub fn fetch_author(runner: &JjRunner, revision: &str) -> Result<String, XorcistError> {
+    let template = r#"author.name() ++ " <" ++ author.email() ++ ">""#;
+    runner.run_capture(&["log", "-r", revision, "--no-graph", "-T", template])
+}
 
 /// Fetch show output for a revision.
 pub fn fetch_show(runner: &JjRunner, revision: &str) -> Result<ShowOutput, XorcistError> {
-    // 1. Fetch metadata using template
-    let meta_output =
-        runner.run_capture(&["log", "-r", revision, "--no-graph", "-T", SHOW_TEMPLATE])?;
-    let meta = parse_show_meta(&meta_output)?;
-
-    // 2. Fetch diff summary
-    let diff_output = runner.run_capture(&["diff", "-r", revision, "--summary"])?;
-    let diff_summary = parse_diff_summary(&diff_output);
+    let template = if runner.supports(Capability::SignatureTemplate) {
+        SHOW_TEMPLATE
+    } else {
+        SHOW_TEMPLATE_UNSIGNED
+    };
+    let output = runner.run_capture(&["log", "-r", revision, "--no-graph", "-T", template])?;
+    runner.time_parse("show", || parse_show_output(&output))
+}
 
-    Ok(ShowOutput {
-        change_id: meta.change_id,
-        change_id_prefix: meta.change_id_prefix,
-        change_id_rest: meta.change_id_rest,
-        commit_id: meta.commit_id,
-        commit_id_prefix: meta.commit_id_prefix,
-        commit_id_rest: meta.commit_id_rest,
-        author: meta.author,
-        timestamp: meta.timestamp,
-        description: meta.description,
-        bookmarks: meta.bookmarks,
-        diff_summary,
-    })
+/// Fetch the children of a revision, newest first.
+pub fn fetch_children(
+    runner: &JjRunner,
+    revision: &str,
+) -> Result<Vec<RelatedCommit>, XorcistError> {
+    let revset = format!("children({revision})");
+    let output =
+        runner.run_capture(&["log", "-r", &revset, "--no-graph", "-T", RELATED_TEMPLATE])?;
+    Ok(parse_related(&output))
 }
 
 /// Fetch diff output for a specific file in a revision.
+///
+/// The output keeps jj's own ANSI coloring (including word-level highlights)
+/// and is converted for display by the UI.
 pub fn fetch_diff_file(
     runner: &JjRunner,
     revision: &str,
     path: &str,
 ) -> Result<String, XorcistError> {
-    runner.run_capture(&["diff", "-r", revision, "--color=never", "--git", "--", path])
+    runner.run_capture(&[
+        "diff",
+        "-r",
+        revision,
+        "--color=always",
+        "--git",
+        "--",
+        path,
+    ])
+}
+
+/// Fetch the full diff of a revision (all files, with jj's ANSI coloring).
+pub fn fetch_diff(runner: &JjRunner, revision: &str) -> Result<String, XorcistError> {
+    runner.run_capture(&["diff", "-r", revision, "--color=always", "--git"])
 }
 
-/// Parsed metadata from jj log output.
-struct ShowMeta {
-    change_id: String,
-    change_id_prefix: String,
-    change_id_rest: String,
-    commit_id: String,
-    commit_id_prefix: String,
-    commit_id_rest: String,
-    author: String,
-    timestamp: String,
-    description: String,
-    bookmarks: Vec<String>,
+/// Fetch the list of files changed between two revisions.
+pub fn fetch_diff_summary_range(
+    runner: &JjRunner,
+    range: &DiffRange,
+) -> Result<Vec<DiffEntry>, XorcistError> {
+    let output = runner.run_capture(&[
+        "diff",
+        "--from",
+        &range.from,
+        "--to",
+        &range.to,
+        "--summary",
+    ])?;
+    Ok(runner.time_parse("diff summary", || parse_diff_summary(&output)))
 }
 
-/// Parse metadata from jj log output.
+/// Fetch diff output for a specific file between two revisions.
+pub fn fetch_diff_file_range(
+    runner: &JjRunner,
+    range: &DiffRange,
+    path: &str,
+) -> Result<String, XorcistError> {
+    runner.run_capture(&[
+        "diff",
+        "--from",
+        &range.from,
+        "--to",
+        &range.to,
+        "--color=always",
+        "--git",
+        "--",
+        path,
+    ])
+}
+
+/// Parse the output of [`SHOW_TEMPLATE`].
 ///
-/// The output format is: change_prefix\x00change_rest\x00commit_prefix\x00commit_rest\x00author\x00timestamp\x00description\x00bookmarks\n
-/// Note: description may contain newlines, so we split by \x00 on the entire output
+/// The output format is: change_prefix\x00change_rest\x00commit_prefix\x00commit_rest\x00commit_id\x00author\x00timestamp\x00description\x00bookmarks\x00diff_summary
+/// Note: description and diff summary may contain newlines, so we split by \x00 on the entire output
 /// rather than processing line by line.
-fn parse_show_meta(output: &str) -> Result<ShowMeta, XorcistError> {
-    // Remove trailing newline if present, then split by null byte
-    let output = output.trim_end_matches('\n');
+pub(crate) fn parse_show_output(output: &str) -> Result<ShowOutput, XorcistError> {
     let parts: Vec<&str> = output.split('\x00').collect();
 
-    if parts.len() < 8 {
+    if parts.len() < SHOW_FIELD_COUNT {
         return Err(XorcistError::JjError(format!(
-            "unexpected show output format: expected 8 fields, got {}",
+            "unexpected show output format: expected {SHOW_FIELD_COUNT} fields, got {}",
             parts.len()
         )));
     }
 
-    let bookmarks = super::parse_bookmarks_field(parts[7]);
+    let bookmarks = super::parse_bookmarks_field(parts[8]);
 
     // Trim trailing newline from description (jj adds one at the end)
-    let description = parts[6].trim_end_matches('\n').to_string();
+    let description = parts[7].trim_end_matches('\n').to_string();
 
     let change_id_prefix = parts[0].to_string();
     let change_id_rest = parts[1].to_string();
     let commit_id_prefix = parts[2].to_string();
     let commit_id_rest = parts[3].to_string();
 
-    Ok(ShowMeta {
+    Ok(ShowOutput {
         change_id: format!("{change_id_prefix}{change_id_rest}"),
         change_id_prefix,
         change_id_rest,
-        commit_id: format!("{commit_id_prefix}{commit_id_rest}"),
+        commit_id: parts[4].to_string(),
         commit_id_prefix,
         commit_id_rest,
-        author: parts[4].to_string(),
-        timestamp: parts[5].to_string(),
+        author: parts[5].to_string(),
+        timestamp: parts[6].to_string(),
         description,
         bookmarks,
+        diff_summary: parse_diff_summary(parts[9]),
+        signature: parts.get(10).and_then(|field| parse_signature(field)),
+        parents: parts
+            .get(11)
+            .map_or_else(Vec::new, |field| parse_related(field)),
+        divergent: parts.get(12).is_some_and(|field| field.starts_with('d')),
+    })
+}
+
+/// Parse commits in the [`RELATED_TEMPLATE`] format.
+fn parse_related(output: &str) -> Vec<RelatedCommit> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let (change_id, description) = record.trim_start_matches('\n').split_once('\x1f')?;
+            Some(RelatedCommit {
+                change_id: change_id.to_string(),
+                description: description.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse the `status\x1fdisplay` signature field (empty = unsigned).
+fn parse_signature(field: &str) -> Option<Signature> {
+    let field = field.trim_end_matches('\n');
+    if field.is_empty() {
+        return None;
+    }
+    let (status, display) = field.split_once('\x1f').unwrap_or((field, ""));
+    Some(Signature {
+        status: status.to_string(),
+        display: display.to_string(),
     })
 }
 
 /// Parse diff summary output from jj diff --summary.
-pub(crate) fn parse_diff_summary(output: &str) -> Vec<DiffEntry> {
+fn parse_diff_summary(output: &str) -> Vec<DiffEntry> {
     output
         .lines()
         .filter_map(|line| {
@@ -178,11 +342,10 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_show_meta() {
-        // Format: change_prefix\0change_rest\0commit_prefix\0commit_rest\0author\0timestamp\0description\0bookmarks
-        let output =
-            "abc\x00123\x00def\x00456\x00Alice\x002 hours ago\x00Add feature\x00main,dev\n";
-        let result = parse_show_meta(output).unwrap();
+    fn test_parse_show_output() {
+        // Format: change_prefix\0change_rest\0commit_prefix\0commit_rest\0commit_id\0author\0timestamp\0description\0bookmarks\0diff_summary
+        let output = "abc\x00123\x00def\x00456\x00def456\x00Alice\x002 hours ago\x00Add feature\x00main,dev\x00";
+        let result = parse_show_output(output).unwrap();
 
         assert_eq!(result.change_id_prefix, "abc");
         assert_eq!(result.change_id_rest, "123");
@@ -197,40 +360,50 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_show_meta_no_bookmarks() {
-        let output = "abc\x00123\x00def\x00456\x00Alice\x002 hours ago\x00Add feature\x00\n";
-        let result = parse_show_meta(output).unwrap();
+    fn test_parse_show_output_full_commit_id() {
+        // The prefix and rest are only as long as needed to be unique
+        let output = "abc\x00123\x00def\x00456\x00def4567890abcdef1234567890abcdef12345678\x00Alice\x00now\x00Test\x00\x00";
+        let result = parse_show_output(output).unwrap();
+        assert_eq!(result.commit_id, "def4567890abcdef1234567890abcdef12345678");
+        assert_eq!(result.commit_id_prefix, "def");
+        assert_eq!(result.commit_id_rest, "456");
+    }
+
+    #[test]
+    fn test_parse_show_output_no_bookmarks() {
+        let output =
+            "abc\x00123\x00def\x00456\x00def456\x00Alice\x002 hours ago\x00Add feature\x00\x00";
+        let result = parse_show_output(output).unwrap();
 
         assert!(result.bookmarks.is_empty());
     }
 
     #[test]
-    fn test_parse_show_meta_multiline_description() {
+    fn test_parse_show_output_multiline_description() {
         // In jj template output, newlines within description are preserved.
         // Our parser handles multi-line descriptions correctly.
-        let output =
-            "abc\x00123\x00def\x00456\x00Alice\x002 hours ago\x00First line\nSecond line\x00main\n";
-        let result = parse_show_meta(output).unwrap();
+        let output = "abc\x00123\x00def\x00456\x00def456\x00Alice\x002 hours ago\x00First line\nSecond line\x00main\x00";
+        let result = parse_show_output(output).unwrap();
 
         assert_eq!(result.description, "First line\nSecond line");
         assert_eq!(result.bookmarks, vec!["main"]);
     }
 
     #[test]
-    fn test_parse_show_meta_description_with_trailing_newline() {
+    fn test_parse_show_output_description_with_trailing_newline() {
         // jj's description often has a trailing newline, which should be trimmed
-        let output = "abc\x00123\x00def\x00456\x00Alice\x002 hours ago\x00Add feature\n\x00main\n";
-        let result = parse_show_meta(output).unwrap();
+        let output = "abc\x00123\x00def\x00456\x00def456\x00Alice\x002 hours ago\x00Add feature\n\x00main\x00";
+        let result = parse_show_output(output).unwrap();
 
         assert_eq!(result.description, "Add feature");
         assert_eq!(result.bookmarks, vec!["main"]);
     }
 
     #[test]
-    fn test_parse_show_meta_empty_rest() {
+    fn test_parse_show_output_empty_rest() {
         // When the entire ID is the unique prefix, rest is empty
-        let output = "abcd\x00\x00defg\x00\x00Alice\x00now\x00Test\x00\n";
-        let result = parse_show_meta(output).unwrap();
+        let output = "abcd\x00\x00defg\x00\x00defg\x00Alice\x00now\x00Test\x00\x00";
+        let result = parse_show_output(output).unwrap();
 
         assert_eq!(result.change_id_prefix, "abcd");
         assert!(result.change_id_rest.is_empty());
@@ -239,6 +412,56 @@ mod tests {
         assert!(result.commit_id_rest.is_empty());
     }
 
+    #[test]
+    fn test_parse_show_output_with_diff_summary() {
+        let output = "abc\x00123\x00def\x00456\x00def456\x00Alice\x00now\x00Test\x00\x00M src/main.rs\nA src/new.rs\n";
+        let result = parse_show_output(output).unwrap();
+
+        assert_eq!(result.diff_summary.len(), 2);
+        assert_eq!(result.diff_summary[0].status, DiffStatus::Modified);
+        assert_eq!(result.diff_summary[0].path, "src/main.rs");
+        assert_eq!(result.diff_summary[1].status, DiffStatus::Added);
+        assert_eq!(result.diff_summary[1].path, "src/new.rs");
+    }
+
+    #[test]
+    fn test_parse_show_output_signature() {
+        let output = "abc\x00123\x00def\x00456\x00def456\x00Alice\x00now\x00Test\x00\x00M a.rs\n\x00good\x1fAlice <alice@example.com>";
+        let signature = parse_show_output(output).unwrap().signature.unwrap();
+        assert!(signature.is_good());
+        assert_eq!(signature.display, "Alice <alice@example.com>");
+
+        let unsigned = "abc\x00123\x00def\x00456\x00def456\x00Alice\x00now\x00Test\x00\x00\x00";
+        assert!(parse_show_output(unsigned).unwrap().signature.is_none());
+    }
+
+    #[test]
+    fn test_parse_show_output_parents() {
+        let output = "abc\x00123\x00def\x00456\x00def456\x00Alice\x00now\x00Merge\x00\x00\x00\x00\
+                      zsuskuln\x1fchore: release\x1erlvkpnrz\x1f\x1e";
+        let result = parse_show_output(output).unwrap();
+        assert!(!result.divergent);
+        let parents = result.parents;
+        assert_eq!(parents.len(), 2);
+        assert_eq!(parents[0].change_id, "zsuskuln");
+        assert_eq!(parents[0].description, "chore: release");
+        assert_eq!(parents[1].description, "");
+
+        let divergent = format!("{output}\x00d");
+        assert!(parse_show_output(&divergent).unwrap().divergent);
+
+        let children = parse_related("qzmtztvn\x1ffeat: add\x1e\nkxqpwsml\x1f\x1e");
+        assert_eq!(children[0].change_id, "qzmtztvn");
+        assert_eq!(children[1].change_id, "kxqpwsml");
+        assert!(parse_related("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_show_output_too_few_fields() {
+        let output = "abc\x00123\x00def\x00456\x00def456\x00Alice\x00now\x00Test\x00main\n";
+        assert!(parse_show_output(output).is_err());
+    }
+
     #[test]
     fn test_parse_diff_summary() {
         let output = r#"A src/new_file.rs
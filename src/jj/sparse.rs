@@ -0,0 +1,37 @@
+//! Sparse patterns of the working copy (`jj sparse list`).
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Fetch the sparse patterns of the working copy, in `jj sparse list` order.
+///
+/// A working copy without sparse patterns set lists `.` (the whole repository).
+pub fn fetch_sparse_patterns(runner: &JjRunner) -> Result<Vec<String>, XorcistError> {
+    let output = runner.run_capture(&["sparse", "list"])?;
+    Ok(parse_sparse_patterns(&output))
+}
+
+/// Parse the output of `jj sparse list`: one pattern per line.
+fn parse_sparse_patterns(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sparse_patterns() {
+        assert_eq!(
+            parse_sparse_patterns("services/api\nlibs/common path\n\n"),
+            vec!["services/api", "libs/common path"]
+        );
+        assert_eq!(parse_sparse_patterns(".\n"), vec!["."]);
+        assert!(parse_sparse_patterns("").is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+//! Stacks of mutable commits on top of trunk, like git-branchless or
+//! Graphite stacks.
+//!
+//! Every mutable head that is not in trunk starts a stack; the stack is the
+//! head's mutable ancestors that are not in trunk either.
+
+use std::collections::HashSet;
+
+use crate::error::XorcistError;
+use crate::jj::parse_bookmarks_field;
+use crate::jj::runner::JjRunner;
+
+/// Revset of the stack heads.
+const STACK_HEADS: &str = "heads(mutable() & ~::trunk())";
+
+/// Template for the commits of a stack: `\x1f`-separated fields, one commit per `\x1e`.
+const STACK_COMMIT_TEMPLATE: &str = r#"change_id.shortest(8) ++ "\x1f" ++ bookmarks.map(|b| b.name()).join(",") ++ "\x1f" ++ if(conflict, "c") ++ if(empty, "e") ++ "\x1f" ++ description.first_line() ++ "\x1e""#;
+
+/// Number of fields produced by [`STACK_COMMIT_TEMPLATE`].
+const STACK_COMMIT_FIELD_COUNT: usize = 4;
+
+/// One commit of a stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackCommit {
+    /// Short change ID.
+    pub change_id: String,
+    /// Bookmarks on the commit.
+    pub bookmarks: Vec<String>,
+    /// Whether the commit has conflicts.
+    pub conflict: bool,
+    /// Whether the commit is empty.
+    pub empty: bool,
+    /// First line of the description.
+    pub description: String,
+}
+
+/// A stack of commits ending at a head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stack {
+    /// Commits of the stack, head first.
+    pub commits: Vec<StackCommit>,
+    /// Whether trunk moved since the stack was started (its base is not
+    /// the current trunk), so it needs a rebase.
+    pub needs_rebase: bool,
+}
+
+impl Stack {
+    /// The head commit (the stack is never empty).
+    pub fn head(&self) -> &StackCommit {
+        &self.commits[0]
+    }
+
+    /// Name of the stack: the bookmarks nearest the head, or its change ID.
+    pub fn name(&self) -> String {
+        self.commits
+            .iter()
+            .find(|commit| !commit.bookmarks.is_empty())
+            .map_or_else(
+                || self.head().change_id.clone(),
+                |commit| commit.bookmarks.join(", "),
+            )
+    }
+
+    /// Whether any commit of the stack has conflicts.
+    pub fn has_conflict(&self) -> bool {
+        self.commits.iter().any(|commit| commit.conflict)
+    }
+}
+
+/// Fetch the stacks of the repository, in `jj log` order of their heads.
+pub fn fetch_stacks(runner: &JjRunner) -> Result<Vec<Stack>, XorcistError> {
+    let heads = fetch_change_ids(runner, STACK_HEADS)?;
+    let up_to_date = fetch_change_ids(runner, &format!("({STACK_HEADS}) & trunk()::"))?;
+    let up_to_date: HashSet<&str> = up_to_date.lines().collect();
+    heads
+        .lines()
+        .filter(|head| !head.is_empty())
+        .map(|head| {
+            let revset = format!("(trunk()..{head}) & mutable()");
+            let output = runner.run_capture(&[
+                "log",
+                "--no-graph",
+                "-r",
+                &revset,
+                "-T",
+                STACK_COMMIT_TEMPLATE,
+            ])?;
+            let commits = runner.time_parse("stack", || parse_stack_commits(&output))?;
+            Ok((!commits.is_empty()).then(|| Stack {
+                commits,
+                needs_rebase: !up_to_date.contains(head),
+            }))
+        })
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+/// Short change IDs of a revset, one per line.
+fn fetch_change_ids(runner: &JjRunner, revset: &str) -> Result<String, XorcistError> {
+    runner.run_capture(&[
+        "log",
+        "--no-graph",
+        "-r",
+        revset,
+        "-T",
+        r#"change_id.shortest(8) ++ "\n""#,
+    ])
+}
+
+/// Parse the output of [`STACK_COMMIT_TEMPLATE`].
+fn parse_stack_commits(output: &str) -> Result<Vec<StackCommit>, XorcistError> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let fields: Vec<&str> = record
+                .trim_start_matches('\n')
+                .splitn(STACK_COMMIT_FIELD_COUNT, '\x1f')
+                .collect();
+            let [change_id, bookmarks, flags, description] = fields[..] else {
+                return Err(XorcistError::JjError(format!(
+                    "unexpected stack format: expected {STACK_COMMIT_FIELD_COUNT} fields, got {}",
+                    fields.len()
+                )));
+            };
+            Ok(StackCommit {
+                change_id: change_id.to_string(),
+                bookmarks: parse_bookmarks_field(bookmarks),
+                conflict: flags.contains('c'),
+                empty: flags.contains('e'),
+                description: description.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stack_commits() {
+        let output = "qzmtztvn\x1f\x1fe\x1f\x1e\
+                      rvzpxnov\x1ffeature,wip\x1fc\x1frefactor: split\x1e";
+        let commits = parse_stack_commits(output).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert!(commits[0].empty);
+        assert!(commits[0].bookmarks.is_empty());
+        assert_eq!(commits[1].bookmarks, ["feature", "wip"]);
+        assert!(commits[1].conflict);
+        assert_eq!(commits[1].description, "refactor: split");
+
+        let stack = Stack {
+            commits,
+            needs_rebase: true,
+        };
+        assert_eq!(stack.head().change_id, "qzmtztvn");
+        assert_eq!(stack.name(), "feature, wip");
+        assert!(stack.has_conflict());
+
+        assert!(parse_stack_commits("qzmtztvn\x1f\x1e").is_err());
+        assert!(parse_stack_commits("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//! Changed line counts per file (`jj diff --stat`).
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+use crate::jj::show::DiffRange;
+
+/// Added and removed line counts of one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileStat {
+    /// File path as printed by jj (long paths may be shortened with `...`).
+    pub path: String,
+    /// Number of added lines.
+    pub added: usize,
+    /// Number of removed lines.
+    pub removed: usize,
+}
+
+impl FileStat {
+    /// Number of changed lines.
+    pub fn changes(&self) -> usize {
+        self.added + self.removed
+    }
+}
+
+/// Parsed `jj diff --stat` output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    /// One entry per changed file, in jj's (path) order.
+    pub files: Vec<FileStat>,
+    /// Total added lines (from jj's summary line).
+    pub added: usize,
+    /// Total removed lines (from jj's summary line).
+    pub removed: usize,
+}
+
+impl DiffStat {
+    /// Counts of the file at `index` of the diff summary, whose path is `path`.
+    ///
+    /// jj lists files in the same order for `--stat` and `--summary`, so the
+    /// entry at `index` is used when its (possibly shortened) path matches.
+    pub fn for_file(&self, index: usize, path: &str) -> Option<&FileStat> {
+        let matches = |stat: &&FileStat| match stat.path.strip_prefix("...") {
+            Some(tail) => path.ends_with(tail),
+            None => stat.path == path || stat.path.contains(" => "),
+        };
+        self.files
+            .get(index)
+            .filter(matches)
+            .or_else(|| self.files.iter().find(|stat| stat.path == path))
+    }
+
+    /// Largest number of changed lines of any file (for scaling histograms).
+    pub fn max_changes(&self) -> usize {
+        self.files.iter().map(FileStat::changes).max().unwrap_or(0)
+    }
+}
+
+/// Fetch the changed line counts of a revision.
+pub fn fetch_diff_stat(runner: &JjRunner, revision: &str) -> Result<DiffStat, XorcistError> {
+    let output = runner.run_capture(&["diff", "-r", revision, "--stat", "--color=never"])?;
+    Ok(runner.time_parse("diff stat", || parse_diff_stat(&output)))
+}
+
+/// Fetch the changed line counts between two revisions.
+pub fn fetch_diff_stat_range(
+    runner: &JjRunner,
+    range: &DiffRange,
+) -> Result<DiffStat, XorcistError> {
+    let output = runner.run_capture(&[
+        "diff",
+        "--from",
+        &range.from,
+        "--to",
+        &range.to,
+        "--stat",
+        "--color=never",
+    ])?;
+    Ok(runner.time_parse("diff stat", || parse_diff_stat(&output)))
+}
+
+/// Parse `jj diff --stat` output.
+///
+/// Each file line is `path | <changes> <+++-->`. When a file has more changes
+/// than fit, jj scales the bar down, so the split between added and removed
+/// lines is estimated from the bar's proportions; the totals are exact.
+fn parse_diff_stat(output: &str) -> DiffStat {
+    let mut stat = DiffStat::default();
+    let mut has_summary = false;
+    for line in output.lines() {
+        if let Some((path, counts)) = line.rsplit_once('|') {
+            stat.files
+                .push(parse_file_stat(path.trim_end(), counts.trim()));
+        } else if line.contains("changed") {
+            for part in line.split(',') {
+                let count = leading_number(part.trim());
+                if part.contains("insertion") {
+                    stat.added = count;
+                } else if part.contains("deletion") {
+                    stat.removed = count;
+                }
+            }
+            has_summary = true;
+        }
+    }
+    if !has_summary {
+        stat.added = stat.files.iter().map(|file| file.added).sum();
+        stat.removed = stat.files.iter().map(|file| file.removed).sum();
+    }
+    stat
+}
+
+fn parse_file_stat(path: &str, counts: &str) -> FileStat {
+    let (total, bar) = counts.split_once(' ').unwrap_or((counts, ""));
+    let total: usize = total.parse().unwrap_or(0);
+    let plus = bar.matches('+').count();
+    let minus = bar.matches('-').count();
+    let marks = plus + minus;
+    let added = (total * plus + marks / 2).checked_div(marks).unwrap_or(0);
+    FileStat {
+        path: path.to_string(),
+        added,
+        removed: total - added,
+    }
+}
+
+/// Number at the start of `text` ("12 insertions(+)" → 12).
+fn leading_number(text: &str) -> usize {
+    text.split(' ')
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diff_stat() {
+        let output = "\
+src/main.rs            |   5 +++--
+...ong/path/to/file.rs | 300 ++++++++++++++++++++---------
+README.md              |   2 --
+3 files changed, 210 insertions(+), 97 deletions(-)
+";
+        let stat = parse_diff_stat(output);
+        assert_eq!(stat.files.len(), 3);
+        assert_eq!(
+            stat.files[0],
+            FileStat {
+                path: "src/main.rs".to_string(),
+                added: 3,
+                removed: 2,
+            }
+        );
+        // Scaled bar: 20 of 29 marks are insertions
+        assert_eq!(stat.files[1].added, 207);
+        assert_eq!(stat.files[1].changes(), 300);
+        assert_eq!((stat.files[2].added, stat.files[2].removed), (0, 2));
+        assert_eq!((stat.added, stat.removed), (210, 97));
+        assert_eq!(stat.max_changes(), 300);
+
+        let file = stat.for_file(1, "a/very/long/path/to/file.rs").unwrap();
+        assert_eq!(file.added, 207);
+        assert_eq!(stat.for_file(0, "README.md").unwrap().removed, 2);
+        assert!(stat.for_file(0, "src/other.rs").is_none());
+
+        let stat = parse_diff_stat("0 files changed, 0 insertions(+), 0 deletions(-)\n");
+        assert!(stat.files.is_empty());
+        assert_eq!(stat.max_changes(), 0);
+    }
+}
@@ -0,0 +1,82 @@
+//! jj tags (`jj tag list`).
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Template for [`fetch_tags`]: `\x1f`-separated fields, one tag per `\x1e`.
+///
+/// Conflicted tags have no single target, so their commit fields are empty.
+const TAG_TEMPLATE: &str = r#"name ++ "\x1f" ++ if(conflict, "c") ++ "\x1f" ++ if(normal_target, normal_target.change_id().short() ++ "\x1f" ++ normal_target.commit_id().short() ++ "\x1f" ++ normal_target.description().first_line(), "\x1f\x1f") ++ "\x1e""#;
+
+/// Number of fields produced by [`TAG_TEMPLATE`].
+const TAG_FIELD_COUNT: usize = 5;
+
+/// One tag of the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagEntry {
+    /// Tag name (e.g., "v1.0.0").
+    pub name: String,
+    /// Whether the tag points at several commits.
+    pub conflict: bool,
+    /// Short change ID of the tagged commit (empty if conflicted).
+    pub change_id: String,
+    /// Short commit ID of the tagged commit (empty if conflicted).
+    pub commit_id: String,
+    /// First line of the tagged commit's description.
+    pub description: String,
+}
+
+/// Fetch every tag, in `jj tag list` (name) order.
+pub fn fetch_tags(runner: &JjRunner) -> Result<Vec<TagEntry>, XorcistError> {
+    let output = runner.run_capture(&["tag", "list", "-T", TAG_TEMPLATE])?;
+    runner.time_parse("tag list", || parse_tags(&output))
+}
+
+/// Parse the output of [`TAG_TEMPLATE`].
+fn parse_tags(output: &str) -> Result<Vec<TagEntry>, XorcistError> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let fields: Vec<&str> = record
+                .trim_start_matches('\n')
+                .splitn(TAG_FIELD_COUNT, '\x1f')
+                .collect();
+            let [name, conflict, change_id, commit_id, description] = fields[..] else {
+                return Err(XorcistError::JjError(format!(
+                    "unexpected tag list format: expected {TAG_FIELD_COUNT} fields, got {}",
+                    fields.len()
+                )));
+            };
+            Ok(TagEntry {
+                name: name.to_string(),
+                conflict: conflict == "c",
+                change_id: change_id.to_string(),
+                commit_id: commit_id.to_string(),
+                description: description.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        let output = "v0.1.0\x1f\x1fzsuskuln\x1fa1b2c3d4\x1frelease 0.1.0\x1e\
+                      v0.2.0\x1fc\x1f\x1f\x1f\x1e";
+        let tags = parse_tags(output).unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].name, "v0.1.0");
+        assert!(!tags[0].conflict);
+        assert_eq!(tags[0].change_id, "zsuskuln");
+        assert_eq!(tags[0].description, "release 0.1.0");
+        assert!(tags[1].conflict);
+        assert_eq!(tags[1].change_id, "");
+
+        assert!(parse_tags("v0.1.0\x1f\x1e").is_err());
+        assert!(parse_tags("").unwrap().is_empty());
+    }
+}
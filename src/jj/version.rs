@@ -0,0 +1,138 @@
+//! jj version detection, and the jj features xorcist can do without.
+//!
+//! Features newer than the installed jj are replaced by a fallback (e.g. a
+//! simpler template) instead of failing with a template error.
+
+use std::fmt;
+
+/// A jj release number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JjVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl JjVersion {
+    /// Create a version number.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the output of `jj --version` (e.g., "jj 0.28.2-1a2b3c4d").
+    pub fn parse(output: &str) -> Option<Self> {
+        let number = output.split_whitespace().nth(1)?;
+        let mut parts = number
+            .split(|c: char| !c.is_ascii_digit())
+            .map(|part| part.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for JjVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A jj feature xorcist uses, which older releases lack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// `String.replace()` in templates, used to shorten the log timestamps.
+    TemplateReplace,
+    /// `signature` in commit templates, shown in the detail view.
+    SignatureTemplate,
+    /// The `jj sign` and `jj unsign` commands.
+    SignCommand,
+    /// The `jj metaedit` command, used to change the author of a revision.
+    MetaeditCommand,
+    /// `--config NAME=VALUE`, used to override settings that break the log.
+    ConfigOption,
+}
+
+/// Every capability, in the order their fallbacks are reported.
+pub const CAPABILITIES: &[Capability] = &[
+    Capability::TemplateReplace,
+    Capability::SignatureTemplate,
+    Capability::SignCommand,
+    Capability::MetaeditCommand,
+    Capability::ConfigOption,
+];
+
+impl Capability {
+    /// First jj release with this feature.
+    pub fn min_version(self) -> JjVersion {
+        match self {
+            Capability::TemplateReplace => JjVersion::new(0, 32, 0),
+            Capability::SignatureTemplate => JjVersion::new(0, 25, 0),
+            Capability::SignCommand => JjVersion::new(0, 26, 0),
+            Capability::MetaeditCommand => JjVersion::new(0, 31, 0),
+            Capability::ConfigOption => JjVersion::new(0, 25, 0),
+        }
+    }
+
+    /// What xorcist does without it, for the startup warning.
+    pub fn fallback(self) -> &'static str {
+        match self {
+            Capability::TemplateReplace => "long log timestamps",
+            Capability::SignatureTemplate => "no signatures in the detail view",
+            Capability::SignCommand => "no jj sign / unsign",
+            Capability::MetaeditCommand => "authors changed with jj describe",
+            Capability::ConfigOption => "log settings overridden with --config-toml",
+        }
+    }
+}
+
+/// Warning for a jj release that lacks some capabilities (None if it has all).
+pub fn compatibility_warning(version: JjVersion) -> Option<String> {
+    let missing: Vec<&str> = CAPABILITIES
+        .iter()
+        .filter(|capability| version < capability.min_version())
+        .map(|capability| capability.fallback())
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "jj {version} is older than xorcist expects: {}",
+        missing.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            JjVersion::parse("jj 0.28.2\n"),
+            Some(JjVersion::new(0, 28, 2))
+        );
+        assert_eq!(
+            JjVersion::parse("jj 0.33.0-1a2b3c4d5e6f"),
+            Some(JjVersion::new(0, 33, 0))
+        );
+        assert_eq!(JjVersion::parse("jj 1.0"), Some(JjVersion::new(1, 0, 0)));
+        assert_eq!(JjVersion::parse("jj"), None);
+        assert_eq!(JjVersion::parse("jj unknown"), None);
+        assert!(JjVersion::new(0, 9, 0) < JjVersion::new(0, 10, 0));
+    }
+
+    #[test]
+    fn test_compatibility_warning() {
+        assert_eq!(compatibility_warning(JjVersion::new(0, 32, 0)), None);
+        assert_eq!(
+            compatibility_warning(JjVersion::new(0, 25, 1)).unwrap(),
+            "jj 0.25.1 is older than xorcist expects: long log timestamps, no jj sign / unsign, \
+             authors changed with jj describe"
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! jj workspaces (`jj workspace list`).
+
+use crate::error::XorcistError;
+use crate::jj::runner::JjRunner;
+
+/// Template for [`fetch_workspaces`]: `\x1f`-separated fields, one workspace per `\x1e`.
+const WORKSPACE_TEMPLATE: &str = r#"name ++ "\x1f" ++ if(target.current_working_copy(), "@") ++ "\x1f" ++ target.change_id().short() ++ "\x1f" ++ target.commit_id().short() ++ "\x1f" ++ target.description().first_line() ++ "\x1e""#;
+
+/// Number of fields produced by [`WORKSPACE_TEMPLATE`].
+const WORKSPACE_FIELD_COUNT: usize = 5;
+
+/// One workspace of the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceEntry {
+    /// Workspace name (e.g., "default").
+    pub name: String,
+    /// Whether xorcist runs in this workspace.
+    pub is_current: bool,
+    /// Short change ID of the workspace's working-copy commit.
+    pub change_id: String,
+    /// Short commit ID of the working-copy commit.
+    pub commit_id: String,
+    /// First line of the working-copy commit's description.
+    pub description: String,
+}
+
+/// Fetch every workspace, in `jj workspace list` order.
+pub fn fetch_workspaces(runner: &JjRunner) -> Result<Vec<WorkspaceEntry>, XorcistError> {
+    let output = runner.run_capture(&["workspace", "list", "-T", WORKSPACE_TEMPLATE])?;
+    runner.time_parse("workspace list", || parse_workspaces(&output))
+}
+
+/// Parse the output of [`WORKSPACE_TEMPLATE`].
+fn parse_workspaces(output: &str) -> Result<Vec<WorkspaceEntry>, XorcistError> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let fields: Vec<&str> = record
+                .trim_start_matches('\n')
+                .splitn(WORKSPACE_FIELD_COUNT, '\x1f')
+                .collect();
+            let [name, current, change_id, commit_id, description] = fields[..] else {
+                return Err(XorcistError::JjError(format!(
+                    "unexpected workspace list format: expected {WORKSPACE_FIELD_COUNT} fields, got {}",
+                    fields.len()
+                )));
+            };
+            Ok(WorkspaceEntry {
+                name: name.to_string(),
+                is_current: current == "@",
+                change_id: change_id.to_string(),
+                commit_id: commit_id.to_string(),
+                description: description.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_workspaces() {
+        let output = "default\x1f@\x1fqzmtztvn\x1fa1b2c3d4\x1ffix typo\x1e\
+                      feature\x1f\x1frvzpxnov\x1f0f9e8d7c\x1f\x1e";
+        let entries = parse_workspaces(output).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "default");
+        assert!(entries[0].is_current);
+        assert_eq!(entries[0].description, "fix typo");
+        assert!(!entries[1].is_current);
+        assert_eq!(entries[1].change_id, "rvzpxnov");
+        assert_eq!(entries[1].description, "");
+
+        assert!(parse_workspaces("default\x1f@\x1e").is_err());
+    }
+}
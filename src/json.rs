@@ -0,0 +1,114 @@
+//! Minimal JSON serialization for `--format json` output.
+
+use std::fmt::Write;
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Bool(bool),
+    String(String),
+    Array(Vec<Json>),
+    /// Object with keys in insertion order.
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    /// String value.
+    pub fn str(s: impl Into<String>) -> Self {
+        Json::String(s.into())
+    }
+
+    /// Array of strings.
+    pub fn strings<S: AsRef<str>>(items: &[S]) -> Self {
+        Json::Array(items.iter().map(|s| Json::str(s.as_ref())).collect())
+    }
+
+    /// Serialize with two-space indentation.
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0);
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Json::String(s) => write_string(out, s),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    push_indent(out, indent + 1);
+                    item.write_pretty(out, indent + 1);
+                }
+                out.push('\n');
+                push_indent(out, indent);
+                out.push(']');
+            }
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Object(fields) => {
+                out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    push_indent(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_pretty(out, indent + 1);
+                }
+                out.push('\n');
+                push_indent(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.push_str(&"  ".repeat(indent));
+}
+
+/// Write a quoted, escaped JSON string.
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if u32::from(ch) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(ch));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_strings() {
+        let json = Json::str("a \"quote\"\\\n\u{1b}");
+        assert_eq!(json.to_pretty_string(), r#""a \"quote\"\\\n\u001b""#);
+    }
+
+    #[test]
+    fn test_pretty_nested() {
+        let json = Json::Object(vec![
+            ("id", Json::str("abc")),
+            ("parents", Json::strings(&["p1"])),
+            ("tags", Json::Array(Vec::new())),
+            ("empty", Json::Bool(false)),
+        ]);
+        assert_eq!(
+            json.to_pretty_string(),
+            "{\n  \"id\": \"abc\",\n  \"parents\": [\n    \"p1\"\n  ],\n  \"tags\": [],\n  \
+             \"empty\": false\n}"
+        );
+    }
+}
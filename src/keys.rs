@@ -1,20 +1,63 @@
 //! Keyboard event handlers.
 
 use anyhow::{Context, Result};
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use tui_input::backend::crossterm::EventHandler;
 
-use crate::app::{App, InputMode, View};
+use crate::app::{App, InputMode, ModalState, NewPlacement, PendingAction, View};
+
+pub mod help;
 
 /// Handle key events in log view.
 pub fn handle_log_keys(app: &mut App, key: KeyEvent) -> Result<()> {
     // Track if we need to check for loading more entries
     let mut check_load_more = false;
 
+    if app.is_yank_pending() {
+        return finish_yank(app, key);
+    }
+
     match key.code {
+        KeyCode::Esc if app.squash_source().is_some() => {
+            app.cancel_squash_target_pick();
+        }
+        KeyCode::Enter if app.squash_source().is_some() => {
+            app.finish_squash_target_pick()
+                .context("failed to execute jj squash")?;
+        }
+        KeyCode::Esc if app.is_visual_mode() => {
+            app.cancel_visual_mode();
+        }
+        KeyCode::Esc if app.has_search() => {
+            app.clear_search();
+        }
+        KeyCode::Esc if app.has_marks() || app.compare_base().is_some() => {
+            app.clear_marks();
+            app.clear_compare_base();
+        }
+        KeyCode::Esc if app.author_filter().is_some() => {
+            app.set_author_filter(None)
+                .context("failed to reload log")?;
+        }
+        KeyCode::Esc if app.path_filter().is_some() => {
+            app.set_path_filter(None).context("failed to reload log")?;
+        }
+        KeyCode::Esc if app.has_elided_expansions() => {
+            app.collapse_elided().context("failed to reload log")?;
+        }
         KeyCode::Char('q') | KeyCode::Esc => {
             app.quit();
         }
+        KeyCode::Char(' ') => {
+            app.toggle_mark();
+            check_load_more = true;
+        }
+        KeyCode::Char('V') => {
+            // Range select: V at one end, move, V at the other end marks the range
+            app.toggle_visual_mode();
+        }
         KeyCode::Char('j') | KeyCode::Down => {
             app.select_next();
             check_load_more = true;
@@ -46,14 +89,142 @@ pub fn handle_log_keys(app: &mut App, key: KeyEvent) -> Result<()> {
         KeyCode::PageUp => {
             app.page_up(10);
         }
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_timestamp_format()
+                .context("failed to reload log")?;
+        }
+        KeyCode::Char('w') => {
+            app.toggle_wrap_descriptions();
+        }
+        // Preview pane: v opens it, shows the diff, closes it; < / > resize the log
+        KeyCode::Char('v') => {
+            app.cycle_preview();
+        }
+        KeyCode::Char('<') if app.preview().is_some() => {
+            app.resize_preview(false);
+        }
+        KeyCode::Char('>') if app.preview().is_some() => {
+            app.resize_preview(true);
+        }
+        KeyCode::Char('J') => {
+            app.select_trunk();
+            check_load_more = true;
+        }
+        // Follow the graph to the parent / child (like jj prev / jj next)
+        KeyCode::Char('[') => {
+            app.select_parent().context("failed to find the parent")?;
+        }
+        KeyCode::Char(']') => {
+            app.select_child().context("failed to find the child")?;
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
+        KeyCode::Char('m') => {
+            app.open_context_menu();
+        }
+        KeyCode::Char('c') => {
+            app.compare_selected()
+                .context("failed to open comparison")?;
+        }
+        KeyCode::Char('C') => {
+            app.start_diff_prompts();
+        }
+        KeyCode::Char('O') => {
+            app.open_op_log().context("failed to load operation log")?;
+        }
+        KeyCode::Char('W') => {
+            app.open_workspaces().context("failed to load workspaces")?;
+        }
+        KeyCode::Char('T') => {
+            app.open_tags().context("failed to load tags")?;
+        }
+        KeyCode::Char('K') => {
+            app.open_stacks().context("failed to load stacks")?;
+        }
+        KeyCode::Char('Z') => {
+            app.open_sparse()
+                .context("failed to load sparse patterns")?;
+        }
+        KeyCode::Char('A') => {
+            app.open_author_filter();
+        }
+        KeyCode::Char('F') => {
+            app.start_path_filter();
+        }
+        KeyCode::Char('U') => {
+            app.start_set_author();
+        }
+        KeyCode::Char('h') => {
+            app.toggle_hidden_commits()
+                .context("failed to toggle hidden commits")?;
+        }
+        KeyCode::Char('E') => {
+            app.expand_elided()
+                .context("failed to expand elided revisions")?;
+        }
+        KeyCode::Char('!') => {
+            app.toggle_quick_mode();
+        }
+        KeyCode::Char('$') => {
+            app.start_shell();
+        }
+        KeyCode::Char('/') => {
+            app.start_search();
+        }
+        // Command palette: Ctrl+Shift+P where the terminal reports it, else Alt+P
+        KeyCode::Char('p' | 'P')
+            if key.modifiers.contains(KeyModifiers::ALT)
+                || key
+                    .modifiers
+                    .contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+                || (key.code == KeyCode::Char('P')
+                    && key.modifiers.contains(KeyModifiers::CONTROL)) =>
+        {
+            app.open_palette();
+        }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_finder();
+        }
+        // n/N jump between matches while a search is active
+        KeyCode::Char('n') if app.has_search() => {
+            app.search_next();
+            check_load_more = true;
+        }
+        KeyCode::Char('N') if app.has_search() => {
+            app.search_previous();
+            check_load_more = true;
+        }
+        KeyCode::Char(':') => {
+            app.start_input_mode(InputMode::Command);
+        }
+        // Copy to clipboard: yy change ID, yc commit ID, yd description
+        KeyCode::Char('y') => {
+            app.start_yank();
+        }
+        // Open the commit on its forge (GitHub, GitLab, ...) in the browser
+        KeyCode::Char('B') => {
+            app.open_on_forge().context("failed to list git remotes")?;
+        }
         // jj commands with confirmation
         KeyCode::Char('a') => {
             // jj abandon (with confirmation)
-            app.show_abandon_confirm();
+            app.show_abandon_confirm()
+                .context("failed to execute jj abandon")?;
+        }
+        KeyCode::Char('X') => {
+            // jj abandon of every empty mutable commit (with confirmation)
+            app.show_abandon_empty_confirm()
+                .context("failed to execute jj abandon")?;
         }
         KeyCode::Char('s') => {
             // jj squash (with confirmation)
-            app.show_squash_confirm();
+            app.show_squash_confirm()
+                .context("failed to execute jj squash")?;
+        }
+        KeyCode::Char('S') => {
+            // jj squash --into a commit picked in the log
+            app.start_squash_target_pick();
         }
         KeyCode::Char('f') => {
             // jj git fetch (no confirmation - read-only operation)
@@ -61,8 +232,12 @@ pub fn handle_log_keys(app: &mut App, key: KeyEvent) -> Result<()> {
                 .context("failed to execute jj git fetch")?;
         }
         KeyCode::Char('p') => {
-            // jj git push (with confirmation)
-            app.show_push_confirm();
+            // jj git push of the bookmarks chosen in the push dialog
+            app.open_push_dialog();
+        }
+        KeyCode::Char('P') => {
+            // Write the selected (or marked) commits as patch files
+            app.start_patch_export();
         }
         KeyCode::Char('u') => {
             // jj undo (with confirmation)
@@ -77,13 +252,34 @@ pub fn handle_log_keys(app: &mut App, key: KeyEvent) -> Result<()> {
             // jj new -m (with message input)
             app.start_input_mode(InputMode::NewWithMessage);
         }
+        KeyCode::Char('i') => {
+            // jj new --insert-after / --insert-before (placement menu)
+            app.open_placement_menu(false);
+        }
+        KeyCode::Char('I') => {
+            // The same, then the message input
+            app.open_placement_menu(true);
+        }
+        KeyCode::Char('M') => {
+            // jj commit -m (describe @ and start a new change on top)
+            app.start_input_mode(InputMode::Commit);
+        }
         KeyCode::Char('e') => {
             // jj edit
             app.execute_edit().context("failed to execute jj edit")?;
         }
         KeyCode::Char('d') => {
-            // jj describe -m (input mode)
-            app.start_input_mode(InputMode::Describe);
+            // jj describe -m (input mode, prefilled with the current message)
+            app.start_describe().context("failed to load description")?;
+        }
+        KeyCode::Char('t') => {
+            // jj describe -m with a conventional message (type, scope, subject)
+            app.start_conventional_describe();
+        }
+        KeyCode::Char('D') => {
+            // jj describe in $EDITOR (multi-line)
+            app.start_external_describe()
+                .context("failed to load description")?;
         }
         KeyCode::Char('b') => {
             // jj bookmark set (input mode)
@@ -93,6 +289,10 @@ pub fn handle_log_keys(app: &mut App, key: KeyEvent) -> Result<()> {
             // jj rebase -d (input mode)
             app.start_input_mode(InputMode::RebaseDestination);
         }
+        KeyCode::Char('Y') => {
+            // jj duplicate (destination input, then confirmation)
+            app.start_input_mode(InputMode::DuplicateDestination);
+        }
         _ => {}
     }
 
@@ -113,9 +313,48 @@ pub fn handle_input_keys(app: &mut App, key: KeyEvent, event: &Event) -> Result<
         KeyCode::Esc => {
             app.cancel_input_mode();
         }
+        KeyCode::Tab if app.input_mode == Some(InputMode::Command) => {
+            app.complete_command_line();
+        }
+        // Move through the fuzzy finder's (or command palette's) results
+        KeyCode::Down if app.finder().is_some() => {
+            app.finder_select_next();
+        }
+        KeyCode::Up if app.finder().is_some() => {
+            app.finder_select_previous();
+        }
+        KeyCode::Char('n')
+            if key.modifiers.contains(KeyModifiers::CONTROL) && app.finder().is_some() =>
+        {
+            app.finder_select_next();
+        }
+        KeyCode::Char('p')
+            if key.modifiers.contains(KeyModifiers::CONTROL) && app.finder().is_some() =>
+        {
+            app.finder_select_previous();
+        }
+        // Continue the description in $EDITOR
+        KeyCode::Char('e')
+            if key.modifiers.contains(KeyModifiers::CONTROL)
+                && app.input_mode == Some(InputMode::Describe) =>
+        {
+            app.start_external_describe()
+                .context("failed to load description")?;
+        }
         _ => {
             // Pass other keys to tui-input
             app.input.handle_event(event);
+            match app.input_mode {
+                Some(InputMode::Search) => app.update_search(),
+                Some(
+                    InputMode::Finder
+                    | InputMode::Palette
+                    | InputMode::ConventionalType
+                    | InputMode::ConventionalScope
+                    | InputMode::AuthorFilter,
+                ) => app.update_finder(),
+                _ => {}
+            }
         }
     }
     Ok(())
@@ -123,13 +362,124 @@ pub fn handle_input_keys(app: &mut App, key: KeyEvent, event: &Event) -> Result<
 
 /// Handle key events in modal dialog.
 pub fn handle_modal_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    if matches!(app.modal, ModalState::Menu(_)) {
+        return handle_menu_keys(app, key);
+    }
+    if matches!(app.modal, ModalState::Push(_)) {
+        return handle_push_dialog_keys(app, key);
+    }
+    if matches!(app.modal, ModalState::Placement(_)) {
+        return handle_placement_menu_keys(app, key);
+    }
     match key.code {
         KeyCode::Char('y') | KeyCode::Char('Y') => {
             app.confirm_action().context("failed to execute action")?;
         }
+        KeyCode::Char('t')
+            if matches!(app.modal, ModalState::Confirm(PendingAction::Squash { .. })) =>
+        {
+            app.start_squash_target_pick();
+        }
+        KeyCode::Char('t')
+            if matches!(
+                app.modal,
+                ModalState::Confirm(PendingAction::RestoreFiles { .. })
+            ) =>
+        {
+            app.start_restore_target_input();
+        }
+        KeyCode::Char('t')
+            if matches!(
+                app.modal,
+                ModalState::Confirm(PendingAction::ResolveDivergence { .. })
+            ) =>
+        {
+            app.toggle_divergence_resolution();
+        }
         KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
             app.close_modal();
         }
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+            app.toggle_confirm_focus();
+        }
+        KeyCode::Enter => {
+            app.activate_confirm_focus()
+                .context("failed to execute action")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in the context menu.
+fn handle_menu_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    let Some(menu) = app.context_menu_mut() else {
+        return Ok(());
+    };
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('m') => {
+            app.close_modal();
+        }
+        KeyCode::Char('j') | KeyCode::Down => menu.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => menu.select_previous(),
+        KeyCode::Enter => {
+            app.activate_menu_item()
+                .context("failed to execute menu action")?;
+        }
+        KeyCode::Char(ch) => {
+            if let Some(action) = menu.action_for_key(ch) {
+                app.execute_menu_action(action)
+                    .context("failed to execute menu action")?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in the placement menu of a new change.
+fn handle_placement_menu_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    let Some(menu) = app.placement_menu_mut() else {
+        return Ok(());
+    };
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.close_modal();
+        }
+        KeyCode::Char('j') | KeyCode::Down => menu.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => menu.select_previous(),
+        KeyCode::Enter => {
+            app.activate_placement()
+                .context("failed to execute jj new")?;
+        }
+        KeyCode::Char(ch) => {
+            if let Some(placement) = NewPlacement::ALL.into_iter().find(|p| p.key() == ch) {
+                app.pick_placement(placement)
+                    .context("failed to execute jj new")?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in the push dialog.
+fn handle_push_dialog_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    let Some(dialog) = app.push_dialog_mut() else {
+        return Ok(());
+    };
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('p') => {
+            app.close_modal();
+        }
+        KeyCode::Char('j') | KeyCode::Down => dialog.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => dialog.select_previous(),
+        KeyCode::Char(' ') => dialog.toggle_selected(),
+        KeyCode::Char('a') => dialog.toggle_all(),
+        KeyCode::Enter => {
+            app.push_checked_bookmarks()
+                .context("failed to execute jj git push")?;
+        }
         _ => {}
     }
     Ok(())
@@ -137,6 +487,9 @@ pub fn handle_modal_keys(app: &mut App, key: KeyEvent) -> Result<()> {
 
 /// Handle key events in detail view.
 pub fn handle_detail_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    if app.is_yank_pending() {
+        return finish_yank(app, key);
+    }
     match key.code {
         KeyCode::Char('q') | KeyCode::Esc => {
             app.close_detail();
@@ -163,11 +516,67 @@ pub fn handle_detail_keys(app: &mut App, key: KeyEvent) -> Result<()> {
         KeyCode::Char('d') => {
             app.open_diff_view().context("failed to open diff view")?;
         }
+        // Pick files to split out in the diff view
+        KeyCode::Char('s') => {
+            app.start_split().context("failed to open diff view")?;
+        }
+        // Full diff below the file summary, with collapsible files
+        KeyCode::Char('D') => {
+            app.toggle_detail_diff().context("failed to load diff")?;
+        }
+        KeyCode::Char(']') => {
+            app.detail_next_file();
+        }
+        KeyCode::Char('[') => {
+            app.detail_previous_file();
+        }
+        KeyCode::Char('z') => {
+            app.toggle_detail_file_collapse();
+        }
+        KeyCode::Char('Z') => {
+            app.toggle_detail_collapse_all();
+        }
+        KeyCode::Char('y') => {
+            app.start_yank();
+        }
+        // Drill down through the parents and children
+        KeyCode::Tab => {
+            app.detail_select_next_related();
+        }
+        KeyCode::BackTab => {
+            app.detail_select_previous_related();
+        }
+        KeyCode::Enter => {
+            app.open_related_detail()
+                .context("failed to open detail view")?;
+        }
+        KeyCode::Char('B') => {
+            app.open_on_forge().context("failed to list git remotes")?;
+        }
+        // jj sign / jj unsign (signing must be configured in jj)
+        KeyCode::Char('S') => {
+            app.execute_sign();
+        }
+        KeyCode::Char('U') => {
+            app.execute_unsign();
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
         _ => {}
     }
     Ok(())
 }
 
+/// Handle the key after `y`, copying the picked text to the clipboard.
+fn finish_yank(app: &mut App, key: KeyEvent) -> Result<()> {
+    let ch = match key.code {
+        KeyCode::Char(ch) => Some(ch),
+        _ => None,
+    };
+    app.finish_yank(ch).context("failed to copy to clipboard")
+}
+
 /// Handle key events in diff view.
 pub fn handle_diff_keys(app: &mut App, key: KeyEvent) -> Result<()> {
     match key.code {
@@ -201,45 +610,466 @@ pub fn handle_diff_keys(app: &mut App, key: KeyEvent) -> Result<()> {
         KeyCode::Left => {
             app.diff_scroll_left(8);
         }
+        // Mark files, then jj split or jj squash them (with confirmation)
+        KeyCode::Char(' ') => {
+            app.toggle_file_mark().context("failed to refresh diff")?;
+        }
+        KeyCode::Char('s') => {
+            app.show_split_confirm();
+        }
+        KeyCode::Char('S') => {
+            app.show_squash_files_confirm();
+        }
+        // jj restore the marked files into the working copy (with confirmation)
+        KeyCode::Char('r') => {
+            app.show_restore_files_confirm();
+        }
+        // History of the selected file
+        KeyCode::Char('h') => {
+            app.open_file_history()
+                .context("failed to load file history")?;
+        }
+        // The selected file in the external diff tool (terminal released)
+        KeyCode::Char('t') => {
+            app.open_diff_tool();
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in operation log view.
+pub fn handle_op_log_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.close_op_log();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.op_log_select_next();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.op_log_select_previous();
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.op_log_select_first();
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.op_log_select_last();
+        }
+        // jj op restore (with confirmation)
+        KeyCode::Enter | KeyCode::Char('r') => {
+            app.show_op_restore_confirm();
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in the tags view.
+pub fn handle_tags_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.close_tags();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.tags_select_next();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.tags_select_previous();
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.tags_select_first();
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.tags_select_last();
+        }
+        KeyCode::Enter => {
+            app.open_tag_detail()
+                .context("failed to open detail view")?;
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in the stacks view.
+pub fn handle_stacks_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.close_stacks();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.stacks_select_next();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.stacks_select_previous();
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.stacks_select_first();
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.stacks_select_last();
+        }
+        KeyCode::Enter => {
+            app.open_stack_detail()
+                .context("failed to open detail view")?;
+        }
+        // jj rebase -b <head> -d trunk() (with confirmation)
+        KeyCode::Char('r') => {
+            app.show_rebase_stack_confirm()
+                .context("failed to execute jj rebase")?;
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in the workspaces view.
+pub fn handle_workspaces_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.close_workspaces();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.workspaces_select_next();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.workspaces_select_previous();
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.workspaces_select_first();
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.workspaces_select_last();
+        }
+        KeyCode::Enter => {
+            app.open_workspace_detail()
+                .context("failed to open detail view")?;
+        }
+        // jj workspace add (path input)
+        KeyCode::Char('a') => {
+            app.start_workspace_add();
+        }
+        // jj workspace forget (with confirmation)
+        KeyCode::Char('x') => {
+            app.show_workspace_forget_confirm()
+                .context("failed to execute jj workspace forget")?;
+        }
+        KeyCode::Char('u') => {
+            app.execute_workspace_update_stale();
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in the sparse patterns view.
+pub fn handle_sparse_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.close_sparse();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.sparse_select_next();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.sparse_select_previous();
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.sparse_select_first();
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.sparse_select_last();
+        }
+        // jj sparse set --add (path input)
+        KeyCode::Char('a') => {
+            app.start_sparse_add();
+        }
+        // jj sparse set --remove (with confirmation)
+        KeyCode::Char('x') => {
+            app.show_sparse_remove_confirm()
+                .context("failed to execute jj sparse set")?;
+        }
+        // jj sparse reset (with confirmation)
+        KeyCode::Char('r') => {
+            app.show_sparse_reset_confirm()
+                .context("failed to execute jj sparse reset")?;
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events while the output pane is open.
+fn handle_output_pane_keys(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('o') | KeyCode::Char('q') | KeyCode::Esc => app.toggle_output_pane(),
+        KeyCode::Char('j') | KeyCode::Down => app.output_scroll_down(1),
+        KeyCode::Char('k') | KeyCode::Up => app.output_scroll_up(1),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.output_scroll_down(10);
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.output_scroll_up(10);
+        }
+        KeyCode::PageDown => app.output_scroll_down(10),
+        KeyCode::PageUp => app.output_scroll_up(10),
+        _ => {}
+    }
+}
+
+/// Handle key events in the message history panel.
+fn handle_messages_panel_keys(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('L') | KeyCode::Char('q') | KeyCode::Esc => app.toggle_messages_panel(),
+        KeyCode::Char('j') | KeyCode::Down => app.messages_scroll_down(1),
+        KeyCode::Char('k') | KeyCode::Up => app.messages_scroll_up(1),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.messages_scroll_down(10);
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.messages_scroll_up(10);
+        }
+        KeyCode::PageDown => app.messages_scroll_down(10),
+        KeyCode::PageUp => app.messages_scroll_up(10),
+        _ => {}
+    }
+}
+
+/// Handle key events in the command history panel.
+fn handle_command_history_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('H') | KeyCode::Char('q') | KeyCode::Esc => app.toggle_command_history(),
+        KeyCode::Char('j') | KeyCode::Down => app.command_history_select_next(),
+        KeyCode::Char('k') | KeyCode::Up => app.command_history_select_previous(),
+        KeyCode::Char('g') | KeyCode::Home => app.command_history_select_first(),
+        KeyCode::Char('G') | KeyCode::End => app.command_history_select_last(),
+        KeyCode::Enter => {
+            app.rerun_selected_command()
+                .context("failed to run the command again")?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle key events in file history view.
+pub fn handle_file_history_keys(app: &mut App, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.close_file_history();
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.file_history_select_next();
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.file_history_select_previous();
+        }
+        KeyCode::Char('g') | KeyCode::Home => {
+            app.file_history_select_first();
+        }
+        KeyCode::Char('G') | KeyCode::End => {
+            app.file_history_select_last();
+        }
+        KeyCode::Enter => {
+            app.open_file_history_detail()
+                .context("failed to open detail view")?;
+        }
+        KeyCode::Char('R') | KeyCode::F(5) => {
+            app.reload_all().context("failed to refresh")?;
+        }
         _ => {}
     }
     Ok(())
 }
 
+/// Handle the keys that scroll or close the help overlay.
+///
+/// Returns `false` for other keys, which close the help and run as usual.
+fn handle_help_keys(app: &mut App, key: KeyEvent) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_help(),
+        KeyCode::Char('j') | KeyCode::Down => app.help_scroll_down(1),
+        KeyCode::Char('k') | KeyCode::Up => app.help_scroll_up(1),
+        KeyCode::Char('d') if ctrl => app.help_scroll_down(10),
+        KeyCode::Char('u') if ctrl => app.help_scroll_up(10),
+        KeyCode::PageDown => app.help_scroll_down(10),
+        KeyCode::PageUp => app.help_scroll_up(10),
+        KeyCode::Char('g') | KeyCode::Home => app.help_scroll_up(usize::MAX),
+        KeyCode::Char('G') | KeyCode::End => app.help_scroll_down(usize::MAX),
+        _ => return false,
+    }
+    true
+}
+
 /// Dispatch key event to appropriate handler based on app state.
 ///
 /// Returns `true` if the event was fully handled (e.g., help toggle),
 /// meaning the caller should `continue` the event loop.
 pub fn dispatch_key_event(app: &mut App, key: KeyEvent, event: &Event) -> Result<bool> {
+    app.record_macro_key(key);
+
     // Handle ? key globally for help toggle
     if key.code == KeyCode::Char('?') {
         app.toggle_help();
         return Ok(true);
     }
 
-    // If help is showing, close it and execute the command
+    // If help is showing, scroll it, or close it and execute the command
     if app.show_help {
-        if key.code == KeyCode::Esc {
-            app.close_help();
+        if handle_help_keys(app, key) {
             return Ok(true);
         }
         // Close help and fall through to execute the command
         app.close_help();
     }
 
+    // The output pane captures keys while it is open
+    if app.is_output_pane_open() {
+        handle_output_pane_keys(app, key);
+        return Ok(true);
+    }
+
+    // So does the command history panel
+    if app.is_command_history_open() {
+        handle_command_history_keys(app, key)?;
+        return Ok(true);
+    }
+
+    // And the message history
+    if app.is_messages_panel_open() {
+        handle_messages_panel_keys(app, key);
+        return Ok(true);
+    }
+
     // Modal dialog takes highest priority
     if app.is_modal_open() {
         handle_modal_keys(app, key)?;
     } else if app.is_input_mode() {
         // Input mode takes second priority
         handle_input_keys(app, key, event)?;
+    } else if app.is_macro_prompt_pending() {
+        app.finish_macro_prompt(key);
+    } else if key.code == KeyCode::Char('Q') && !app.is_yank_pending() {
+        // Record a macro into a register (Q again stops), from every view
+        app.toggle_macro_recording();
+    } else if key.code == KeyCode::Char('@') && !app.is_yank_pending() {
+        app.start_macro_replay();
+    } else if key.code == KeyCode::Char('o') && !app.is_yank_pending() {
+        // Output of recent commands, from every view
+        app.toggle_output_pane();
+    } else if key.code == KeyCode::Char('H') && !app.is_yank_pending() {
+        // Every jj command run this session, from every view
+        app.toggle_command_history();
+    } else if key.code == KeyCode::Char('L') && !app.is_yank_pending() {
+        // Status bar messages of this session, from every view
+        app.toggle_messages_panel();
     } else {
         match app.view {
             View::Log => handle_log_keys(app, key)?,
             View::Detail => handle_detail_keys(app, key)?,
             View::Diff => handle_diff_keys(app, key)?,
+            View::OpLog => handle_op_log_keys(app, key)?,
+            View::FileHistory => handle_file_history_keys(app, key)?,
+            View::Workspaces => handle_workspaces_keys(app, key)?,
+            View::Tags => handle_tags_keys(app, key)?,
+            View::Stacks => handle_stacks_keys(app, key)?,
+            View::Sparse => handle_sparse_keys(app, key)?,
         }
     }
 
     Ok(false)
 }
+
+/// Dispatch mouse event to the appropriate handler.
+///
+/// In the log view, clicking a row selects that commit and right-clicking
+/// also opens its context menu; clicking `~  (elided revisions)` expands it. The wheel scrolls every view, and clicks on
+/// a modal's buttons or menu items activate them.
+pub fn dispatch_mouse_event(app: &mut App, mouse: MouseEvent) -> Result<()> {
+    if app.is_input_mode() {
+        return Ok(());
+    }
+    if app.show_help {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => app.help_scroll_down(3),
+            MouseEventKind::ScrollUp => app.help_scroll_up(3),
+            _ => {}
+        }
+        return Ok(());
+    }
+    if app.is_output_pane_open() {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => app.output_scroll_down(3),
+            MouseEventKind::ScrollUp => app.output_scroll_up(3),
+            _ => {}
+        }
+        return Ok(());
+    }
+    if app.is_command_history_open() {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => app.command_history_select_next(),
+            MouseEventKind::ScrollUp => app.command_history_select_previous(),
+            _ => {}
+        }
+        return Ok(());
+    }
+    if app.is_messages_panel_open() {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => app.messages_scroll_down(3),
+            MouseEventKind::ScrollUp => app.messages_scroll_up(3),
+            _ => {}
+        }
+        return Ok(());
+    }
+    if app.is_modal_open() {
+        if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+            app.click_modal(mouse.column, mouse.row)
+                .context("failed to execute action")?;
+        }
+        return Ok(());
+    }
+    match mouse.kind {
+        MouseEventKind::ScrollDown => app.scroll_wheel(true),
+        MouseEventKind::ScrollUp => app.scroll_wheel(false),
+        MouseEventKind::Down(MouseButton::Left) if app.view == View::Log => {
+            match app.elided_line_at_row(mouse.row) {
+                Some(line_idx) => app
+                    .expand_elided_line(line_idx)
+                    .context("failed to expand elided revisions")?,
+                None => {
+                    app.select_at_row(mouse.row);
+                }
+            }
+        }
+        MouseEventKind::Down(MouseButton::Right)
+            if app.view == View::Log && app.select_at_row(mouse.row) =>
+        {
+            app.open_context_menu();
+        }
+        _ => return Ok(()),
+    }
+    if app.view == View::Log {
+        app.request_load_more_check();
+    }
+    Ok(())
+}
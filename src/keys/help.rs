@@ -0,0 +1,452 @@
+//! Key bindings listed in the help overlay, per view.
+//!
+//! Each table mirrors the matching `handle_*_keys` function in `keys.rs`;
+//! add a row here when adding a key there. A test reads the handlers' match
+//! arms and fails when a handled key has no row, or a row no handler.
+
+use crate::app::View;
+
+/// Width of the key column in the help overlay.
+pub const KEY_COLUMN_WIDTH: usize = 11;
+
+/// A titled group of key bindings.
+#[derive(Debug)]
+pub struct HelpSection {
+    /// Section heading.
+    pub title: &'static str,
+    /// `(keys, action)` rows.
+    pub bindings: &'static [(&'static str, &'static str)],
+}
+
+const LOG_NAVIGATION: HelpSection = HelpSection {
+    title: "Navigation",
+    bindings: &[
+        ("j / ↓", "Move down"),
+        ("k / ↑", "Move up"),
+        ("g / Home", "Go to top"),
+        ("G / End", "Go to bottom"),
+        ("Ctrl+d", "Page down"),
+        ("Ctrl+u", "Page up"),
+        ("w", "Toggle wrapped descriptions"),
+        ("v", "Preview pane: detail, then diff, then closed"),
+        ("< / >", "Narrow / widen the log beside the preview"),
+        ("Ctrl+t", "Toggle relative / absolute timestamps"),
+        ("/", "Search descriptions, change IDs, authors, bookmarks"),
+        ("n / N", "Next / previous match (while searching)"),
+        ("Ctrl+p", "Fuzzy-find a commit and jump to it"),
+        ("Alt+p", "Command palette: run any action by name"),
+        ("[ / ]", "Go to the parent / child (follows the graph)"),
+        ("J", "Go to trunk() (marked \"trunk\" in the log)"),
+        ("Enter", "Open detail view"),
+        ("yy/yc/yd", "Copy change ID / commit ID / description"),
+        ("yg", "Copy the git SHA (colocated repositories)"),
+        ("B", "Open commit on its forge in the browser"),
+    ],
+};
+
+const LOG_COMMANDS: HelpSection = HelpSection {
+    title: "jj Commands",
+    bindings: &[
+        ("n", "New change"),
+        ("N", "New change with message"),
+        ("i / I", "Insert new change after/before (I: message)"),
+        ("M", "Commit @ (describe + new, like jj commit)"),
+        ("e", "Edit revision"),
+        ("d", "Describe revision (edits the current message)"),
+        ("D", "Describe in $EDITOR (multi-line)"),
+        ("t", "Describe as type(scope): subject"),
+        ("U", "Set author (empty: reset to you)"),
+        ("b", "Set bookmark"),
+        ("a", "Abandon revision (marked, if any)"),
+        ("X", "Abandon every empty commit in the log"),
+        ("s", "Squash into parent (marked: into selected)"),
+        ("S", "Squash into a commit picked in the log (Enter)"),
+        ("f", "Git fetch"),
+        ("p", "Git push (choose bookmarks)"),
+        ("P", "Export patch (marked: patch series)"),
+        ("u", "Undo last operation"),
+        ("r", "Rebase to destination (marked, if any)"),
+        ("Y", "Duplicate (onto a destination)"),
+        ("R / F5", "Refresh (keeps position)"),
+    ],
+};
+
+const LOG_SELECTION: HelpSection = HelpSection {
+    title: "Marks and Views",
+    bindings: &[
+        ("Space", "Mark / unmark commit"),
+        ("V", "Visual mode: mark a range (V again to mark)"),
+        ("Esc", "Clear marks, search, filters, expansions"),
+        ("A", "Filter the log by an author of the loaded log"),
+        ("F", "Filter the log by a path (file or directory)"),
+        ("E", "Expand the revisions elided below (~)"),
+        ("h", "Show / hide recently hidden commits"),
+        ("c", "Compare with base (2 marked: diff them)"),
+        ("C", "Diff --from/--to revisions"),
+        ("m", "Action menu (also right-click)"),
+        ("O", "Operation log (restore an earlier state)"),
+        ("W", "Workspaces (add, forget, update stale)"),
+        ("T", "Tags (Enter shows the tagged commit)"),
+        ("K", "Stacks on top of trunk (r: rebase onto trunk)"),
+        ("Z", "Sparse patterns (add, remove, reset)"),
+        ("!", "Toggle quick mode (no confirmations)"),
+        ("$", "Shell in the repository root (exit to return)"),
+        (":", "Command line (Tab completes)"),
+        ("q", "Quit"),
+    ],
+};
+
+const DETAIL: HelpSection = HelpSection {
+    title: "Detail View",
+    bindings: &[
+        ("j / k", "Scroll down / up"),
+        ("Ctrl+d/u", "Page down / up"),
+        ("d", "View file diffs"),
+        ("s", "Split: pick files in the diff view"),
+        ("D", "Show / hide the full diff"),
+        ("] / [", "Next / previous file in the full diff"),
+        ("z / Z", "Collapse / expand file (Z: all files)"),
+        ("S / U", "Sign / unsign the revision"),
+        ("Tab/S-Tab", "Select a parent / child"),
+        ("Enter", "Open the selected parent / child"),
+        ("yy/yc/yd", "Copy change ID / commit ID / description"),
+        ("yg", "Copy the git SHA (colocated repositories)"),
+        ("B", "Open commit on its forge in the browser"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const DIFF: HelpSection = HelpSection {
+    title: "Diff View",
+    bindings: &[
+        ("j / ↓", "Select next file"),
+        ("k / ↑", "Select previous file"),
+        ("Ctrl+d/u", "Scroll diff vertically"),
+        ("← / →", "Scroll diff horizontally"),
+        ("Space", "Mark / unmark file for split or squash"),
+        ("s", "Split marked files into a new commit"),
+        ("S", "Squash marked files into the parent"),
+        ("r", "Restore marked files into the working copy"),
+        ("h", "History of the selected file"),
+        ("t", "Open the file in the diff tool (diff.tool)"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const OP_LOG: HelpSection = HelpSection {
+    title: "Operation Log",
+    bindings: &[
+        ("j / k", "Select older / newer operation"),
+        ("g / G", "First / last operation"),
+        ("Enter / r", "Restore repository to operation"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const FILE_HISTORY: HelpSection = HelpSection {
+    title: "File History",
+    bindings: &[
+        ("j / k", "Select newer / older commit"),
+        ("g / G", "Newest / oldest commit"),
+        ("Enter", "Open commit in the detail view"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const WORKSPACES: HelpSection = HelpSection {
+    title: "Workspaces",
+    bindings: &[
+        ("j / k", "Select workspace"),
+        ("g / G", "First / last workspace"),
+        ("Enter", "Show the working-copy commit"),
+        ("a", "Add a workspace (path input)"),
+        ("x", "Forget the selected workspace"),
+        ("u", "Update the stale current workspace"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const TAGS: HelpSection = HelpSection {
+    title: "Tags",
+    bindings: &[
+        ("j / k", "Select tag"),
+        ("g / G", "First / last tag"),
+        ("Enter", "Show the tagged commit"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const STACKS: HelpSection = HelpSection {
+    title: "Stacks",
+    bindings: &[
+        ("j / k", "Select stack"),
+        ("g / G", "First / last stack"),
+        ("Enter", "Show the head of the stack"),
+        ("r", "Rebase the whole stack onto trunk()"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const SPARSE: HelpSection = HelpSection {
+    title: "Sparse Patterns",
+    bindings: &[
+        ("j / k", "Select pattern"),
+        ("g / G", "First / last pattern"),
+        ("a", "Add a pattern (path input)"),
+        ("x", "Remove the selected pattern"),
+        ("r", "Reset: check out every file"),
+        ("R / F5", "Refresh"),
+        ("q / Esc", "Back"),
+    ],
+};
+
+const GENERAL: HelpSection = HelpSection {
+    title: "General",
+    bindings: &[
+        ("o", "Full output of recent commands"),
+        ("H", "History of every jj command (Enter runs it again)"),
+        ("L", "Status bar messages"),
+        ("Q<a-z>", "Record a macro (Q stops)"),
+        ("@<a-z>", "Replay a macro"),
+        ("?", "Toggle this help"),
+    ],
+};
+
+/// Help sections for a view: its own bindings first, then the global ones.
+pub fn help_sections(view: View) -> Vec<&'static HelpSection> {
+    let own: &[&'static HelpSection] = match view {
+        View::Log => &[&LOG_NAVIGATION, &LOG_COMMANDS, &LOG_SELECTION],
+        View::Detail => &[&DETAIL],
+        View::Diff => &[&DIFF],
+        View::OpLog => &[&OP_LOG],
+        View::FileHistory => &[&FILE_HISTORY],
+        View::Workspaces => &[&WORKSPACES],
+        View::Tags => &[&TAGS],
+        View::Stacks => &[&STACKS],
+        View::Sparse => &[&SPARSE],
+    };
+    own.iter().copied().chain([&GENERAL]).collect()
+}
+
+/// Name of a view in the help overlay's title.
+pub fn view_name(view: View) -> &'static str {
+    match view {
+        View::Log => "Log",
+        View::Detail => "Detail",
+        View::Diff => "Diff",
+        View::OpLog => "Operation Log",
+        View::FileHistory => "File History",
+        View::Workspaces => "Workspaces",
+        View::Tags => "Tags",
+        View::Stacks => "Stacks",
+        View::Sparse => "Sparse Patterns",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::{BTreeSet, HashSet};
+    use unicode_width::UnicodeWidthStr;
+
+    const KEYS_SOURCE: &str = include_str!("../keys.rs");
+
+    /// Keys that need no row of their own: they do what the listed key does.
+    const ALIASES: &[(&str, &str)] = &[
+        ("Down", "j"),
+        ("Up", "k"),
+        ("Home", "g"),
+        ("End", "G"),
+        ("PageDown", "Ctrl+d"),
+        ("PageUp", "Ctrl+u"),
+        ("F5", "R"),
+    ];
+
+    /// Body of the function `name` in `keys.rs`.
+    fn handler_body(name: &str) -> &'static str {
+        let start = KEYS_SOURCE
+            .find(&format!("fn {name}("))
+            .unwrap_or_else(|| panic!("{name} not found in keys.rs"));
+        let body = &KEYS_SOURCE[start..];
+        &body[..body.find("\n}\n").unwrap()]
+    }
+
+    /// Keys in `pattern`, prefixed with the modifier its guard requires.
+    fn pattern_keys(pattern: &str, keys: &mut BTreeSet<String>) {
+        let (pattern, guard) = pattern.split_once(" if ").unwrap_or((pattern, ""));
+        let modifier = if guard.contains("KeyModifiers::ALT") {
+            "Alt+"
+        } else if guard.contains("KeyModifiers::CONTROL") {
+            "Ctrl+"
+        } else {
+            ""
+        };
+        let code = Regex::new(r"KeyCode::(?:Char\(([^)]*)\)|F\((\d+)\)|(\w+))").unwrap();
+        let ch = Regex::new(r"'(.)'").unwrap();
+        for caps in code.captures_iter(pattern) {
+            if let Some(chars) = caps.get(1) {
+                for c in ch.captures_iter(chars.as_str()) {
+                    let c = &c[1];
+                    keys.insert(match (modifier, c) {
+                        ("", " ") => "Space".to_string(),
+                        ("", c) => c.to_string(),
+                        (modifier, c) => format!("{modifier}{}", c.to_lowercase()),
+                    });
+                }
+            } else if let Some(n) = caps.get(2) {
+                keys.insert(format!("F{}", n.as_str()));
+            } else {
+                keys.insert(caps[3].to_string());
+            }
+        }
+    }
+
+    /// Keys matched by the arms of the handler `name`.
+    fn handled_keys(name: &str) -> BTreeSet<String> {
+        let mut keys = BTreeSet::new();
+        let mut arm: Option<String> = None;
+        for line in handler_body(name).lines().map(str::trim) {
+            if arm.is_none() && line.starts_with("KeyCode::") {
+                arm = Some(String::new());
+            }
+            if let Some(pattern) = arm.as_mut() {
+                pattern.push_str(line);
+                pattern.push(' ');
+                if line.contains("=>") {
+                    pattern_keys(pattern.split("=>").next().unwrap(), &mut keys);
+                    arm = None;
+                }
+            }
+        }
+        without_aliases(keys)
+    }
+
+    fn without_aliases(mut keys: BTreeSet<String>) -> BTreeSet<String> {
+        for (alias, key) in ALIASES {
+            if keys.contains(*key) {
+                keys.remove(*alias);
+            }
+        }
+        keys
+    }
+
+    /// Keys a help row lists: `"Ctrl+d/u"` is Ctrl+d and Ctrl+u, `"yy/yc"`
+    /// and `"Q<a-z>"` start with y and Q.
+    fn row_keys(label: &str) -> Vec<String> {
+        let mut keys = Vec::new();
+        let mut modifier = "";
+        for part in label.split(" / ") {
+            let tokens: Vec<&str> = if part.len() > 1 {
+                part.split('/').collect()
+            } else {
+                vec![part]
+            };
+            for token in tokens {
+                let token = token.trim_end_matches("<a-z>");
+                let key = match token {
+                    "↓" => "Down".to_string(),
+                    "↑" => "Up".to_string(),
+                    "←" => "Left".to_string(),
+                    "→" => "Right".to_string(),
+                    "S-Tab" => "BackTab".to_string(),
+                    _ if token.starts_with("Ctrl+") || token.starts_with("Alt+") => {
+                        modifier = &token[..token.find('+').unwrap() + 1];
+                        token.to_string()
+                    }
+                    _ if token.chars().count() == 1 && !modifier.is_empty() => {
+                        format!("{modifier}{token}")
+                    }
+                    _ if token.len() == 2 && token.chars().all(|c| c.is_ascii_lowercase()) => {
+                        token[..1].to_string()
+                    }
+                    _ => token.to_string(),
+                };
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    fn listed_keys(sections: &[&HelpSection]) -> BTreeSet<String> {
+        without_aliases(
+            sections
+                .iter()
+                .flat_map(|section| section.bindings)
+                .flat_map(|(label, _)| row_keys(label))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_row_keys() {
+        assert_eq!(row_keys("j / ↓"), ["j", "Down"]);
+        assert_eq!(row_keys("Ctrl+d/u"), ["Ctrl+d", "Ctrl+u"]);
+        assert_eq!(row_keys("yy/yc/yd"), ["y", "y", "y"]);
+        assert_eq!(row_keys("Tab/S-Tab"), ["Tab", "BackTab"]);
+        assert_eq!(row_keys("Q<a-z>"), ["Q"]);
+        assert_eq!(row_keys("/"), ["/"]);
+    }
+
+    #[test]
+    fn test_every_handled_key_has_a_help_row() {
+        for (view, handler) in [
+            (View::Log, "handle_log_keys"),
+            (View::Detail, "handle_detail_keys"),
+            (View::Diff, "handle_diff_keys"),
+            (View::OpLog, "handle_op_log_keys"),
+            (View::FileHistory, "handle_file_history_keys"),
+            (View::Workspaces, "handle_workspaces_keys"),
+            (View::Tags, "handle_tags_keys"),
+            (View::Stacks, "handle_stacks_keys"),
+            (View::Sparse, "handle_sparse_keys"),
+        ] {
+            let sections = help_sections(view);
+            let (general, own) = sections.split_last().unwrap();
+            let (handled, listed) = (handled_keys(handler), listed_keys(own));
+            let missing: Vec<_> = handled.difference(&listed).collect();
+            assert!(missing.is_empty(), "{view:?}: no help row for {missing:?}");
+            let stale: Vec<_> = listed.difference(&handled).collect();
+            assert!(stale.is_empty(), "{view:?}: {stale:?} not handled");
+            assert!(listed.is_disjoint(&listed_keys(&[general])), "{view:?}");
+        }
+
+        // Global keys are checked with `key.code == KeyCode::Char(..)`
+        let global: BTreeSet<String> = Regex::new(r"key\.code == KeyCode::Char\('(.)'\)")
+            .unwrap()
+            .captures_iter(handler_body("dispatch_key_event"))
+            .map(|caps| caps[1].to_string())
+            .collect();
+        assert_eq!(global, listed_keys(&[&GENERAL]));
+    }
+
+    #[test]
+    fn test_help_sections_fit_and_do_not_repeat_keys() {
+        for view in [
+            View::Log,
+            View::Detail,
+            View::Diff,
+            View::OpLog,
+            View::FileHistory,
+            View::Workspaces,
+            View::Tags,
+            View::Stacks,
+            View::Sparse,
+        ] {
+            let sections = help_sections(view);
+            assert_eq!(sections.last().unwrap().title, "General");
+            let mut seen = HashSet::new();
+            for (keys, _) in sections.iter().flat_map(|section| section.bindings) {
+                assert!(keys.width() < KEY_COLUMN_WIDTH, "{keys}");
+                assert!(seen.insert(*keys), "{keys} listed twice in {view:?}");
+            }
+        }
+    }
+}
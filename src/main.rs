@@ -1,57 +1,237 @@
 //! xorcist - A TUI client for jj (Jujutsu VCS).
 
 mod app;
+mod clipboard;
+mod config;
 mod conventional;
+mod editor;
 mod error;
+mod export;
+mod external;
+mod forge;
+mod highlight;
 mod jj;
+mod json;
 mod keys;
+mod profile;
 mod text;
+mod theme;
 mod ui;
+mod word_diff;
 
 use std::env;
+use std::io::stdout;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use crossterm::event::{self, Event, KeyEventKind};
+use clap::{Parser, Subcommand};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
+};
+use crossterm::execute;
 
-use app::App;
+use app::{App, CommandResult};
+use config::Config;
 use error::XorcistError;
-use jj::{JjRunner, fetch_graph_log, find_jj_repo};
+use export::{ExportFormat, export_detail, export_log};
+use jj::{
+    GraphLog, JjRepo, JjRunner, Journal, LineStream, MAX_UNPARSED_PERCENT, MINIMAL_LOG_TEMPLATE,
+    fetch_graph_log, fetch_show, find_jj_repo, stream_graph_log, version::compatibility_warning,
+};
+use profile::Profiler;
+use theme::Theme;
 
 /// A TUI client for jj (Jujutsu VCS).
 #[derive(Parser, Debug)]
 #[command(name = "xor", version, about)]
 struct Args {
-    /// Maximum number of log entries to load.
+    /// Maximum number of log entries to load (default: 500, or `log.limit` in the config file).
     /// Use --all to load the entire history.
-    #[arg(short = 'n', long, default_value = "500")]
-    limit: usize,
+    #[arg(short = 'n', long, global = true)]
+    limit: Option<usize>,
 
     /// Load all history (may be slow on large repositories).
-    #[arg(long)]
+    #[arg(long, global = true)]
     all: bool,
+
+    /// Refresh the log every N seconds while idle (default: `startup.refresh`
+    /// in the config file, or 0, which disables auto-refresh).
+    #[arg(long, value_name = "SECONDS")]
+    refresh: Option<u64>,
+
+    /// Show only the revisions in this revset (same as `:revset` in the TUI;
+    /// default: `log.revset` in the config file).
+    #[arg(short = 'r', long, value_name = "REVSET", global = true)]
+    revisions: Option<String>,
+
+    /// Print the log to stdout instead of starting the TUI.
+    #[arg(long)]
+    print: bool,
+
+    /// Output format for --print / --output.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Text, global = true)]
+    format: ExportFormat,
+
+    /// Write the log to FILE instead of starting the TUI (implies --print).
+    #[arg(short = 'o', long, value_name = "FILE", global = true)]
+    output: Option<PathBuf>,
+
+    /// Print the details and changed files of one revision (implies --print).
+    #[arg(long, value_name = "REVISION")]
+    show: Option<String>,
+
+    /// Record render, jj command and parse timings; show them in an overlay
+    /// and print a summary on exit.
+    #[arg(long)]
+    profile: bool,
+
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+/// Non-interactive subcommands, for scripts.
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// Print the log instead of starting the TUI (same as --print).
+    Log {
+        /// Print structured JSON (same as --format json).
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the details and changed files of one revision (same as --show).
+    Show {
+        /// Revision to show.
+        #[arg(default_value = "@")]
+        revision: String,
+
+        /// Print structured JSON (same as --format json).
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+impl Args {
+    /// Parse the command line, folding a subcommand into the equivalent flags.
+    fn parse_command_line() -> Self {
+        let mut args = Self::parse();
+        let json = match args.command.take() {
+            Some(CliCommand::Log { json }) => {
+                args.print = true;
+                json
+            }
+            Some(CliCommand::Show { revision, json }) => {
+                args.show = Some(revision);
+                json
+            }
+            None => false,
+        };
+        if json {
+            args.format = ExportFormat::Json;
+        }
+        args
+    }
+
+    /// Whether to print output instead of starting the TUI.
+    fn is_print_mode(&self) -> bool {
+        self.print || self.output.is_some() || self.show.is_some()
+    }
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let args = Args::parse_command_line();
 
     // Find jj repository
     let current_dir = env::current_dir().context("failed to get current directory")?;
     let repo = find_jj_repo(&current_dir).ok_or(XorcistError::NotInRepo)?;
+    let config = Config::load_for_repo(&repo.root)?;
 
     // Create runner and fetch log
-    let runner = JjRunner::new().with_work_dir(&repo.root);
+    let mut runner = JjRunner::new().with_work_dir(&repo.root);
+    if config.journal.enabled {
+        runner = runner.with_journal(Journal::for_repo(&repo.root));
+    }
+    if let Some(template) = &config.log.template {
+        runner = runner.with_log_template(template.clone());
+    }
+    runner = runner.with_timestamp_format(config.log.timestamps);
+    let profiler = args.profile.then(Profiler::new);
+    if let Some(profiler) = &profiler {
+        runner = runner.with_profiler(profiler.clone());
+    }
+
+    // Check that jj is available; features it lacks fall back (see jj::version)
+    let version = runner.probe_version()?;
+    runner = runner.with_version(version);
+
+    // Determine limit: --all overrides --limit, which overrides the config file
+    let limit = if args.all {
+        None
+    } else {
+        Some(args.limit.unwrap_or(config.log.limit))
+    };
 
-    // Check if jj is available
-    if !runner.is_available() {
-        return Err(XorcistError::JjNotFound.into());
+    // An empty -r shows jj's default log even if the config file sets a revset
+    let revset = args
+        .revisions
+        .clone()
+        .or_else(|| config.log.revset.clone())
+        .filter(|r| !r.trim().is_empty());
+
+    let result = if args.is_print_mode() {
+        // Non-interactive output
+        run_print(&args, &runner, revset.as_deref(), limit)
+    } else {
+        run_app(&args, &config, &repo, runner, revset, limit)
+    };
+
+    if let Some(profiler) = &profiler {
+        eprint!("{}", profiler.summary());
     }
+    result
+}
+
+/// Load the log and run the TUI.
+fn run_app(
+    args: &Args,
+    config: &Config,
+    repo: &JjRepo,
+    runner: JjRunner,
+    revset: Option<String>,
+    limit: Option<usize>,
+) -> Result<()> {
+    let mut terminal = ratatui::init();
+    // Mouse support is optional; ignore terminals without mouse capture
+    let _ = execute!(stdout(), EnableMouseCapture);
+
+    let result = start_app(&mut terminal, args, config, repo, runner, revset, limit);
 
-    // Determine limit: --all overrides --limit
-    let limit = if args.all { None } else { Some(args.limit) };
+    let _ = execute!(stdout(), DisableMouseCapture);
+    ratatui::restore();
+
+    result
+}
 
-    // Fetch graph log
-    let graph_log = fetch_graph_log(&runner, limit).context("failed to fetch jj log")?;
+/// Load the log into a new App and run the event loop.
+fn start_app(
+    terminal: &mut ratatui::DefaultTerminal,
+    args: &Args,
+    config: &Config,
+    repo: &JjRepo,
+    mut runner: JjRunner,
+    revset: Option<String>,
+    limit: Option<usize>,
+) -> Result<()> {
+    let (graph_log, stream, fell_back) = load_startup_log(
+        terminal,
+        &mut runner,
+        revset.as_deref(),
+        limit,
+        &config.theme,
+    )?;
+    let (graph_log, stream, log_warning) =
+        check_log_parsing(&mut runner, (graph_log, stream), revset.as_deref(), limit);
+    let version_warning = runner.version().and_then(compatibility_warning);
 
     // Create app state
     let repo_root_display = repo
@@ -61,30 +241,229 @@ fn main() -> Result<()> {
         .unwrap_or_else(|| repo.root.to_string_lossy().to_string());
 
     let mut app = App::new(graph_log, repo_root_display, runner);
+    app.set_initial_revset(revset);
     app.set_log_limit(limit);
+    if let Some(stream) = stream {
+        app.start_log_stream(stream);
+    }
+    app.apply_log_config(&config.log);
+    app.apply_confirm_config(&config.confirm);
+    app.apply_diff_config(&config.diff);
+    app.apply_push_config(&config.push);
+    app.set_theme(config.theme);
+    app.refresh_metadata();
+    app.set_op_heads_dir(repo.op_heads_dir());
+    app.set_colocated(repo.colocated);
+    let refresh = args.refresh.unwrap_or(config.startup.refresh);
+    app.set_auto_refresh_interval(Some(Duration::from_secs(refresh)));
+    let warning = if fell_back {
+        Some("Using the minimal log template (no tags, long timestamps)".to_string())
+    } else {
+        version_warning
+    };
+    // The parsing warning is reported last, so it is the one left on screen
+    for message in [warning, log_warning].into_iter().flatten() {
+        app.report(CommandResult {
+            success: false,
+            message,
+        });
+    }
 
-    // Run TUI
-    run_tui(app)
+    app.open_startup_view(config.startup.view);
+
+    run_event_loop(terminal, &mut app)
 }
 
-/// Run the TUI application.
-fn run_tui(mut app: App) -> Result<()> {
-    let mut terminal = ratatui::init();
+/// Fetch the first log entries (all of history is read while the UI already
+/// runs), showing the error screen until it works or the user quits.
+///
+/// Returns whether the minimal log template had to be used.
+fn load_startup_log(
+    terminal: &mut ratatui::DefaultTerminal,
+    runner: &mut JjRunner,
+    revset: Option<&str>,
+    limit: Option<usize>,
+    theme: &Theme,
+) -> Result<(GraphLog, Option<LineStream>, bool)> {
+    let mut minimal = false;
+    loop {
+        let error = match load_log(runner, revset, limit).context("failed to fetch jj log") {
+            Ok((graph_log, stream)) => return Ok((graph_log, stream, minimal)),
+            Err(error) => error,
+        };
+        match startup_error_screen(terminal, &error, !minimal, theme)? {
+            StartupChoice::Retry => {}
+            StartupChoice::Minimal => {
+                runner.set_log_template(MINIMAL_LOG_TEMPLATE.to_string());
+                minimal = true;
+            }
+            StartupChoice::Quit => return Err(error),
+        }
+    }
+}
 
-    let result = run_event_loop(&mut terminal, &mut app);
+/// Fetch the first `limit` log entries, or start streaming all of them.
+fn load_log(
+    runner: &JjRunner,
+    revset: Option<&str>,
+    limit: Option<usize>,
+) -> Result<(GraphLog, Option<LineStream>), XorcistError> {
+    match limit {
+        Some(_) => fetch_graph_log(runner, revset, limit).map(|log| (log, None)),
+        None => stream_graph_log(runner, revset).map(|stream| (GraphLog::default(), Some(stream))),
+    }
+}
 
-    ratatui::restore();
+/// Commits fetched to check a streamed log, whose lines are not read yet.
+const LOG_CHECK_SAMPLE: usize = 50;
 
-    result
+/// Check that the startup log parses. If too much of it does not (a custom
+/// `log.template` or jj settings such as ASCII node symbols), load it again
+/// with xorcist's template and the jj settings it relies on forced (see
+/// [`JjRunner::force_builtin_log`]).
+///
+/// Returns the log to use and a warning for the status bar.
+fn check_log_parsing(
+    runner: &mut JjRunner,
+    loaded: (GraphLog, Option<LineStream>),
+    revset: Option<&str>,
+    limit: Option<usize>,
+) -> (GraphLog, Option<LineStream>, Option<String>) {
+    let unparsed = unparsed_percent(runner, &loaded, revset);
+    if unparsed <= MAX_UNPARSED_PERCENT {
+        return (loaded.0, loaded.1, None);
+    }
+    let mut forced = runner.clone();
+    forced.force_builtin_log();
+    let reloaded = match load_log(&forced, revset, limit) {
+        Ok(reloaded) => reloaded,
+        Err(e) => {
+            let message =
+                format!("{unparsed}% of the log did not parse, and reloading it failed: {e}");
+            return (loaded.0, loaded.1, Some(message));
+        }
+    };
+    *runner = forced;
+    let message = if unparsed_percent(runner, &reloaded, revset) <= MAX_UNPARSED_PERCENT {
+        format!(
+            "{unparsed}% of the log did not parse; using xorcist's log template and jj settings"
+        )
+    } else {
+        format!(
+            "{unparsed}% of the log did not parse, even with xorcist's log template and jj settings"
+        )
+    };
+    (reloaded.0, reloaded.1, Some(message))
+}
+
+/// [`GraphLog::unparsed_percent`] of a loaded log, or of a sample of it
+/// while it is streamed (0 if the sample cannot be fetched).
+fn unparsed_percent(
+    runner: &JjRunner,
+    (graph_log, stream): &(GraphLog, Option<LineStream>),
+    revset: Option<&str>,
+) -> usize {
+    if stream.is_none() {
+        return graph_log.unparsed_percent();
+    }
+    fetch_graph_log(runner, revset, Some(LOG_CHECK_SAMPLE))
+        .map_or(0, |sample| sample.unparsed_percent())
+}
+
+/// What to do after the log failed to load at startup.
+enum StartupChoice {
+    Retry,
+    /// Retry with [`MINIMAL_LOG_TEMPLATE`].
+    Minimal,
+    Quit,
+}
+
+/// Show a startup error until the user picks what to do next.
+fn startup_error_screen(
+    terminal: &mut ratatui::DefaultTerminal,
+    error: &anyhow::Error,
+    can_fall_back: bool,
+    theme: &Theme,
+) -> Result<StartupChoice> {
+    // `{:?}` lists every cause, including jj's own message
+    let message = format!("{error:?}");
+    loop {
+        terminal.draw(|frame| ui::render_startup_error(frame, &message, can_fall_back, theme))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('r') => return Ok(StartupChoice::Retry),
+                KeyCode::Char('m') if can_fall_back => return Ok(StartupChoice::Minimal),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(StartupChoice::Quit),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Print the log (or one revision with `--show`) instead of starting the TUI.
+fn run_print(
+    args: &Args,
+    runner: &JjRunner,
+    revset: Option<&str>,
+    limit: Option<usize>,
+) -> Result<()> {
+    let text = match &args.show {
+        Some(revision) => {
+            let show = fetch_show(runner, revision).context("failed to fetch revision")?;
+            export_detail(&show, args.format)
+        }
+        None => {
+            let graph_log =
+                fetch_graph_log(runner, revset, limit).context("failed to fetch jj log")?;
+            export_log(runner, &graph_log, revset, args.format)
+                .context("failed to export jj log")?
+        }
+    };
+    match &args.output {
+        Some(path) => std::fs::write(path, text)
+            .with_context(|| format!("failed to write {}", path.display())),
+        None => {
+            print!("{text}");
+            Ok(())
+        }
+    }
+}
+
+/// Release the terminal while `f` runs (e.g., an external editor or a
+/// shell), then take it back and redraw from scratch.
+///
+/// Input still pending once the program exits (keys it left unread, or
+/// bytes the terminal sent while the screen was switched) is discarded, so
+/// it is not replayed as key presses in the TUI.
+fn suspend_tui<T>(terminal: &mut ratatui::DefaultTerminal, f: impl FnOnce() -> T) -> Result<T> {
+    let _ = execute!(stdout(), DisableMouseCapture);
+    ratatui::restore();
+    let result = f();
+    *terminal = ratatui::init();
+    let _ = execute!(stdout(), EnableMouseCapture);
+    while event::poll(Duration::ZERO)? {
+        event::read()?;
+    }
+    terminal.clear()?;
+    Ok(result)
 }
 
 /// Main event loop.
 fn run_event_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> Result<()> {
     loop {
+        // Follow the selection with the preview pane before drawing
+        app.update_preview();
+
         // Draw UI
+        let start = Instant::now();
         terminal.draw(|frame| {
             ui::render(frame, app);
         })?;
+        if let Some(profiler) = app.profiler() {
+            profiler.record_frame(start.elapsed());
+        }
 
         // Check if we need to load more entries (after drawing, so "Loading..." is visible)
         if app.should_load_more() {
@@ -98,13 +477,53 @@ fn run_event_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> Res
                 .context("failed to load more entries")?;
         }
 
+        // Pick up the result of a command running in the background
+        app.poll_background_command()
+            .context("failed to refresh after command")?;
+        app.poll_log_stream().context("failed to fetch jj log")?;
+
+        // Replay the next key of a macro, or wait for input
+        let event = match app.next_macro_key() {
+            Some(key) => Event::Key(key),
+            None => {
+                // Wake up periodically to update the sync age, spinner and auto-refresh
+                if !event::poll(app.poll_timeout())? {
+                    app.on_idle_tick();
+                    continue;
+                }
+                let event = event::read()?;
+                // A key press stops the macro being replayed
+                if let Event::Key(key) = &event
+                    && key.kind == KeyEventKind::Press
+                    && app.stop_macro_replay()
+                {
+                    continue;
+                }
+                event
+            }
+        };
+
         // Handle events
-        let event = event::read()?;
         if let Event::Key(key) = &event
             && key.kind == KeyEventKind::Press
-            && keys::dispatch_key_event(app, *key, &event)?
         {
-            continue;
+            app.record_activity();
+            if keys::dispatch_key_event(app, *key, &event)? {
+                continue;
+            }
+        } else if let Event::Mouse(mouse) = event {
+            app.record_activity();
+            keys::dispatch_mouse_event(app, mouse)?;
+        }
+
+        if let Some(request) = app.take_editor_request() {
+            let edited = suspend_tui(terminal, || editor::edit_description(&request.text))?;
+            app.finish_external_describe(request, edited);
+        }
+        if let Some(command) = app.take_external_command() {
+            let status = suspend_tui(terminal, || command.run())?;
+            app.finish_external_command(&command, status)
+                .context("failed to refresh after external command")?;
         }
 
         if app.should_quit {
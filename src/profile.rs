@@ -0,0 +1,288 @@
+//! Performance profiling for `xor --profile`.
+//!
+//! Records how long frames take to render, how long each kind of jj
+//! command takes, and how long parsing its output takes. The live numbers
+//! are shown in a debug overlay and a summary is printed on exit.
+
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// jj commands whose first argument is a group (`jj git push`, `jj op log`).
+const COMMAND_GROUPS: [&str; 7] = [
+    "bookmark",
+    "config",
+    "file",
+    "git",
+    "op",
+    "operation",
+    "workspace",
+];
+
+/// Number of entries per section shown in the overlay.
+const OVERLAY_ENTRIES: usize = 4;
+
+/// Aggregated durations of one kind of measurement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    /// Number of samples.
+    pub count: u64,
+    /// Sum of all samples.
+    pub total: Duration,
+    /// Longest sample.
+    pub max: Duration,
+    /// Most recent sample.
+    pub last: Duration,
+}
+
+impl Timings {
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        self.last = elapsed;
+    }
+
+    /// Mean duration (zero without samples).
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(count) => self.total / count,
+            Err(_) => Duration::from_secs_f64(self.total.as_secs_f64() / self.count as f64),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    frames: Timings,
+    /// Per command (e.g. `jj log`), in first-seen order.
+    commands: Vec<(String, Timings)>,
+    /// Per parser (e.g. `graph log`), in first-seen order.
+    parses: Vec<(&'static str, Timings)>,
+}
+
+/// Shared handle collecting profiling data.
+///
+/// Clones share the same data, so the runner, the event loop and the UI
+/// can all hold one.
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    stats: Arc<Mutex<Stats>>,
+}
+
+impl Profiler {
+    /// Create an empty profiler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn stats(&self) -> MutexGuard<'_, Stats> {
+        // Timings stay meaningful even if a panic interrupted an update
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record the time taken to draw one frame.
+    pub fn record_frame(&self, elapsed: Duration) {
+        self.stats().frames.record(elapsed);
+    }
+
+    /// Record the latency of a jj command.
+    pub fn record_command(&self, args: &[&str], elapsed: Duration) {
+        let key = command_key(args);
+        let mut stats = self.stats();
+        match stats.commands.iter_mut().find(|(name, _)| *name == key) {
+            Some((_, timings)) => timings.record(elapsed),
+            None => {
+                let mut timings = Timings::default();
+                timings.record(elapsed);
+                stats.commands.push((key, timings));
+            }
+        }
+    }
+
+    /// Record the time taken to parse jj output.
+    pub fn record_parse(&self, name: &'static str, elapsed: Duration) {
+        let mut stats = self.stats();
+        match stats.parses.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, timings)) => timings.record(elapsed),
+            None => {
+                let mut timings = Timings::default();
+                timings.record(elapsed);
+                stats.parses.push((name, timings));
+            }
+        }
+    }
+
+    /// Run `f` and record its duration as a parse step.
+    pub fn time_parse<T>(&self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record_parse(name, start.elapsed());
+        result
+    }
+
+    /// Live stats for the debug overlay, slowest (by total) first.
+    pub fn overlay_lines(&self) -> Vec<String> {
+        let stats = self.stats();
+        let frames = stats.frames;
+        let mut lines = vec![format!(
+            "frame  last {}  mean {}  max {}",
+            format_ms(frames.last),
+            format_ms(frames.mean()),
+            format_ms(frames.max)
+        )];
+        for (name, timings) in sorted_by_total(&stats.commands)
+            .into_iter()
+            .take(OVERLAY_ENTRIES)
+        {
+            lines.push(overlay_line(name, timings));
+        }
+        for (name, timings) in sorted_by_total(&stats.parses)
+            .into_iter()
+            .take(OVERLAY_ENTRIES)
+        {
+            lines.push(overlay_line(&format!("parse {name}"), timings));
+        }
+        lines
+    }
+
+    /// Summary table printed on exit.
+    pub fn summary(&self) -> String {
+        let stats = self.stats();
+        let mut out = String::from("xorcist profile\n");
+        let _ = writeln!(
+            out,
+            "frames: {} rendered, mean {}, max {}",
+            stats.frames.count,
+            format_ms(stats.frames.mean()),
+            format_ms(stats.frames.max)
+        );
+        write_section(&mut out, "jj commands", &sorted_by_total(&stats.commands));
+        write_section(&mut out, "parsing", &sorted_by_total(&stats.parses));
+        out
+    }
+}
+
+/// Name under which a command is aggregated, e.g. `jj git push`.
+fn command_key(args: &[&str]) -> String {
+    let mut key = String::from("jj");
+    // Arguments start with the subcommand; only its group keyword is kept
+    let mut words = args.iter().filter(|arg| !arg.starts_with('-'));
+    if let Some(first) = words.next() {
+        key.push(' ');
+        key.push_str(first);
+        if COMMAND_GROUPS.contains(first)
+            && let Some(second) = words.next()
+        {
+            key.push(' ');
+            key.push_str(second);
+        }
+    }
+    key
+}
+
+fn sorted_by_total<K: AsRef<str>>(entries: &[(K, Timings)]) -> Vec<(&str, Timings)> {
+    let mut sorted: Vec<(&str, Timings)> = entries
+        .iter()
+        .map(|(name, timings)| (name.as_ref(), *timings))
+        .collect();
+    sorted.sort_by_key(|(_, timings)| std::cmp::Reverse(timings.total));
+    sorted
+}
+
+fn overlay_line(name: &str, timings: Timings) -> String {
+    format!(
+        "{name}  ×{}  mean {}  max {}",
+        timings.count,
+        format_ms(timings.mean()),
+        format_ms(timings.max)
+    )
+}
+
+fn write_section(out: &mut String, title: &str, entries: &[(&str, Timings)]) {
+    if entries.is_empty() {
+        return;
+    }
+    let width = entries
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let _ = writeln!(
+        out,
+        "{title}:\n  {:<width$}  {:>6}  {:>10}  {:>10}  {:>10}",
+        "", "count", "total", "mean", "max"
+    );
+    for (name, timings) in entries {
+        let _ = writeln!(
+            out,
+            "  {name:<width$}  {:>6}  {:>10}  {:>10}  {:>10}",
+            timings.count,
+            format_ms(timings.total),
+            format_ms(timings.mean()),
+            format_ms(timings.max)
+        );
+    }
+}
+
+/// Format a duration in milliseconds with one decimal.
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_key_groups_subcommands() {
+        assert_eq!(command_key(&["log", "-r", "::"]), "jj log");
+        assert_eq!(
+            command_key(&["git", "push", "--change", "x"]),
+            "jj git push"
+        );
+        assert_eq!(command_key(&["diff", "--color=always"]), "jj diff");
+        assert_eq!(command_key(&[]), "jj");
+    }
+
+    #[test]
+    fn test_timings_aggregate() {
+        let profiler = Profiler::new();
+        profiler.record_command(&["log"], Duration::from_millis(10));
+        profiler.record_command(&["log", "-n", "5"], Duration::from_millis(30));
+        profiler.record_command(&["show"], Duration::from_millis(100));
+        profiler.record_frame(Duration::from_millis(2));
+
+        let stats = profiler.stats();
+        let (name, log) = &stats.commands[0];
+        assert_eq!(name, "jj log");
+        assert_eq!(log.count, 2);
+        assert_eq!(log.mean(), Duration::from_millis(20));
+        assert_eq!(log.max, Duration::from_millis(30));
+        assert_eq!(log.last, Duration::from_millis(30));
+        assert_eq!(stats.frames.count, 1);
+    }
+
+    #[test]
+    fn test_summary_sorts_by_total() {
+        let profiler = Profiler::new();
+        profiler.record_command(&["log"], Duration::from_millis(10));
+        profiler.record_command(&["show"], Duration::from_millis(100));
+        let parsed = profiler.time_parse("graph log", || 42);
+        assert_eq!(parsed, 42);
+
+        let summary = profiler.summary();
+        let show = summary.find("jj show").unwrap();
+        let log = summary.find("jj log").unwrap();
+        assert!(show < log, "{summary}");
+        assert!(summary.contains("parsing:\n"), "{summary}");
+        assert!(summary.contains("  graph log  "), "{summary}");
+        assert_eq!(profiler.overlay_lines().len(), 4);
+    }
+
+    #[test]
+    fn test_timings_mean_without_samples() {
+        assert_eq!(Timings::default().mean(), Duration::ZERO);
+    }
+}
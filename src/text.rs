@@ -1,4 +1,6 @@
-//! Text utilities for display truncation.
+//! Text utilities for display truncation and formatting.
+
+use std::time::Duration;
 
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
@@ -26,6 +28,146 @@ pub fn truncate_str(s: &str, max_width: usize) -> String {
     format!("{}...", &s[..end_idx])
 }
 
+/// Fit a string into exactly `width` display columns.
+///
+/// Longer strings are truncated with `…`; shorter ones are padded with spaces.
+pub fn fit_width(s: &str, width: usize) -> String {
+    let mut out = truncate_with_ellipsis(s, width);
+    let pad = width.saturating_sub(out.width());
+    out.extend(std::iter::repeat_n(' ', pad));
+    out
+}
+
+/// Like [`fit_width`], but pads on the left so the text is right-aligned.
+pub fn fit_width_right(s: &str, width: usize) -> String {
+    let out = truncate_with_ellipsis(s, width);
+    let pad = width.saturating_sub(out.width());
+    format!("{}{out}", " ".repeat(pad))
+}
+
+/// Truncate a string to at most `max_width` columns, ending with `…` if cut.
+fn truncate_with_ellipsis(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let target_width = max_width - 1; // Reserve one column for "…"
+    let mut current_width = 0;
+    let mut end_idx = 0;
+    for (idx, ch) in s.char_indices() {
+        let ch_width = ch.width().unwrap_or(0);
+        if current_width + ch_width > target_width {
+            break;
+        }
+        current_width += ch_width;
+        end_idx = idx + ch.len_utf8();
+    }
+
+    format!("{}…", &s[..end_idx])
+}
+
+/// Wrap a string into lines of at most `width` display columns.
+///
+/// Breaks at spaces where possible; words wider than `width` are split.
+pub fn wrap_text(s: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![s.to_string()];
+    }
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+
+    for word in s.split(' ') {
+        let word_width = word.width();
+        // Start a new line if the word (after a separating space) does not fit
+        if current_width > 0 && current_width + 1 + word_width > width {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        if current_width > 0 {
+            current.push(' ');
+            current_width += 1;
+        }
+        for ch in word.chars() {
+            let ch_width = ch.width().unwrap_or(0);
+            if current_width > 0 && current_width + ch_width > width {
+                lines.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            current.push(ch);
+            current_width += ch_width;
+        }
+    }
+    lines.push(current);
+
+    lines
+}
+
+/// Score how well `query` fuzzy-matches `text` (None = no match).
+///
+/// Every query character must appear in `text` in order, ignoring case.
+/// Matches score higher when they are consecutive or start a word, and
+/// each skipped character costs a little, so `fxp` prefers "fix parser"
+/// over "fix the parser".
+pub fn fuzzy_score(text: &str, query: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().collect();
+    let query: Vec<char> = query
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let Some(&first) = query.first() else {
+        return Some(0);
+    };
+
+    let lower: Vec<char> = text
+        .iter()
+        .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect();
+    // Try every position of the first character and keep the best greedy match
+    (0..lower.len())
+        .filter(|&start| lower[start] == first)
+        .filter_map(|start| {
+            let mut score = 0;
+            let mut previous: Option<usize> = None;
+            let mut pos = start;
+            for &want in &query {
+                let idx = (pos..lower.len()).find(|&i| lower[i] == want)?;
+                let word_start = idx == 0 || !text[idx - 1].is_alphanumeric();
+                score += 1;
+                if word_start {
+                    score += 10;
+                }
+                match previous {
+                    Some(prev) if idx == prev + 1 => score += 8,
+                    Some(prev) => score -= (idx - prev - 1).min(10) as i64,
+                    None => {}
+                }
+                previous = Some(idx);
+                pos = idx + 1;
+            }
+            Some(score)
+        })
+        .max()
+}
+
+/// Format an elapsed duration in the same short style as the log timestamps.
+///
+/// Examples: `5s`, `3m`, `2h`, `1d`.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +207,75 @@ mod tests {
         assert_eq!(truncate_str("hello", 3), "...");
         assert_eq!(truncate_str("hello", 4), "h...");
     }
+
+    #[test]
+    fn test_fit_width() {
+        assert_eq!(fit_width("hello", 8), "hello   ");
+        assert_eq!(fit_width("hello world", 8), "hello w…");
+        assert_eq!(fit_width("exact", 5), "exact");
+        assert_eq!(fit_width("hello", 1), "…");
+        assert_eq!(fit_width("hello", 0), "");
+    }
+
+    #[test]
+    fn test_fit_width_wide_chars() {
+        // A wide character that does not fit before "…" is replaced by padding
+        assert_eq!(fit_width("日本語テスト", 6), "日本… ");
+        assert_eq!(fit_width("日本語テスト", 7), "日本語…");
+        assert_eq!(fit_width("日本語テスト", 7).width(), 7);
+        assert_eq!(fit_width("日本", 2), "… ");
+    }
+
+    #[test]
+    fn test_fit_width_right() {
+        assert_eq!(fit_width_right("1h", 4), "  1h");
+        assert_eq!(fit_width_right("Jane Doe", 6), "Jane …");
+        assert_eq!(fit_width_right("世界", 5), " 世界");
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("anything", ""), Some(0));
+        assert!(fuzzy_score("fix parser", "fxp").is_some());
+        assert!(fuzzy_score("fix parser", "pxf").is_none());
+        assert!(fuzzy_score("Fix Parser", "FIXP").is_some());
+
+        // Consecutive and word-start matches win
+        let tight = fuzzy_score("fix parser", "fixp").unwrap();
+        let loose = fuzzy_score("fix the parser", "fixp").unwrap();
+        let scattered = fuzzy_score("a file index pass", "fixp").unwrap();
+        assert!(tight > loose, "{tight} {loose}");
+        assert!(loose > scattered, "{loose} {scattered}");
+
+        // The best occurrence of the first character is used
+        assert!(
+            fuzzy_score("of the foo", "foo").unwrap() > fuzzy_score("of the fxoxo", "foo").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(wrap_text("short", 10), vec!["short"]);
+        assert_eq!(
+            wrap_text("fix the parser for long lines", 10),
+            vec!["fix the", "parser for", "long lines"]
+        );
+        // Words wider than the line are split
+        assert_eq!(
+            wrap_text("abcdefghij xy", 4),
+            vec!["abcd", "efgh", "ij", "xy"]
+        );
+        // Wide characters without spaces
+        assert_eq!(wrap_text("日本語テスト", 5), vec!["日本", "語テ", "スト"]);
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_secs(0)), "0s");
+        assert_eq!(format_elapsed(Duration::from_secs(59)), "59s");
+        assert_eq!(format_elapsed(Duration::from_secs(60)), "1m");
+        assert_eq!(format_elapsed(Duration::from_secs(3599)), "59m");
+        assert_eq!(format_elapsed(Duration::from_secs(7200)), "2h");
+        assert_eq!(format_elapsed(Duration::from_secs(86400 * 3)), "3d");
+    }
 }
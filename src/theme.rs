@@ -0,0 +1,417 @@
+//! Color themes for the TUI.
+//!
+//! Every color used by the UI comes from a [`Theme`]. The built-in presets
+//! can be selected with `[theme] preset = "..."` in the config file, and
+//! individual colors overridden by role (e.g. `selection_bg = "#303030"`).
+
+use std::str::FromStr;
+
+use ratatui::style::Color;
+
+/// Names of the built-in presets.
+pub const PRESET_NAMES: [&str; 3] = ["dark", "light", "solarized"];
+
+/// Number of colors the log graph lanes cycle through.
+pub const GRAPH_LANE_COUNT: usize = 6;
+
+/// Colors of the UI, by role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Text of the title bars.
+    pub title_fg: Color,
+    /// Background of the log title bar.
+    pub title_bg: Color,
+    /// Background of the detail view and operation log title bars.
+    pub detail_title_bg: Color,
+    /// Background of the diff view title bar.
+    pub diff_title_bg: Color,
+    /// Background of the diff view title bar when comparing two revisions.
+    pub compare_title_bg: Color,
+    /// Text of the status bars.
+    pub status_fg: Color,
+    /// Background of the status bars.
+    pub status_bg: Color,
+    /// Background of the selected row.
+    pub selection_bg: Color,
+    /// Background of rows matching the search.
+    pub search_match_bg: Color,
+    /// Borders of prompts and menus, bookmarks, and the author in the detail view.
+    pub accent: Color,
+    /// Keys in help texts and menus, and the confirmation dialog border.
+    pub key: Color,
+    /// Placeholders, separators and other secondary text.
+    pub dim: Color,
+    /// Successful command results.
+    pub success: Color,
+    /// Failed command results and warnings about destructive modes.
+    pub error: Color,
+    /// Running commands, loading indicators and attention banners.
+    pub warning: Color,
+    /// Text drawn on `warning` and diff title backgrounds.
+    pub inverse_fg: Color,
+    /// Change IDs.
+    pub change_id: Color,
+    /// Commit IDs.
+    pub commit_id: Color,
+    /// Tags in the log, the detail view and the tags view.
+    pub tag: Color,
+    /// Operation IDs in the operation log.
+    pub op_id: Color,
+    /// Author column of the log and users in the operation log.
+    pub author: Color,
+    /// Timestamp column of the log and times in the operation log.
+    pub timestamp: Color,
+    /// Mark on marked commits and files.
+    pub mark: Color,
+    /// Added files and lines.
+    pub added: Color,
+    /// Removed files and lines.
+    pub removed: Color,
+    /// Modified files.
+    pub modified: Color,
+    /// Renamed files.
+    pub renamed: Color,
+    /// Copied files.
+    pub copied: Color,
+    /// Background of added lines in highlighted diffs.
+    pub added_bg: Color,
+    /// Background of removed lines in highlighted diffs.
+    pub removed_bg: Color,
+    /// Background of changed words in added lines of highlighted diffs.
+    pub added_word_bg: Color,
+    /// Background of changed words in removed lines of highlighted diffs.
+    pub removed_word_bg: Color,
+    /// Syntax highlighting: keywords.
+    pub syntax_keyword: Color,
+    /// Syntax highlighting: type names.
+    pub syntax_type: Color,
+    /// Syntax highlighting: constants.
+    pub syntax_constant: Color,
+    /// Syntax highlighting: string literals.
+    pub syntax_string: Color,
+    /// Syntax highlighting: number literals.
+    pub syntax_number: Color,
+    /// Syntax highlighting: comments.
+    pub syntax_comment: Color,
+    /// Edges of the log graph, per lane (cycled from the left).
+    pub graph_lanes: [Color; GRAPH_LANE_COUNT],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Role names accepted in the `[theme]` config section.
+    pub const ROLES: [&'static str; 44] = [
+        "title_fg",
+        "title_bg",
+        "detail_title_bg",
+        "diff_title_bg",
+        "compare_title_bg",
+        "status_fg",
+        "status_bg",
+        "selection_bg",
+        "search_match_bg",
+        "accent",
+        "key",
+        "dim",
+        "success",
+        "error",
+        "warning",
+        "inverse_fg",
+        "change_id",
+        "commit_id",
+        "tag",
+        "op_id",
+        "author",
+        "timestamp",
+        "mark",
+        "added",
+        "removed",
+        "modified",
+        "renamed",
+        "copied",
+        "added_bg",
+        "removed_bg",
+        "added_word_bg",
+        "removed_word_bg",
+        "syntax_keyword",
+        "syntax_type",
+        "syntax_constant",
+        "syntax_string",
+        "syntax_number",
+        "syntax_comment",
+        "graph_lane_1",
+        "graph_lane_2",
+        "graph_lane_3",
+        "graph_lane_4",
+        "graph_lane_5",
+        "graph_lane_6",
+    ];
+
+    /// Theme for dark terminals (the default).
+    pub fn dark() -> Self {
+        Self {
+            title_fg: Color::White,
+            title_bg: Color::Blue,
+            detail_title_bg: Color::Magenta,
+            diff_title_bg: Color::Green,
+            compare_title_bg: Color::Black,
+            status_fg: Color::White,
+            status_bg: Color::DarkGray,
+            selection_bg: Color::Indexed(236),
+            search_match_bg: Color::Indexed(58),
+            accent: Color::Cyan,
+            key: Color::Yellow,
+            dim: Color::DarkGray,
+            success: Color::Green,
+            error: Color::Red,
+            warning: Color::Yellow,
+            inverse_fg: Color::Black,
+            change_id: Color::Magenta,
+            commit_id: Color::Yellow,
+            tag: Color::LightGreen,
+            op_id: Color::Blue,
+            author: Color::Yellow,
+            timestamp: Color::Cyan,
+            mark: Color::Magenta,
+            added: Color::Green,
+            removed: Color::Red,
+            modified: Color::Yellow,
+            renamed: Color::Cyan,
+            copied: Color::Blue,
+            added_bg: Color::Indexed(22),
+            removed_bg: Color::Indexed(52),
+            added_word_bg: Color::Indexed(28),
+            removed_word_bg: Color::Indexed(88),
+            syntax_keyword: Color::Magenta,
+            syntax_type: Color::Yellow,
+            syntax_constant: Color::LightCyan,
+            syntax_string: Color::LightGreen,
+            syntax_number: Color::LightYellow,
+            syntax_comment: Color::DarkGray,
+            graph_lanes: [
+                Color::Blue,
+                Color::Magenta,
+                Color::Green,
+                Color::Yellow,
+                Color::Cyan,
+                Color::Red,
+            ],
+        }
+    }
+
+    /// Theme for light terminals.
+    pub fn light() -> Self {
+        Self {
+            title_fg: Color::White,
+            title_bg: Color::Indexed(25),
+            detail_title_bg: Color::Indexed(90),
+            diff_title_bg: Color::Indexed(28),
+            compare_title_bg: Color::Indexed(238),
+            status_fg: Color::Black,
+            status_bg: Color::Indexed(252),
+            selection_bg: Color::Indexed(254),
+            search_match_bg: Color::Indexed(230),
+            accent: Color::Indexed(31),
+            key: Color::Indexed(130),
+            dim: Color::Indexed(244),
+            success: Color::Indexed(28),
+            error: Color::Indexed(124),
+            warning: Color::Indexed(130),
+            inverse_fg: Color::White,
+            change_id: Color::Indexed(90),
+            commit_id: Color::Indexed(130),
+            tag: Color::Indexed(28),
+            op_id: Color::Indexed(25),
+            author: Color::Indexed(130),
+            timestamp: Color::Indexed(31),
+            mark: Color::Indexed(90),
+            added: Color::Indexed(28),
+            removed: Color::Indexed(124),
+            modified: Color::Indexed(130),
+            renamed: Color::Indexed(31),
+            copied: Color::Indexed(25),
+            added_bg: Color::Indexed(194),
+            removed_bg: Color::Indexed(224),
+            added_word_bg: Color::Indexed(157),
+            removed_word_bg: Color::Indexed(217),
+            syntax_keyword: Color::Indexed(90),
+            syntax_type: Color::Indexed(130),
+            syntax_constant: Color::Indexed(30),
+            syntax_string: Color::Indexed(28),
+            syntax_number: Color::Indexed(94),
+            syntax_comment: Color::Indexed(244),
+            graph_lanes: [
+                Color::Indexed(25),
+                Color::Indexed(90),
+                Color::Indexed(28),
+                Color::Indexed(130),
+                Color::Indexed(31),
+                Color::Indexed(124),
+            ],
+        }
+    }
+
+    /// Solarized (dark) palette.
+    pub fn solarized() -> Self {
+        const BASE03: Color = Color::Rgb(0x00, 0x2b, 0x36);
+        const BASE02: Color = Color::Rgb(0x07, 0x36, 0x42);
+        const BASE01: Color = Color::Rgb(0x58, 0x6e, 0x75);
+        const BASE1: Color = Color::Rgb(0x93, 0xa1, 0xa1);
+        const BASE3: Color = Color::Rgb(0xfd, 0xf6, 0xe3);
+        const YELLOW: Color = Color::Rgb(0xb5, 0x89, 0x00);
+        const ORANGE: Color = Color::Rgb(0xcb, 0x4b, 0x16);
+        const RED: Color = Color::Rgb(0xdc, 0x32, 0x2f);
+        const MAGENTA: Color = Color::Rgb(0xd3, 0x36, 0x82);
+        const VIOLET: Color = Color::Rgb(0x6c, 0x71, 0xc4);
+        const BLUE: Color = Color::Rgb(0x26, 0x8b, 0xd2);
+        const CYAN: Color = Color::Rgb(0x2a, 0xa1, 0x98);
+        const GREEN: Color = Color::Rgb(0x85, 0x99, 0x00);
+        Self {
+            title_fg: BASE3,
+            title_bg: BLUE,
+            detail_title_bg: VIOLET,
+            diff_title_bg: GREEN,
+            compare_title_bg: BASE02,
+            status_fg: BASE1,
+            status_bg: BASE02,
+            selection_bg: BASE02,
+            search_match_bg: Color::Rgb(0x3b, 0x3a, 0x12),
+            accent: CYAN,
+            key: YELLOW,
+            dim: BASE01,
+            success: GREEN,
+            error: RED,
+            warning: ORANGE,
+            inverse_fg: BASE03,
+            change_id: MAGENTA,
+            commit_id: YELLOW,
+            tag: VIOLET,
+            op_id: BLUE,
+            author: YELLOW,
+            timestamp: CYAN,
+            mark: MAGENTA,
+            added: GREEN,
+            removed: RED,
+            modified: YELLOW,
+            renamed: CYAN,
+            copied: BLUE,
+            added_bg: Color::Rgb(0x0d, 0x3b, 0x2a),
+            removed_bg: Color::Rgb(0x3f, 0x1d, 0x24),
+            added_word_bg: Color::Rgb(0x1a, 0x5c, 0x42),
+            removed_word_bg: Color::Rgb(0x66, 0x2a, 0x33),
+            syntax_keyword: GREEN,
+            syntax_type: YELLOW,
+            syntax_constant: VIOLET,
+            syntax_string: CYAN,
+            syntax_number: MAGENTA,
+            syntax_comment: BASE01,
+            graph_lanes: [BLUE, MAGENTA, GREEN, YELLOW, CYAN, ORANGE],
+        }
+    }
+
+    /// Built-in preset by name.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            _ => None,
+        }
+    }
+
+    /// Mutable access to a color by role name (see [`Theme::ROLES`]).
+    pub fn role_mut(&mut self, role: &str) -> Option<&mut Color> {
+        let color = match role {
+            "title_fg" => &mut self.title_fg,
+            "title_bg" => &mut self.title_bg,
+            "detail_title_bg" => &mut self.detail_title_bg,
+            "diff_title_bg" => &mut self.diff_title_bg,
+            "compare_title_bg" => &mut self.compare_title_bg,
+            "status_fg" => &mut self.status_fg,
+            "status_bg" => &mut self.status_bg,
+            "selection_bg" => &mut self.selection_bg,
+            "search_match_bg" => &mut self.search_match_bg,
+            "accent" => &mut self.accent,
+            "key" => &mut self.key,
+            "dim" => &mut self.dim,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "inverse_fg" => &mut self.inverse_fg,
+            "change_id" => &mut self.change_id,
+            "commit_id" => &mut self.commit_id,
+            "tag" => &mut self.tag,
+            "op_id" => &mut self.op_id,
+            "author" => &mut self.author,
+            "timestamp" => &mut self.timestamp,
+            "mark" => &mut self.mark,
+            "added" => &mut self.added,
+            "removed" => &mut self.removed,
+            "modified" => &mut self.modified,
+            "renamed" => &mut self.renamed,
+            "copied" => &mut self.copied,
+            "added_bg" => &mut self.added_bg,
+            "removed_bg" => &mut self.removed_bg,
+            "added_word_bg" => &mut self.added_word_bg,
+            "removed_word_bg" => &mut self.removed_word_bg,
+            "syntax_keyword" => &mut self.syntax_keyword,
+            "syntax_type" => &mut self.syntax_type,
+            "syntax_constant" => &mut self.syntax_constant,
+            "syntax_string" => &mut self.syntax_string,
+            "syntax_number" => &mut self.syntax_number,
+            "syntax_comment" => &mut self.syntax_comment,
+            "graph_lane_1" => &mut self.graph_lanes[0],
+            "graph_lane_2" => &mut self.graph_lanes[1],
+            "graph_lane_3" => &mut self.graph_lanes[2],
+            "graph_lane_4" => &mut self.graph_lanes[3],
+            "graph_lane_5" => &mut self.graph_lanes[4],
+            "graph_lane_6" => &mut self.graph_lanes[5],
+            _ => return None,
+        };
+        Some(color)
+    }
+}
+
+/// Parse a color: a name (`"red"`, `"dark-gray"`), `"#rrggbb"` or a 256-color index.
+pub fn parse_color(text: &str) -> Result<Color, String> {
+    Color::from_str(text.trim()).map_err(|_| format!("invalid color `{text}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_by_name() {
+        for name in PRESET_NAMES {
+            assert!(Theme::preset(name).is_some(), "{name}");
+        }
+        assert_eq!(Theme::preset("dark"), Some(Theme::default()));
+        assert_eq!(Theme::preset("neon"), None);
+    }
+
+    #[test]
+    fn test_every_role_is_settable() {
+        let mut theme = Theme::dark();
+        for role in Theme::ROLES {
+            *theme.role_mut(role).unwrap_or_else(|| panic!("{role}")) = Color::Indexed(1);
+        }
+        assert!(theme.role_mut("background").is_none());
+        assert_eq!(theme.selection_bg, Color::Indexed(1));
+        assert_eq!(theme.syntax_comment, Color::Indexed(1));
+        assert_eq!(theme.graph_lanes, [Color::Indexed(1); GRAPH_LANE_COUNT]);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("red"), Ok(Color::Red));
+        assert_eq!(parse_color("#ff8000"), Ok(Color::Rgb(0xff, 0x80, 0x00)));
+        assert_eq!(parse_color("236"), Ok(Color::Indexed(236)));
+        assert!(parse_color("not-a-color").is_err());
+    }
+}
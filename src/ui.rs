@@ -6,11 +6,29 @@ use ratatui::{
     layout::{Constraint, Flex, Layout, Position, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    widgets::{
+        Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
+    },
 };
+use std::ops::Range;
 
-use crate::app::{App, InputMode, ModalState, View};
-use crate::jj::{DiffStatus, ShowOutput};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::app::{
+    App, CommandOutput, ConfirmButton, ContextMenu, ConventionalDraft, DetailDiff, InputMode,
+    ModalRegion, ModalState, ModalTarget, NewPlacement, PALETTE_ENTRIES, PlacementMenu,
+    PreviewKind, ProtectedPush, PushDialog, StatusMessage, View,
+};
+use crate::config::{LogColumn, LogColumnKind};
+use crate::highlight::{Token, TokenKind, syntax_for_path};
+use crate::jj::{
+    DiffLine, DiffLineKind, DiffStat, DiffStatus, FileStat, GraphLine, HistoryEntry, RelatedCommit,
+    RepoMetadata, ShowOutput,
+};
+use crate::keys::help;
+use crate::profile::Profiler;
+use crate::text::{fit_width, fit_width_right};
+use crate::theme::Theme;
 
 /// Render the entire UI based on current view.
 pub fn render(frame: &mut Frame, app: &mut App) {
@@ -18,22 +36,50 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         View::Log => render_log_view(frame, app),
         View::Detail => render_detail_view(frame, app),
         View::Diff => render_diff_view(frame, app),
+        View::OpLog => render_op_log_view(frame, app),
+        View::FileHistory => render_file_history_view(frame, app),
+        View::Workspaces => render_workspaces_view(frame, app),
+        View::Tags => render_tags_view(frame, app),
+        View::Stacks => render_stacks_view(frame, app),
+        View::Sparse => render_sparse_view(frame, app),
     }
 
     // Render input overlay if in input mode
-    if app.is_input_mode() {
+    if app.finder().is_some() {
+        render_finder(frame, app);
+    } else if app.is_input_mode() {
         render_input_overlay(frame, app);
     }
 
     // Render help modal on top if visible
     if app.show_help {
-        render_help(frame);
+        render_help(frame, app);
+    }
+
+    // Render the output pane if open
+    if app.is_output_pane_open() {
+        render_output_pane(frame, app);
+    }
+
+    // Render the command history panel if open
+    if app.is_command_history_open() {
+        render_command_history_panel(frame, app);
+    }
+
+    // Render the message history if open
+    if app.is_messages_panel_open() {
+        render_messages_panel(frame, app);
     }
 
     // Render modal dialog if open
     if app.is_modal_open() {
         render_modal_overlay(frame, app);
     }
+
+    // Render live timings on top of everything with --profile
+    if let Some(profiler) = app.profiler() {
+        render_profile_overlay(frame, profiler, app.theme());
+    }
 }
 
 /// Render the log view.
@@ -46,171 +92,598 @@ fn render_log_view(frame: &mut Frame, app: &mut App) {
     .split(frame.area());
 
     render_title_bar(frame, chunks[0], app);
-    render_log_list(frame, chunks[1], app);
+    if let Some(log_percent) = app.preview().map(|preview| preview.log_percent) {
+        let panes = Layout::horizontal([
+            Constraint::Percentage(log_percent),
+            Constraint::Percentage(100 - log_percent),
+        ])
+        .split(chunks[1]);
+        render_log_list(frame, panes[0], app);
+        render_preview(frame, panes[1], app);
+    } else {
+        render_log_list(frame, chunks[1], app);
+    }
     render_log_status_bar(frame, chunks[2], app);
 }
 
+/// Render the preview pane beside the log.
+fn render_preview(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(preview) = app.preview() else {
+        return;
+    };
+    let theme = app.theme();
+    let title = match preview.kind {
+        Some(PreviewKind::Diff) => " Diff (v: close) ",
+        _ => " Preview (v: diff) ",
+    };
+    // Until the new commit is fetched, the previous preview stays, marked as loading
+    let title = if preview.is_loading() && preview.content.is_some() {
+        Line::from(vec![
+            Span::raw(title),
+            Span::styled("loading… ", Style::default().fg(theme.dim)),
+        ])
+    } else {
+        Line::raw(title)
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(theme.dim));
+    let text_width = (area.width as usize).saturating_sub(1);
+    let lines = match &preview.content {
+        None => vec![Line::styled("Loading...", Style::default().fg(theme.dim))],
+        Some(Err(message)) => vec![Line::styled(
+            message.clone(),
+            Style::default().fg(theme.error),
+        )],
+        Some(Ok(content)) => match &content.diff {
+            Some(diff) => build_full_diff_lines(diff, theme).0,
+            None => build_detail_lines(
+                &content.show_output,
+                &content.stat,
+                &[],
+                usize::MAX,
+                app.metadata(),
+                text_width,
+                theme,
+            ),
+        },
+    };
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 /// Render the title bar.
 fn render_title_bar(frame: &mut Frame, area: Rect, app: &App) {
-    let title = format!(" xorcist - {} ", app.repo_root);
-    let title_bar = Paragraph::new(title).style(Style::default().bg(Color::Blue).fg(Color::White));
+    let theme = app.theme();
+    let title = match app.revset() {
+        Some(revset) => format!(" xorcist - {} [revset: {revset}] ", app.repo_root),
+        None => format!(" xorcist - {} ", app.repo_root),
+    };
+    let mut spans = vec![Span::raw(title)];
+    if let Some(author) = app.author_filter() {
+        spans.push(Span::styled(
+            format!(" author: {author} (Esc: clear) "),
+            Style::default()
+                .bg(theme.accent)
+                .fg(theme.inverse_fg)
+                .bold(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if let Some(path) = app.path_filter() {
+        spans.push(Span::styled(
+            format!(" path: {path} (Esc: clear) "),
+            Style::default()
+                .bg(theme.accent)
+                .fg(theme.inverse_fg)
+                .bold(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if app.shows_hidden_commits() {
+        spans.push(Span::styled(
+            format!(" hidden: {} (h: hide) ", app.hidden_commit_count()),
+            Style::default()
+                .bg(theme.accent)
+                .fg(theme.inverse_fg)
+                .bold(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    if app.has_elided_expansions() {
+        spans.push(Span::styled(
+            format!(
+                " expanded: {} (Esc: collapse) ",
+                app.elided_expansion_count()
+            ),
+            Style::default()
+                .bg(theme.accent)
+                .fg(theme.inverse_fg)
+                .bold(),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    let op_id = app.metadata().map(|meta| meta.op_id.as_str());
+    let working_copy = app
+        .metadata()
+        .and_then(|meta| meta.working_copy_change_id());
+    if let Some(state) = repo_state_text(op_id, working_copy, app.is_colocated()) {
+        spans.push(Span::raw(state));
+    }
+    if app.is_repo_changed() {
+        spans.push(Span::styled(
+            " repository changed — press R to refresh ",
+            Style::default()
+                .bg(theme.warning)
+                .fg(theme.inverse_fg)
+                .bold(),
+        ));
+    }
+    if let Some(register) = app.recording_macro() {
+        spans.push(Span::styled(
+            format!(" recording @{register} (Q: stop) "),
+            Style::default()
+                .bg(theme.warning)
+                .fg(theme.inverse_fg)
+                .bold(),
+        ));
+    }
+    if app.quick_mode() {
+        spans.push(Span::styled(
+            " QUICK MODE: no confirmations ",
+            Style::default().bg(theme.error).fg(theme.title_fg).bold(),
+        ));
+    }
+    let title_bar = Paragraph::new(Line::from(spans))
+        .style(Style::default().bg(theme.title_bg).fg(theme.title_fg));
     frame.render_widget(title_bar, area);
 }
 
+/// Length of the working-copy change id shown in the title bar.
+const TITLE_CHANGE_ID_LEN: usize = 8;
+
+/// Repository state for the title bar: `op <id> · @ <change id> · colocated`.
+fn repo_state_text(
+    op_id: Option<&str>,
+    working_copy: Option<&str>,
+    colocated: bool,
+) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(op_id) = op_id.filter(|id| !id.is_empty()) {
+        parts.push(format!("op {op_id}"));
+    }
+    if let Some(change_id) = working_copy {
+        let short: String = change_id.chars().take(TITLE_CHANGE_ID_LEN).collect();
+        parts.push(format!("@ {short}"));
+    }
+    if colocated {
+        parts.push("colocated".to_string());
+    }
+    (!parts.is_empty()).then(|| format!("{} ", parts.join(" · ")))
+}
+
 /// Render the log list with ANSI graph output.
+/// Graph lines laid out above and below the viewport, when descriptions are
+/// not wrapped.
+const LOG_RENDER_MARGIN: usize = 20;
+
+/// Render the log list.
+///
+/// Without wrapping, each graph line is one row, so only the lines around
+/// the viewport are laid out and frame times do not grow with the history.
+/// Wrapped descriptions need every line's height to place the viewport, so
+/// all lines are laid out then.
 fn render_log_list(frame: &mut Frame, area: Rect, app: &mut App) {
+    let theme = *app.theme();
     let viewport_height = area.height as usize;
 
-    // Ensure selected line is visible
-    app.ensure_selected_visible(viewport_height);
-
     // Get the selected line index for highlighting
     let selected_line_idx = app.selected_line_index();
 
-    // Build text from graph lines
-    let mut lines: Vec<Line> = Vec::new();
+    // Leave room for the scrollbar so the right-aligned columns stay visible.
+    // Wrapped rows can need a scrollbar even when the lines alone fit.
+    let total_lines = app.line_count();
+    let reserve_scrollbar = app.wrap_descriptions || total_lines > viewport_height;
+    let row_width = (area.width as usize).saturating_sub(usize::from(reserve_scrollbar));
 
-    for (idx, graph_line) in app.graph_log.lines.iter().enumerate() {
-        // Parse ANSI codes to ratatui Line
-        let text = graph_line
-            .raw
-            .as_bytes()
-            .into_text()
-            .unwrap_or_else(|_| Text::raw(&graph_line.raw));
-
-        // Get the first line (should only be one line per graph_line)
-        let mut line = if text.lines.is_empty() {
-            Line::raw("")
+    let visible_lines = if app.wrap_descriptions {
+        0..total_lines
+    } else {
+        app.ensure_selected_visible(viewport_height);
+        let end = app.scroll_offset + viewport_height + LOG_RENDER_MARGIN;
+        app.scroll_offset.saturating_sub(LOG_RENDER_MARGIN)..end.min(total_lines)
+    };
+    let first_line = visible_lines.start;
+    let columns = app.log_columns();
+
+    // Build rows from graph lines (one or more rows per line)
+    let mut rows: Vec<Line> = Vec::new();
+    let mut line_heights: Vec<usize> = Vec::new();
+
+    for idx in visible_lines {
+        let graph_line = &app.graph_log.lines[idx];
+        let line = color_graph_lanes(ansi_line(&graph_line.raw), &theme);
+
+        // Lay out commit lines into columns
+        let mut line_rows = if graph_line.is_commit_line() {
+            let continuation = app.wrap_descriptions.then(|| {
+                let next = app.graph_log.lines.get(idx + 1);
+                continuation_graph(next.map_or("", |l| l.plain.as_str()))
+            });
+            let marker = graph_line
+                .change_id
+                .as_deref()
+                .and_then(|id| row_marker(app, id));
+            layout_commit_line(
+                line,
+                graph_line,
+                row_width,
+                marker,
+                continuation.as_deref(),
+                &columns,
+                &theme,
+            )
+        } else if graph_line.is_elided() {
+            // Elided revisions: dimmed, with a hint under the selected commit
+            let mut line = line.style(Style::default().fg(theme.dim).italic());
+            if selected_line_idx.is_some() && app.graph_log.elided_owner(idx) == selected_line_idx {
+                line.spans.push(Span::raw("  E: expand"));
+            }
+            vec![line]
         } else {
-            text.lines.into_iter().next().unwrap()
+            vec![line]
         };
 
-        // Transform description for commit lines
-        if let Some(ref desc) = graph_line.description {
-            line = transform_line_description(line, &graph_line.plain, desc);
+        // Hidden commits are dimmed whole, so they stand apart from the log
+        if graph_line.flags.hidden {
+            line_rows = line_rows
+                .into_iter()
+                .map(|row| Line::from_iter(row.spans.into_iter().map(|span| span.fg(theme.dim))))
+                .collect();
         }
 
-        // Highlight selected line
+        // Highlight selected commit (all of its rows), and search matches
         if Some(idx) == selected_line_idx {
             // Apply background color to indicate selection
-            line = line.bg(Color::Indexed(236)).bold();
+            line_rows = line_rows
+                .into_iter()
+                .map(|row| row.bg(theme.selection_bg).bold())
+                .collect();
+        } else if app.is_search_match(idx) {
+            line_rows = line_rows
+                .into_iter()
+                .map(|row| row.bg(theme.search_match_bg))
+                .collect();
         }
 
-        lines.push(line);
+        if app.wrap_descriptions {
+            line_heights.push(line_rows.len());
+        }
+        rows.extend(line_rows);
     }
 
-    let paragraph = Paragraph::new(lines).scroll((app.scroll_offset as u16, 0));
+    // Ensure selected commit is visible (done above without wrapping)
+    let total_rows = if app.wrap_descriptions {
+        app.ensure_selected_visible_rows(viewport_height, &line_heights);
+        rows.len()
+    } else {
+        total_lines
+    };
+
+    app.set_log_layout(area.y, area.height, line_heights);
+
+    let scroll = app.scroll_offset.saturating_sub(first_line);
+    let paragraph = Paragraph::new(rows).scroll((scroll as u16, 0));
     frame.render_widget(paragraph, area);
 
     // Scrollbar
-    let total_lines = app.line_count();
-    if total_lines > viewport_height {
+    if total_rows > viewport_height {
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("▲"))
             .end_symbol(Some("▼"));
-        let mut scrollbar_state = ScrollbarState::new(total_lines.saturating_sub(viewport_height))
+        let mut scrollbar_state = ScrollbarState::new(total_rows.saturating_sub(viewport_height))
             .position(app.scroll_offset);
         frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
     }
 }
 
-/// Transform a line's description part.
+/// Marker shown before the bookmarks of a commit row, if any.
+fn row_marker(app: &App, change_id: &str) -> Option<Span<'static>> {
+    let theme = app.theme();
+    if app.compare_base() == Some(change_id) {
+        Some(Span::styled(
+            "base ",
+            Style::default()
+                .fg(theme.inverse_fg)
+                .bg(theme.warning)
+                .bold(),
+        ))
+    } else if app.squash_source() == Some(change_id) {
+        Some(Span::styled(
+            "squash ",
+            Style::default().fg(theme.inverse_fg).bg(theme.mark).bold(),
+        ))
+    } else if app.is_marked(change_id) || app.in_visual_range(change_id) {
+        Some(Span::styled("● ", Style::default().fg(theme.mark).bold()))
+    } else if app.is_trunk(change_id) {
+        Some(Span::styled(
+            "trunk ",
+            Style::default()
+                .fg(theme.inverse_fg)
+                .bg(theme.accent)
+                .bold(),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Graph prefix for the continuation rows of a wrapped commit line.
+///
+/// Derived from the graph line below so that the lanes passing through keep
+/// going: vertical edges and commit nodes become `│`, everything else (merge
+/// and fork corners, elision markers) becomes a space.
+fn continuation_graph(next_plain: &str) -> String {
+    next_plain
+        .chars()
+        .take_while(|ch| !ch.is_alphanumeric() && *ch != '(')
+        .map(|ch| match ch {
+            '│' | '├' | '┤' | '┼' | '@' | '◆' | '○' | '●' | '◉' | '×' => '│',
+            _ => ' ',
+        })
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+/// Characters of the log graph drawn in their lane's color.
+const GRAPH_EDGE_CHARS: [char; 12] = ['│', '─', '├', '┤', '┬', '┴', '┼', '╭', '╮', '╯', '╰', '~'];
+
+/// Color the edges of a log line's graph prefix by lane.
+///
+/// Lanes are two columns wide and cycle through the theme's `graph_lanes`;
+/// a horizontal edge between two lanes takes the color of the lane on its
+/// right, which is the one merging or forking. Commit nodes, and edges jj
+/// colors itself, keep their colors.
+fn color_graph_lanes(line: Line<'static>, theme: &Theme) -> Line<'static> {
+    let mut spans = Vec::with_capacity(line.spans.len());
+    let mut column = 0;
+    let mut in_graph = true;
+    for span in line.spans {
+        if !in_graph || span.style.fg.is_some() {
+            column += span.content.width();
+            in_graph &= !span
+                .content
+                .contains(|ch: char| ch.is_alphanumeric() || ch == '(');
+            spans.push(span);
+            continue;
+        }
+        let mut run = String::new();
+        let mut run_style = span.style;
+        for ch in span.content.chars() {
+            in_graph &= !ch.is_alphanumeric() && ch != '(';
+            let style = if in_graph && GRAPH_EDGE_CHARS.contains(&ch) {
+                let lane = column.div_ceil(2) % theme.graph_lanes.len();
+                span.style.fg(theme.graph_lanes[lane])
+            } else {
+                span.style
+            };
+            if style != run_style && !run.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut run), run_style));
+            }
+            run_style = style;
+            run.push(ch);
+            column += ch.width().unwrap_or(0);
+        }
+        if !run.is_empty() {
+            spans.push(Span::styled(run, run_style));
+        }
+    }
+    Line::from(spans).style(line.style)
+}
+
+/// Lay out a commit line to fill `width` display columns.
+///
+/// The graph symbols and change_id keep jj's colors. Bookmarks and the
+/// description follow, and the description is truncated with `…` (or padded)
+/// so that the `columns` (author, timestamp, …) end up right-aligned in
+/// fixed-width columns.
 ///
-/// - Empty description: replace with "(no desc)" in DarkGray italic
-/// - Conventional commits: convert to emoji format
-fn transform_line_description<'a>(line: Line<'a>, plain: &str, description: &str) -> Line<'a> {
-    // Handle empty description: keep all original spans and append "(no desc)"
+/// An optional `marker` (e.g., `●` for marked commits) goes before the
+/// bookmarks. With a `continuation` graph prefix, a long description wraps
+/// onto extra rows indented to the description column instead of being
+/// truncated.
+///
+/// - Empty description: "(no desc)" in DarkGray italic
+/// - Conventional commits: converted to emoji format
+fn layout_commit_line<'a>(
+    line: Line<'a>,
+    graph_line: &GraphLine,
+    width: usize,
+    marker: Option<Span<'a>>,
+    continuation: Option<&str>,
+    columns: &[LogColumn],
+    theme: &Theme,
+) -> Vec<Line<'a>> {
+    let Some(prefix_chars) = graph_line.id_prefix_char_count() else {
+        return vec![line];
+    };
+
+    let mut spans = take_chars(line.spans, prefix_chars);
+    spans.push(Span::raw(" "));
+    let mut used: usize = spans.iter().map(|span| span.content.width()).sum();
+
+    if let Some(marker) = marker {
+        used += marker.content.width();
+        spans.push(marker);
+    }
+
+    if !graph_line.bookmarks.is_empty() {
+        let bookmarks = format!("[{}] ", graph_line.bookmarks.join(","));
+        used += bookmarks.width();
+        spans.push(Span::styled(bookmarks, Style::default().fg(theme.accent)));
+    }
+    if !graph_line.tags.is_empty() {
+        let tags = format!("<{}> ", graph_line.tags.join(","));
+        used += tags.width();
+        spans.push(Span::styled(tags, Style::default().fg(theme.tag)));
+    }
+
+    let columns_width: usize = columns.iter().map(|column| 1 + column.width).sum();
+    let description_width = width.saturating_sub(used + columns_width);
+    let description = graph_line.description.as_deref().unwrap_or_default();
+    let mut wrapped_rows = Vec::new();
     if description.is_empty() {
-        let mut spans: Vec<Span<'a>> = line.spans.into_iter().collect();
-        // Prevent selection highlight from filling this separator space.
-        // (Spans with explicit bg=Reset override the line background.)
-        spans.push(Span::styled(" ", Style::default().bg(Color::Reset)));
         spans.push(Span::styled(
-            "(no desc)",
-            Style::default().fg(Color::DarkGray).italic(),
+            fit_width("(no desc)", description_width),
+            Style::default().fg(theme.dim).italic(),
         ));
-        return Line::from(spans);
+    } else {
+        let transformed = crate::conventional::format_commit_message(description);
+        match continuation {
+            Some(graph) if description_width > 0 && transformed.width() > description_width => {
+                let mut chunks =
+                    crate::text::wrap_text(&transformed, description_width).into_iter();
+                let first = chunks.next().unwrap_or_default();
+                spans.push(Span::raw(fit_width(&first, description_width)));
+                wrapped_rows = chunks
+                    .map(|chunk| {
+                        let graph = color_graph_lanes(Line::raw(fit_width(graph, used)), theme);
+                        Line::from_iter(graph.spans.into_iter().chain([
+                            Span::raw(fit_width(&chunk, description_width)),
+                            Span::raw(" ".repeat(columns_width)),
+                        ]))
+                    })
+                    .collect();
+            }
+            _ => spans.push(Span::raw(fit_width(&transformed, description_width))),
+        }
     }
 
-    // Find the position of description in the plain text
-    let desc_start = match plain.find(description) {
-        Some(pos) => pos,
-        None => return line, // Description not found, return unchanged
-    };
-
-    // Calculate prefix (everything before description)
-    let prefix_plain = &plain[..desc_start];
+    for column in columns {
+        let (value, color) = match column.kind {
+            LogColumnKind::Author => (&graph_line.author, theme.author),
+            LogColumnKind::Timestamp => (&graph_line.timestamp, theme.timestamp),
+            LogColumnKind::CommitId => (&graph_line.commit_id, theme.commit_id),
+        };
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            fit_width_right(value.as_deref().unwrap_or_default(), column.width),
+            Style::default().fg(color),
+        ));
+    }
 
-    // Find how many characters to keep from the original spans
-    let mut prefix_char_count = 0;
-    let mut prefix_spans: Vec<Span<'a>> = Vec::new();
+    let mut rows = vec![Line::from(spans)];
+    rows.extend(wrapped_rows);
+    rows
+}
 
-    for span in line.spans.into_iter() {
-        let span_len = span.content.chars().count();
-        let prefix_remaining = prefix_plain
-            .chars()
-            .count()
-            .saturating_sub(prefix_char_count);
+/// Keep the first `count` characters of a list of spans, preserving styles.
+fn take_chars(spans: Vec<Span<'_>>, count: usize) -> Vec<Span<'_>> {
+    let mut remaining = count;
+    let mut kept = Vec::new();
 
-        if prefix_remaining == 0 {
-            // We've collected all prefix spans, skip the rest (description part)
+    for span in spans {
+        if remaining == 0 {
             break;
-        } else if span_len <= prefix_remaining {
+        }
+        let span_len = span.content.chars().count();
+        if span_len <= remaining {
             // This entire span is part of the prefix
-            prefix_char_count += span_len;
-            prefix_spans.push(span);
+            remaining -= span_len;
+            kept.push(span);
         } else {
             // This span partially overlaps - split it
-            let chars: String = span.content.chars().take(prefix_remaining).collect();
-            prefix_spans.push(Span::styled(chars, span.style));
+            let chars: String = span.content.chars().take(remaining).collect();
+            kept.push(Span::styled(chars, span.style));
             break;
         }
     }
 
-    // Apply conventional commits emoji transformation
-    let transformed = crate::conventional::format_commit_message(description);
-    if transformed != description {
-        // Conventional commit was transformed - use default style for emoji text
-        prefix_spans.push(Span::raw(transformed));
-    } else {
-        // Not a conventional commit - keep original style (just use raw text)
-        prefix_spans.push(Span::raw(description.to_string()));
-    }
-
-    Line::from(prefix_spans)
+    kept
 }
 
 /// Render the status bar for log view.
 fn render_log_status_bar(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = app.theme();
     // Show command result if available, otherwise show help text
-    let (text, style) = if app.is_loading_more {
+    let (text, style) = if let Some(running) = running_command_line(app) {
+        (
+            running,
+            Style::default().bg(theme.status_bg).fg(theme.warning),
+        )
+    } else if app.is_loading_more {
+        (
+            Line::raw(" Loading more entries... "),
+            Style::default().bg(theme.status_bg).fg(theme.warning),
+        )
+    } else if app.is_log_streaming() {
         (
-            " Loading more entries... ".to_string(),
-            Style::default().bg(Color::DarkGray).fg(Color::Yellow),
+            Line::raw(format!(
+                " Loading log... {} commits so far ",
+                app.commit_count()
+            )),
+            Style::default().bg(theme.status_bg).fg(theme.warning),
         )
     } else if let Some(result) = &app.last_command_result {
         let color = if result.success {
-            Color::Green
+            theme.success
         } else {
-            Color::Red
+            theme.error
         };
         let prefix = if result.success { "✓" } else { "✗" };
         let msg = format!(
             " {prefix} {} ",
             truncate_message(&result.message, area.width as usize - 4)
         );
-        (msg, Style::default().bg(Color::DarkGray).fg(color))
+        (
+            Line::raw(msg),
+            Style::default().bg(theme.status_bg).fg(color),
+        )
     } else {
-        // Build help text with entry count info
+        // Build help text with entry count and sync age info
         let count_info = if app.has_more_entries {
             format!("[{}+ commits] ", app.commit_count())
         } else {
             format!("[{} commits] ", app.commit_count())
         };
-        let help = format!(
-            " {count_info}n: new  e: edit  d: describe  b: bookmark  r: rebase  Enter: show  ?: help "
+        let marks_info = match app.mark_count() {
+            _ if app.is_visual_mode() => format!("[VISUAL {}] ", app.visual_range_len()),
+            0 => String::new(),
+            n => format!("[{n} marked] "),
+        };
+        let search_info = match app.search() {
+            Some(search) => match search.match_position(app.selected) {
+                Some(pos) => format!("[/{} {}/{}] ", search.query, pos + 1, search.matches.len()),
+                None => format!("[/{} {} matches] ", search.query, search.matches.len()),
+            },
+            None => String::new(),
+        };
+        let symbol = if app.auto_refresh_interval().is_some() {
+            "⟳"
+        } else {
+            "synced"
+        };
+        let sync_info = format!(
+            "{symbol} {} ago",
+            crate::text::format_elapsed(app.since_last_refresh())
         );
-        (help, Style::default().bg(Color::DarkGray).fg(Color::White))
+        let sync_style = if app.is_log_stale() {
+            Style::default().fg(theme.warning).bold()
+        } else {
+            Style::default()
+        };
+        let help = Line::from(vec![
+            Span::raw(format!(" {count_info}{marks_info}{search_info}")),
+            Span::styled(sync_info, sync_style),
+            Span::raw(
+                "  n: new  e: edit  d: describe  b: bookmark  r: rebase  Enter: show  ?: help ",
+            ),
+        ]);
+        (
+            help,
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
     };
 
     let status_bar = Paragraph::new(text).style(style);
@@ -223,100 +696,711 @@ fn truncate_message(msg: &str, max_width: usize) -> String {
     crate::text::truncate_str(first_line, max_width)
 }
 
-/// Render the detail view.
-fn render_detail_view(frame: &mut Frame, app: &mut App) {
-    let Some(state) = &app.detail_state else {
-        return;
+/// Spinner frames for a running background command.
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Status line for the command running in the background, if any.
+fn running_command_line(app: &App) -> Option<Line<'static>> {
+    let (label, elapsed) = app.running_command()?;
+    let frame = SPINNER_FRAMES[(elapsed.as_millis() / 100) as usize % SPINNER_FRAMES.len()];
+    Some(Line::raw(format!(
+        " {frame} Running {label}… {}  (navigation still works) ",
+        crate::text::format_elapsed(elapsed)
+    )))
+}
+
+/// Status bar line for the running command or the last command result, if any.
+fn command_status(app: &App, width: u16) -> Option<(Line<'static>, Style)> {
+    let theme = app.theme();
+    if let Some(running) = running_command_line(app) {
+        return Some((
+            running,
+            Style::default().bg(theme.status_bg).fg(theme.warning),
+        ));
+    }
+    let result = app.last_command_result.as_ref()?;
+    let (prefix, color) = if result.success {
+        ("✓", theme.success)
+    } else {
+        ("✗", theme.error)
     };
+    let msg = format!(
+        " {prefix} {} ",
+        truncate_message(&result.message, (width as usize).saturating_sub(4))
+    );
+    Some((
+        Line::raw(msg),
+        Style::default().bg(theme.status_bg).fg(color),
+    ))
+}
 
+/// Render the operation log view.
+fn render_op_log_view(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
     let chunks = Layout::vertical([
         Constraint::Length(1), // Title bar
-        Constraint::Min(3),    // Content
+        Constraint::Min(3),    // Operation list
         Constraint::Length(1), // Status bar
     ])
     .split(frame.area());
 
-    // Title bar
-    let change_id_short = &state.show_output.change_id[..8.min(state.show_output.change_id.len())];
-    let title = format!(" Revision: {change_id_short} ");
-    let title_bar =
-        Paragraph::new(title).style(Style::default().bg(Color::Magenta).fg(Color::White));
+    let title_bar = Paragraph::new(format!(" Operation log - {} ", app.repo_root)).style(
+        Style::default()
+            .bg(theme.detail_title_bg)
+            .fg(theme.title_fg),
+    );
     frame.render_widget(title_bar, chunks[0]);
 
-    // Content area
-    let content_area = chunks[1];
-    render_detail_content(frame, content_area, app);
+    // Two rows per operation: summary and command tags
+    let area = chunks[1];
+    app.ensure_op_log_visible(area.height as usize / 2);
+    let state = &app.op_log_state;
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, entry) in state.entries.iter().enumerate().skip(state.scroll) {
+        let marker = if entry.is_current { "@ " } else { "○ " };
+        let summary = Line::from(vec![
+            Span::styled(marker, Style::default().fg(theme.success).bold()),
+            Span::styled(entry.id.clone(), Style::default().fg(theme.op_id).bold()),
+            Span::raw(" "),
+            Span::styled(entry.user.clone(), Style::default().fg(theme.author)),
+            Span::raw(" "),
+            Span::styled(entry.time.clone(), Style::default().fg(theme.timestamp)),
+            Span::raw("  "),
+            Span::raw(entry.description.clone()),
+        ]);
+        let tags = Line::styled(format!("  {}", entry.tags), Style::default().fg(theme.dim));
+        if idx == state.selected {
+            lines.push(summary.bg(theme.selection_bg).bold());
+            lines.push(tags.bg(theme.selection_bg));
+        } else {
+            lines.push(summary);
+            lines.push(tags);
+        }
+    }
+    if state.entries.is_empty() {
+        lines.push(Line::styled(
+            "  (no operations)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
 
-    // Status bar
-    render_detail_status_bar(frame, chunks[2]);
+    // Status bar: running command, last command result, or key help
+    let (text, style) = command_status(app, chunks[2].width).unwrap_or_else(|| {
+        (
+            Line::raw(" j/k: select  Enter/r: restore to operation  R: refresh  q/Esc: back "),
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
+    });
+    frame.render_widget(Paragraph::new(text).style(style), chunks[2]);
 }
 
-/// Render the detail content with scrolling.
-fn render_detail_content(frame: &mut Frame, area: Rect, app: &mut App) {
-    let Some(state) = &app.detail_state else {
-        return;
-    };
+/// Render the workspaces view.
+fn render_workspaces_view(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Min(3),    // Workspace list
+        Constraint::Length(1), // Status bar
+    ])
+    .split(frame.area());
 
-    // Build content lines
-    let lines = build_detail_lines(&state.show_output);
-    let content_height = lines.len();
+    let title_bar = Paragraph::new(format!(" Workspaces - {} ", app.repo_root)).style(
+        Style::default()
+            .bg(theme.detail_title_bg)
+            .fg(theme.title_fg),
+    );
+    frame.render_widget(title_bar, chunks[0]);
 
-    // Update content height in app state
-    app.set_detail_content_height(content_height);
+    let area = chunks[1];
+    app.ensure_workspaces_visible(area.height as usize);
+    let state = app.workspaces();
+    let name_width = state
+        .entries
+        .iter()
+        .map(|e| e.name.width())
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, entry) in state.entries.iter().enumerate().skip(state.scroll) {
+        let marker = if entry.is_current { "@ " } else { "○ " };
+        let description = if entry.description.is_empty() {
+            Span::styled(
+                "(no description set)",
+                Style::default().fg(theme.dim).italic(),
+            )
+        } else {
+            Span::raw(entry.description.clone())
+        };
+        let line = Line::from(vec![
+            Span::styled(marker, Style::default().fg(theme.success).bold()),
+            Span::styled(
+                fit_width(&entry.name, name_width),
+                Style::default().fg(theme.accent).bold(),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                entry.change_id.clone(),
+                Style::default().fg(theme.change_id),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                entry.commit_id.clone(),
+                Style::default().fg(theme.commit_id),
+            ),
+            Span::raw("  "),
+            description,
+        ]);
+        if idx == state.selected {
+            lines.push(line.bg(theme.selection_bg).bold());
+        } else {
+            lines.push(line);
+        }
+    }
+    if state.entries.is_empty() {
+        lines.push(Line::styled(
+            "  (no workspaces)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
 
-    // Get current scroll position (re-borrow after mutation)
-    let scroll = app.detail_state.as_ref().map(|s| s.scroll).unwrap_or(0);
+    // Status bar: running command, last command result, or key help
+    let (text, style) = command_status(app, chunks[2].width).unwrap_or_else(|| {
+        (
+            Line::raw(
+                " j/k: select  Enter: show  a: add  x: forget  u: update stale  R: refresh  q/Esc: back ",
+            ),
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
+    });
+    frame.render_widget(Paragraph::new(text).style(style), chunks[2]);
+}
 
-    let visible_height = area.height as usize;
-    let max_scroll = content_height.saturating_sub(visible_height);
-    let clamped_scroll = scroll.min(max_scroll);
+/// Render the tags view.
+fn render_tags_view(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Min(3),    // Tag list
+        Constraint::Length(1), // Status bar
+    ])
+    .split(frame.area());
 
-    let paragraph = Paragraph::new(lines)
-        .scroll((clamped_scroll as u16, 0))
-        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
-    frame.render_widget(paragraph, area);
+    let title_bar = Paragraph::new(format!(" Tags - {} ", app.repo_root)).style(
+        Style::default()
+            .bg(theme.detail_title_bg)
+            .fg(theme.title_fg),
+    );
+    frame.render_widget(title_bar, chunks[0]);
 
-    // Scrollbar
-    if content_height > visible_height {
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some("▲"))
-            .end_symbol(Some("▼"));
-        let mut scrollbar_state =
-            ScrollbarState::new(content_height.saturating_sub(visible_height))
-                .position(clamped_scroll);
-        frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+    let area = chunks[1];
+    app.ensure_tags_visible(area.height as usize);
+    let state = app.tags();
+    let name_width = state
+        .entries
+        .iter()
+        .map(|e| e.name.width())
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, entry) in state.entries.iter().enumerate().skip(state.scroll) {
+        let mut spans = vec![
+            Span::raw(" "),
+            Span::styled(
+                fit_width(&entry.name, name_width),
+                Style::default().fg(theme.tag).bold(),
+            ),
+            Span::raw("  "),
+        ];
+        if entry.conflict {
+            spans.push(Span::styled(
+                "(conflicted)",
+                Style::default().fg(theme.error).bold(),
+            ));
+        } else {
+            spans.push(Span::styled(
+                entry.change_id.clone(),
+                Style::default().fg(theme.change_id),
+            ));
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                entry.commit_id.clone(),
+                Style::default().fg(theme.commit_id),
+            ));
+            spans.push(Span::raw("  "));
+            spans.push(if entry.description.is_empty() {
+                Span::styled(
+                    "(no description set)",
+                    Style::default().fg(theme.dim).italic(),
+                )
+            } else {
+                Span::raw(entry.description.clone())
+            });
+        }
+        let line = Line::from(spans);
+        if idx == state.selected {
+            lines.push(line.bg(theme.selection_bg).bold());
+        } else {
+            lines.push(line);
+        }
+    }
+    if state.entries.is_empty() {
+        lines.push(Line::styled(
+            "  (no tags)",
+            Style::default().fg(theme.dim).italic(),
+        ));
     }
+    frame.render_widget(Paragraph::new(lines), area);
+
+    // Status bar: running command, last command result, or key help
+    let (text, style) = command_status(app, chunks[2].width).unwrap_or_else(|| {
+        (
+            Line::raw(" j/k: select  Enter: show  R: refresh  q/Esc: back "),
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
+    });
+    frame.render_widget(Paragraph::new(text).style(style), chunks[2]);
 }
 
-fn styled_id_line(label: &'static str, prefix: &str, rest: &str, color: Color) -> Line<'static> {
-    Line::from(vec![
-        Span::styled(label, Style::default().bold()),
-        Span::styled(
-            prefix.to_string(),
-            Style::default().fg(color).add_modifier(Modifier::BOLD),
-        ),
-        Span::styled(rest.to_string(), Style::default().fg(Color::DarkGray)),
+/// Render the sparse patterns view.
+fn render_sparse_view(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Min(3),    // Pattern list
+        Constraint::Length(1), // Status bar
     ])
-}
+    .split(frame.area());
+
+    let title_bar = Paragraph::new(format!(" Sparse Patterns - {} ", app.repo_root)).style(
+        Style::default()
+            .bg(theme.detail_title_bg)
+            .fg(theme.title_fg),
+    );
+    frame.render_widget(title_bar, chunks[0]);
+
+    let area = chunks[1];
+    app.ensure_sparse_visible(area.height as usize);
+    let state = app.sparse();
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, pattern) in state.patterns.iter().enumerate().skip(state.scroll) {
+        let mut spans = vec![Span::raw(" "), Span::raw(pattern.clone())];
+        if pattern == "." {
+            spans.push(Span::styled(
+                "  (whole repository)",
+                Style::default().fg(theme.dim).italic(),
+            ));
+        }
+        let line = Line::from(spans);
+        if idx == state.selected {
+            lines.push(line.bg(theme.selection_bg).bold());
+        } else {
+            lines.push(line);
+        }
+    }
+    if state.patterns.is_empty() {
+        lines.push(Line::styled(
+            "  (no files checked out)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+
+    // Status bar: running command, last command result, or key help
+    let (text, style) = command_status(app, chunks[2].width).unwrap_or_else(|| {
+        (
+            Line::raw(" j/k: select  a: add  x: remove  r: reset  R: refresh  q/Esc: back "),
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
+    });
+    frame.render_widget(Paragraph::new(text).style(style), chunks[2]);
+}
+
+/// Render the stacks view: a header per stack, then its commits, head first.
+fn render_stacks_view(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Min(3),    // Stack list
+        Constraint::Length(1), // Status bar
+    ])
+    .split(frame.area());
+
+    let title_bar = Paragraph::new(format!(" Stacks - {} ", app.repo_root)).style(
+        Style::default()
+            .bg(theme.detail_title_bg)
+            .fg(theme.title_fg),
+    );
+    frame.render_widget(title_bar, chunks[0]);
+
+    let area = chunks[1];
+    app.ensure_stacks_visible(area.height as usize);
+    let state = app.stacks();
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, stack) in state.entries.iter().enumerate().skip(state.scroll) {
+        let selected = idx == state.selected;
+        let mut spans = vec![
+            Span::styled(
+                if selected { "▌ " } else { "  " },
+                Style::default().fg(theme.accent),
+            ),
+            Span::styled(stack.name(), Style::default().fg(theme.accent).bold()),
+            Span::raw("  "),
+            Span::styled(
+                format!("{} ahead of trunk", stack.commits.len()),
+                Style::default().fg(theme.dim),
+            ),
+        ];
+        if stack.needs_rebase {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                "needs rebase",
+                Style::default().fg(theme.warning).bold(),
+            ));
+        }
+        if stack.has_conflict() {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                "conflict",
+                Style::default().fg(theme.error).bold(),
+            ));
+        }
+        let header = Line::from(spans);
+        lines.push(if selected {
+            header.bg(theme.selection_bg)
+        } else {
+            header
+        });
+        for commit in &stack.commits {
+            let mut spans = vec![
+                Span::styled("  │ ○ ", Style::default().fg(theme.dim)),
+                Span::styled(
+                    commit.change_id.clone(),
+                    Style::default().fg(theme.change_id),
+                ),
+                Span::raw(" "),
+            ];
+            if !commit.bookmarks.is_empty() {
+                spans.push(Span::styled(
+                    format!("{} ", commit.bookmarks.join(" ")),
+                    Style::default().fg(theme.tag).bold(),
+                ));
+            }
+            spans.push(if commit.description.is_empty() {
+                let text = if commit.empty {
+                    "(empty) (no description set)"
+                } else {
+                    "(no description set)"
+                };
+                Span::styled(text, Style::default().fg(theme.dim).italic())
+            } else {
+                Span::raw(commit.description.clone())
+            });
+            lines.push(Line::from(spans));
+        }
+    }
+    if state.entries.is_empty() {
+        lines.push(Line::styled(
+            "  (no mutable commits outside trunk)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+
+    // Status bar: running command, last command result, or key help
+    let (text, style) = command_status(app, chunks[2].width).unwrap_or_else(|| {
+        (
+            Line::raw(
+                " j/k: select  Enter: show head  r: rebase onto trunk  R: refresh  q/Esc: back ",
+            ),
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
+    });
+    frame.render_widget(Paragraph::new(text).style(style), chunks[2]);
+}
+
+/// Render the file history view.
+fn render_file_history_view(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Min(3),    // Commit list
+        Constraint::Length(1), // Status bar
+    ])
+    .split(frame.area());
+
+    let area = chunks[1];
+    app.ensure_file_history_visible(area.height as usize);
+    let Some(state) = app.file_history() else {
+        return;
+    };
+
+    let title_bar = Paragraph::new(format!(" History: {} ", state.path)).style(
+        Style::default()
+            .bg(theme.diff_title_bg)
+            .fg(theme.inverse_fg),
+    );
+    frame.render_widget(title_bar, chunks[0]);
+
+    let selected_line_idx = state.graph_log.line_index_for_selection(state.selected);
+    let mut lines: Vec<Line> = Vec::new();
+    for (idx, graph_line) in state
+        .graph_log
+        .lines
+        .iter()
+        .enumerate()
+        .skip(state.scroll)
+        .take(area.height as usize)
+    {
+        let line = color_graph_lanes(ansi_line(&graph_line.raw), &theme);
+        let line = if graph_line.is_commit_line() {
+            layout_commit_line(
+                line,
+                graph_line,
+                area.width as usize,
+                None,
+                None,
+                &app.log_columns(),
+                &theme,
+            )
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+        } else {
+            line
+        };
+        lines.push(if Some(idx) == selected_line_idx {
+            line.bg(theme.selection_bg)
+        } else {
+            line
+        });
+    }
+    if state.graph_log.commit_count() == 0 {
+        lines.push(Line::styled(
+            "  (no commits touched this file)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+
+    let (text, style) = command_status(app, chunks[2].width).unwrap_or_else(|| {
+        (
+            Line::raw(" j/k: select  Enter: show commit  R: refresh  q/Esc: back "),
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
+    });
+    frame.render_widget(Paragraph::new(text).style(style), chunks[2]);
+}
+
+/// Render the detail view.
+fn render_detail_view(frame: &mut Frame, app: &mut App) {
+    let Some(state) = &app.detail_state else {
+        return;
+    };
+
+    let theme = *app.theme();
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Min(3),    // Content
+        Constraint::Length(1), // Status bar
+    ])
+    .split(frame.area());
+
+    // Title bar
+    let change_id_short = &state.show_output.change_id[..8.min(state.show_output.change_id.len())];
+    let title_bar = if state.working_copy {
+        Paragraph::new(format!(
+            " Working copy: {change_id_short} (uncommitted changes) "
+        ))
+        .style(Style::default().bg(theme.warning).fg(theme.inverse_fg))
+    } else {
+        Paragraph::new(format!(" Revision: {change_id_short} ")).style(
+            Style::default()
+                .bg(theme.detail_title_bg)
+                .fg(theme.title_fg),
+        )
+    };
+    frame.render_widget(title_bar, chunks[0]);
+
+    // Content area
+    let content_area = chunks[1];
+    render_detail_content(frame, content_area, app);
+
+    // Status bar
+    render_detail_status_bar(frame, chunks[2], &theme);
+}
+
+/// Render the detail content with scrolling.
+fn render_detail_content(frame: &mut Frame, area: Rect, app: &mut App) {
+    let Some(state) = &app.detail_state else {
+        return;
+    };
+
+    // Build content lines (inside the left and right borders)
+    let theme = app.theme();
+    let text_width = (area.width as usize).saturating_sub(2);
+    let mut lines = build_detail_lines(
+        &state.show_output,
+        &state.stat,
+        &state.children,
+        state.related,
+        app.metadata(),
+        text_width,
+        theme,
+    );
+    if app.is_colocated() {
+        // With the git backend the commit ID is the git commit's SHA
+        lines.insert(
+            2,
+            Line::from(vec![
+                Span::styled("Git SHA:   ", Style::default().bold()),
+                Span::styled(
+                    state.show_output.commit_id.clone(),
+                    Style::default().fg(theme.commit_id),
+                ),
+            ]),
+        );
+    }
+    if state.working_copy {
+        lines.splice(
+            0..0,
+            [
+                Line::styled(
+                    "● Uncommitted: the working copy's changes against its parent (jj diff)",
+                    Style::default().fg(theme.warning).bold(),
+                ),
+                Line::raw(""),
+            ],
+        );
+    }
+    let file_offsets = state.full_diff.as_ref().map(|full_diff| {
+        let (diff_lines, offsets) = build_full_diff_lines(full_diff, theme);
+        let start = lines.len();
+        lines.extend(diff_lines);
+        offsets.into_iter().map(|o| o + start).collect::<Vec<_>>()
+    });
+    let content_height = lines.len();
+
+    // Update content height (and file positions) in app state
+    app.set_detail_content_height(content_height);
+    if let Some(offsets) = file_offsets {
+        app.set_detail_file_offsets(offsets);
+    }
+
+    // Get current scroll position (re-borrow after mutation)
+    let scroll = app.detail_state.as_ref().map(|s| s.scroll).unwrap_or(0);
+
+    let visible_height = area.height as usize;
+    let max_scroll = content_height.saturating_sub(visible_height);
+    let clamped_scroll = scroll.min(max_scroll);
+
+    let paragraph = Paragraph::new(lines)
+        .scroll((clamped_scroll as u16, 0))
+        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+    frame.render_widget(paragraph, area);
+
+    // Scrollbar
+    if content_height > visible_height {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("▲"))
+            .end_symbol(Some("▼"));
+        let mut scrollbar_state =
+            ScrollbarState::new(content_height.saturating_sub(visible_height))
+                .position(clamped_scroll);
+        frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+    }
+}
+
+fn styled_id_line(
+    label: &'static str,
+    prefix: &str,
+    rest: &str,
+    color: Color,
+    dim: Color,
+) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(label, Style::default().bold()),
+        Span::styled(
+            prefix.to_string(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(rest.to_string(), Style::default().fg(dim)),
+    ])
+}
+
+/// Rows of the parents or the children in the detail view.
+///
+/// `selected` indexes these commits (`None` when the selection is elsewhere).
+fn related_lines(
+    label: &'static str,
+    commits: &[RelatedCommit],
+    selected: Option<usize>,
+    width: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let description_width = width.saturating_sub(11 + 9);
+    commits
+        .iter()
+        .enumerate()
+        .map(|(idx, commit)| {
+            let style = if selected == Some(idx) {
+                Style::default().bg(theme.selection_bg)
+            } else {
+                Style::default()
+            };
+            let description = if commit.description.is_empty() {
+                "(no description)"
+            } else {
+                &commit.description
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<11}", if idx == 0 { label } else { "" }),
+                    Style::default().bold(),
+                ),
+                Span::styled(
+                    format!("{} ", fit_width(&commit.change_id, 8)),
+                    style.fg(theme.change_id),
+                ),
+                Span::styled(fit_width(description, description_width), style),
+            ])
+        })
+        .collect()
+}
+
+/// Build lines for detail view content.
+///
+/// `related` is the selected entry of the parents followed by the children.
+fn build_detail_lines(
+    output: &ShowOutput,
+    stat: &DiffStat,
+    children: &[RelatedCommit],
+    related: usize,
+    metadata: Option<&RepoMetadata>,
+    width: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let commit_meta = metadata.and_then(|m| m.for_change(&output.change_id));
 
-/// Build lines for detail view content.
-fn build_detail_lines(output: &ShowOutput) -> Vec<Line<'static>> {
     let mut lines = vec![
         styled_id_line(
             "Change ID: ",
             &output.change_id_prefix,
             &output.change_id_rest,
-            Color::Magenta,
+            theme.change_id,
+            theme.dim,
         ),
         styled_id_line(
             "Commit ID: ",
             &output.commit_id_prefix,
             &output.commit_id_rest,
-            Color::Yellow,
+            theme.commit_id,
+            theme.dim,
         ),
         Line::from(vec![
             Span::styled("Author:    ", Style::default().bold()),
-            Span::styled(output.author.clone(), Style::default().fg(Color::Cyan)),
+            Span::styled(output.author.clone(), Style::default().fg(theme.author)),
         ]),
         Line::from(vec![
             Span::styled("Date:      ", Style::default().bold()),
@@ -324,37 +1408,120 @@ fn build_detail_lines(output: &ShowOutput) -> Vec<Line<'static>> {
         ]),
     ];
 
+    if let Some(signature) = &output.signature {
+        let (symbol, color) = if signature.is_good() {
+            ("✓", theme.success)
+        } else {
+            ("✗", theme.error)
+        };
+        let mut spans = vec![
+            Span::styled("Signature: ", Style::default().bold()),
+            Span::styled(
+                format!("{symbol} {}", signature.status),
+                Style::default().fg(color),
+            ),
+        ];
+        if !signature.display.is_empty() {
+            spans.push(Span::styled(
+                format!(" ({})", signature.display),
+                Style::default().fg(theme.dim),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
     if !output.bookmarks.is_empty() {
+        let mut spans = vec![Span::styled("Bookmarks: ", Style::default().bold())];
+        for (i, bookmark) in output.bookmarks.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(", "));
+            }
+            spans.push(Span::styled(
+                bookmark.clone(),
+                Style::default().fg(theme.accent),
+            ));
+            // Bookmarks shown by jj may carry a "*" suffix when out of sync
+            let name = bookmark.trim_end_matches('*');
+            for tracking in metadata.into_iter().flat_map(|m| m.tracking_for(name)) {
+                if tracking.ahead > 0 || tracking.behind > 0 {
+                    spans.push(Span::styled(
+                        format!(
+                            " ({}: ↑{} ↓{})",
+                            tracking.remote, tracking.ahead, tracking.behind
+                        ),
+                        Style::default().fg(theme.dim),
+                    ));
+                }
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if let Some(meta) = commit_meta {
+        if !meta.tags.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("Tags:      ", Style::default().bold()),
+                Span::styled(meta.tags.join(", "), Style::default().fg(theme.tag)),
+            ]));
+        }
+        if meta.conflict {
+            lines.push(Line::from(vec![
+                Span::styled("Status:    ", Style::default().bold()),
+                Span::styled("conflicted", Style::default().fg(theme.error).bold()),
+            ]));
+        }
+    }
+    if output.divergent {
         lines.push(Line::from(vec![
-            Span::styled("Bookmarks: ", Style::default().bold()),
+            Span::styled("Status:    ", Style::default().bold()),
+            Span::styled("divergent", Style::default().fg(theme.warning).bold()),
             Span::styled(
-                output.bookmarks.join(", "),
-                Style::default().fg(Color::Cyan),
+                " (other commits share the change ID; resolve from the log's menu)",
+                Style::default().fg(theme.dim),
             ),
         ]));
     }
 
+    let parent_count = output.parents.len();
+    lines.extend(related_lines(
+        "Parents:",
+        &output.parents,
+        Some(related).filter(|&idx| idx < parent_count),
+        width,
+        theme,
+    ));
+    lines.extend(related_lines(
+        "Children:",
+        children,
+        related.checked_sub(parent_count),
+        width,
+        theme,
+    ));
+
     lines.push(Line::raw(""));
 
-    // Description (first line gets emoji conversion)
+    // Description (first line gets emoji conversion), wrapped to the width
     lines.push(Line::styled(
         "─── Description ───",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme.dim),
     ));
     let mut desc_lines = output.description.lines();
     if let Some(first_line) = desc_lines.next() {
         // Apply conventional commits emoji to first line only
         let formatted = crate::conventional::format_commit_message(first_line);
-        lines.push(Line::raw(formatted));
         // Remaining lines as-is
-        for desc_line in desc_lines {
-            lines.push(Line::raw(desc_line.to_string()));
+        for desc_line in std::iter::once(formatted.as_str()).chain(desc_lines) {
+            lines.extend(
+                crate::text::wrap_text(desc_line, width)
+                    .into_iter()
+                    .map(Line::raw),
+            );
         }
     }
     if output.description.is_empty() {
         lines.push(Line::styled(
             "(no description)",
-            Style::default().fg(Color::DarkGray).italic(),
+            Style::default().fg(theme.dim).italic(),
         ));
     }
 
@@ -363,37 +1530,239 @@ fn build_detail_lines(output: &ShowOutput) -> Vec<Line<'static>> {
     // Diff summary
     lines.push(Line::styled(
         "─── Changed Files ───",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme.dim),
     ));
-    for entry in &output.diff_summary {
+    let path_width = output
+        .diff_summary
+        .iter()
+        .map(|entry| entry.path.width())
+        .max()
+        .unwrap_or(0)
+        .min(width.saturating_sub(STAT_BAR_WIDTH + 16));
+    let max_changes = stat.max_changes();
+    for (idx, entry) in output.diff_summary.iter().enumerate() {
         let (symbol, color) = match entry.status {
-            DiffStatus::Added => ("+", Color::Green),
-            DiffStatus::Modified => ("~", Color::Yellow),
-            DiffStatus::Deleted => ("-", Color::Red),
-            DiffStatus::Renamed => ("→", Color::Cyan),
-            DiffStatus::Copied => ("⊕", Color::Blue),
+            DiffStatus::Added => ("+", theme.added),
+            DiffStatus::Modified => ("~", theme.modified),
+            DiffStatus::Deleted => ("-", theme.removed),
+            DiffStatus::Renamed => ("→", theme.renamed),
+            DiffStatus::Copied => ("⊕", theme.copied),
         };
-        lines.push(Line::from(vec![
-            Span::styled(format!(" {symbol} "), Style::default().fg(color).bold()),
-            Span::raw(entry.path.clone()),
-        ]));
+        let mut spans = vec![Span::styled(
+            format!(" {symbol} "),
+            Style::default().fg(color).bold(),
+        )];
+        match stat.for_file(idx, &entry.path) {
+            Some(file_stat) => {
+                spans.push(Span::raw(fit_width(&entry.path, path_width)));
+                spans.push(Span::raw("  "));
+                spans.extend(file_stat_spans(
+                    file_stat,
+                    max_changes,
+                    STAT_BAR_WIDTH,
+                    theme,
+                ));
+            }
+            None => spans.push(Span::raw(entry.path.clone())),
+        }
+        lines.push(Line::from(spans));
     }
 
     if output.diff_summary.is_empty() {
         lines.push(Line::styled(
             "  (no changes)",
-            Style::default().fg(Color::DarkGray).italic(),
+            Style::default().fg(theme.dim).italic(),
+        ));
+    } else if !stat.files.is_empty() {
+        lines.push(Line::styled(
+            format!(" {}", stat_summary_text(output.diff_summary.len(), stat)),
+            Style::default().fg(theme.dim),
         ));
     }
 
     lines
 }
 
+/// Width of the change histogram of the file with the most changes.
+const STAT_BAR_WIDTH: usize = 20;
+
+/// Width of the change histogram in the diff view's file list.
+const FILE_LIST_STAT_BAR_WIDTH: usize = 6;
+
+/// `+added -removed` counts and a histogram bar for one file.
+///
+/// The bar is scaled so that the file with `max_changes` fills `bar_width`.
+fn file_stat_spans(
+    stat: &FileStat,
+    max_changes: usize,
+    bar_width: usize,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    let (added_len, removed_len) =
+        stat_bar_lengths(stat.added, stat.removed, max_changes, bar_width);
+    vec![
+        Span::styled(
+            format!("+{:<4}", stat.added),
+            Style::default().fg(theme.added),
+        ),
+        Span::styled(
+            format!("-{:<4} ", stat.removed),
+            Style::default().fg(theme.removed),
+        ),
+        Span::styled("■".repeat(added_len), Style::default().fg(theme.added)),
+        Span::styled("■".repeat(removed_len), Style::default().fg(theme.removed)),
+    ]
+}
+
+/// Lengths of the added and removed parts of a histogram bar.
+///
+/// Any change gets at least one cell, so small edits stay visible.
+fn stat_bar_lengths(
+    added: usize,
+    removed: usize,
+    max_changes: usize,
+    width: usize,
+) -> (usize, usize) {
+    let total = added + removed;
+    if total == 0 || max_changes == 0 {
+        return (0, 0);
+    }
+    let len = (total * width).div_ceil(max_changes).clamp(1, width);
+    let added_len = (len * added + total / 2) / total;
+    (added_len, len - added_len)
+}
+
+/// Total line of a diff stat: `2 files changed, 9 insertions(+), 4 deletions(-)`.
+fn stat_summary_text(files: usize, stat: &DiffStat) -> String {
+    let plural = |count: usize, word: &str| {
+        if count == 1 {
+            format!("{count} {word}")
+        } else {
+            format!("{count} {word}s")
+        }
+    };
+    format!(
+        "{} changed, {}(+), {}(-)",
+        plural(files, "file"),
+        plural(stat.added, "insertion"),
+        plural(stat.removed, "deletion")
+    )
+}
+
+/// Build the full diff section of the detail view.
+///
+/// Returns the lines and the line index of each file header. Collapsed files
+/// show only their header.
+fn build_full_diff_lines(
+    full_diff: &DetailDiff,
+    theme: &Theme,
+) -> (Vec<Line<'static>>, Vec<usize>) {
+    let mut lines = vec![
+        Line::raw(""),
+        Line::styled("─── Diff ───", Style::default().fg(theme.dim)),
+    ];
+    let mut offsets = Vec::with_capacity(full_diff.diff.files.len());
+    for (idx, file) in full_diff.diff.files.iter().enumerate() {
+        let path = file
+            .new_path
+            .as_deref()
+            .or(file.old_path.as_deref())
+            .unwrap_or("?");
+        let body = file.hunks.iter().flat_map(|h| h.lines.iter());
+        let added = body
+            .clone()
+            .filter(|l| l.kind == DiffLineKind::Added)
+            .count();
+        let removed = body.filter(|l| l.kind == DiffLineKind::Removed).count();
+        let collapsed = full_diff.collapsed.contains(&idx);
+
+        offsets.push(lines.len());
+        lines.push(Line::from(vec![
+            Span::styled(
+                if collapsed { "▸ " } else { "▾ " },
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled(path.to_string(), Style::default().bold()),
+            Span::styled(format!(" +{added}"), Style::default().fg(theme.added)),
+            Span::styled(format!(" -{removed}"), Style::default().fg(theme.removed)),
+        ]));
+        if collapsed {
+            continue;
+        }
+        for hunk in &file.hunks {
+            for line in std::iter::once(&hunk.header).chain(hunk.lines.iter()) {
+                lines.push(ansi_diff_line(line));
+            }
+        }
+    }
+    if full_diff.diff.files.is_empty() {
+        lines.push(Line::styled(
+            "  (no changes)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    (lines, offsets)
+}
+
+/// Parse a line of jj output with ANSI colors (only the first line is kept).
+fn ansi_line(raw: &str) -> Line<'static> {
+    let text = raw
+        .as_bytes()
+        .into_text()
+        .unwrap_or_else(|_| Text::raw(raw.to_string()));
+    text.lines.into_iter().next().unwrap_or_default()
+}
+
+/// Convert a diff line with jj's ANSI colors for display.
+fn ansi_diff_line(line: &DiffLine) -> Line<'static> {
+    line.raw
+        .as_bytes()
+        .into_text()
+        .ok()
+        .and_then(|text| text.lines.into_iter().next())
+        .unwrap_or_else(|| Line::raw(line.text.clone()))
+}
+
+/// Render the screen shown when the log cannot be loaded at startup.
+///
+/// `can_fall_back` offers a retry with the minimal log template.
+pub fn render_startup_error(frame: &mut Frame, error: &str, can_fall_back: bool, theme: &Theme) {
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Title bar
+        Constraint::Min(3),    // Error
+        Constraint::Length(1), // Status bar
+    ])
+    .split(frame.area());
+
+    let title_bar = Paragraph::new(" xorcist: the log could not be loaded ")
+        .style(Style::default().bg(theme.error).fg(theme.inverse_fg));
+    frame.render_widget(title_bar, chunks[0]);
+
+    let error = Paragraph::new(Text::raw(error.to_string()))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.error))
+                .title(" Error "),
+        );
+    frame.render_widget(error, chunks[1]);
+
+    let help_text = if can_fall_back {
+        " r: retry  m: retry with the minimal log template  q/Esc: quit "
+    } else {
+        " r: retry  q/Esc: quit "
+    };
+    let status_bar =
+        Paragraph::new(help_text).style(Style::default().bg(theme.status_bg).fg(theme.status_fg));
+    frame.render_widget(status_bar, chunks[2]);
+}
+
 /// Render the status bar for detail view.
-fn render_detail_status_bar(frame: &mut Frame, area: Rect) {
-    let help_text = " j/k: scroll  d: view diff  Ctrl+d/u: page  q/Esc: back  ?: help ";
+fn render_detail_status_bar(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let help_text = " j/k: scroll  d: view diff  D: full diff  ]/[: next/prev file  z: fold  s: split  Tab/Enter: parents/children  q/Esc: back  ?: help ";
     let status_bar =
-        Paragraph::new(help_text).style(Style::default().bg(Color::DarkGray).fg(Color::White));
+        Paragraph::new(help_text).style(Style::default().bg(theme.status_bg).fg(theme.status_fg));
     frame.render_widget(status_bar, area);
 }
 
@@ -402,6 +1771,7 @@ const MIN_WIDTH_FOR_HORIZONTAL_DIFF: u16 = 120;
 
 /// Render the diff view.
 fn render_diff_view(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
     let chunks = Layout::vertical([
         Constraint::Length(1), // Title bar
         Constraint::Min(3),    // Content
@@ -410,13 +1780,40 @@ fn render_diff_view(frame: &mut Frame, app: &mut App) {
     .split(frame.area());
 
     // Title bar
-    let change_id_short = if app.diff_state.change_id.len() >= 8 {
-        &app.diff_state.change_id[..8]
+    let title = match &app.diff_state.range {
+        // Label both sides: removed lines come from the base, added lines from the other
+        Some(range) => Line::from(vec![
+            Span::raw(" Compare  "),
+            Span::styled(
+                format!("− base: {}", range.from),
+                Style::default().fg(theme.removed).bold(),
+            ),
+            Span::raw("  →  "),
+            Span::styled(
+                format!("+ other: {}", range.to),
+                Style::default().fg(theme.added).bold(),
+            ),
+            Span::raw(" "),
+        ]),
+        None => {
+            let change_id_short = if app.diff_state.change_id.len() >= 8 {
+                &app.diff_state.change_id[..8]
+            } else {
+                &app.diff_state.change_id
+            };
+            Line::raw(format!(" Diff: {change_id_short} "))
+        }
+    };
+    let title_style = if app.diff_state.range.is_some() {
+        Style::default()
+            .bg(theme.compare_title_bg)
+            .fg(theme.title_fg)
     } else {
-        &app.diff_state.change_id
+        Style::default()
+            .bg(theme.diff_title_bg)
+            .fg(theme.inverse_fg)
     };
-    let title = format!(" Diff: {change_id_short} ");
-    let title_bar = Paragraph::new(title).style(Style::default().bg(Color::Green).fg(Color::Black));
+    let title_bar = Paragraph::new(title).style(title_style);
     frame.render_widget(title_bar, chunks[0]);
 
     // Content: responsive layout based on width
@@ -445,7 +1842,7 @@ fn render_diff_view(frame: &mut Frame, app: &mut App) {
     }
 
     // Status bar
-    render_diff_status_bar(frame, chunks[2]);
+    render_diff_status_bar(frame, chunks[2], app);
 }
 
 /// Render the file list in diff view.
@@ -453,31 +1850,49 @@ fn render_diff_file_list(frame: &mut Frame, area: Rect, app: &mut App) {
     let visible_height = area.height.saturating_sub(1) as usize; // Account for border title
     app.ensure_diff_file_visible(visible_height);
 
+    let theme = app.theme();
     let state = &app.diff_state;
     let mut lines: Vec<Line> = Vec::new();
+    let max_changes = state.stat.max_changes();
 
     for (idx, entry) in state.files.iter().enumerate() {
         let (symbol, color) = match entry.status {
-            DiffStatus::Added => ("+", Color::Green),
-            DiffStatus::Modified => ("~", Color::Yellow),
-            DiffStatus::Deleted => ("-", Color::Red),
-            DiffStatus::Renamed => ("→", Color::Cyan),
-            DiffStatus::Copied => ("⊕", Color::Blue),
+            DiffStatus::Added => ("+", theme.added),
+            DiffStatus::Modified => ("~", theme.modified),
+            DiffStatus::Deleted => ("-", theme.removed),
+            DiffStatus::Renamed => ("→", theme.renamed),
+            DiffStatus::Copied => ("⊕", theme.copied),
         };
 
         let is_selected = idx == state.selected;
         let path_style = if is_selected {
-            Style::default().bg(Color::Indexed(236)).bold()
+            Style::default().bg(theme.selection_bg).bold()
         } else {
             Style::default()
         };
 
-        let line = Line::from(vec![
-            Span::styled(format!(" {symbol} "), Style::default().fg(color).bold()),
+        let mark = if app.is_file_marked(&entry.path) {
+            Span::styled("●", Style::default().fg(theme.mark).bold())
+        } else {
+            Span::raw(" ")
+        };
+        let mut spans = vec![
+            mark,
+            Span::styled(format!("{symbol} "), Style::default().fg(color).bold()),
             Span::styled(entry.path.clone(), path_style),
-        ]);
+        ];
+        if let Some(file_stat) = state.stat.for_file(idx, &entry.path) {
+            spans.push(Span::raw(" "));
+            spans.extend(file_stat_spans(
+                file_stat,
+                max_changes,
+                FILE_LIST_STAT_BAR_WIDTH,
+                theme,
+            ));
+        }
+        let line = Line::from(spans);
         lines.push(if is_selected {
-            line.bg(Color::Indexed(236))
+            line.bg(theme.selection_bg)
         } else {
             line
         });
@@ -486,12 +1901,17 @@ fn render_diff_file_list(frame: &mut Frame, area: Rect, app: &mut App) {
     if state.files.is_empty() {
         lines.push(Line::styled(
             "  (no changes)",
-            Style::default().fg(Color::DarkGray).italic(),
+            Style::default().fg(theme.dim).italic(),
         ));
     }
 
+    let title = if state.stat.files.is_empty() {
+        " Files ".to_string()
+    } else {
+        format!(" Files +{} -{} ", state.stat.added, state.stat.removed)
+    };
     let paragraph = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::RIGHT).title(" Files "))
+        .block(Block::default().borders(Borders::RIGHT).title(title))
         .scroll((state.file_scroll as u16, 0));
     frame.render_widget(paragraph, area);
 }
@@ -505,41 +1925,50 @@ fn render_diff_text(frame: &mut Frame, area: Rect, app: &mut App) {
     app.clamp_diff_scroll(visible_height);
     app.clamp_diff_h_scroll(visible_width);
 
+    let theme = app.theme();
     let state = &app.diff_state;
     let h_scroll = state.diff_h_scroll;
     let v_scroll = state.diff_scroll;
 
+    // Syntax-highlight hunk lines when available; otherwise keep jj's ANSI colors
+    let highlighted = state.highlights.len() == state.diff.line_count();
     let lines: Vec<Line> = state
-        .diff_lines
-        .iter()
-        .map(|line| {
-            let style = if line.starts_with('+') && !line.starts_with("+++") {
-                Style::default().fg(Color::Green)
-            } else if line.starts_with('-') && !line.starts_with("---") {
-                Style::default().fg(Color::Red)
-            } else if line.starts_with("@@") {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default()
-            };
-            Line::styled(line.clone(), style)
+        .diff
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| match highlighted {
+            true if matches!(
+                line.kind,
+                DiffLineKind::Added | DiffLineKind::Removed | DiffLineKind::Context
+            ) =>
+            {
+                let changes = state.word_changes.get(idx).map_or(&[][..], Vec::as_slice);
+                highlighted_diff_line(line, &state.highlights[idx], changes, theme)
+            }
+            _ => ansi_diff_line(line),
         })
         .collect();
 
-    if lines.is_empty() {
+    if state.diff.is_empty() {
         let empty_msg = Paragraph::new("  (select a file to view diff)")
-            .style(Style::default().fg(Color::DarkGray).italic())
+            .style(Style::default().fg(theme.dim).italic())
             .block(Block::default().borders(Borders::ALL).title(" Diff "));
         frame.render_widget(empty_msg, area);
         return;
     }
 
-    // Build title with scroll indicator
-    let title = if h_scroll > 0 {
-        format!(" Diff (←{}) ", h_scroll)
-    } else {
-        " Diff ".to_string()
-    };
+    // Build title with the highlighted language and scroll indicator
+    let mut title = " Diff ".to_string();
+    if highlighted
+        && let Some(syntax) = state
+            .selected_file()
+            .and_then(|file| syntax_for_path(&file.path))
+    {
+        title.push_str(&format!("[{}] ", syntax.name));
+    }
+    if h_scroll > 0 {
+        title.push_str(&format!("(←{h_scroll}) "));
+    }
 
     // Use Paragraph::scroll for both vertical and horizontal scrolling
     let paragraph = Paragraph::new(lines)
@@ -548,7 +1977,7 @@ fn render_diff_text(frame: &mut Frame, area: Rect, app: &mut App) {
     frame.render_widget(paragraph, area);
 
     // Scrollbar
-    let content_height = state.diff_lines.len();
+    let content_height = state.diff.line_count();
     if content_height > visible_height {
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("▲"))
@@ -559,154 +1988,190 @@ fn render_diff_text(frame: &mut Frame, area: Rect, app: &mut App) {
     }
 }
 
+/// Build a hunk line with syntax colors on an added/removed background tint.
+///
+/// The byte ranges in `changes` (the words changed from the paired line) get
+/// a stronger tint.
+fn highlighted_diff_line(
+    line: &DiffLine,
+    tokens: &[Token],
+    changes: &[Range<usize>],
+    theme: &Theme,
+) -> Line<'static> {
+    let (prefix_style, base, word_bg) = match line.kind {
+        DiffLineKind::Added => (
+            Style::default().fg(theme.added).bold(),
+            Style::default().bg(theme.added_bg),
+            theme.added_word_bg,
+        ),
+        DiffLineKind::Removed => (
+            Style::default().fg(theme.removed).bold(),
+            Style::default().bg(theme.removed_bg),
+            theme.removed_word_bg,
+        ),
+        _ => (Style::default(), Style::default(), Color::Reset),
+    };
+    let text = line.text.as_str();
+    let prefix_len = text.chars().next().map_or(0, char::len_utf8);
+    let mut spans = vec![Span::styled(
+        text[..prefix_len].to_string(),
+        prefix_style.patch(base),
+    )];
+    let mut pos = prefix_len;
+    for token in tokens {
+        if token.start < pos || token.end > text.len() {
+            continue;
+        }
+        if token.start > pos {
+            spans.push(Span::styled(text[pos..token.start].to_string(), base));
+        }
+        let color = match token.kind {
+            TokenKind::Keyword => theme.syntax_keyword,
+            TokenKind::Type => theme.syntax_type,
+            TokenKind::Constant => theme.syntax_constant,
+            TokenKind::String => theme.syntax_string,
+            TokenKind::Number => theme.syntax_number,
+            TokenKind::Comment => theme.syntax_comment,
+        };
+        spans.push(Span::styled(
+            text[token.start..token.end].to_string(),
+            base.fg(color),
+        ));
+        pos = token.end;
+    }
+    if pos < text.len() {
+        spans.push(Span::styled(text[pos..].to_string(), base));
+    }
+    if changes.is_empty() {
+        return Line::from(spans);
+    }
+    Line::from(emphasize_ranges(spans, changes, word_bg))
+}
+
+/// Split `spans` at the edges of `ranges` (byte offsets into their joined
+/// text) and give the parts inside a range the background `bg`.
+fn emphasize_ranges(
+    spans: Vec<Span<'static>>,
+    ranges: &[Range<usize>],
+    bg: Color,
+) -> Vec<Span<'static>> {
+    let mut result = Vec::with_capacity(spans.len() + 2 * ranges.len());
+    let mut offset = 0;
+    for span in spans {
+        let content = span.content.as_ref();
+        let end = offset + content.len();
+        let mut pos = offset;
+        while pos < end {
+            // The range covering `pos`, or the next one to start in this span
+            let inside = ranges.iter().find(|r| r.start <= pos && pos < r.end);
+            let cut = match inside {
+                Some(range) => range.end.min(end),
+                None => ranges
+                    .iter()
+                    .map(|r| r.start)
+                    .filter(|&start| start > pos)
+                    .min()
+                    .map_or(end, |start| start.min(end)),
+            };
+            let Some(part) = content.get(pos - offset..cut - offset) else {
+                // Not on a character boundary: keep the rest unsplit
+                result.push(Span::styled(
+                    content[pos - offset..].to_string(),
+                    span.style,
+                ));
+                break;
+            };
+            let style = match inside {
+                Some(_) => span.style.bg(bg),
+                None => span.style,
+            };
+            result.push(Span::styled(part.to_string(), style));
+            pos = cut;
+        }
+        offset = end;
+    }
+    result
+}
+
 /// Render the status bar for diff view.
-fn render_diff_status_bar(frame: &mut Frame, area: Rect) {
-    let help_text = " j/k: select file  Ctrl+d/u: scroll  ←/→: pan  q/Esc: back ";
-    let status_bar =
-        Paragraph::new(help_text).style(Style::default().bg(Color::DarkGray).fg(Color::White));
-    frame.render_widget(status_bar, area);
+fn render_diff_status_bar(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = app.theme();
+    let (text, style) = command_status(app, area.width).unwrap_or_else(|| {
+        let marks_info = match app.diff_state.marked_files.len() {
+            0 => String::new(),
+            n => format!("[{n} marked] "),
+        };
+        (
+            Line::raw(format!(
+                " {marks_info}j/k: select file  Space: mark  s: split  h: history  Ctrl+d/u: scroll  ←/→: pan  q/Esc: back "
+            )),
+            Style::default().bg(theme.status_bg).fg(theme.status_fg),
+        )
+    });
+    frame.render_widget(Paragraph::new(text).style(style), area);
 }
 
-/// Render the help modal.
-fn render_help(frame: &mut Frame) {
+/// Render the help modal: the key bindings of the current view, scrollable.
+fn render_help(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
     let area = centered_rect(frame.area(), 50, 80);
 
     // Clear the area first to avoid background bleed-through
     frame.render_widget(Clear, area);
 
-    let help_lines = vec![
-        Line::styled(
-            "─── Keyboard Shortcuts ───",
-            Style::default().fg(Color::Cyan).bold(),
-        ),
-        Line::raw(""),
-        Line::styled("  Navigation", Style::default().bold()),
-        Line::from(vec![
-            Span::styled("  j / ↓      ", Style::default().fg(Color::Yellow)),
-            Span::raw("Move down"),
-        ]),
-        Line::from(vec![
-            Span::styled("  k / ↑      ", Style::default().fg(Color::Yellow)),
-            Span::raw("Move up"),
-        ]),
-        Line::from(vec![
-            Span::styled("  g / Home   ", Style::default().fg(Color::Yellow)),
-            Span::raw("Go to top"),
-        ]),
-        Line::from(vec![
-            Span::styled("  G / End    ", Style::default().fg(Color::Yellow)),
-            Span::raw("Go to bottom"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Ctrl+d     ", Style::default().fg(Color::Yellow)),
-            Span::raw("Page down"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Ctrl+u     ", Style::default().fg(Color::Yellow)),
-            Span::raw("Page up"),
-        ]),
-        Line::raw(""),
-        Line::styled("  jj Commands", Style::default().bold()),
-        Line::from(vec![
-            Span::styled("  n          ", Style::default().fg(Color::Yellow)),
-            Span::raw("New change"),
-        ]),
-        Line::from(vec![
-            Span::styled("  N          ", Style::default().fg(Color::Yellow)),
-            Span::raw("New change with message"),
-        ]),
-        Line::from(vec![
-            Span::styled("  e          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Edit revision"),
-        ]),
-        Line::from(vec![
-            Span::styled("  d          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Describe revision"),
-        ]),
-        Line::from(vec![
-            Span::styled("  b          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Set bookmark"),
-        ]),
-        Line::from(vec![
-            Span::styled("  a          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Abandon revision"),
-        ]),
-        Line::from(vec![
-            Span::styled("  s          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Squash into parent"),
-        ]),
-        Line::from(vec![
-            Span::styled("  f          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Git fetch"),
-        ]),
-        Line::from(vec![
-            Span::styled("  p          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Git push"),
-        ]),
-        Line::from(vec![
-            Span::styled("  u          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Undo last operation"),
-        ]),
-        Line::from(vec![
-            Span::styled("  r          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Rebase to destination"),
-        ]),
-        Line::raw(""),
-        Line::styled("  Detail View", Style::default().bold()),
-        Line::from(vec![
-            Span::styled("  d          ", Style::default().fg(Color::Yellow)),
-            Span::raw("View file diffs"),
-        ]),
-        Line::raw(""),
-        Line::styled("  Diff View", Style::default().bold()),
-        Line::from(vec![
-            Span::styled("  j / ↓      ", Style::default().fg(Color::Yellow)),
-            Span::raw("Select next file"),
-        ]),
-        Line::from(vec![
-            Span::styled("  k / ↑      ", Style::default().fg(Color::Yellow)),
-            Span::raw("Select previous file"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Ctrl+d/u   ", Style::default().fg(Color::Yellow)),
-            Span::raw("Scroll diff vertically"),
-        ]),
-        Line::from(vec![
-            Span::styled("  ← / →      ", Style::default().fg(Color::Yellow)),
-            Span::raw("Scroll diff horizontally"),
-        ]),
-        Line::from(vec![
-            Span::styled("  q / Esc    ", Style::default().fg(Color::Yellow)),
-            Span::raw("Back to detail"),
-        ]),
-        Line::raw(""),
-        Line::styled("  General", Style::default().bold()),
-        Line::from(vec![
-            Span::styled("  Enter      ", Style::default().fg(Color::Yellow)),
-            Span::raw("Open detail view"),
-        ]),
-        Line::from(vec![
-            Span::styled("  q          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Quit / Close view"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Esc        ", Style::default().fg(Color::Yellow)),
-            Span::raw("Close detail / help"),
-        ]),
-        Line::from(vec![
-            Span::styled("  ?          ", Style::default().fg(Color::Yellow)),
-            Span::raw("Toggle this help"),
-        ]),
-    ];
+    let lines = build_help_lines(app.view, &theme);
+    let content_height = lines.len();
+    let visible_height = area.height.saturating_sub(2) as usize;
+    app.clamp_help_scroll(content_height, visible_height);
+    let scroll = app.help_scroll();
+
+    let help_widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .title(format!(
+                    " Help: {} (j/k: scroll  ?/Esc: close) ",
+                    help::view_name(app.view)
+                )),
+        )
+        .scroll((scroll as u16, 0));
+    frame.render_widget(help_widget, area);
 
-    let help_widget = Paragraph::new(help_lines).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
-            .title(" Help "),
-    );
+    if content_height > visible_height {
+        let mut scrollbar_state =
+            ScrollbarState::new(content_height - visible_height).position(scroll);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            area,
+            &mut scrollbar_state,
+        );
+    }
+}
 
-    frame.render_widget(help_widget, area);
+/// Build the help lines of a view from its key binding tables.
+fn build_help_lines(view: View, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::styled(
+        "─── Keyboard Shortcuts ───",
+        Style::default().fg(theme.accent).bold(),
+    )];
+    for section in help::help_sections(view) {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!("  {}", section.title),
+            Style::default().bold(),
+        ));
+        for (keys, action) in section.bindings {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}", fit_width(keys, help::KEY_COLUMN_WIDTH)),
+                    Style::default().fg(theme.key),
+                ),
+                Span::raw(*action),
+            ]));
+        }
+    }
+    lines
 }
 
 /// Calculate a centered rectangle with given percentage of width and height.
@@ -718,18 +2183,255 @@ fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
     area
 }
 
-/// Render the modal overlay for confirmation dialogs.
-fn render_modal_overlay(frame: &mut Frame, app: &App) {
-    let ModalState::Confirm(action) = &app.modal else {
-        return;
+/// Render the output pane with the full output of recent commands.
+fn render_output_pane(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let area = centered_rect(frame.area(), 80, 70);
+    frame.render_widget(Clear, area);
+
+    let lines = build_output_lines(app.output_pane().entries.iter(), &theme);
+    let visible_height = area.height.saturating_sub(2) as usize;
+    app.clamp_output_scroll(lines.len(), visible_height);
+    let scroll = app.output_pane().scroll;
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(" Command output (j/k: scroll  o/Esc: close) ")
+        .title_style(Style::default().fg(theme.accent).bold());
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((scroll as u16, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// Build the output pane lines: a header per command, newest first, then its output.
+fn build_output_lines<'a>(
+    entries: impl Iterator<Item = &'a CommandOutput>,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for entry in entries {
+        if !lines.is_empty() {
+            lines.push(Line::raw(""));
+        }
+        let (symbol, color) = if entry.success {
+            ("✓", theme.success)
+        } else {
+            ("✗", theme.error)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{symbol} "), Style::default().fg(color).bold()),
+            Span::styled(entry.label.clone(), Style::default().bold()),
+            Span::styled(
+                format!(
+                    "  {} ago",
+                    crate::text::format_elapsed(entry.finished.elapsed())
+                ),
+                Style::default().fg(theme.dim),
+            ),
+        ]));
+        if entry.output.is_empty() {
+            lines.push(Line::styled(
+                "  (no output)",
+                Style::default().fg(theme.dim).italic(),
+            ));
+        }
+        lines.extend(
+            entry
+                .output
+                .lines()
+                .map(|line| Line::raw(format!("  {line}"))),
+        );
+    }
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            "  (no commands run yet)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    lines
+}
+
+/// Render the history of status bar messages.
+fn render_messages_panel(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let area = centered_rect(frame.area(), 80, 70);
+    frame.render_widget(Clear, area);
+
+    let entries = &app.messages_panel().entries;
+    let count = entries.len();
+    let lines = build_message_lines(entries.iter(), &theme);
+    let visible_height = area.height.saturating_sub(2) as usize;
+    app.clamp_messages_scroll(lines.len(), visible_height);
+    let scroll = app.messages_panel().scroll;
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(format!(" Messages: {count} (j/k: scroll  L/Esc: close) "))
+        .title_style(Style::default().fg(theme.accent).bold());
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((scroll as u16, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// Build the message history lines, newest first: outcome, age and the
+/// first line, then the rest of the message indented.
+fn build_message_lines<'a>(
+    entries: impl Iterator<Item = &'a StatusMessage>,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for entry in entries {
+        let (symbol, color) = if entry.success {
+            ("✓", theme.success)
+        } else {
+            ("✗", theme.error)
+        };
+        let mut message = entry.message.lines();
+        lines.push(Line::from(vec![
+            Span::styled(format!("{symbol} "), Style::default().fg(color).bold()),
+            Span::styled(
+                format!(
+                    "{:>4} ago  ",
+                    crate::text::format_elapsed(entry.shown.elapsed())
+                ),
+                Style::default().fg(theme.dim),
+            ),
+            Span::raw(message.next().unwrap_or_default().to_string()),
+        ]));
+        lines.extend(message.map(|line| Line::raw(format!("             {line}"))));
+    }
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            "  (no messages yet)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    lines
+}
+
+/// Render the panel listing every jj command run this session.
+fn render_command_history_panel(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let area = centered_rect(frame.area(), 90, 70);
+    frame.render_widget(Clear, area);
+
+    let entries = app.command_history();
+    let visible_height = area.height.saturating_sub(2) as usize;
+    app.ensure_command_history_visible(entries.len(), visible_height);
+    let panel = app.command_history_panel();
+    let lines = build_command_history_lines(&entries, panel.selected, &theme)
+        .into_iter()
+        .skip(panel.scroll)
+        .collect::<Vec<_>>();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(format!(
+            " Command history: {} (Enter: run again  H/Esc: close) ",
+            entries.len()
+        ))
+        .title_style(Style::default().fg(theme.accent).bold());
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Build one line per command, newest first: outcome, duration, age and command line.
+fn build_command_history_lines(
+    entries: &[HistoryEntry],
+    selected: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| {
+            let (symbol, color) = if entry.success {
+                ("✓", theme.success)
+            } else {
+                ("✗", theme.error)
+            };
+            let duration = if entry.duration.as_secs() == 0 {
+                format!("{}ms", entry.duration.as_millis())
+            } else {
+                format!("{:.1}s", entry.duration.as_secs_f64())
+            };
+            let line = Line::from(vec![
+                Span::styled(format!("{symbol} "), Style::default().fg(color).bold()),
+                Span::styled(format!("{:<8}", entry.status), Style::default().fg(color)),
+                Span::styled(
+                    format!(
+                        "{duration:>7} {:>4} ago  ",
+                        crate::text::format_elapsed(entry.finished.elapsed())
+                    ),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::raw(entry.command_line()),
+            ]);
+            if idx == selected {
+                line.bg(theme.selection_bg).bold()
+            } else {
+                line
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            "  (no commands run yet)",
+            Style::default().fg(theme.dim).italic(),
+        ));
+    }
+    lines
+}
+
+/// Render the modal overlay for confirmation dialogs and menus.
+fn render_modal_overlay(frame: &mut Frame, app: &mut App) {
+    let action = match &app.modal {
+        ModalState::Confirm(action) => action,
+        ModalState::Menu(menu) => {
+            let regions = render_context_menu(frame, menu, app.theme());
+            app.set_modal_regions(regions);
+            return;
+        }
+        ModalState::Push(dialog) => {
+            let regions = render_push_dialog(frame, dialog, app.theme());
+            app.set_modal_regions(regions);
+            return;
+        }
+        ModalState::Placement(menu) => {
+            let regions = render_placement_menu(frame, menu, app.theme());
+            app.set_modal_regions(regions);
+            return;
+        }
+        ModalState::None => return,
     };
 
+    let theme = *app.theme();
     let message = action.confirm_message();
+    let command = format!("$ {}", action.command_preview());
+    let details = action.details();
 
-    // Calculate centered area for modal box
+    // Calculate centered area for modal box (room for the command and the
+    // details list, if any); a long command wraps
     let area = frame.area();
-    let width = (message.len() as u16 + 6).max(30).min(area.width - 4);
-    let height = 5;
+    let content_width = details
+        .iter()
+        .map(|line| line.width())
+        .chain([message.width(), command.width()])
+        .max()
+        .unwrap_or(0);
+    let width = (content_width as u16 + 6).max(30).min(area.width - 4);
+    let command_width = width.saturating_sub(6).max(1) as usize;
+    let command_height = command.width().div_ceil(command_width) as u16;
+    let details_height = if details.is_empty() {
+        0
+    } else {
+        details.len() as u16 + 1
+    };
+    let height = (5 + command_height + details_height).min(area.height);
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
     let modal_area = Rect::new(x, y, width, height);
@@ -740,18 +2442,20 @@ fn render_modal_overlay(frame: &mut Frame, app: &App) {
     // Build the modal box
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
+        .border_style(Style::default().fg(theme.key))
         .title(" Confirm ")
-        .title_style(Style::default().fg(Color::Yellow).bold());
+        .title_style(Style::default().fg(theme.key).bold());
 
     let inner_area = block.inner(modal_area);
     frame.render_widget(block, modal_area);
 
-    // Split inner area for message and buttons
+    // Split inner area for message, command, details and buttons
     let chunks = Layout::vertical([
-        Constraint::Length(1), // Message
-        Constraint::Length(1), // Spacing
-        Constraint::Length(1), // Buttons
+        Constraint::Length(1),              // Message
+        Constraint::Length(command_height), // Command
+        Constraint::Length(details_height), // Details
+        Constraint::Min(1),                 // Spacing
+        Constraint::Length(1),              // Buttons
     ])
     .split(inner_area);
 
@@ -759,14 +2463,270 @@ fn render_modal_overlay(frame: &mut Frame, app: &App) {
     let message_paragraph = Paragraph::new(message).alignment(ratatui::layout::Alignment::Center);
     frame.render_widget(message_paragraph, chunks[0]);
 
-    // Render buttons
+    // Render the exact command line below it
+    let command_area = Rect {
+        x: chunks[1].x + 1,
+        width: chunks[1].width.saturating_sub(2),
+        ..chunks[1]
+    };
+    frame.render_widget(
+        Paragraph::new(command)
+            .style(Style::default().fg(theme.dim))
+            .alignment(ratatui::layout::Alignment::Center)
+            .wrap(Wrap { trim: false }),
+        command_area,
+    );
+
+    // Render details below a blank line (left-aligned list)
+    if !details.is_empty() {
+        let detail_lines: Vec<Line> = std::iter::once(Line::raw(""))
+            .chain(
+                details
+                    .into_iter()
+                    .map(|line| Line::raw(format!("  {line}"))),
+            )
+            .collect();
+        frame.render_widget(Paragraph::new(detail_lines), chunks[2]);
+    }
+
+    // Render buttons (the focused one reversed)
+    let button = |label: &'static str, color: Color, focused: bool| {
+        let style = Style::default().fg(color).bold();
+        Span::styled(label, if focused { style.reversed() } else { style })
+    };
     let buttons = Line::from(vec![
-        Span::styled(" [Y]es ", Style::default().fg(Color::Green).bold()),
+        button(
+            " [Y]es ",
+            theme.success,
+            app.confirm_focus == ConfirmButton::Yes,
+        ),
         Span::raw("  "),
-        Span::styled(" [N]o ", Style::default().fg(Color::Red).bold()),
+        button(
+            " [N]o ",
+            theme.error,
+            app.confirm_focus == ConfirmButton::No,
+        ),
     ]);
+    // Record the button positions for mouse clicks (same centering as the paragraph)
+    let buttons_area = chunks[4];
+    let left = buttons_area.x + buttons_area.width.saturating_sub(buttons.width() as u16) / 2;
+    let yes_width = buttons.spans[0].width() as u16;
+    let no_x = left + yes_width + buttons.spans[1].width() as u16;
+    let regions = vec![
+        ModalRegion {
+            x: left,
+            y: buttons_area.y,
+            width: yes_width,
+            target: ModalTarget::Yes,
+        },
+        ModalRegion {
+            x: no_x,
+            y: buttons_area.y,
+            width: buttons.spans[2].width() as u16,
+            target: ModalTarget::No,
+        },
+    ];
     let buttons_paragraph = Paragraph::new(buttons).alignment(ratatui::layout::Alignment::Center);
-    frame.render_widget(buttons_paragraph, chunks[2]);
+    frame.render_widget(buttons_paragraph, buttons_area);
+    app.set_modal_regions(regions);
+}
+
+/// Render the context menu popup, returning the screen region of each item.
+fn render_context_menu(frame: &mut Frame, menu: &ContextMenu, theme: &Theme) -> Vec<ModalRegion> {
+    let items = menu
+        .items
+        .iter()
+        .map(|action| (action.key(), action.label()));
+    render_menu_popup(
+        frame,
+        format!(" Actions: {} ", menu.change_id),
+        items,
+        menu.selected,
+        theme,
+    )
+}
+
+/// Render the placement menu of a new change.
+fn render_placement_menu(
+    frame: &mut Frame,
+    menu: &PlacementMenu,
+    theme: &Theme,
+) -> Vec<ModalRegion> {
+    let items = NewPlacement::ALL
+        .iter()
+        .map(|placement| (placement.key(), placement.label()));
+    render_menu_popup(
+        frame,
+        format!(" New change: {} ", menu.change_id),
+        items,
+        menu.selected,
+        theme,
+    )
+}
+
+/// Render a centered menu of `(key, label)` items, returning the screen
+/// region of each item.
+fn render_menu_popup<'a>(
+    frame: &mut Frame,
+    title: String,
+    items: impl Iterator<Item = (char, &'a str)>,
+    selected: usize,
+    theme: &Theme,
+) -> Vec<ModalRegion> {
+    let items: Vec<Line> = items
+        .enumerate()
+        .map(|(idx, (key, label))| {
+            let key = match key {
+                ' ' => "Spc".to_string(),
+                ch => ch.to_string(),
+            };
+            let line = Line::from(vec![
+                Span::styled(format!(" {key:>3} "), Style::default().fg(theme.key)),
+                Span::raw(label),
+            ]);
+            if idx == selected {
+                line.bg(theme.selection_bg).bold()
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    // Calculate centered area for the menu box
+    let area = frame.area();
+    let content_width = items.iter().map(|line| line.width()).max().unwrap_or(0);
+    let width = (content_width as u16 + 4).max(30).min(area.width);
+    let height = (items.len() as u16 + 2).min(area.height);
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let menu_area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, menu_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(title)
+        .title_style(Style::default().fg(theme.accent).bold());
+    let inner = block.inner(menu_area);
+    let regions = (0..items.len())
+        .map(|idx| ModalRegion {
+            x: inner.x,
+            y: inner.y + idx as u16,
+            width: inner.width,
+            target: ModalTarget::MenuItem(idx),
+        })
+        .filter(|region| region.y < inner.bottom())
+        .collect();
+    frame.render_widget(Paragraph::new(items).block(block), menu_area);
+    regions
+}
+
+/// Render the push dialog: one checkbox row per local bookmark.
+fn render_push_dialog(frame: &mut Frame, dialog: &PushDialog, theme: &Theme) -> Vec<ModalRegion> {
+    let name_width = dialog
+        .bookmarks
+        .iter()
+        .map(|b| b.name.width())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<Line> = dialog
+        .bookmarks
+        .iter()
+        .enumerate()
+        .map(|(idx, bookmark)| {
+            let check = if bookmark.checked { "[x] " } else { "[ ] " };
+            let mut spans = vec![
+                Span::styled(format!(" {check}"), Style::default().fg(theme.key)),
+                Span::styled(
+                    fit_width(&bookmark.name, name_width),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::raw("  "),
+            ];
+            if bookmark.protected {
+                spans.push(Span::styled("protected ", Style::default().fg(theme.error)));
+            }
+            if bookmark.is_new() {
+                spans.push(Span::styled(
+                    "new (--allow-new)",
+                    Style::default().fg(theme.warning),
+                ));
+            }
+            for tracking in &bookmark.tracking {
+                let (text, color) = match (tracking.ahead, tracking.behind) {
+                    (0, 0) => (format!("{} in sync ", tracking.remote), theme.dim),
+                    (ahead, behind) => (
+                        format!("{} ↑{ahead} ↓{behind} ", tracking.remote),
+                        if behind > 0 {
+                            theme.warning
+                        } else {
+                            theme.success
+                        },
+                    ),
+                };
+                spans.push(Span::styled(text, Style::default().fg(color)));
+            }
+            let line = Line::from(spans);
+            if idx == dialog.selected {
+                line.bg(theme.selection_bg).bold()
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    let area = frame.area();
+    let content_width = items.iter().map(|line| line.width()).max().unwrap_or(0);
+    let width = (content_width as u16 + 4).max(56).min(area.width);
+    let height = (items.len() as u16 + 2).min(area.height);
+    let x = (area.width.saturating_sub(width)) / 2;
+    let y = (area.height.saturating_sub(height)) / 2;
+    let dialog_area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, dialog_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(" Push bookmarks (Space: select  a: all  Enter: push  Esc: cancel) ")
+        .title_style(Style::default().fg(theme.accent).bold());
+    let inner = block.inner(dialog_area);
+    let regions = (0..items.len())
+        .map(|idx| ModalRegion {
+            x: inner.x,
+            y: inner.y + idx as u16,
+            width: inner.width,
+            target: ModalTarget::MenuItem(idx),
+        })
+        .filter(|region| region.y < inner.bottom())
+        .collect();
+    frame.render_widget(Paragraph::new(items).block(block), dialog_area);
+    regions
+}
+
+/// Render the `--profile` debug overlay in the bottom-right corner.
+fn render_profile_overlay(frame: &mut Frame, profiler: &Profiler, theme: &Theme) {
+    let lines: Vec<Line> = profiler
+        .overlay_lines()
+        .into_iter()
+        .map(Line::from)
+        .collect();
+
+    // Keep clear of the status bar
+    let area = frame.area();
+    let content_width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+    let width = (content_width as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(1));
+    let x = area.width.saturating_sub(width);
+    let y = area.height.saturating_sub(height + 1);
+    let overlay_area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, overlay_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.dim))
+        .title(" Profile ")
+        .title_style(Style::default().fg(theme.accent).bold());
+    frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
 }
 
 /// Render the input overlay for text entry.
@@ -774,6 +2734,10 @@ fn render_input_overlay(frame: &mut Frame, app: &App) {
     let Some(mode) = &app.input_mode else {
         return;
     };
+    if matches!(mode, InputMode::Command | InputMode::Search) {
+        render_command_line(frame, app, *mode);
+        return;
+    }
 
     // Calculate centered area for input box
     let area = frame.area();
@@ -787,18 +2751,53 @@ fn render_input_overlay(frame: &mut Frame, app: &App) {
     frame.render_widget(Clear, input_area);
 
     // Build the input box
+    let subject_title;
     let title = match mode {
+        InputMode::Describe if app.describe_keeps_body() => " Describe (subject; body kept) ",
         InputMode::Describe => " Describe ",
         InputMode::BookmarkSet => " Set Bookmark ",
         InputMode::NewWithMessage => " New Change ",
+        InputMode::Commit => " Commit @ ",
         InputMode::RebaseDestination => " Rebase to ",
+        InputMode::DuplicateDestination => " Duplicate onto ",
+        InputMode::RebaseSkipEmptiedDestination => " Rebase (skip emptied) to ",
+        InputMode::WorkspaceAdd => " Add workspace at ",
+        InputMode::SparseAdd => " Add sparse pattern ",
+        InputMode::SetAuthor => " Author ",
+        InputMode::RestoreInto => " Restore into ",
+        InputMode::PatchPath => " Export patch ",
+        InputMode::Finder => " Find ",
+        InputMode::Palette => " Commands ",
+        InputMode::Command => " Command ",
+        InputMode::Search => " Search ",
+        InputMode::DiffFrom => " Diff --from ",
+        InputMode::DiffTo => " Diff --to ",
+        InputMode::ConventionalType | InputMode::ConventionalScope => " Describe ",
+        InputMode::AuthorFilter => " Author ",
+        InputMode::PathFilter => " Path ",
+        InputMode::ProtectedPush => {
+            subject_title = app
+                .protected_push()
+                .map(ProtectedPush::prompt_title)
+                .unwrap_or_default();
+            &subject_title
+        }
+        InputMode::ConventionalSubject => {
+            let prefix = app
+                .conventional_draft()
+                .map(ConventionalDraft::prefix)
+                .unwrap_or_default();
+            subject_title = format!(" Describe {prefix}");
+            &subject_title
+        }
     };
 
+    let theme = app.theme();
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
+        .border_style(Style::default().fg(theme.accent))
         .title(title)
-        .title_style(Style::default().fg(Color::Cyan).bold());
+        .title_style(Style::default().fg(theme.accent).bold());
 
     let inner_area = block.inner(input_area);
     frame.render_widget(block, input_area);
@@ -806,7 +2805,7 @@ fn render_input_overlay(frame: &mut Frame, app: &App) {
     // Render the input text
     let input_value = app.input.value();
     let display_text = if input_value.is_empty() {
-        Span::styled(mode.placeholder(), Style::default().fg(Color::DarkGray))
+        Span::styled(mode.placeholder(), Style::default().fg(theme.dim))
     } else {
         Span::raw(input_value)
     };
@@ -823,9 +2822,293 @@ fn render_input_overlay(frame: &mut Frame, app: &App) {
     }
 }
 
+/// Render the fuzzy finder: the query on top, the best matching commits below.
+///
+/// The command palette is drawn the same way, listing actions instead.
+fn render_finder(frame: &mut Frame, app: &mut App) {
+    let theme = *app.theme();
+    let area = centered_rect(frame.area(), 80, 60);
+    frame.render_widget(Clear, area);
+
+    // One row of query, the rest for results
+    let visible_height = area.height.saturating_sub(3) as usize;
+    app.ensure_finder_visible(visible_height);
+    let Some(finder) = app.finder() else {
+        return;
+    };
+    let mode = app.input_mode.unwrap_or(InputMode::Finder);
+    let title = if mode == InputMode::Palette {
+        format!(
+            " Commands: {} (↑/↓ or Ctrl+N/P: move  Enter: run  Esc: cancel) ",
+            finder.results.len()
+        )
+    } else if mode == InputMode::AuthorFilter {
+        format!(
+            " Authors: {} (↑/↓: move  Enter: filter  Esc: cancel) ",
+            app.author_choices().len().saturating_sub(1)
+        )
+    } else if mode == InputMode::ConventionalType {
+        " Commit type (↑/↓: move  Enter: pick  Esc: cancel) ".to_string()
+    } else if mode == InputMode::ConventionalScope {
+        let prefix = app
+            .conventional_draft()
+            .map(|draft| draft.commit_type.as_str())
+            .unwrap_or_default();
+        format!(" Scope of {prefix} (↑/↓: move  Enter: pick  Esc: cancel) ")
+    } else {
+        format!(
+            " Find commit: {} (↑/↓ or Ctrl+N/P: move  Enter: jump  Esc: cancel) ",
+            finder.results.len()
+        )
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(title)
+        .title_style(Style::default().fg(theme.accent).bold());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let query = app.input.value();
+    let query_line = if query.is_empty() {
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.accent)),
+            Span::styled(mode.placeholder(), Style::default().fg(theme.dim)),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.accent)),
+            Span::raw(query),
+        ])
+    };
+
+    let width = inner.width as usize;
+    let mut lines = vec![query_line];
+    for (row, &commit) in finder
+        .results
+        .iter()
+        .enumerate()
+        .skip(finder.scroll)
+        .take(visible_height)
+    {
+        if mode == InputMode::AuthorFilter {
+            let Some((name, count)) = app.author_choices().get(commit) else {
+                continue;
+            };
+            let (name, hint) = match commit {
+                0 => ("(all authors)", String::new()),
+                _ => (name.as_str(), format!("{count} loaded")),
+            };
+            lines.push(palette_entry_line(
+                name,
+                &hint,
+                row == finder.selected,
+                width,
+                &theme,
+            ));
+            continue;
+        }
+        if let Some(draft) = app.conventional_draft()
+            && let Some((value, hint)) = draft.options.get(commit)
+        {
+            // Entry 0 is the typed query (or, for scopes, no scope at all)
+            let (value, hint) = match (commit, value.is_empty()) {
+                (0, true) => ("(no scope)", ""),
+                (0, false) => (value.as_str(), "new"),
+                _ => (value.as_str(), hint.as_str()),
+            };
+            lines.push(palette_entry_line(
+                value,
+                hint,
+                row == finder.selected,
+                width,
+                &theme,
+            ));
+            continue;
+        }
+        if mode == InputMode::Palette {
+            let entry = &PALETTE_ENTRIES[commit];
+            lines.push(palette_entry_line(
+                entry.name,
+                entry.key,
+                row == finder.selected,
+                width,
+                &theme,
+            ));
+            continue;
+        }
+        let Some(line) = app
+            .graph_log
+            .line_index_for_selection(commit)
+            .and_then(|idx| app.graph_log.lines.get(idx))
+        else {
+            continue;
+        };
+        lines.push(finder_result_line(
+            line,
+            row == finder.selected,
+            width,
+            &theme,
+        ));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+
+    let scroll = app.input.visual_scroll(width.saturating_sub(2));
+    let cursor_x = 2 + app.input.visual_cursor().saturating_sub(scroll);
+    frame.set_cursor_position(Position::new(inner.x + cursor_x as u16, inner.y));
+}
+
+/// One action of the command palette: its name, and its key on the right.
+fn palette_entry_line(
+    name: &str,
+    key: &str,
+    selected: bool,
+    width: usize,
+    theme: &Theme,
+) -> Line<'static> {
+    let name_width = width.saturating_sub(key.width() + 1);
+    let row = Line::from(vec![
+        Span::raw(fit_width(name, name_width)),
+        Span::raw(" "),
+        Span::styled(key.to_string(), Style::default().fg(theme.key)),
+    ]);
+    if selected {
+        row.bg(theme.selection_bg).bold()
+    } else {
+        row
+    }
+}
+
+/// One result of the fuzzy finder: change ID, bookmarks, description and author.
+fn finder_result_line(
+    line: &GraphLine,
+    selected: bool,
+    width: usize,
+    theme: &Theme,
+) -> Line<'static> {
+    let change_id = line.change_id.clone().unwrap_or_default();
+    let author = line.author.clone().unwrap_or_default();
+    let mut spans = vec![Span::styled(
+        format!("{change_id} "),
+        Style::default().fg(theme.change_id),
+    )];
+    let mut used = change_id.width() + 1;
+    if !line.bookmarks.is_empty() {
+        let bookmarks = format!("[{}] ", line.bookmarks.join(","));
+        used += bookmarks.width();
+        spans.push(Span::styled(bookmarks, Style::default().fg(theme.accent)));
+    }
+    if !line.tags.is_empty() {
+        let tags = format!("<{}> ", line.tags.join(","));
+        used += tags.width();
+        spans.push(Span::styled(tags, Style::default().fg(theme.tag)));
+    }
+    let description = line.description.as_deref().unwrap_or_default();
+    let description_width = width.saturating_sub(used + 1 + author.width());
+    if description.is_empty() {
+        spans.push(Span::styled(
+            fit_width("(no desc)", description_width),
+            Style::default().fg(theme.dim).italic(),
+        ));
+    } else {
+        spans.push(Span::raw(fit_width(description, description_width)));
+    }
+    spans.push(Span::raw(" "));
+    spans.push(Span::styled(author, Style::default().fg(theme.author)));
+    let row = Line::from(spans);
+    if selected {
+        row.bg(theme.selection_bg).bold()
+    } else {
+        row
+    }
+}
+
+/// Render the `:` command line over the bottom row.
+///
+/// While the command name is being typed, matching commands are listed to
+/// the right of the input.
+fn render_command_line(frame: &mut Frame, app: &App, mode: InputMode) {
+    let area = frame.area();
+    let line_area = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+    frame.render_widget(Clear, line_area);
+
+    let theme = app.theme();
+    let input_value = app.input.value();
+    let prompt = if mode == InputMode::Search { "/" } else { ":" };
+    let mut spans = vec![Span::styled(
+        prompt,
+        Style::default().fg(theme.accent).bold(),
+    )];
+    if input_value.is_empty() {
+        spans.push(Span::styled(
+            mode.placeholder(),
+            Style::default().fg(theme.dim),
+        ));
+    } else if mode == InputMode::Search {
+        spans.push(Span::raw(input_value));
+        let count = app.search().map_or(0, |s| s.matches.len());
+        spans.push(Span::styled(
+            format!("  [{count} matches]"),
+            Style::default().fg(theme.dim),
+        ));
+    } else {
+        spans.push(Span::raw(input_value));
+        if !input_value.contains(char::is_whitespace) {
+            let candidates = crate::app::matching_commands(input_value);
+            if candidates.len() > 1 {
+                spans.push(Span::styled(
+                    format!("  [{}]", candidates.join(" ")),
+                    Style::default().fg(theme.dim),
+                ));
+            }
+        }
+    }
+
+    // Keep the cursor visible for long input (1 column for the prompt)
+    let input_width = line_area.width.saturating_sub(1) as usize;
+    let scroll = app.input.visual_scroll(input_width);
+    let paragraph = Paragraph::new(Line::from(spans)).scroll((0, scroll as u16));
+    frame.render_widget(paragraph, line_area);
+
+    let cursor_x = 1 + app.input.visual_cursor().saturating_sub(scroll);
+    frame.set_cursor_position(Position::new(line_area.x + cursor_x as u16, line_area.y));
+}
+
+/// Render the UI into an off-screen buffer of `width` x `height` cells.
+///
+/// The snapshot tests draw through this instead of a real terminal.
+#[cfg(test)]
+pub fn render_to_buffer(app: &mut App, width: u16, height: u16) -> ratatui::buffer::Buffer {
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height))
+        .expect("test backend never fails");
+    terminal
+        .draw(|frame| render(frame, app))
+        .expect("test backend never fails");
+    terminal.backend().buffer().clone()
+}
+
+#[cfg(test)]
+mod snapshot_tests;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::DEFAULT_LOG_COLUMNS;
+    use crate::jj::GraphLog;
+
+    #[test]
+    fn test_repo_state_text() {
+        assert_eq!(
+            repo_state_text(Some("1a2b3c4d5e6f"), Some("qzmtztvnkxyz"), true).as_deref(),
+            Some("op 1a2b3c4d5e6f · @ qzmtztvn · colocated ")
+        );
+        assert_eq!(
+            repo_state_text(Some(""), None, false),
+            None,
+            "nothing to show before metadata is loaded"
+        );
+    }
 
     #[test]
     fn test_truncate_message_ascii() {
@@ -856,4 +3139,328 @@ mod tests {
             "Error: 失敗..."
         );
     }
+
+    fn two_file_diff() -> DetailDiff {
+        let output = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n-old\n+new\n same\n\
+                      diff --git a/b.rs b/b.rs\n--- a/b.rs\n+++ b/b.rs\n@@ -1 +1,2 @@\n keep\n+added\n";
+        DetailDiff {
+            diff: crate::jj::GitDiff::parse(output),
+            ..Default::default()
+        }
+    }
+
+    fn plain(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_build_output_lines() {
+        let entries = [
+            CommandOutput {
+                label: "jj git push".to_string(),
+                success: true,
+                output: "Changes to push to origin:\n  Move bookmark main".to_string(),
+                finished: std::time::Instant::now(),
+            },
+            CommandOutput {
+                label: "jj new".to_string(),
+                success: false,
+                output: String::new(),
+                finished: std::time::Instant::now(),
+            },
+        ];
+        let lines: Vec<String> = build_output_lines(entries.iter(), &Theme::default())
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(lines[0], "✓ jj git push  0s ago");
+        assert_eq!(lines[2], "    Move bookmark main");
+        assert_eq!(lines[3], "");
+        assert!(lines[4].starts_with("✗ jj new"));
+        assert_eq!(lines[5], "  (no output)");
+        assert!(
+            build_output_lines([].iter(), &Theme::default())[0]
+                .to_string()
+                .contains("no commands")
+        );
+    }
+
+    #[test]
+    fn test_build_detail_lines_wraps_description() {
+        let output = ShowOutput {
+            change_id: "qzmtztvnxyz".to_string(),
+            change_id_prefix: "qz".to_string(),
+            change_id_rest: "mtztvnxyz".to_string(),
+            commit_id: "a1b2c3d4".to_string(),
+            commit_id_prefix: "a1".to_string(),
+            commit_id_rest: "b2c3d4".to_string(),
+            author: "Jane Doe".to_string(),
+            timestamp: "2024-05-01 12:00".to_string(),
+            description: "一二三四五六 seven eight\n\nnine".to_string(),
+            bookmarks: Vec::new(),
+            diff_summary: Vec::new(),
+            signature: None,
+            parents: Vec::new(),
+            divergent: false,
+        };
+        let stat = DiffStat::default();
+        let lines: Vec<String> =
+            build_detail_lines(&output, &stat, &[], 0, None, 12, &Theme::default())
+                .iter()
+                .map(plain)
+                .collect();
+        let start = lines
+            .iter()
+            .position(|l| l == "─── Description ───")
+            .unwrap()
+            + 1;
+        assert_eq!(
+            lines[start..start + 5],
+            ["一二三四五六", "seven eight", "", "nine", ""]
+        );
+    }
+
+    #[test]
+    fn test_stat_bar_lengths() {
+        assert_eq!(stat_bar_lengths(30, 10, 40, 20), (15, 5));
+        assert_eq!(stat_bar_lengths(3, 1, 40, 20), (2, 0));
+        // Tiny changes still get one cell
+        assert_eq!(stat_bar_lengths(0, 1, 1000, 20), (0, 1));
+        assert_eq!(stat_bar_lengths(0, 0, 40, 20), (0, 0));
+        let stat = DiffStat {
+            files: Vec::new(),
+            added: 1,
+            removed: 4,
+        };
+        assert_eq!(
+            stat_summary_text(1, &stat),
+            "1 file changed, 1 insertion(+), 4 deletions(-)"
+        );
+    }
+
+    #[test]
+    fn test_build_full_diff_lines() {
+        let mut full_diff = two_file_diff();
+        let (lines, offsets) = build_full_diff_lines(&full_diff, &Theme::default());
+        assert_eq!(offsets, vec![2, 7]);
+        assert_eq!(plain(&lines[2]), "▾ a.rs +1 -1");
+        assert_eq!(plain(&lines[3]), "@@ -1,2 +1,2 @@");
+        assert_eq!(plain(&lines[7]), "▾ b.rs +1 -0");
+        assert_eq!(lines.len(), 11);
+
+        // A collapsed file keeps only its header
+        full_diff.collapsed.insert(0);
+        let (lines, offsets) = build_full_diff_lines(&full_diff, &Theme::default());
+        assert_eq!(offsets, vec![2, 3]);
+        assert_eq!(plain(&lines[2]), "▸ a.rs +1 -1");
+        assert_eq!(lines.len(), 7);
+    }
+
+    #[test]
+    fn test_full_diff_keeps_jj_colors() {
+        let output =
+            "diff --git a/f b/f\n@@ -1 +1 @@\n\x1b[38;5;1m-a\x1b[39m\n\x1b[38;5;2m+b\x1b[39m\n";
+        let full_diff = DetailDiff {
+            diff: crate::jj::GitDiff::parse(output),
+            ..Default::default()
+        };
+        let (lines, _) = build_full_diff_lines(&full_diff, &Theme::default());
+        let removed = &lines[4];
+        assert_eq!(plain(removed), "-a");
+        assert_eq!(removed.spans[0].style.fg, Some(Color::Indexed(1)));
+        assert_eq!(lines[5].spans[0].style.fg, Some(Color::Indexed(2)));
+    }
+
+    fn layout_rows(raw: &str, width: usize, continuation: Option<&str>) -> Vec<String> {
+        let graph_line = GraphLog::from_output(raw).lines.remove(0);
+        let line = raw.as_bytes().into_text().unwrap().lines.remove(0);
+        layout_commit_line(
+            line,
+            &graph_line,
+            width,
+            None,
+            continuation,
+            &DEFAULT_LOG_COLUMNS,
+            &Theme::default(),
+        )
+        .iter()
+        .map(|row| row.spans.iter().map(|span| span.content.as_ref()).collect())
+        .collect()
+    }
+
+    fn layout_plain(raw: &str, width: usize) -> String {
+        layout_rows(raw, width, None).remove(0)
+    }
+
+    #[test]
+    fn test_layout_commit_line_right_aligns_columns() {
+        let row = layout_plain(
+            "@  qzmtztvn\x1fJane Doe\x1f11m\x1fmain\x1f\x1fa long description here",
+            70,
+        );
+        assert_eq!(row.width(), 70);
+        assert!(row.starts_with("@  qzmtztvn [main] a long description here"));
+        assert!(row.ends_with("        Jane Doe  11m"));
+    }
+
+    #[test]
+    fn test_layout_commit_line_truncates_description() {
+        let row = layout_plain(
+            "○  rvzpxnov\x1fA\x1f2d\x1f\x1f\x1f説明がとても長いコミットメッセージ",
+            40,
+        );
+        assert_eq!(row.width(), 40);
+        assert!(row.starts_with("○  rvzpxnov 説明… "), "{row}");
+        assert!(row.ends_with(" A   2d"));
+    }
+
+    #[test]
+    fn test_layout_commit_line_empty_description() {
+        let row = layout_plain("○  rvzpxnov\x1fA\x1f2d\x1f\x1f\x1f", 50);
+        assert_eq!(row.width(), 50);
+        assert!(row.starts_with("○  rvzpxnov (no desc) "));
+    }
+
+    #[test]
+    fn test_layout_commit_line_wraps_description() {
+        let raw = "○  rvzpxnov\x1fA\x1f2d\x1f\x1f\x1fone two three four five six";
+        // 12 columns of prefix + 10 of description + 22 of author/timestamp
+        let rows = layout_rows(raw, 44, Some("│"));
+        assert_eq!(
+            rows,
+            vec![
+                format!("○  rvzpxnov one two   {}A   2d", " ".repeat(16)),
+                format!("│           three four{}", " ".repeat(22)),
+                format!("│           five six  {}", " ".repeat(22)),
+            ]
+        );
+
+        // Short descriptions stay on one row
+        let raw = "○  rvzpxnov\x1fA\x1f2d\x1f\x1f\x1fshort";
+        assert_eq!(layout_rows(raw, 44, Some("│")).len(), 1);
+    }
+
+    #[test]
+    fn test_layout_commit_line_custom_columns() {
+        let raw = "@  qzmtztvn\x1fJane Doe\x1f2024-05-01\x1f\x1f\x1fdesc";
+        let graph_line = GraphLog::from_output(raw).lines.remove(0);
+        let line = raw.as_bytes().into_text().unwrap().lines.remove(0);
+        let columns = [
+            LogColumn {
+                kind: LogColumnKind::Timestamp,
+                width: 10,
+            },
+            LogColumn {
+                kind: LogColumnKind::Author,
+                width: 6,
+            },
+        ];
+        let rows = layout_commit_line(
+            line,
+            &graph_line,
+            50,
+            None,
+            None,
+            &columns,
+            &Theme::default(),
+        );
+        let row: String = rows[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(row.width(), 50);
+        assert!(row.ends_with(" 2024-05-01 Jane …"), "{row}");
+
+        let line = raw.as_bytes().into_text().unwrap().lines.remove(0);
+        let rows = layout_commit_line(line, &graph_line, 30, None, None, &[], &Theme::default());
+        let row: String = rows[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(row.trim_end(), "@  qzmtztvn desc");
+    }
+
+    #[test]
+    fn test_continuation_graph() {
+        assert_eq!(continuation_graph("○  rvzpxnov"), "│");
+        assert_eq!(continuation_graph("├─╮"), "│");
+        assert_eq!(continuation_graph("│ ○  xyzwvuts"), "│ │");
+        assert_eq!(continuation_graph("~  (elided revisions)"), "");
+        assert_eq!(continuation_graph(""), "");
+    }
+
+    #[test]
+    fn test_color_graph_lanes() {
+        let theme = Theme::default();
+        let fg = |line: &Line, text: &str| {
+            line.spans
+                .iter()
+                .find(|span| span.content == text)
+                .unwrap_or_else(|| panic!("no span {text:?} in {line:?}"))
+                .style
+                .fg
+        };
+
+        // Each lane has its own color; the corner merging in takes its lane's
+        let line = color_graph_lanes(Line::raw("│ ├─╯"), &theme);
+        assert_eq!(fg(&line, "│"), Some(theme.graph_lanes[0]));
+        assert_eq!(fg(&line, "├"), Some(theme.graph_lanes[1]));
+        assert_eq!(fg(&line, "─╯"), Some(theme.graph_lanes[2]));
+
+        // Nodes and jj's own colors are kept, and the text after the graph
+        let line = Line::from(vec![
+            Span::raw("│ ○  "),
+            Span::styled("rvzpxnov", Style::default().fg(Color::Magenta)),
+            Span::raw(" fix │ pipes"),
+        ]);
+        let line = color_graph_lanes(line, &theme);
+        assert_eq!(fg(&line, "│"), Some(theme.graph_lanes[0]));
+        assert_eq!(fg(&line, " ○  "), None);
+        assert_eq!(fg(&line, "rvzpxnov"), Some(Color::Magenta));
+        assert_eq!(fg(&line, " fix │ pipes"), None);
+    }
+
+    #[test]
+    fn test_layout_commit_line_marked() {
+        let raw = "○  rvzpxnov\x1fA\x1f2d\x1fmain\x1f\x1fdesc";
+        let graph_line = GraphLog::from_output(raw).lines.remove(0);
+        let line = raw.as_bytes().into_text().unwrap().lines.remove(0);
+        let marker = Span::raw("● ");
+        let rows = layout_commit_line(
+            line,
+            &graph_line,
+            50,
+            Some(marker),
+            None,
+            &DEFAULT_LOG_COLUMNS,
+            &Theme::default(),
+        );
+        let row: String = rows[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert!(row.starts_with("○  rvzpxnov ● [main] desc"), "{row}");
+        assert_eq!(row.width(), 50);
+    }
+
+    #[test]
+    fn test_emphasize_ranges() {
+        let spans = vec![
+            Span::styled("+", Style::default().bold()),
+            Span::styled("let", Style::default().fg(Color::Blue)),
+            Span::raw(" a = 12;"),
+        ];
+        // "+let a = 12;": emphasize "let" partly and "12"
+        let parts = emphasize_ranges(spans, &[2..4, 9..11], Color::Green);
+        let texts: Vec<(&str, Option<Color>)> = parts
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.bg))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                ("+", None),
+                ("l", None),
+                ("et", Some(Color::Green)),
+                (" a = ", None),
+                ("12", Some(Color::Green)),
+                (";", None),
+            ]
+        );
+        assert_eq!(parts[2].style.fg, Some(Color::Blue));
+    }
 }
@@ -0,0 +1,254 @@
+//! Snapshot tests of whole screens, rendered into a test buffer.
+//!
+//! Each test renders a view from fixture data and compares the screen text
+//! with `src/ui/snapshots/<name>.snap`. After an intended UI change, rerun
+//! the tests with `UPDATE_SNAPSHOTS=1` to rewrite the snapshots, and review
+//! the diff before committing them.
+
+use std::path::PathBuf;
+
+use ratatui::buffer::Buffer;
+use unicode_width::UnicodeWidthStr;
+
+use super::{render_startup_error, render_to_buffer};
+use crate::app::{App, DetailDiff, DetailState, View};
+use crate::jj::{
+    DiffEntry, DiffStat, DiffStatus, FileStat, GitDiff, GraphLog, JjRunner, RelatedCommit,
+    ShowOutput,
+};
+use crate::theme::Theme;
+
+/// Log of a small repository: a working copy on a feature branch beside main.
+const FIXTURE_LOG: &str = "\
+@  qzmtztvn\x1fJane Doe\x1f5m\x1f\x1fw\x1ffeat: add snapshot tests
+○  rvzpxnov\x1fJane Doe\x1f2h\x1ffeature\x1f\x1frefactor: split the renderer
+│ ○  kxqpwsml\x1fJohn Roe\x1f1d\x1f\x1fe\x1f
+├─╯
+◆  zsuskuln\x1fJohn Roe\x1f3w\x1fmain\x1dv0.1.2\x1fi\x1fchore: release 0.1.2
+~";
+
+fn fixture_app() -> App {
+    let runner = JjRunner::new().with_work_dir(std::path::Path::new("/tmp"));
+    App::new(
+        GraphLog::from_output(FIXTURE_LOG),
+        "/repo".to_string(),
+        runner,
+    )
+}
+
+fn fixture_show() -> ShowOutput {
+    ShowOutput {
+        change_id: "rvzpxnovqqkklmtsnwpkxqoyyspvyxvl".to_string(),
+        change_id_prefix: "rv".to_string(),
+        change_id_rest: "zpxnovqqkklmtsnwpkxqoyyspvyxvl".to_string(),
+        commit_id: "0f9e8d7c6b5a49382716a5b4c3d2e1f0a9b8c7d6".to_string(),
+        commit_id_prefix: "0f9".to_string(),
+        commit_id_rest: "e8d7c6b5a49382716a5b4c3d2e1f0a9b8c7d6".to_string(),
+        author: "Jane Doe <jane@example.com>".to_string(),
+        timestamp: "2026-10-14 09:30:00".to_string(),
+        description: "refactor: split the renderer\n\nMove each view into its own function."
+            .to_string(),
+        bookmarks: vec!["feature".to_string()],
+        diff_summary: vec![
+            DiffEntry {
+                status: DiffStatus::Modified,
+                path: "src/ui.rs".to_string(),
+            },
+            DiffEntry {
+                status: DiffStatus::Added,
+                path: "src/ui/snapshot_tests.rs".to_string(),
+            },
+        ],
+        signature: None,
+        parents: vec![RelatedCommit {
+            change_id: "zsuskuln".to_string(),
+            description: "chore: release 0.1.2".to_string(),
+        }],
+        divergent: false,
+    }
+}
+
+/// Screen text of a buffer, one line per row, without trailing spaces.
+fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut row = String::new();
+        let mut x = area.left();
+        while x < area.right() {
+            let symbol = buffer[(x, y)].symbol();
+            row.push_str(symbol);
+            // Wide characters cover the next cell too
+            x += symbol.width().max(1) as u16;
+        }
+        text.push_str(row.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Compare `app` rendered at `width` x `height` with the snapshot `name`.
+fn assert_snapshot(name: &str, app: &mut App, width: u16, height: u16) {
+    assert_snapshot_text(name, buffer_text(&render_to_buffer(app, width, height)));
+}
+
+/// Compare screen text with the snapshot `name`.
+fn assert_snapshot_text(name: &str, actual: String) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/ui/snapshots")
+        .join(format!("{name}.snap"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &actual).expect("failed to write snapshot");
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run with UPDATE_SNAPSHOTS=1 to create it:\n{actual}",
+            path.display()
+        )
+    });
+    assert!(
+        actual == expected,
+        "snapshot {name} changed; run with UPDATE_SNAPSHOTS=1 to accept it\n\
+         --- expected\n{expected}--- actual\n{actual}"
+    );
+}
+
+#[test]
+fn test_snapshot_log_view() {
+    let mut app = fixture_app();
+    app.select_next();
+    assert_snapshot("log_view", &mut app, 80, 10);
+}
+
+#[test]
+fn test_snapshot_detail_view() {
+    let mut app = fixture_app();
+    app.view = View::Detail;
+    app.detail_state = Some(DetailState {
+        show_output: fixture_show(),
+        scroll: 0,
+        content_height: 0,
+        full_diff: None,
+        stat: DiffStat {
+            files: vec![
+                FileStat {
+                    path: "src/ui.rs".to_string(),
+                    added: 12,
+                    removed: 30,
+                },
+                FileStat {
+                    path: "src/ui/snapshot_tests.rs".to_string(),
+                    added: 140,
+                    removed: 0,
+                },
+            ],
+            added: 152,
+            removed: 30,
+        },
+        children: vec![RelatedCommit {
+            change_id: "qzmtztvn".to_string(),
+            description: "feat: add snapshot tests".to_string(),
+        }],
+        related: 0,
+        working_copy: false,
+    });
+    assert_snapshot("detail_view", &mut app, 80, 20);
+}
+
+#[test]
+fn test_snapshot_working_copy_detail() {
+    let mut app = fixture_app();
+    app.view = View::Detail;
+    let diff = "\
+diff --git a/src/ui.rs b/src/ui.rs
+index 1111111..2222222 100644
+--- a/src/ui.rs
++++ b/src/ui.rs
+@@ -1,2 +1,2 @@
+ use ratatui::Frame;
+-use ratatui::text::Line;
++use ratatui::text::{Line, Span};
+";
+    app.detail_state = Some(DetailState {
+        show_output: ShowOutput {
+            bookmarks: Vec::new(),
+            diff_summary: vec![DiffEntry {
+                status: DiffStatus::Modified,
+                path: "src/ui.rs".to_string(),
+            }],
+            ..fixture_show()
+        },
+        scroll: 0,
+        content_height: 0,
+        full_diff: Some(DetailDiff {
+            diff: GitDiff::parse(diff),
+            ..DetailDiff::default()
+        }),
+        stat: DiffStat::default(),
+        children: Vec::new(),
+        related: 0,
+        working_copy: true,
+    });
+    assert_snapshot("working_copy_detail", &mut app, 80, 24);
+}
+
+#[test]
+fn test_snapshot_confirm_modal() {
+    let mut app = fixture_app();
+    app.select_next();
+    app.show_abandon_confirm().unwrap();
+    assert_snapshot("confirm_modal", &mut app, 80, 16);
+}
+
+#[test]
+fn test_snapshot_help() {
+    let mut app = fixture_app();
+    app.toggle_help();
+    assert_snapshot("help", &mut app, 120, 40);
+
+    // Scrolled to the end, on another view
+    app.view = View::Tags;
+    app.help_scroll_down(usize::MAX);
+    assert_snapshot("help_tags_scrolled", &mut app, 80, 16);
+}
+
+#[test]
+fn test_snapshot_startup_error() {
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 10))
+        .expect("test backend never fails");
+    let error = "failed to fetch jj log\n\nCaused by:\n    jj command failed: Error: Failed to parse template: Keyword `tags` doesn't exist";
+    terminal
+        .draw(|frame| render_startup_error(frame, error, true, &Theme::default()))
+        .expect("test backend never fails");
+    assert_snapshot_text("startup_error", buffer_text(terminal.backend().buffer()));
+}
+
+#[test]
+fn test_log_view_renders_only_around_the_viewport() {
+    let output: String = (0..500)
+        .map(|i| {
+            format!(
+                "○  {}\x1fJane Doe\x1f{i}h\x1f\x1f\x1fcommit {i}\n",
+                change_id(i)
+            )
+        })
+        .collect();
+    let runner = JjRunner::new().with_work_dir(std::path::Path::new("/tmp"));
+    let mut app = App::new(GraphLog::from_output(&output), "/repo".to_string(), runner);
+    app.selected = 400;
+
+    let text = buffer_text(&render_to_buffer(&mut app, 80, 10));
+    // The selection is brought into view and the lines above are scrolled off
+    let top = app.scroll_offset;
+    assert!(text.contains("commit 400 "), "{text}");
+    assert!(text.contains(&format!("commit {top} ")), "{text}");
+    assert!(!text.contains(&format!("commit {} ", top - 1)), "{text}");
+}
+
+/// Distinct 8-letter change ID for `i`.
+fn change_id(i: usize) -> String {
+    (0..8)
+        .map(|digit| char::from(b'a' + (i / 26usize.pow(digit) % 26) as u8))
+        .collect()
+}
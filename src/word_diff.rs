@@ -0,0 +1,233 @@
+//! Word-level differences between the removed and added lines of a hunk.
+//!
+//! Each run of removed lines directly followed by a run of added lines is
+//! paired line by line. Both lines of a pair are split into words, runs of
+//! whitespace and single punctuation characters, and the tokens outside
+//! their longest common subsequence are the changed ones. Pairs that share
+//! too little are left alone, so rewritten lines are not speckled with
+//! emphasis. Colors are assigned by the UI.
+
+use std::ops::Range;
+
+use crate::jj::{DiffLineKind, GitDiff};
+
+/// Longest line, in tokens, that is compared (the comparison is quadratic).
+const MAX_TOKENS: usize = 256;
+
+/// Minimum share, in percent, of the longer line left unchanged for a pair
+/// to be emphasized.
+const MIN_COMMON_PERCENT: usize = 40;
+
+/// Byte ranges of a line.
+type Ranges = Vec<Range<usize>>;
+
+/// Changed byte ranges of each display line of a diff (empty for lines
+/// that are not part of a removed/added pair).
+///
+/// Ranges index the line's text, prefix included, like syntax tokens.
+pub fn word_diff(diff: &GitDiff) -> Vec<Vec<Range<usize>>> {
+    let lines: Vec<_> = diff.lines().collect();
+    let mut changes = vec![Vec::new(); lines.len()];
+    let mut i = 0;
+    while i < lines.len() {
+        let removed_start = i;
+        while i < lines.len() && lines[i].kind == DiffLineKind::Removed {
+            i += 1;
+        }
+        let added_start = i;
+        while i < lines.len() && lines[i].kind == DiffLineKind::Added {
+            i += 1;
+        }
+        if removed_start == added_start || added_start == i {
+            // Not a removed run followed by an added run
+            i = i.max(removed_start + 1);
+            continue;
+        }
+        for (old, new) in (removed_start..added_start).zip(added_start..i) {
+            let (Some(old_code), Some(new_code)) =
+                (lines[old].text.get(1..), lines[new].text.get(1..))
+            else {
+                continue;
+            };
+            if let Some((old_ranges, new_ranges)) = changed_ranges(old_code, new_code) {
+                changes[old] = shift(old_ranges);
+                changes[new] = shift(new_ranges);
+            }
+        }
+    }
+    changes
+}
+
+/// Changed byte ranges of `old` and `new`, or `None` if the lines are too
+/// different (or too long) to compare word by word.
+fn changed_ranges(old: &str, new: &str) -> Option<(Ranges, Ranges)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len() > MAX_TOKENS || new_tokens.len() > MAX_TOKENS {
+        return None;
+    }
+    let (old_common, new_common) = common_tokens(old, &old_tokens, new, &new_tokens);
+
+    let common_len: usize = old_tokens
+        .iter()
+        .zip(&old_common)
+        .filter(|(range, common)| **common && !old[(*range).clone()].trim().is_empty())
+        .map(|(range, _)| range.len())
+        .sum();
+    let longer = old.trim().len().max(new.trim().len());
+    if longer == 0 || common_len * 100 < longer * MIN_COMMON_PERCENT {
+        return None;
+    }
+    Some((
+        uncommon_ranges(&old_tokens, &old_common),
+        uncommon_ranges(&new_tokens, &new_common),
+    ))
+}
+
+/// Split a line into words, runs of whitespace and single other characters.
+fn tokenize(text: &str) -> Vec<Range<usize>> {
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut previous: Option<CharClass> = None;
+    for (i, c) in text.char_indices() {
+        let class = CharClass::of(c);
+        match tokens.last_mut() {
+            Some(last) if previous == Some(class) && class != CharClass::Other => {
+                last.end = i + c.len_utf8();
+            }
+            _ => tokens.push(i..i + c.len_utf8()),
+        }
+        previous = Some(class);
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Space,
+    Other,
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        if c.is_alphanumeric() || c == '_' {
+            Self::Word
+        } else if c.is_whitespace() {
+            Self::Space
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Mark the tokens of each side that belong to a longest common subsequence.
+fn common_tokens(
+    old: &str,
+    old_tokens: &[Range<usize>],
+    new: &str,
+    new_tokens: &[Range<usize>],
+) -> (Vec<bool>, Vec<bool>) {
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    let eq = |i: usize, j: usize| old[old_tokens[i].clone()] == new[new_tokens[j].clone()];
+    // lengths[i][j]: LCS length of old_tokens[i..] and new_tokens[j..]
+    let mut lengths = vec![vec![0u16; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if eq(i, j) {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut old_common = vec![false; n];
+    let mut new_common = vec![false; m];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if eq(i, j) {
+            old_common[i] = true;
+            new_common[j] = true;
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (old_common, new_common)
+}
+
+/// Byte ranges of the tokens not marked common, adjacent ones merged.
+fn uncommon_ranges(tokens: &[Range<usize>], common: &[bool]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (range, _) in tokens.iter().zip(common).filter(|(_, common)| !**common) {
+        match ranges.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => ranges.push(range.clone()),
+        }
+    }
+    ranges
+}
+
+/// Shift ranges past the +/- prefix.
+fn shift(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges
+        .into_iter()
+        .map(|range| range.start + 1..range.end + 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+        ranges.iter().map(|range| &text[range.clone()]).collect()
+    }
+
+    #[test]
+    fn test_changed_ranges() {
+        let old = "let total = count + 1;";
+        let new = "let total = count * 2;";
+        let (old_ranges, new_ranges) = changed_ranges(old, new).unwrap();
+        assert_eq!(changed(old, &old_ranges), ["+", "1"]);
+        assert_eq!(changed(new, &new_ranges), ["*", "2"]);
+
+        // Adjacent changed tokens are merged
+        let (_, new_ranges) = changed_ranges("call(a)", "call(a, b)").unwrap();
+        assert_eq!(changed("call(a, b)", &new_ranges), [", b"]);
+
+        // Rewritten lines are not compared word by word
+        assert!(changed_ranges("fn main() {", "    return value;").is_none());
+        assert!(changed_ranges("", "").is_none());
+    }
+
+    #[test]
+    fn test_word_diff_pairs_adjacent_runs() {
+        let diff = GitDiff::parse(
+            "diff --git a/x.rs b/x.rs\n\
+             @@ -1,3 +1,3 @@\n \
+             fn f() {\n\
+             -    let a = 1;\n\
+             +    let a = 2;\n\
+             +    let b = 3;\n \
+             }\n",
+        );
+        let lines: Vec<_> = diff.lines().collect();
+        let changes = word_diff(&diff);
+        assert_eq!(changes.len(), lines.len());
+        let removed = lines
+            .iter()
+            .position(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!(changed(&lines[removed].text, &changes[removed]), ["1"]);
+        assert_eq!(
+            changed(&lines[removed + 1].text, &changes[removed + 1]),
+            ["2"]
+        );
+        // The second added line has no removed counterpart
+        assert!(changes[removed + 2].is_empty());
+        assert!(changes[removed - 1].is_empty());
+    }
+}